@@ -6,6 +6,7 @@ use fe2o3_amqp::{link::DetachError, session::SessionHandle, types::definitions::
 use fe2o3_amqp_management::{
     client::{MgmtClient, MgmtClientBuilder},
     error::{AttachError, Error as MgmtError},
+    status::StatusCode,
 };
 
 use crate::{
@@ -39,11 +40,14 @@ impl CbsClient {
     }
 
     /// Put a CBS token
+    ///
+    /// Returns the status code of the response, which is either 200 (OK) or 202 (Accepted) if the
+    /// token is accepted by the CBS node.
     pub async fn put_token<'a>(
         &mut self,
         name: impl Into<Cow<'a, str>>,
         token: CbsToken<'a>,
-    ) -> Result<(), MgmtError> {
+    ) -> Result<StatusCode, MgmtError> {
         let entity_type = token.token_type;
         let req = PutTokenRequest::new(
             name,
@@ -52,8 +56,8 @@ impl CbsClient {
             entity_type,
             None,
         );
-        let _res: PutTokenResponse = self.mgmt_client.call(req).await?;
-        Ok(())
+        let res: PutTokenResponse = self.mgmt_client.call(req).await?;
+        Ok(res.status_code)
     }
 }
 
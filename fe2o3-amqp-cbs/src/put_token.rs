@@ -4,7 +4,14 @@ use fe2o3_amqp::types::{
     messaging::{ApplicationProperties, Message},
     primitives::{SimpleValue, Timestamp, Value},
 };
-use fe2o3_amqp_management::{constants::NAME, request::Request, response::Response};
+use fe2o3_amqp_management::{
+    constants::NAME,
+    error::{Error, InvalidType, StatusCodeNotFound, StatusError},
+    mgmt_ext::AmqpMessageManagementExt,
+    request::Request,
+    response::Response,
+    status::StatusCode,
+};
 use std::borrow::Cow;
 
 use crate::constants::{EXPIRATION, PUT_TOKEN};
@@ -84,19 +91,141 @@ impl<'a> Request for PutTokenRequest<'a> {
 }
 
 /// The response to a PutToken request.
+///
+/// The CBS node responds with either 200 (OK) or 202 (Accepted) if the token is accepted.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct PutTokenResponse {}
+pub struct PutTokenResponse {
+    /// The status code of the response
+    pub status_code: StatusCode,
+}
 
-impl PutTokenResponse {}
+impl PutTokenResponse {
+    /// Status code 200 (OK)
+    pub const OK: u16 = 200;
+}
 
 impl Response for PutTokenResponse {
     const STATUS_CODE: u16 = 202;
 
     type Body = Value;
 
-    type Error = fe2o3_amqp_management::error::Error;
+    type Error = Error;
+
+    /// Accepts both 200 (OK) and 202 (Accepted) as the status code of a successful response
+    fn verify_status_code(message: &mut Message<Self::Body>) -> Result<StatusCode, Self::Error> {
+        let status_code = match message.remove_status_code().ok_or(StatusCodeNotFound {})? {
+            Ok(status_code) => status_code,
+            Err(err) => {
+                return Err(InvalidType {
+                    expected: "u16".to_string(),
+                    actual: format!("{:?}", err),
+                }
+                .into())
+            }
+        };
 
-    fn decode_message(_message: Message<Self::Body>) -> Result<Self, Self::Error> {
-        Ok(Self {})
+        match status_code.0.get() {
+            Self::OK | Self::STATUS_CODE => Ok(status_code),
+            _ => Err(StatusError {
+                code: status_code,
+                description: message.remove_status_description().and_then(Result::ok),
+            }
+            .into()),
+        }
+    }
+
+    /// The status code is the only information carried by the response
+    fn decode_message(mut message: Message<Self::Body>) -> Result<Self, Self::Error> {
+        let status_code = Self::verify_status_code(&mut message)?;
+        Ok(Self { status_code })
+    }
+
+    fn from_message(message: Message<Self::Body>) -> Result<Self, Self::Error> {
+        Self::decode_message(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp::types::{
+        messaging::{ApplicationProperties, Message},
+        primitives::{SimpleValue, Timestamp, Value},
+    };
+    use fe2o3_amqp_management::{
+        constants::{NAME, OPERATION, TYPE},
+        error::Error,
+        request::Request,
+        response::Response,
+    };
+
+    use crate::constants::{EXPIRATION, PUT_TOKEN};
+
+    use super::{PutTokenRequest, PutTokenResponse};
+
+    fn response(status_code: u16) -> Message<Value> {
+        Message::builder()
+            .application_properties(
+                ApplicationProperties::builder()
+                    .insert("status-code", SimpleValue::Int(status_code as i32))
+                    .insert("status-description", "description")
+                    .build(),
+            )
+            .body(Value::Null)
+            .build()
+    }
+
+    #[test]
+    fn test_put_token_request_into_message() {
+        let expiration = Timestamp::from_milliseconds(1_700_000_000_000);
+        let request = PutTokenRequest::new(
+            "sb://example.servicebus.windows.net/q1",
+            "SharedAccessSignature sr=...",
+            expiration.clone(),
+            "servicebus.windows.net:sastoken",
+            None,
+        );
+        let message = request.into_message();
+
+        let properties = message.application_properties.unwrap();
+        assert_eq!(
+            properties.get(OPERATION),
+            Some(&SimpleValue::String(PUT_TOKEN.to_string()))
+        );
+        assert_eq!(
+            properties.get(TYPE),
+            Some(&SimpleValue::String(
+                "servicebus.windows.net:sastoken".to_string()
+            ))
+        );
+        assert_eq!(
+            properties.get(NAME),
+            Some(&SimpleValue::String(
+                "sb://example.servicebus.windows.net/q1".to_string()
+            ))
+        );
+        assert_eq!(
+            properties.get(EXPIRATION),
+            Some(&SimpleValue::Timestamp(expiration))
+        );
+        assert_eq!(message.body, "SharedAccessSignature sr=...");
+    }
+
+    #[test]
+    fn test_put_token_response_accepts_ok_and_accepted() {
+        for code in [200, 202] {
+            let response = PutTokenResponse::from_message(response(code)).unwrap();
+            assert_eq!(response.status_code.0.get(), code);
+        }
+    }
+
+    #[test]
+    fn test_put_token_response_with_unauthorized_status_code() {
+        match PutTokenResponse::from_message(response(401)) {
+            Err(Error::Status(error)) => {
+                assert_eq!(error.code.0.get(), 401);
+                assert_eq!(error.description.as_deref(), Some("description"));
+            }
+            other => panic!("Expecting a status error, found {:?}", other),
+        }
     }
 }
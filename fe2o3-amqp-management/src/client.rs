@@ -16,6 +16,7 @@ use fe2o3_amqp_types::{
 
 use crate::{
     error::{AttachError, DetachThenResumeError, Error},
+    operations::{QueryRequest, QueryResponse},
     request::Request,
     response::Response,
     DEFAULT_CLIENT_NODE_ADDRESS, MANAGEMENT_NODE_ADDRESS,
@@ -127,6 +128,52 @@ impl MgmtClient {
         let _accepted = outcome.accepted_or_else(Error::NotAccepted)?;
         self.recv_response().await
     }
+
+    /// Send a Query request and all the requests needed to retrieve the following pages.
+    ///
+    /// The `count` of the request is used as the page size, and the `offset` of each successive
+    /// request is advanced by the number of results returned until a page contains fewer than
+    /// `count` results. If `count` is not set, the whole result set is returned in a single
+    /// response and only one request is sent.
+    ///
+    /// Retrieving pages also stops if a page contains the same results as the previous page,
+    /// which happens if the management node ignores the `offset` of the request. The repeated
+    /// page is not added to the merged results.
+    ///
+    /// The returned [`QueryResponse`] contains the results of all pages in order, and its `count`
+    /// is the total number of results.
+    ///
+    /// # Consistency
+    ///
+    /// The management node does not guarantee that the result set remains consistent between
+    /// requests for successive pages. If the set of entities matching the query changes while the
+    /// pages are retrieved, some entities may be missing or returned more than once.
+    pub async fn query_all(
+        &mut self,
+        mut request: QueryRequest<'_>,
+    ) -> Result<QueryResponse, Error> {
+        let mut offset = request.offset.unwrap_or(0);
+        let mut merged: QueryResponse = self.call(request.clone()).await?;
+
+        if let Some(page_size) = request.count {
+            let mut last_count = merged.count;
+            let mut last_results = merged.results.clone();
+            while last_count > 0 && last_count >= page_size {
+                offset += last_count;
+                request.offset = Some(offset);
+                let page: QueryResponse = self.call(request.clone()).await?;
+                if page.results == last_results {
+                    break;
+                }
+                last_count = page.count;
+                last_results.clone_from(&page.results);
+                merged.count += page.count;
+                merged.results.extend(page.results);
+            }
+        }
+
+        Ok(merged)
+    }
 }
 
 /// A builder for a management client.
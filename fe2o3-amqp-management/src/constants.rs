@@ -42,6 +42,9 @@ pub const DEREGISTER: &str = "DEREGISTER";
 /// NAME key
 pub const NAME: &str = "name";
 
+/// Value of the NAME key for operations directed to the Management Node itself
+pub const SELF: &str = "self";
+
 /// IDENTITY key
 pub const IDENTITY: &str = "identity";
 
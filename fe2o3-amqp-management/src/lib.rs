@@ -16,6 +16,9 @@ pub mod response;
 
 pub mod mgmt_ext;
 
+#[cfg(test)]
+mod test_util;
+
 /// The default address of the management node.
 pub const MANAGEMENT_NODE_ADDRESS: &str = "$management";
 
@@ -1,11 +1,16 @@
 use std::borrow::Cow;
 
 use fe2o3_amqp_types::{
-    messaging::Message,
+    messaging::{ApplicationProperties, Message},
     primitives::{OrderedMap, Value},
 };
 
-use crate::{constants::CREATE, error::Error, request::Request, response::Response};
+use crate::{
+    constants::{CREATE, NAME},
+    error::Error,
+    request::Request,
+    response::Response,
+};
 
 /// The Create operation is used to create a new Manageable Entity.
 ///
@@ -99,6 +104,14 @@ impl<'a> Request for CreateRequest<'a> {
         self.locales.as_ref().map(|s| s.to_string())
     }
 
+    fn encode_application_properties(&mut self) -> Option<ApplicationProperties> {
+        Some(
+            ApplicationProperties::builder()
+                .insert(NAME, &self.name[..])
+                .build(),
+        )
+    }
+
     fn encode_body(self) -> Self::Body {
         self.body
     }
@@ -108,11 +121,12 @@ impl<'a> Request for CreateRequest<'a> {
 /// message MUST consist of an amqp-value section that contains a map containing the actual
 /// attributes of the entity created. These MAY differ from those requested in two ways:
 ///
-///     • Default values may be returned for values not specified
-///     • Specific/concrete values may be returned for generic/base values specified
-///     • The value associated with an attribute may have been converted into the correct amqp type
+/// - Default values may be returned for values not specified
+/// - Specific/concrete values may be returned for generic/base values specified
+/// - The value associated with an attribute may have been converted into the correct amqp type
+///   (e.g. the string “2” into the integer value 2)
 ///
-/// (e.g. the string “2” into the integer value 2) A map containing attributes that are not
+/// A map containing attributes that are not
 /// applicable for the entity being created, or invalid values for a given attribute, MUST result in
 /// a failure response with a statusCode of 400 (Bad Request).
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -139,3 +153,76 @@ impl Response for CreateResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        messaging::Message,
+        primitives::{OrderedMap, SimpleValue, Value},
+    };
+
+    use crate::{
+        constants::{CREATE, NAME, OPERATION, TYPE},
+        error::Error,
+        request::Request,
+        response::Response,
+        test_util,
+    };
+
+    use super::{CreateRequest, CreateResponse};
+
+    fn attributes() -> OrderedMap<String, Value> {
+        test_util::attributes([
+            ("durable", Value::Bool(true)),
+            ("max-size", Value::String("1024".to_string())),
+        ])
+    }
+
+    fn response(status_code: u16) -> Message<Option<OrderedMap<String, Value>>> {
+        let mut created = attributes();
+        created.insert("max-size".to_string(), Value::Ulong(1024));
+        test_util::response(status_code, Some(created))
+    }
+
+    #[test]
+    fn test_create_request_into_message() {
+        let request = CreateRequest::new("q1", "org.example.Queue", None::<String>, attributes());
+        let message = request.into_message();
+
+        let properties = message.application_properties.unwrap();
+        assert_eq!(
+            properties.get(OPERATION),
+            Some(&SimpleValue::String(CREATE.to_string()))
+        );
+        assert_eq!(
+            properties.get(TYPE),
+            Some(&SimpleValue::String("org.example.Queue".to_string()))
+        );
+        assert_eq!(
+            properties.get(NAME),
+            Some(&SimpleValue::String("q1".to_string()))
+        );
+        assert_eq!(message.body, attributes());
+    }
+
+    #[test]
+    fn test_create_response_from_message() {
+        let response = CreateResponse::from_message(response(201)).unwrap();
+        assert_eq!(
+            response.entity_attributes.get("max-size"),
+            Some(&Value::Ulong(1024))
+        );
+        assert_eq!(
+            response.entity_attributes.get("durable"),
+            Some(&Value::Bool(true))
+        );
+    }
+
+    #[test]
+    fn test_create_response_with_unexpected_status_code() {
+        match CreateResponse::from_message(response(400)) {
+            Err(Error::Status(error)) => assert_eq!(error.code.0.get(), 400),
+            other => panic!("Expecting a status error, found {:?}", other),
+        }
+    }
+}
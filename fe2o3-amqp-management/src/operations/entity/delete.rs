@@ -145,3 +145,72 @@ impl Response for DeleteResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        messaging::{message::__private::Deserializable, Message},
+        primitives::{OrderedMap, SimpleValue, Value},
+    };
+    use serde_amqp::{from_slice, to_vec};
+
+    use crate::{
+        constants::{DELETE, IDENTITY, OPERATION, TYPE},
+        error::Error,
+        request::Request,
+        response::Response,
+        test_util::{self, status_code},
+    };
+
+    use super::{DeleteRequest, DeleteResponse};
+
+    #[test]
+    fn test_delete_request_into_message() {
+        let request = DeleteRequest::identity("id-1", "org.example.Queue", None);
+        let message = request.into_message();
+
+        let properties = message.application_properties.unwrap();
+        assert_eq!(
+            properties.get(OPERATION),
+            Some(&SimpleValue::String(DELETE.to_string()))
+        );
+        assert_eq!(
+            properties.get(TYPE),
+            Some(&SimpleValue::String("org.example.Queue".to_string()))
+        );
+        assert_eq!(
+            properties.get(IDENTITY),
+            Some(&SimpleValue::String("id-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_delete_response_without_body_section() {
+        // A message that consists of only the application-properties section. The missing body
+        // section is decoded with `FromEmptyBody`
+        let buf = to_vec(&status_code(204)).unwrap();
+        let message: Deserializable<Message<Option<OrderedMap<String, Value>>>> =
+            from_slice(&buf).unwrap();
+        assert!(message.0.body.is_none());
+
+        let response = DeleteResponse::from_message(message.0).unwrap();
+        assert!(response.empty_map.is_empty());
+    }
+
+    #[test]
+    fn test_delete_response_with_empty_map() {
+        let message = test_util::response(204, Some(OrderedMap::new()));
+        let response = DeleteResponse::from_message(message).unwrap();
+        assert!(response.empty_map.is_empty());
+    }
+
+    #[test]
+    fn test_delete_response_with_non_empty_map() {
+        let map = test_util::attributes([("name", Value::String("q1".to_string()))]);
+        let message = test_util::response(204, Some(map));
+        assert!(matches!(
+            DeleteResponse::from_message(message),
+            Err(Error::DecodeError(_))
+        ));
+    }
+}
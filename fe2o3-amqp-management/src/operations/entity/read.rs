@@ -138,3 +138,83 @@ impl Response for ReadResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        messaging::{AmqpValue, Message},
+        primitives::{OrderedMap, SimpleValue, Value},
+    };
+
+    use crate::{
+        constants::{IDENTITY, NAME, OPERATION, READ, TYPE},
+        error::Error,
+        request::Request,
+        response::Response,
+        test_util,
+    };
+
+    use super::{ReadRequest, ReadResponse};
+
+    type ResponseMessage = Message<Option<OrderedMap<String, Value>>>;
+
+    fn attributes() -> OrderedMap<String, Value> {
+        test_util::attributes([
+            ("name", Value::String("q1".to_string())),
+            ("durable", Value::Bool(true)),
+        ])
+    }
+
+    #[test]
+    fn test_read_request_into_message() {
+        let message = ReadRequest::name("q1", "org.example.Queue", None).into_message();
+        let properties = message.application_properties.unwrap();
+        assert_eq!(
+            properties.get(OPERATION),
+            Some(&SimpleValue::String(READ.to_string()))
+        );
+        assert_eq!(
+            properties.get(TYPE),
+            Some(&SimpleValue::String("org.example.Queue".to_string()))
+        );
+        assert_eq!(
+            properties.get(NAME),
+            Some(&SimpleValue::String("q1".to_string()))
+        );
+        assert!(properties.get(IDENTITY).is_none());
+    }
+
+    #[test]
+    fn test_read_response_from_encoded_message() {
+        let message = test_util::response(200, AmqpValue(attributes()));
+        let message: ResponseMessage = test_util::encode_then_decode(message).unwrap();
+
+        let response = ReadResponse::from_message(message).unwrap();
+        assert_eq!(response.entity_attributes, attributes());
+    }
+
+    #[test]
+    fn test_read_response_with_unexpected_status_code() {
+        let message = test_util::response(404, None);
+        match ReadResponse::from_message(message) {
+            Err(Error::Status(error)) => assert_eq!(error.code.0.get(), 404),
+            other => panic!("Expecting a status error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_response_without_status_code() {
+        let message = Message::builder().body(Some(attributes())).build();
+        assert!(matches!(
+            ReadResponse::from_message(message),
+            Err(Error::StatusCodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_read_response_with_malformed_body() {
+        let message = test_util::response(200, AmqpValue(vec![Value::Bool(true)]));
+        let result: Result<ResponseMessage, _> = test_util::encode_then_decode(message);
+        assert!(result.is_err());
+    }
+}
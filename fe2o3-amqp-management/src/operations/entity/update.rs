@@ -168,3 +168,63 @@ impl Response for UpdateResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::primitives::{OrderedMap, SimpleValue, Value};
+
+    use crate::{
+        constants::{IDENTITY, NAME, OPERATION, TYPE, UPDATE},
+        request::Request,
+        response::Response,
+        test_util,
+    };
+
+    use super::{UpdateRequest, UpdateResponse};
+
+    fn attributes() -> OrderedMap<String, Value> {
+        test_util::attributes([("max-size", Value::Ulong(2048))])
+    }
+
+    #[test]
+    fn test_update_request_by_name_into_message() {
+        let request = UpdateRequest::name("q1", "org.example.Queue", None, attributes());
+        let message = request.into_message();
+
+        let properties = message.application_properties.unwrap();
+        assert_eq!(
+            properties.get(OPERATION),
+            Some(&SimpleValue::String(UPDATE.to_string()))
+        );
+        assert_eq!(
+            properties.get(TYPE),
+            Some(&SimpleValue::String("org.example.Queue".to_string()))
+        );
+        assert_eq!(
+            properties.get(NAME),
+            Some(&SimpleValue::String("q1".to_string()))
+        );
+        assert!(properties.get(IDENTITY).is_none());
+        assert_eq!(message.body, attributes());
+    }
+
+    #[test]
+    fn test_update_request_by_identity_into_message() {
+        let request = UpdateRequest::identity("id-1", "org.example.Queue", None, attributes());
+        let message = request.into_message();
+
+        let properties = message.application_properties.unwrap();
+        assert_eq!(
+            properties.get(IDENTITY),
+            Some(&SimpleValue::String("id-1".to_string()))
+        );
+        assert!(properties.get(NAME).is_none());
+    }
+
+    #[test]
+    fn test_update_response_from_message() {
+        let message = test_util::response(200, Some(attributes()));
+        let response = UpdateResponse::from_message(message).unwrap();
+        assert_eq!(response.entity_attributes, attributes());
+    }
+}
@@ -2,7 +2,7 @@ use std::borrow::Cow;
 
 use fe2o3_amqp_types::messaging::ApplicationProperties;
 
-use crate::constants::ENTITY_TYPE;
+use crate::constants::{ENTITY_TYPE, NAME, SELF};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct GetRequest<'a> {
@@ -36,11 +36,12 @@ impl<'a> GetRequest<'a> {
         self.locales.as_ref().map(|x| x.to_string())
     }
 
+    /// The name MUST be "self" because the operation is directed to the Management Node itself
     pub(crate) fn encode_application_properties(&mut self) -> Option<ApplicationProperties> {
-        self.entity_type.as_ref().map(|entity_type| {
-            ApplicationProperties::builder()
-                .insert(ENTITY_TYPE, entity_type.to_string())
-                .build()
-        })
+        let mut builder = ApplicationProperties::builder().insert(NAME, SELF);
+        if let Some(entity_type) = self.entity_type.as_ref() {
+            builder = builder.insert(ENTITY_TYPE, entity_type.to_string());
+        }
+        Some(builder.build())
     }
 }
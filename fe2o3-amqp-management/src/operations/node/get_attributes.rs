@@ -94,3 +94,63 @@ impl Response for GetAttributesResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        messaging::{
+            message::__private::{Deserializable, Serializable},
+            AmqpValue, ApplicationProperties, Message,
+        },
+        primitives::{OrderedMap, SimpleValue},
+    };
+    use serde_amqp::{from_slice, to_vec};
+
+    use crate::{
+        constants::{ENTITY_TYPE, GET_ATTRIBUTES, NAME, OPERATION, SELF},
+        request::Request,
+        response::Response,
+    };
+
+    use super::{GetAttributesRequest, GetAttributesResponse};
+
+    #[test]
+    fn test_get_attributes_request_into_message() {
+        let request = GetAttributesRequest::new(None, "org.amqp.management", None::<String>);
+        let message = request.into_message();
+
+        let properties = message.application_properties.unwrap();
+        assert_eq!(
+            properties.get(OPERATION),
+            Some(&SimpleValue::String(GET_ATTRIBUTES.to_string()))
+        );
+        assert_eq!(
+            properties.get(NAME),
+            Some(&SimpleValue::String(SELF.to_string()))
+        );
+        assert!(properties.get(ENTITY_TYPE).is_none());
+    }
+
+    #[test]
+    fn test_get_attributes_response_from_encoded_message() {
+        let mut attributes = OrderedMap::new();
+        attributes.insert(
+            "org.example.Queue".to_string(),
+            vec!["name".to_string(), "durable".to_string()],
+        );
+        let message = Message::builder()
+            .application_properties(
+                ApplicationProperties::builder()
+                    .insert("statusCode", SimpleValue::Int(200))
+                    .build(),
+            )
+            .body(AmqpValue(attributes.clone()))
+            .build();
+        let buf = to_vec(&Serializable(message)).unwrap();
+        let message: Deserializable<Message<<GetAttributesResponse as Response>::Body>> =
+            from_slice(&buf).unwrap();
+
+        let response = GetAttributesResponse::from_message(message.0).unwrap();
+        assert_eq!(response.body, attributes);
+    }
+}
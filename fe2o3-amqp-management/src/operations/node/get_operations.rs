@@ -97,3 +97,62 @@ impl Response for GetOperationsResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        messaging::{
+            message::__private::{Deserializable, Serializable},
+            AmqpValue, ApplicationProperties, Message,
+        },
+        primitives::{OrderedMap, SimpleValue},
+    };
+    use serde_amqp::{from_slice, to_vec};
+
+    use crate::{
+        constants::{GET_OPERATIONS, NAME, OPERATION, SELF},
+        request::Request,
+        response::Response,
+    };
+
+    use super::{GetOperationsRequest, GetOperationsResponse};
+
+    #[test]
+    fn test_get_operations_request_into_message() {
+        let request = GetOperationsRequest::new(None, "org.amqp.management", None::<String>);
+        let message = request.into_message();
+
+        let properties = message.application_properties.unwrap();
+        assert_eq!(
+            properties.get(OPERATION),
+            Some(&SimpleValue::String(GET_OPERATIONS.to_string()))
+        );
+        assert_eq!(
+            properties.get(NAME),
+            Some(&SimpleValue::String(SELF.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_get_operations_response_from_encoded_message() {
+        let mut queue_operations = OrderedMap::new();
+        queue_operations.insert("READ".to_string(), Vec::new());
+        queue_operations.insert("PURGE".to_string(), vec!["count".to_string()]);
+        let mut operations = OrderedMap::new();
+        operations.insert("org.example.Queue".to_string(), queue_operations);
+        let message = Message::builder()
+            .application_properties(
+                ApplicationProperties::builder()
+                    .insert("statusCode", SimpleValue::Int(200))
+                    .build(),
+            )
+            .body(AmqpValue(operations.clone()))
+            .build();
+        let buf = to_vec(&Serializable(message)).unwrap();
+        let message: Deserializable<Message<<GetOperationsResponse as Response>::Body>> =
+            from_slice(&buf).unwrap();
+
+        let response = GetOperationsResponse::from_message(message.0).unwrap();
+        assert_eq!(response.body, operations);
+    }
+}
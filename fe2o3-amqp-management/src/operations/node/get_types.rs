@@ -84,3 +84,75 @@ impl Response for GetTypesResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        messaging::{
+            message::__private::{Deserializable, Serializable},
+            AmqpValue, ApplicationProperties, Message,
+        },
+        primitives::{OrderedMap, SimpleValue},
+    };
+    use serde_amqp::{from_slice, to_vec};
+
+    use crate::{
+        constants::{ENTITY_TYPE, GET_TYPES, NAME, OPERATION, SELF, TYPE},
+        request::Request,
+        response::Response,
+    };
+
+    use super::{GetTypesRequest, GetTypesResponse};
+
+    #[test]
+    fn test_get_types_request_into_message() {
+        let request = GetTypesRequest::new(
+            Some("org.example.Queue".into()),
+            "org.amqp.management",
+            None::<String>,
+        );
+        let message = request.into_message();
+
+        let properties = message.application_properties.unwrap();
+        assert_eq!(
+            properties.get(OPERATION),
+            Some(&SimpleValue::String(GET_TYPES.to_string()))
+        );
+        assert_eq!(
+            properties.get(TYPE),
+            Some(&SimpleValue::String("org.amqp.management".to_string()))
+        );
+        assert_eq!(
+            properties.get(NAME),
+            Some(&SimpleValue::String(SELF.to_string()))
+        );
+        assert_eq!(
+            properties.get(ENTITY_TYPE),
+            Some(&SimpleValue::String("org.example.Queue".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_get_types_response_from_encoded_message() {
+        let mut types = OrderedMap::new();
+        types.insert("org.example.Node".to_string(), Vec::new());
+        types.insert(
+            "org.example.Queue".to_string(),
+            vec!["org.example.Node".to_string()],
+        );
+        let message = Message::builder()
+            .application_properties(
+                ApplicationProperties::builder()
+                    .insert("statusCode", SimpleValue::Int(200))
+                    .build(),
+            )
+            .body(AmqpValue(types.clone()))
+            .build();
+        let buf = to_vec(&Serializable(message)).unwrap();
+        let message: Deserializable<Message<<GetTypesResponse as Response>::Body>> =
+            from_slice(&buf).unwrap();
+
+        let response = GetTypesResponse::from_message(message.0).unwrap();
+        assert_eq!(response.types, types);
+    }
+}
@@ -0,0 +1,47 @@
+//! Fixtures shared by the unit tests of the operations
+
+use fe2o3_amqp_types::{
+    messaging::{
+        message::__private::{Deserializable, Serializable},
+        ApplicationProperties, FromBody, Message, SerializableBody,
+    },
+    primitives::{OrderedMap, SimpleValue, Value},
+};
+use serde_amqp::{from_slice, to_vec};
+
+/// Entity attributes with the given entries
+pub(crate) fn attributes<'a>(
+    entries: impl IntoIterator<Item = (&'a str, Value)>,
+) -> OrderedMap<String, Value> {
+    let mut attributes = OrderedMap::new();
+    for (key, value) in entries {
+        attributes.insert(key.to_string(), value);
+    }
+    attributes
+}
+
+/// Application properties that only contain the `status-code`
+pub(crate) fn status_code(code: u16) -> ApplicationProperties {
+    ApplicationProperties::builder()
+        .insert("status-code", SimpleValue::Ushort(code))
+        .build()
+}
+
+/// A response message with the given `status-code` and body
+pub(crate) fn response<B>(code: u16, body: B) -> Message<B> {
+    Message::builder()
+        .application_properties(status_code(code))
+        .body(body)
+        .build()
+}
+
+/// Encodes the message and decodes it with the body type `B`, as if it was received
+pub(crate) fn encode_then_decode<T, B>(message: Message<T>) -> Result<Message<B>, serde_amqp::Error>
+where
+    T: SerializableBody,
+    for<'de> B: FromBody<'de>,
+{
+    let buf = to_vec(&Serializable(message))?;
+    let message: Deserializable<Message<B>> = from_slice(&buf)?;
+    Ok(message.0)
+}
@@ -0,0 +1,178 @@
+//! Tests `MgmtClient::query_all` against an in-process management node built with the acceptor
+
+use fe2o3_amqp::{
+    acceptor::{
+        link::{LinkAcceptor, LinkEndpoint},
+        session::SessionAcceptor,
+        ConnectionAcceptor,
+    },
+    Connection, Receiver, Sender, Session,
+};
+use fe2o3_amqp_management::{operations::QueryRequest, MgmtClient};
+use fe2o3_amqp_types::{
+    messaging::{AmqpValue, ApplicationProperties, Body, Message},
+    primitives::{OrderedMap, SimpleValue, Value},
+};
+use tokio::{io::DuplexStream, task::JoinHandle};
+
+const ENTITIES: u32 = 5;
+
+fn entity(i: u32) -> Vec<Value> {
+    vec![Value::String(format!("q{}", i)), Value::Uint(i)]
+}
+
+/// Answers `QUERY` requests with pages of `ENTITIES` entities and returns the `offset` and `count`
+/// of each request once the client detaches. The `offset` of the requests is treated as zero if
+/// `ignore_offset` is set.
+fn spawn_management_node(
+    stream: DuplexStream,
+    ignore_offset: bool,
+) -> JoinHandle<Vec<(Option<u32>, Option<u32>)>> {
+    tokio::spawn(async move {
+        let mut connection = ConnectionAcceptor::new("management-node")
+            .accept(stream)
+            .await
+            .unwrap();
+        let mut session = SessionAcceptor::new()
+            .accept(&mut connection)
+            .await
+            .unwrap();
+        let link_acceptor = LinkAcceptor::new();
+        let mut sender: Option<Sender> = None;
+        let mut receiver: Option<Receiver> = None;
+        while sender.is_none() || receiver.is_none() {
+            match link_acceptor.accept(&mut session).await.unwrap() {
+                LinkEndpoint::Sender(link) => sender = Some(link),
+                LinkEndpoint::Receiver(link) => receiver = Some(link),
+            }
+        }
+        let (mut sender, mut receiver) = (sender.unwrap(), receiver.unwrap());
+
+        let mut requests = Vec::new();
+        while let Ok(delivery) = receiver.recv::<Body<Value>>().await {
+            receiver.accept(&delivery).await.unwrap();
+            let properties = delivery.message().application_properties.as_ref().unwrap();
+            let get = |key: &str| match properties.get(key) {
+                Some(SimpleValue::Uint(value)) => Some(*value),
+                _ => None,
+            };
+            let (offset, count) = (get("offset"), get("count"));
+            requests.push((offset, count));
+
+            let start = match ignore_offset {
+                true => 0,
+                false => offset.unwrap_or(0).min(ENTITIES),
+            };
+            let end = count.map_or(ENTITIES, |c| (start + c).min(ENTITIES));
+            let results: Vec<Value> = (start..end).map(|i| Value::List(entity(i))).collect();
+            let mut body = OrderedMap::new();
+            body.insert(
+                "attributeNames".to_string(),
+                vec![Value::from("name"), Value::from("index")],
+            );
+            body.insert("results".to_string(), results);
+            let response = Message::builder()
+                .application_properties(
+                    ApplicationProperties::builder()
+                        .insert("status-code", SimpleValue::Ushort(200))
+                        .insert("count", end - start)
+                        .build(),
+                )
+                .body(AmqpValue(body))
+                .build();
+            sender.send(response).await.unwrap();
+        }
+
+        let _ = sender.close().await;
+        let _ = receiver.close().await;
+        let _ = session.on_end().await;
+        let _ = connection.on_close().await;
+        requests
+    })
+}
+
+async fn query_all(
+    offset: Option<u32>,
+    count: Option<u32>,
+    ignore_offset: bool,
+) -> (
+    fe2o3_amqp_management::operations::QueryResponse,
+    Vec<(Option<u32>, Option<u32>)>,
+) {
+    let (client_io, node_io) = tokio::io::duplex(64 * 1024);
+    let node = spawn_management_node(node_io, ignore_offset);
+
+    let mut connection = Connection::builder()
+        .container_id("mgmt-test-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut client = MgmtClient::attach(&mut session, "mgmt-client")
+        .await
+        .unwrap();
+
+    let request = QueryRequest::new(None, offset, count, Vec::<String>::new(), "", None);
+    let response = client.query_all(request).await.unwrap();
+
+    client.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    (response, node.await.unwrap())
+}
+
+#[tokio::test]
+async fn query_all_merges_pages_until_a_short_page() {
+    let (response, requests) = query_all(None, Some(2), false).await;
+
+    assert_eq!(
+        requests,
+        vec![(None, Some(2)), (Some(2), Some(2)), (Some(4), Some(2))]
+    );
+    assert_eq!(response.count, ENTITIES);
+    assert_eq!(
+        response.attribute_names,
+        vec!["name".to_string(), "index".to_string()]
+    );
+    assert_eq!(
+        response.results,
+        (0..ENTITIES).map(entity).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn query_all_stops_at_empty_page() {
+    // The last full page is followed by a request that returns no results
+    let (response, requests) = query_all(Some(1), Some(2), false).await;
+
+    assert_eq!(
+        requests,
+        vec![(Some(1), Some(2)), (Some(3), Some(2)), (Some(5), Some(2))]
+    );
+    assert_eq!(response.count, ENTITIES - 1);
+    assert_eq!(
+        response.results,
+        (1..ENTITIES).map(entity).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn query_all_without_count_sends_single_request() {
+    let (response, requests) = query_all(None, None, false).await;
+
+    assert_eq!(requests, vec![(None, None)]);
+    assert_eq!(
+        response.results,
+        (0..ENTITIES).map(entity).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn query_all_stops_when_offset_is_ignored() {
+    // The management node keeps returning the same full page
+    let (response, requests) = query_all(None, Some(2), true).await;
+
+    assert_eq!(requests, vec![(None, Some(2)), (Some(2), Some(2))]);
+    assert_eq!(response.count, 2);
+    assert_eq!(response.results, (0..2).map(entity).collect::<Vec<_>>());
+}
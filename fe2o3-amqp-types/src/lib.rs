@@ -16,6 +16,8 @@
 //! - `"messaging"`: enables the types defined in part 2.7 and part 3 defined in the core specification
 //! - `"transaction"`: enables the types defined in part 4.5 of the core specification
 //! - `"security"`: enables the types defined in part 5 of the core specifiction.
+//! - `"uuid"`: enables conversion from `uuid::Uuid` to `MessageId` and between `uuid::Uuid` and the
+//!   primitive `Uuid`
 //!
 //! ```toml
 //! default = [
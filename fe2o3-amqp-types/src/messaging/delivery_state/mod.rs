@@ -353,6 +353,33 @@ impl Outcome {
         }
     }
 
+    /// Returns true if the message was not processed by the remote peer and sending it again may
+    /// succeed, which is the case for [`Released`] and for [`Modified`] with `delivery-failed`
+    /// set to true.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Released(_) => true,
+            Self::Modified(modified) => modified.delivery_failed == Some(true),
+            _ => false,
+        }
+    }
+
+    /// Returns the error of a [`Rejected`] outcome, if any.
+    pub fn rejection_error(&self) -> Option<&Error> {
+        match self {
+            Self::Rejected(rejected) => rejected.error.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the [`Modified`] outcome, or `None` for any other outcome.
+    pub fn into_modified(self) -> Option<Modified> {
+        match self {
+            Self::Modified(modified) => Some(modified),
+            _ => None,
+        }
+    }
+
     /// Transforms the [`DeliveryState`] into a `Result<Accepted, E>`,
     /// mapping Accepted(accepted) to Ok(accepted) and other variants to Err(err).
     pub fn accepted_or<E>(self, err: E) -> Result<Accepted, E> {
@@ -628,7 +655,9 @@ mod tests {
     //! Test serialization and deserialization
     use serde_amqp::{de::from_slice, format_code::EncodingCodes, from_reader, ser::to_vec};
 
-    use super::{Accepted, DeliveryState, Modified, Received, Rejected, Released};
+    use crate::definitions::{AmqpError, Error};
+
+    use super::{Accepted, DeliveryState, Modified, Outcome, Received, Rejected, Released};
 
     /* ---------------------------- // test Accepted ---------------------------- */
     #[test]
@@ -696,6 +725,50 @@ mod tests {
         assert!(rejected.error.is_none())
     }
 
+    /* ---------------------------- test Outcome helpers ---------------------------- */
+    #[test]
+    fn outcome_is_transient() {
+        assert!(!Outcome::Accepted(Accepted {}).is_transient());
+        assert!(!Outcome::Rejected(Rejected { error: None }).is_transient());
+        assert!(Outcome::Released(Released {}).is_transient());
+
+        let modified = |delivery_failed| Modified {
+            delivery_failed,
+            undeliverable_here: None,
+            message_annotations: None,
+        };
+        assert!(Outcome::Modified(modified(Some(true))).is_transient());
+        assert!(!Outcome::Modified(modified(Some(false))).is_transient());
+        assert!(!Outcome::Modified(modified(None)).is_transient());
+    }
+
+    #[test]
+    fn outcome_rejection_error() {
+        let error = Error::new(AmqpError::InvalidField, None, None);
+        let rejected = Outcome::Rejected(Rejected {
+            error: Some(error.clone()),
+        });
+        assert_eq!(rejected.rejection_error(), Some(&error));
+        assert_eq!(
+            Outcome::Rejected(Rejected { error: None }).rejection_error(),
+            None
+        );
+        assert_eq!(Outcome::Released(Released {}).rejection_error(), None);
+    }
+
+    #[test]
+    fn outcome_into_modified() {
+        let modified = Modified {
+            delivery_failed: Some(true),
+            undeliverable_here: Some(true),
+            message_annotations: None,
+        };
+        let modified = Outcome::Modified(modified).into_modified().unwrap();
+        assert_eq!(modified.delivery_failed, Some(true));
+        assert_eq!(modified.undeliverable_here, Some(true));
+        assert!(Outcome::Accepted(Accepted {}).into_modified().is_none());
+    }
+
     /* ------------------------------ test Released ----------------------------- */
     #[test]
     fn test_serialize_deserialize_released() {
@@ -0,0 +1,243 @@
+//! Implements 3.5.8 Filter Set
+
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Serialize};
+use serde_amqp::{
+    described::Described,
+    descriptor::Descriptor,
+    primitives::{OrderedMap, Symbol},
+    Value,
+};
+
+/// 3.5.8 Filter Set
+///
+/// ```xml
+/// <type name="filter-set" class="restricted" source="map"/>
+/// ```
+///
+/// A set of named filters. Every key in the map MUST be of type symbol,
+/// every value MUST be either null or of a described type which provides
+/// the archetype filter. A filter acts as a function on a message which
+/// returns a boolean result indicating whether the message can pass through
+/// that filter or not. A message will pass through a filter-set if and only
+/// if it passes through each of the named filters. If the value for a given
+/// key is null, this acts as if there were no such key present
+/// (i.e., all messages pass through the null filter).
+/// Filter types are a defined extension point. The filter types that a given
+/// source supports will be indicated by the capabilities of the source.
+/// A registry of commonly defined filter types and their capabilities is
+/// maintained \[AMQPFILTERS\].
+///
+/// Although the value of each entry must be either null or a described type,
+/// many implementations in other programming languages still supports the legacy
+/// format of a filter-set where the value could be any AMQP type. This legacy
+/// format is deprecated and SHOULD NOT be used in new implementations. However,
+/// for compatibility with existing implementations, [`FilterSet::insert_legacy`]
+/// allows the legacy format to be used.
+///
+/// # Example
+///
+/// ```rust
+/// use fe2o3_amqp_types::messaging::FilterSet;
+/// use fe2o3_amqp_types::primitives::Value;
+/// use serde_amqp::descriptor::Descriptor;
+///
+/// let mut filter_set = FilterSet::new();
+/// filter_set.insert_described(
+///     "selector",
+///     Descriptor::Code(0x0000_468c_0000_0004),
+///     Value::String(String::from("color = 'red'")),
+/// );
+///
+/// for (name, _filter) in &filter_set {
+///     assert_eq!(name.as_str(), "selector");
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FilterSet(pub OrderedMap<Symbol, Value>);
+
+impl FilterSet {
+    /// Creates an empty [`FilterSet`]
+    pub fn new() -> Self {
+        Self(OrderedMap::new())
+    }
+
+    /// Inserts a named filter whose value is a described type.
+    ///
+    /// Returns the previous filter if a filter with the same name was already present
+    pub fn insert_described(
+        &mut self,
+        name: impl Into<Symbol>,
+        descriptor: Descriptor,
+        value: impl Into<Value>,
+    ) -> Option<Value> {
+        let described = Described {
+            descriptor,
+            value: value.into(),
+        };
+        self.0
+            .insert(name.into(), Value::Described(Box::new(described)))
+    }
+
+    /// Inserts a named null filter, which all messages pass through.
+    ///
+    /// Returns the previous filter if a filter with the same name was already present
+    pub fn insert_null(&mut self, name: impl Into<Symbol>) -> Option<Value> {
+        self.0.insert(name.into(), Value::Null)
+    }
+
+    /// Inserts a named filter using the legacy format, in which the value could be any AMQP type
+    /// instead of a described type.
+    ///
+    /// Returns the previous filter if a filter with the same name was already present
+    pub fn insert_legacy(
+        &mut self,
+        name: impl Into<Symbol>,
+        value: impl Into<Value>,
+    ) -> Option<Value> {
+        self.0.insert(name.into(), value.into())
+    }
+
+    /// Consumes the wrapper and returns the inner map
+    pub fn into_inner(self) -> OrderedMap<Symbol, Value> {
+        self.0
+    }
+}
+
+impl Deref for FilterSet {
+    type Target = OrderedMap<Symbol, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for FilterSet {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<OrderedMap<Symbol, Value>> for FilterSet {
+    fn from(map: OrderedMap<Symbol, Value>) -> Self {
+        Self(map)
+    }
+}
+
+impl From<FilterSet> for OrderedMap<Symbol, Value> {
+    fn from(filter_set: FilterSet) -> Self {
+        filter_set.0
+    }
+}
+
+impl FromIterator<(Symbol, Value)> for FilterSet {
+    fn from_iter<T: IntoIterator<Item = (Symbol, Value)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<(Symbol, Value)> for FilterSet {
+    fn extend<T: IntoIterator<Item = (Symbol, Value)>>(&mut self, iter: T) {
+        self.0.as_inner_mut().extend(iter)
+    }
+}
+
+impl IntoIterator for FilterSet {
+    type Item = (Symbol, Value);
+    type IntoIter = <OrderedMap<Symbol, Value> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FilterSet {
+    type Item = (&'a Symbol, &'a Value);
+    type IntoIter = <&'a OrderedMap<Symbol, Value> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_amqp::{
+        described::Described, descriptor::Descriptor, from_slice, primitives::Symbol, to_vec, Value,
+    };
+
+    use super::FilterSet;
+
+    #[test]
+    fn test_serialize_multi_filter_set() {
+        let mut filter_set = FilterSet::new();
+        filter_set.insert_described(
+            "sel",
+            Descriptor::Code(0x0000_468c_0000_0004),
+            Value::String(String::from("a")),
+        );
+        filter_set.insert_described(
+            Symbol::from("no-local"),
+            Descriptor::Name(Symbol::from("n")),
+            Value::List(vec![]),
+        );
+        filter_set.insert_null("null");
+
+        let buf = to_vec(&filter_set).unwrap();
+        let expected = vec![
+            0xc1, 0x29, 0x06, // map8, size, count
+            0xa3, 0x03, b's', b'e', b'l', // sym8 "sel"
+            0x00, 0x80, 0x00, 0x00, 0x46, 0x8c, 0x00, 0x00, 0x00, 0x04, // ulong descriptor
+            0xa1, 0x01, b'a', // str8 "a"
+            0xa3, 0x08, b'n', b'o', b'-', b'l', b'o', b'c', b'a', b'l', // sym8 "no-local"
+            0x00, 0xa3, 0x01, b'n', // symbol descriptor
+            0x45, // list0
+            0xa3, 0x04, b'n', b'u', b'l', b'l', // sym8 "null"
+            0x40, // null
+        ];
+        assert_eq!(buf, expected);
+
+        let deserialized: FilterSet = from_slice(&buf).unwrap();
+        assert_eq!(deserialized, filter_set);
+    }
+
+    #[test]
+    fn test_filter_set_iteration_preserves_insertion_order() {
+        let mut filter_set = FilterSet::new();
+        filter_set.insert_described("b", Descriptor::Code(1), Value::Bool(true));
+        filter_set.insert_legacy("a", Value::String(String::from("legacy")));
+
+        let names: Vec<&str> = filter_set.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["b", "a"]);
+
+        let (name, value) = filter_set.into_iter().next().unwrap();
+        assert_eq!(name, Symbol::from("b"));
+        assert_eq!(
+            value,
+            Value::Described(Box::new(Described {
+                descriptor: Descriptor::Code(1),
+                value: Value::Bool(true),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_filter_set_converts_from_and_into_map() {
+        use serde_amqp::primitives::OrderedMap;
+
+        let mut map = OrderedMap::new();
+        map.insert(Symbol::from("legacy"), Value::String(String::from("a")));
+
+        let mut filter_set = FilterSet::from(map.clone());
+        assert_eq!(*filter_set, map);
+
+        // The map methods are available through `DerefMut`
+        filter_set.insert(Symbol::from("other"), Value::Null);
+        assert_eq!(filter_set.len(), 2);
+
+        let map: OrderedMap<Symbol, Value> = filter_set.into();
+        assert_eq!(map.get(&Symbol::from("other")), Some(&Value::Null));
+    }
+}
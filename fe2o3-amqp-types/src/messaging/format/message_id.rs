@@ -42,6 +42,14 @@ impl From<Uuid> for MessageId {
     }
 }
 
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for MessageId {
+    fn from(value: uuid::Uuid) -> Self {
+        Self::Uuid(Uuid::from(value))
+    }
+}
+
 impl From<Binary> for MessageId {
     fn from(value: Binary) -> Self {
         Self::Binary(value)
@@ -185,6 +193,23 @@ mod tests {
         assert_eq!(id, deserialized);
     }
 
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_message_id_from_uuid_crate() {
+        let uuid = uuid::Uuid::from_u128(0x0011_2233_4455_6677_8899_aabb_ccdd_eeff);
+        let id = MessageId::from(uuid);
+        let buf = to_vec(&id).unwrap();
+        // The bytes are encoded in the same order as `uuid::Uuid::as_bytes`
+        assert_eq!(buf[0], 0x98);
+        assert_eq!(&buf[1..], uuid.as_bytes());
+
+        let deserialized: MessageId = from_slice(&buf).unwrap();
+        match deserialized {
+            MessageId::Uuid(value) => assert_eq!(uuid::Uuid::from(value), uuid),
+            _ => panic!("Expecting MessageId::Uuid"),
+        }
+    }
+
     #[test]
     fn test_message_id_binary() {
         let id = MessageId::Binary(Binary::from("amqp"));
@@ -91,6 +91,18 @@ impl<T> Body<T> {
         }
     }
 
+    /// Consume the delivery into a `Vec` of the binary data if the body is one or more
+    /// [`Data`]. An error will be returned if otherwise
+    pub fn try_into_data_vec(self) -> Result<Vec<Binary>, Self> {
+        self.try_into_data().map(Iterator::collect)
+    }
+
+    /// Consume the delivery into a `Vec` of the sequences if the body is one or more
+    /// [`AmqpSequence`]. An error will be returned if otherwise
+    pub fn try_into_sequence_vec(self) -> Result<Vec<Vec<T>>, Self> {
+        self.try_into_sequence().map(Iterator::collect)
+    }
+
     /// Get a reference to the delivery body if the body is an [`AmqpValue`].
     /// An error will be returned if the body isnot an [`AmqpValue`]
     pub fn try_as_value(&self) -> Result<&T, &Self> {
@@ -163,6 +175,12 @@ impl From<Data> for Body<Value> {
     }
 }
 
+impl From<Vec<Data>> for Body<Value> {
+    fn from(val: Vec<Data>) -> Self {
+        Self::Data(Batch::new(val))
+    }
+}
+
 impl<T: Serialize> ser::Serialize for Body<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -362,4 +380,36 @@ where
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use serde_amqp::{primitives::Binary, Value};
+
+    use crate::messaging::{AmqpSequence, AmqpValue, Batch, Data};
+
+    use super::Body;
+
+    #[test]
+    fn test_try_into_data_vec() {
+        let body = Body::<Value>::Data(Batch::new(vec![
+            Data(Binary::from("hello")),
+            Data(Binary::from("AMQP")),
+        ]));
+        let data = body.try_into_data_vec().unwrap();
+        assert_eq!(data, vec![Binary::from("hello"), Binary::from("AMQP")]);
+
+        let body = Body::Value(AmqpValue(Value::Bool(true)));
+        assert_eq!(body.clone().try_into_data_vec(), Err(body));
+    }
+
+    #[test]
+    fn test_try_into_sequence_vec() {
+        let body = Body::Sequence(Batch::new(vec![
+            AmqpSequence(vec![1, 2]),
+            AmqpSequence(vec![3]),
+        ]));
+        let sequences = body.try_into_sequence_vec().unwrap();
+        assert_eq!(sequences, vec![vec![1, 2], vec![3]]);
+
+        let body = Body::<i32>::Empty;
+        assert_eq!(body.clone().try_into_sequence_vec(), Err(body));
+    }
+}
@@ -1,17 +1,22 @@
 //! Implementation of Message as defined in AMQP 1.0 protocol Part 3.2
 
-use std::{io, marker::PhantomData};
+use std::{io, marker::PhantomData, time::Duration};
 
 use serde::{
     de::{self},
     ser::SerializeStruct,
     Serialize,
 };
-use serde_amqp::__constants::{DESCRIBED_BASIC, DESCRIPTOR};
+use serde_amqp::{
+    primitives::{Symbol, Timestamp},
+    Value,
+    __constants::{DESCRIBED_BASIC, DESCRIPTOR},
+};
 
 use super::{
-    AmqpSequence, AmqpValue, ApplicationProperties, Batch, Data, DeliveryAnnotations, Footer,
-    FromBody, Header, IntoBody, MessageAnnotations, Properties, SerializableBody,
+    annotations::OwnedKey, AmqpSequence, AmqpValue, ApplicationProperties, Batch, Data,
+    DeliveryAnnotations, Footer, FromBody, Header, IntoBody, MessageAnnotations, MessageId,
+    Priority, Properties, SerializableBody,
 };
 
 mod body;
@@ -471,6 +476,23 @@ impl<T> Builder<T> {
         self
     }
 
+    /// Insert a single entry into the delivery annotations, creating the section if it is not
+    /// already present.
+    ///
+    /// Delivery annotations are only meant for the infrastructure on the next hop and are not
+    /// propagated to the final receiver. Use [`Builder::message_annotations`] for annotations
+    /// that should be carried end-to-end.
+    pub fn delivery_annotation(
+        mut self,
+        key: impl Into<OwnedKey>,
+        value: impl Into<Value>,
+    ) -> Self {
+        self.delivery_annotations
+            .get_or_insert_with(DeliveryAnnotations::default)
+            .insert(key.into(), value.into());
+        self
+    }
+
     /// Set the message annotations
     pub fn message_annotations(
         mut self,
@@ -486,6 +508,74 @@ impl<T> Builder<T> {
         self
     }
 
+    fn header_mut(&mut self) -> &mut Header {
+        self.header.get_or_insert_with(Header::default)
+    }
+
+    fn properties_mut(&mut self) -> &mut Properties {
+        self.properties.get_or_insert_with(Properties::default)
+    }
+
+    /// Set the `durable` field of the header, creating the header if it is not already present.
+    ///
+    /// This and the other setters of a single header or properties field modify the current
+    /// section in place, and are overwritten by a later call to [`Builder::header`] or
+    /// [`Builder::properties`].
+    pub fn durable(mut self, durable: bool) -> Self {
+        self.header_mut().durable = durable;
+        self
+    }
+
+    /// Set the `priority` field of the header, creating the header if it is not already present
+    pub fn priority(mut self, priority: impl Into<Priority>) -> Self {
+        self.header_mut().priority = priority.into();
+        self
+    }
+
+    /// Set the `ttl` field of the header, creating the header if it is not already present.
+    ///
+    /// The duration is truncated to whole milliseconds and saturates at `u32::MAX` milliseconds.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        let millis = u32::try_from(ttl.as_millis()).unwrap_or(u32::MAX);
+        self.header_mut().ttl = Some(millis);
+        self
+    }
+
+    /// Set the "subject" field of the properties, creating the properties if they are not
+    /// already present
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.properties_mut().subject = Some(subject.into());
+        self
+    }
+
+    /// Set the "correlation-id" field of the properties, creating the properties if they are
+    /// not already present
+    pub fn correlation_id(mut self, correlation_id: impl Into<MessageId>) -> Self {
+        self.properties_mut().correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    /// Set the "content-type" field of the properties, creating the properties if they are not
+    /// already present
+    pub fn content_type(mut self, content_type: impl Into<Symbol>) -> Self {
+        self.properties_mut().content_type = Some(content_type.into());
+        self
+    }
+
+    /// Set the "content-encoding" field of the properties, creating the properties if they are
+    /// not already present
+    pub fn content_encoding(mut self, content_encoding: impl Into<Symbol>) -> Self {
+        self.properties_mut().content_encoding = Some(content_encoding.into());
+        self
+    }
+
+    /// Set the "creation-time" field of the properties, creating the properties if they are not
+    /// already present
+    pub fn creation_time(mut self, creation_time: impl Into<Timestamp>) -> Self {
+        self.properties_mut().creation_time = Some(creation_time.into());
+        self
+    }
+
     /// Set application properties
     pub fn application_properties(
         mut self,
@@ -610,6 +700,7 @@ mod tests {
     use serde_bytes::ByteBuf;
 
     use crate::messaging::{
+        annotations::AnnotationKey,
         message::{
             Body,
             __private::{Deserializable, Serializable},
@@ -889,6 +980,26 @@ mod tests {
         assert_eq!(message.0, expected);
     }
 
+    #[test]
+    fn test_round_trip_message_with_multiple_data_sections() {
+        let sections = vec![
+            Binary::from("first"),
+            Binary::from("second"),
+            Binary::from("third"),
+        ];
+
+        let message = Message::builder().data_batch(sections.clone()).build();
+        let buf = to_vec(&Serializable(message)).unwrap();
+        let decoded: Deserializable<Message<Body<Value>>> = from_slice(&buf).unwrap();
+        assert_eq!(decoded.0.body.try_into_data_vec().unwrap(), sections);
+
+        let body = Body::from(sections.into_iter().map(Data).collect::<Vec<_>>());
+        let message = Message::builder().body(body.clone()).build();
+        let buf = to_vec(&Serializable(message)).unwrap();
+        let decoded: Deserializable<Message<Body<Value>>> = from_slice(&buf).unwrap();
+        assert_eq!(decoded.0.body, body);
+    }
+
     #[test]
     fn test_encode_message_with_sequence_batch() {
         use serde_amqp::extensions::TransparentVec;
@@ -936,4 +1047,137 @@ mod tests {
             .build();
         assert_eq!(message.0, expected);
     }
+
+    #[test]
+    fn test_builder_delivery_annotation_serialized_after_header() {
+        let message = Message::builder()
+            .header(Header::default())
+            .delivery_annotation("x-opt-route", "queue-a")
+            .delivery_annotation(1u64, Value::Uint(7))
+            .message_annotations(
+                MessageAnnotations::builder()
+                    .insert("x-opt-ma", 1i32)
+                    .build(),
+            )
+            .value(true)
+            .build();
+        let annotations = message.delivery_annotations.as_ref().unwrap();
+        assert_eq!(annotations.len(), 2);
+
+        let buf = to_vec(&Serializable(&message)).unwrap();
+        // header (empty list) followed immediately by the delivery-annotations descriptor
+        assert_eq!(&buf[..7], &[0x0, 0x53, 0x70, 0x45, 0x0, 0x53, 0x71]);
+    }
+
+    #[test]
+    fn test_delivery_annotations_stripped_by_intermediary() {
+        let message = Message::builder()
+            .header(Header::default())
+            .delivery_annotation("x-opt-route", "queue-a")
+            .message_annotations(
+                MessageAnnotations::builder()
+                    .insert("x-opt-ma", 1i32)
+                    .build(),
+            )
+            .properties(Properties::builder().message_id(1u64).build())
+            .value(Value::String(String::from("hello")))
+            .build();
+        let buf = to_vec(&Serializable(&message)).unwrap();
+
+        // The intermediary consumes the delivery annotations and forwards the rest
+        let received: Deserializable<Message<Body<Value>>> = from_slice(&buf).unwrap();
+        let mut forwarded = received.0;
+        let delivery_annotations = forwarded.delivery_annotations.take().unwrap();
+        assert_eq!(
+            delivery_annotations.get(&"x-opt-route" as &dyn AnnotationKey),
+            Some(&Value::from("queue-a"))
+        );
+        let buf = to_vec(&Serializable(&forwarded)).unwrap();
+
+        let consumed: Deserializable<Message<Body<Value>>> = from_slice(&buf).unwrap();
+        let consumed = consumed.0;
+        assert!(consumed.delivery_annotations.is_none());
+        assert_eq!(consumed.message_annotations, message.message_annotations);
+        assert_eq!(consumed.properties, message.properties);
+        assert_eq!(
+            consumed.body,
+            Body::Value(AmqpValue(Value::String(String::from("hello"))))
+        );
+    }
+
+    #[test]
+    fn test_builder_sets_header_and_properties_fields() {
+        use std::time::Duration;
+
+        use serde_amqp::primitives::Timestamp;
+
+        let message = Message::builder()
+            .durable(true)
+            .priority(7)
+            .ttl(Duration::from_secs(30))
+            .subject("subject")
+            .correlation_id(String::from("correlation"))
+            .content_type("application/json")
+            .content_encoding("gzip")
+            .creation_time(Timestamp::from_milliseconds(1_700_000_000_000))
+            .value("hello")
+            .build();
+
+        let expected = Message::builder()
+            .header(
+                Header::builder()
+                    .durable(true)
+                    .priority(7)
+                    .ttl(30_000)
+                    .build(),
+            )
+            .properties(
+                Properties::builder()
+                    .subject("subject")
+                    .correlation_id(String::from("correlation"))
+                    .content_type("application/json")
+                    .content_encoding("gzip")
+                    .creation_time(Timestamp::from_milliseconds(1_700_000_000_000))
+                    .build(),
+            )
+            .value("hello")
+            .build();
+        assert_eq!(message.header, expected.header);
+        assert_eq!(message.properties, expected.properties);
+
+        let buf = to_vec(&Serializable(message)).unwrap();
+        assert_eq!(buf, to_vec(&Serializable(expected)).unwrap());
+
+        // header, properties and amqp-value sections in that order
+        let decoded: Deserializable<Message<Value>> = from_slice(&buf).unwrap();
+        let decoded = decoded.0;
+        assert!(decoded.header.unwrap().durable);
+        assert_eq!(
+            decoded.properties.unwrap().content_type,
+            Some("application/json".into())
+        );
+        assert_eq!(buf[2], 0x70);
+        assert!(buf.windows(3).any(|w| w == [0x00, 0x53, 0x73]));
+    }
+
+    #[test]
+    fn test_builder_ttl_saturates() {
+        use std::time::Duration;
+
+        let message = Message::builder()
+            .ttl(Duration::from_secs(u64::MAX))
+            .value(())
+            .build();
+        assert_eq!(message.header.unwrap().ttl, Some(u32::MAX));
+    }
+
+    #[test]
+    fn test_builder_header_overwrites_single_fields() {
+        let message = Message::builder()
+            .durable(true)
+            .header(Header::default())
+            .value(())
+            .build();
+        assert_eq!(message.header, Some(Header::default()));
+    }
 }
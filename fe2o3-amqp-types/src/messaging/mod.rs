@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_amqp::extensions::TransparentVec;
-use serde_amqp::primitives::{Array, OrderedMap};
+use serde_amqp::primitives::Array;
 use serde_amqp::{primitives::Symbol, value::Value};
 
 mod body_section;
@@ -41,33 +41,8 @@ pub use lifetime_policy::*;
 /// Type alias for ergonomics (Batch<Data> and Batch<AmqpSequence>)
 pub type Batch<T> = TransparentVec<T>;
 
-/// 3.5.8 Filter Set
-///
-/// ```xml
-/// <type name="filter-set" class="restricted" source="map"/>
-/// ```
-///
-/// A set of named filters. Every key in the map MUST be of type symbol,
-/// every value MUST be either null or of a described type which provides
-/// the archetype filter. A filter acts as a function on a message which
-/// returns a boolean result indicating whether the message can pass through
-/// that filter or not. A message will pass through a filter-set if and only
-/// if it passes through each of the named filters. If the value for a given
-/// key is null, this acts as if there were no such key present
-/// (i.e., all messages pass through the null filter).
-/// Filter types are a defined extension point. The filter types that a given
-/// source supports will be indicated by the capabilities of the source.
-/// A registry of commonly defined filter types and their capabilities is
-/// maintained \[AMQPFILTERS\].
-///
-/// Although the value of each entry must be either null or a described type,
-/// many implementations in other programming languages still supports the legacy
-/// format of a filter-set where the value could be any AMQP type. This legacy
-/// format is deprecated and SHOULD NOT be used in new implementations. However,
-/// for compatibility with existing implementations, the following type alias
-/// is provided since "0.7.0" to allow the legacy format to be used.
-pub type FilterSet = OrderedMap<Symbol, Value>;
-// pub type FilterSet = OrderedMap<Symbol, Option<Described<Value>>>;
+mod filter_set;
+pub use filter_set::FilterSet;
 
 use crate::definitions::Fields;
 
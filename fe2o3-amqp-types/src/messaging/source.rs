@@ -1,6 +1,6 @@
 use serde_amqp::described::Described;
 use serde_amqp::macros::{DeserializeComposite, SerializeComposite};
-use serde_amqp::primitives::{Array, Boolean, OrderedMap, Symbol};
+use serde_amqp::primitives::{Array, Boolean, Symbol};
 use serde_amqp::Value;
 
 use crate::definitions::{Fields, Seconds};
@@ -229,8 +229,8 @@ impl SourceBuilder {
     /// many implementations in other programming languages still supports the legacy
     /// format of a filter-set where the value could be any AMQP type. This legacy
     /// format is deprecated and SHOULD NOT be used in new implementations. However,
-    /// for compatibility with existing implementations, the following type alias
-    /// is provided since "0.7.0" to allow the legacy format to be used.
+    /// for compatibility with existing implementations, [`FilterSet::insert_legacy`]
+    /// allows the legacy format to be used.
     pub fn filter(mut self, filter_set: FilterSet) -> Self {
         self.source.filter = Some(filter_set);
         self
@@ -242,11 +242,11 @@ impl SourceBuilder {
         key: impl Into<Symbol>,
         value: impl Into<Option<Described<Value>>>,
     ) -> Self {
-        let value = value.into();
-        self.source
-            .filter
-            .get_or_insert(OrderedMap::new())
-            .insert(key.into(), value.map(Into::into).unwrap_or(Value::Null));
+        let filter = self.source.filter.get_or_insert_with(FilterSet::new);
+        match value.into() {
+            Some(described) => filter.insert(key.into(), Value::Described(Box::new(described))),
+            None => filter.insert_null(key),
+        };
         self
     }
 
@@ -258,11 +258,10 @@ impl SourceBuilder {
         key: impl Into<Symbol>,
         value: impl Into<Value>,
     ) -> Self {
-        let value = value.into();
         self.source
             .filter
-            .get_or_insert(OrderedMap::new())
-            .insert(key.into(), value);
+            .get_or_insert_with(FilterSet::new)
+            .insert_legacy(key, value);
         self
     }
 
@@ -262,3 +262,73 @@ fn map_tungstenite_error(error: tungstenite::Error) -> io::Error {
         _ => io::Error::new(io::ErrorKind::Other, error),
     }
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use futures_util::{stream, StreamExt};
+    use tokio_util::codec::{FramedRead, LengthDelimitedCodec};
+
+    use crate::{WebSocketStream, WsMessage};
+
+    /// An AMQP frame with the given payload: 4 bytes size, DOFF = 2, type = 0, channel = 0
+    fn amqp_frame(payload: &[u8]) -> Vec<u8> {
+        let size = (8 + payload.len()) as u32;
+        let mut frame = size.to_be_bytes().to_vec();
+        frame.extend_from_slice(&[2, 0, 0, 0]);
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    /// Reads the frames the same way the AMQP transport decodes the byte stream, which strips
+    /// the 4 bytes size from each frame
+    async fn read_frames(messages: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+        let messages: Vec<Result<WsMessage, tungstenite::Error>> = messages
+            .into_iter()
+            .map(tungstenite::Message::Binary)
+            .map(WsMessage)
+            .map(Ok)
+            .collect();
+        let ws_stream = WebSocketStream {
+            inner: stream::iter(messages),
+            current_binary: None,
+        };
+        let codec = LengthDelimitedCodec::builder()
+            .big_endian()
+            .length_field_length(4)
+            .length_adjustment(-4)
+            .new_codec();
+        FramedRead::new(ws_stream, codec)
+            .map(|frame| frame.unwrap().to_vec())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_amqp_frame_split_across_binary_messages() {
+        let frame = amqp_frame(b"hello AMQP over WebSocket");
+        let (first, second) = frame.split_at(6);
+
+        let frames = read_frames(vec![first.to_vec(), second.to_vec()]).await;
+        assert_eq!(frames, vec![frame[4..].to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn test_multiple_amqp_frames_in_one_binary_message() {
+        let frame1 = amqp_frame(b"first");
+        let frame2 = amqp_frame(b"second");
+        let frame3 = amqp_frame(b"third");
+        let mut message = frame1.clone();
+        message.extend_from_slice(&frame2);
+        message.extend_from_slice(&frame3[..3]);
+
+        let frames = read_frames(vec![message, Vec::new(), frame3[3..].to_vec()]).await;
+        assert_eq!(
+            frames,
+            vec![
+                frame1[4..].to_vec(),
+                frame2[4..].to_vec(),
+                frame3[4..].to_vec()
+            ]
+        );
+    }
+}
@@ -112,6 +112,39 @@ impl WebSocketStream<TokioWebSocketStream<MaybeTlsStream<TcpStream>>> {
         }
     }
 
+    /// Calls [`tokio_tungstenite::connect_async`] internally with a custom handshake request
+    ///
+    /// This allows additional HTTP headers (eg. `Authorization` or `Cookie`) to be sent with the
+    /// handshake. The `"Sec-WebSocket-Protocol"` HTTP header of the request is always set to
+    /// `"amqp"`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use fe2o3_amqp_ws::WebSocketStream;
+    /// use tungstenite::{client::IntoClientRequest, http::HeaderValue};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut request = "ws://localhost:5673".into_client_request()?;
+    /// request
+    ///     .headers_mut()
+    ///     .insert("Authorization", HeaderValue::from_static("Bearer token"));
+    /// let ws_stream = WebSocketStream::connect_with_request(request).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_with_request(req: impl IntoClientRequest) -> Result<Self, Error> {
+        let request = map_amqp_websocket_request(req)?;
+        let (mut ws_stream, response) = connect_async(request).await?;
+        match verify_response(response) {
+            Ok(response) => Ok(Self::from(TokioWebSocketStream::new(ws_stream, response))),
+            Err(error) => {
+                ws_stream.close(None).await?;
+                Err(error)
+            }
+        }
+    }
+
     /// Calls [`tokio_tungstenite::connect_async_with_config`] internally with
     /// `"Sec-WebSocket-Protocol"` HTTP header of the `req` set to `"amqp"`
     /// 
@@ -163,6 +196,26 @@ where
         }
     }
 
+    /// Calls [`tokio_tungstenite::client_async`] internally with a custom handshake request
+    ///
+    /// This allows additional HTTP headers (eg. `Authorization` or `Cookie`) to be sent with the
+    /// handshake. The `"Sec-WebSocket-Protocol"` HTTP header of the request is always set to
+    /// `"amqp"`.
+    pub async fn connect_with_stream_and_request(
+        req: impl IntoClientRequest,
+        stream: S,
+    ) -> Result<Self, Error> {
+        let request = map_amqp_websocket_request(req)?;
+        let (mut ws_stream, response) = client_async(request, stream).await?;
+        match verify_response(response) {
+            Ok(response) => Ok(Self::from(TokioWebSocketStream::new(ws_stream, response))),
+            Err(error) => {
+                ws_stream.close(None).await?;
+                Err(error)
+            }
+        }
+    }
+
     /// Calls [`tokio_tungstenite::client_async_with_config`] internally with
     /// `"Sec-WebSocket-Protocol"` HTTP header of the `req` set to `"amqp"`
     pub async fn connect_with_stream_and_config(
@@ -321,3 +374,74 @@ fn verify_response(response: Response) -> Result<Response, Error> {
         _ => Err(Error::SecWebSocketProtocolIsNotAmqp),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio_tungstenite::accept_hdr_async;
+    use tungstenite::{
+        client::IntoClientRequest,
+        handshake::server::{Request, Response},
+        http::HeaderValue,
+    };
+
+    use crate::WebSocketStream;
+
+    use super::{TokioWebSocketStream, SEC_WEBSOCKET_PROTOCOL};
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_custom_headers_are_sent_with_amqp_subprotocol() {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+
+        let server = tokio::spawn(async move {
+            let mut received = None;
+            let callback = |request: &Request, mut response: Response| {
+                received = Some(request.headers().clone());
+                response.headers_mut().insert(
+                    SEC_WEBSOCKET_PROTOCOL,
+                    HeaderValue::from_static(crate::SEC_WEBSOCKET_PROTOCOL_AMQP),
+                );
+                Ok(response)
+            };
+            let _ws_stream = accept_hdr_async(server_io, callback).await.unwrap();
+            received.unwrap()
+        });
+
+        let mut request = "ws://localhost:5673".into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert("Authorization", HeaderValue::from_static("Bearer token"));
+        request
+            .headers_mut()
+            .insert("Cookie", HeaderValue::from_static("session=abc"));
+        let ws_stream: WebSocketStream<TokioWebSocketStream<_>> =
+            WebSocketStream::connect_with_stream_and_request(request, client_io)
+                .await
+                .unwrap();
+        assert_eq!(
+            ws_stream.response().headers().get(SEC_WEBSOCKET_PROTOCOL),
+            Some(&HeaderValue::from_static("amqp"))
+        );
+
+        let headers = server.await.unwrap();
+        assert_eq!(headers.get("Authorization").unwrap(), "Bearer token");
+        assert_eq!(headers.get("Cookie").unwrap(), "session=abc");
+        assert_eq!(headers.get(SEC_WEBSOCKET_PROTOCOL).unwrap(), "amqp");
+    }
+
+    #[tokio::test]
+    async fn test_custom_subprotocol_header_is_replaced_with_amqp() {
+        let mut request = "ws://localhost:5673".into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static("mqtt"));
+
+        let request = super::map_amqp_websocket_request(request).unwrap();
+        let protocols: Vec<_> = request
+            .headers()
+            .get_all(SEC_WEBSOCKET_PROTOCOL)
+            .iter()
+            .collect();
+        assert_eq!(protocols, vec![HeaderValue::from_static("amqp")]);
+    }
+}
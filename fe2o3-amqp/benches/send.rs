@@ -0,0 +1,60 @@
+//! Compares `Sender::send` against `Sender::send_settled` over an in-memory duplex stream
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fe2o3_amqp::{
+    acceptor::{ConnectionAcceptor, LinkAcceptor, LinkEndpoint, SessionAcceptor},
+    connection::ConnectionHandle,
+    session::SessionHandle,
+    types::primitives::Value,
+    Connection, Sender, Session,
+};
+use tokio::runtime::Runtime;
+
+/// Spawns a peer that accepts every message sent to it
+async fn connect_to_peer() -> (ConnectionHandle<()>, SessionHandle<()>, Sender) {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        let mut connection = ConnectionAcceptor::new("bench-peer")
+            .accept(peer_io)
+            .await
+            .unwrap();
+        let mut session = SessionAcceptor::new()
+            .accept(&mut connection)
+            .await
+            .unwrap();
+        if let Ok(LinkEndpoint::Receiver(mut receiver)) =
+            LinkAcceptor::new().accept(&mut session).await
+        {
+            while let Ok(delivery) = receiver.recv::<Value>().await {
+                let _ = receiver.accept(&delivery).await;
+            }
+        }
+    });
+
+    let mut connection = Connection::builder()
+        .container_id("bench-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let sender = Sender::attach(&mut session, "bench-sender", "q1")
+        .await
+        .unwrap();
+    (connection, session, sender)
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let (_connection, _session, mut sender) = rt.block_on(connect_to_peer());
+
+    c.bench_function("send", |b| {
+        b.iter(|| rt.block_on(sender.send("hello")).unwrap())
+    });
+
+    c.bench_function("send_settled", |b| {
+        b.iter(|| rt.block_on(sender.send_settled("hello")).unwrap())
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
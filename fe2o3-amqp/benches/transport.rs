@@ -0,0 +1,54 @@
+//! Measures writing frames through `Transport`, which reuses its encode buffer across frames
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fe2o3_amqp::{
+    frames::amqp::{Frame, FrameBody},
+    transport::Transport,
+    types::performatives::Open,
+};
+use futures_util::SinkExt;
+use tokio::{io::AsyncReadExt, runtime::Runtime};
+
+fn open_frame() -> Frame {
+    let open = Open {
+        container_id: "bench-client".into(),
+        hostname: Some("127.0.0.1".into()),
+        max_frame_size: 65536.into(),
+        channel_max: 255.into(),
+        idle_time_out: None,
+        outgoing_locales: None,
+        incoming_locales: None,
+        offered_capabilities: None,
+        desired_capabilities: None,
+        properties: None,
+    };
+    Frame::new(0u16, FrameBody::Open(open))
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut transport = rt.block_on(async {
+        let (client_io, mut peer_io) = tokio::io::duplex(64 * 1024);
+        // Drains whatever is written so that the transport never waits on the peer
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 64 * 1024];
+            while let Ok(n) = peer_io.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+            }
+        });
+        Transport::<_, Frame>::bind(client_io, 65536, None)
+    });
+
+    c.bench_function("transport_send_empty_frame", |b| {
+        b.iter(|| rt.block_on(transport.send(Frame::empty())).unwrap())
+    });
+
+    c.bench_function("transport_send_open_frame", |b| {
+        b.iter(|| rt.block_on(transport.send(open_frame())).unwrap())
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,6 +1,6 @@
 //! Builder for acceptors
 
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use fe2o3_amqp_types::{
     definitions::{
@@ -14,19 +14,20 @@ use fe2o3_amqp_types::{
 
 use crate::{
     connection::{DEFAULT_CHANNEL_MAX, DEFAULT_MAX_FRAME_SIZE, DEFAULT_OUTGOING_BUFFER_SIZE},
+    sasl_profile::{ANONYMOUS, PLAIN},
     util::{Initialized, Uninitialized},
 };
 
 use super::{
     link::LinkAcceptor, local_receiver_link::LocalReceiverLinkAcceptor,
     local_sender_link::LocalSenderLinkAcceptor, session::SessionAcceptor, ConnectionAcceptor,
-    SaslAcceptor, SupportedReceiverSettleModes, SupportedSenderSettleModes,
+    SaslAcceptor, SaslCallbackAcceptor, SupportedReceiverSettleModes, SupportedSenderSettleModes,
 };
 
 cfg_transaction! {
     use fe2o3_amqp_types::transaction::TxnCapability;
     
-    use crate::transaction::coordinator::ControlLinkAcceptor;
+    use crate::transaction::coordinator::{ControlLinkAcceptor, TxnIdAllocator, UuidTxnIdAllocator};
 }
 
 /// A generic builder for listener connection, session and link acceptors
@@ -246,6 +247,52 @@ impl<M, Tls, Sasl> Builder<ConnectionAcceptor<Tls, Sasl>, M> {
     }
 }
 
+// =============================================================================
+// SaslCallbackAcceptor builder
+// =============================================================================
+
+impl Default for Builder<SaslCallbackAcceptor, Initialized> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Builder<SaslCallbackAcceptor, Initialized> {
+    /// Creates a new builder for [`SaslCallbackAcceptor`] with no mechanism
+    pub fn new() -> Self {
+        Self {
+            inner: SaslCallbackAcceptor::default(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Offers the PLAIN mechanism and validates the username and password with `validate`
+    pub fn plain<F>(mut self, validate: F) -> Self
+    where
+        F: Fn(&str, &str) -> bool + Send + Sync + 'static,
+    {
+        self.offer(PLAIN);
+        self.inner.plain = Some(Arc::new(validate));
+        self
+    }
+
+    /// Offers the ANONYMOUS mechanism and accepts the client if `allow` returns `true`
+    pub fn anonymous<F>(mut self, allow: F) -> Self
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        self.offer(ANONYMOUS);
+        self.inner.anonymous = Some(Arc::new(allow));
+        self
+    }
+
+    fn offer(&mut self, mechanism: &str) {
+        if !self.inner.mechanisms.iter().any(|m| m.as_str() == mechanism) {
+            self.inner.mechanisms.push(Symbol::from(mechanism));
+        }
+    }
+}
+
 // =============================================================================
 // SessionAcceptor builder
 // =============================================================================
@@ -562,7 +609,12 @@ cfg_transaction! {
         pub fn new() -> Self {
             let shared = Default::default();
             let inner = Default::default();
-            let inner = ControlLinkAcceptor { shared, inner };
+            let inner = ControlLinkAcceptor {
+                shared,
+                inner,
+                accept_global_id: true,
+                txn_id_allocator: Arc::new(UuidTxnIdAllocator),
+            };
     
             Self {
                 inner,
@@ -646,5 +698,23 @@ cfg_transaction! {
             self.inner.inner.target_capabilities = target_capabilities.into();
             self
         }
+
+        /// Whether the coordinator accepts a `Declare` that carries a global transaction ID
+        ///
+        /// This is enabled by default. If disabled, a `Declare` with a global transaction ID
+        /// will be rejected.
+        pub fn accept_global_id(mut self, accept: bool) -> Self {
+            self.inner.accept_global_id = accept;
+            self
+        }
+
+        /// Set the [`TxnIdAllocator`] used to allocate the transaction ID of newly declared
+        /// transactions
+        ///
+        /// The default is [`UuidTxnIdAllocator`]
+        pub fn txn_id_allocator(mut self, allocator: impl TxnIdAllocator + 'static) -> Self {
+            self.inner.txn_id_allocator = Arc::new(allocator);
+            self
+        }
     }
 }
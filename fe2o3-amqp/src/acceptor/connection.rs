@@ -12,6 +12,7 @@ use fe2o3_amqp_types::{
 use futures_util::{Sink, SinkExt, StreamExt};
 use tokio::{
     io::{AsyncRead, AsyncWrite, ReadHalf, WriteHalf},
+    net::TcpListener,
     sync::mpsc::{self, Receiver},
 };
 use tokio_util::codec::{FramedRead, FramedWrite};
@@ -33,6 +34,7 @@ use crate::{
 
 use super::{
     builder::Builder,
+    incoming::IncomingConnections,
     sasl_acceptor::{SaslAcceptor, SaslAcceptorExt},
     IncomingSession,
 };
@@ -206,6 +208,10 @@ impl<Tls, Sasl> ConnectionAcceptor<Tls, Sasl> {
 
         let engine =
             ConnectionEngine::open(transport, listener_connection, control_rx, outgoing_rx).await?;
+        let transport_metrics = engine.transport_metrics();
+        let local_open = engine.local_open().clone();
+        let remote_open = engine.remote_open().cloned();
+        let events = engine.subscribe_events();
         let (handle, outcome) = engine.spawn();
 
         let connection_handle = ConnectionHandle {
@@ -215,6 +221,11 @@ impl<Tls, Sasl> ConnectionAcceptor<Tls, Sasl> {
             outcome,
             outgoing: outgoing_tx,
             session_listener: begin_rx,
+            transport_metrics,
+            local_open,
+            remote_open,
+            events: events.resubscribe(),
+            pending_events: Some(events),
         };
         Ok(connection_handle)
     }
@@ -290,13 +301,25 @@ where
                     transport.send(frame).await?;
                 }
                 SaslServerFrame::Outcome(outcome) => {
+                    let code = outcome.code.clone();
+                    let additional_data = outcome.additional_data.clone();
                     let frame = sasl::Frame::Outcome(outcome);
                     #[cfg(feature = "tracing")]
                     tracing::trace!(sending = ?frame);
                     #[cfg(feature = "log")]
                     log::trace!("sending = {:?}", frame);
                     transport.send(frame).await?;
-                    break;
+
+                    // The client is expected to close the socket if the authentication failed
+                    match code {
+                        SaslCode::Ok => break,
+                        _ => {
+                            return Err(OpenError::SaslError {
+                                code,
+                                additional_data,
+                            })
+                        }
+                    }
                 }
             }
         }
@@ -393,6 +416,16 @@ impl ConnectionAcceptor<(), ()> {
     {
         self.negotiate_amqp_with_stream(stream).await
     }
+
+    /// Accepts incoming connections from the TCP listener
+    ///
+    /// Each incoming socket is accepted with [`accept`](Self::accept) in its own task. See
+    /// [`IncomingConnections`] for more details.
+    pub fn incoming(self, listener: TcpListener) -> IncomingConnections {
+        IncomingConnections::spawn(listener, self, |acceptor, stream| async move {
+            acceptor.accept(stream).await
+        })
+    }
 }
 
 impl<Sasl> ConnectionAcceptor<(), Sasl>
@@ -406,6 +439,19 @@ where
     {
         self.negotiate_sasl_with_stream(stream).await
     }
+
+    /// Accepts incoming connections from the TCP listener
+    ///
+    /// Each incoming socket is accepted with [`accept`](Self::accept) in its own task. See
+    /// [`IncomingConnections`] for more details.
+    pub fn incoming(self, listener: TcpListener) -> IncomingConnections
+    where
+        Sasl: Send + Sync + 'static,
+    {
+        IncomingConnections::spawn(listener, self, |acceptor, stream| async move {
+            acceptor.accept(stream).await
+        })
+    }
 }
 
 cfg_native_tls! {
@@ -417,6 +463,16 @@ cfg_native_tls! {
         {
             self.negotiate_tls_with_native_tls(stream).await
         }
+
+        /// Accepts incoming connections from the TCP listener
+        ///
+        /// Each incoming socket is accepted with [`accept`](Self::accept) in its own task. See
+        /// [`IncomingConnections`] for more details.
+        pub fn incoming(self, listener: TcpListener) -> IncomingConnections {
+            IncomingConnections::spawn(listener, self, |acceptor, stream| async move {
+                acceptor.accept(stream).await
+            })
+        }
     }
     
     impl<Sasl> ConnectionAcceptor<tokio_native_tls::TlsAcceptor, Sasl>
@@ -430,6 +486,19 @@ cfg_native_tls! {
         {
             self.negotiate_tls_with_native_tls(stream).await
         }
+
+        /// Accepts incoming connections from the TCP listener
+        ///
+        /// Each incoming socket is accepted with [`accept`](Self::accept) in its own task. See
+        /// [`IncomingConnections`] for more details.
+        pub fn incoming(self, listener: TcpListener) -> IncomingConnections
+        where
+            Sasl: Send + Sync + 'static,
+        {
+            IncomingConnections::spawn(listener, self, |acceptor, stream| async move {
+                acceptor.accept(stream).await
+            })
+        }
     }
 }
 
@@ -442,6 +511,16 @@ cfg_rustls! {
         {
             self.negotiate_tls_with_rustls(stream).await
         }
+
+        /// Accepts incoming connections from the TCP listener
+        ///
+        /// Each incoming socket is accepted with [`accept`](Self::accept) in its own task. See
+        /// [`IncomingConnections`] for more details.
+        pub fn incoming(self, listener: TcpListener) -> IncomingConnections {
+            IncomingConnections::spawn(listener, self, |acceptor, stream| async move {
+                acceptor.accept(stream).await
+            })
+        }
     }
     
     impl<Sasl> ConnectionAcceptor<tokio_rustls::TlsAcceptor, Sasl>
@@ -455,6 +534,19 @@ cfg_rustls! {
         {
             self.negotiate_tls_with_rustls(stream).await
         }
+
+        /// Accepts incoming connections from the TCP listener
+        ///
+        /// Each incoming socket is accepted with [`accept`](Self::accept) in its own task. See
+        /// [`IncomingConnections`] for more details.
+        pub fn incoming(self, listener: TcpListener) -> IncomingConnections
+        where
+            Sasl: Send + Sync + 'static,
+        {
+            IncomingConnections::spawn(listener, self, |acceptor, stream| async move {
+                acceptor.accept(stream).await
+            })
+        }
     }
 }
 
@@ -488,6 +580,11 @@ impl endpoint::Connection for ListenerConnection {
         self.connection.local_open()
     }
 
+    #[inline]
+    fn remote_open(&self) -> Option<&fe2o3_amqp_types::performatives::Open> {
+        self.connection.remote_open()
+    }
+
     #[inline]
     fn allocate_session(
         &mut self,
@@ -501,6 +598,11 @@ impl endpoint::Connection for ListenerConnection {
         self.connection.deallocate_session(outgoing_channel)
     }
 
+    #[inline]
+    fn has_allocated_sessions(&self) -> bool {
+        self.connection.has_allocated_sessions()
+    }
+
     #[inline]
     fn on_incoming_open(
         &mut self,
@@ -0,0 +1,143 @@
+//! Incoming connections accepted from a TCP listener
+
+use std::{
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::Stream;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    task::JoinHandle,
+    time::{sleep, timeout},
+};
+
+use crate::connection::OpenError;
+
+use super::ListenerConnectionHandle;
+
+/// Buffer size of the channel that holds accepted connections that are not yet taken by the user
+const DEFAULT_INCOMING_BUF: usize = 16;
+
+/// Time given to each incoming socket to complete the handshake before it is dropped
+const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Delay before accepting again after the listener failed to accept a socket
+///
+/// Errors like running out of file descriptors are returned immediately by the listener and
+/// would otherwise turn the accept loop into a busy loop
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// A stream of connections accepted from a [`TcpListener`]
+///
+/// This is created by `ConnectionAcceptor::incoming`. Each incoming socket goes through the
+/// protocol header exchange (as well as the TLS and SASL negotiation if the acceptor is
+/// configured with them) and the Open handshake in its own task, so a slow or misbehaving peer
+/// does not hold back other incoming connections. The handshakes that fail are yielded as an
+/// `Err` and the stream continues with the next incoming socket. A handshake that does not
+/// complete within 60 seconds is dropped and yielded as [`OpenError::OpenTimeout`].
+///
+/// If the listener fails to accept a socket, the error is yielded and the listener waits for a
+/// short while before accepting again.
+///
+/// The listener stops accepting new sockets once this is dropped.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use tokio::net::TcpListener;
+/// use fe2o3_amqp::acceptor::ConnectionAcceptor;
+///
+/// let tcp_listener = TcpListener::bind("localhost:5672").await.unwrap();
+/// let mut incoming = ConnectionAcceptor::new("example-listener").incoming(tcp_listener);
+///
+/// while let Some(result) = incoming.next_connection().await {
+///     match result {
+///         Ok(connection) => {
+///             tokio::spawn(handle_connection(connection));
+///         }
+///         Err(error) => eprintln!("Failed to accept connection: {:?}", error),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct IncomingConnections {
+    local_addr: Option<SocketAddr>,
+    connections: mpsc::Receiver<Result<ListenerConnectionHandle, OpenError>>,
+    accept_loop: JoinHandle<()>,
+}
+
+impl IncomingConnections {
+    pub(crate) fn spawn<A, F, Fut>(listener: TcpListener, acceptor: A, accept: F) -> Self
+    where
+        A: Send + Sync + 'static,
+        F: Fn(Arc<A>, TcpStream) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<ListenerConnectionHandle, OpenError>> + Send + 'static,
+    {
+        let local_addr = listener.local_addr().ok();
+        let (tx, connections) = mpsc::channel(DEFAULT_INCOMING_BUF);
+        let acceptor = Arc::new(acceptor);
+        let accept_loop = tokio::spawn(async move {
+            loop {
+                let result = match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let handshake = accept(acceptor.clone(), stream);
+                        let tx = tx.clone();
+                        tokio::spawn(async move {
+                            let result = timeout(DEFAULT_HANDSHAKE_TIMEOUT, handshake)
+                                .await
+                                .unwrap_or(Err(OpenError::OpenTimeout));
+                            // The connection is dropped and thus closed if the user is no longer
+                            // taking incoming connections
+                            let _ = tx.send(result).await;
+                        });
+                        continue;
+                    }
+                    Err(error) => Err(OpenError::Io(error)),
+                };
+                if tx.send(result).await.is_err() {
+                    break;
+                }
+                sleep(ACCEPT_ERROR_BACKOFF).await;
+            }
+        });
+
+        Self {
+            local_addr,
+            connections,
+            accept_loop,
+        }
+    }
+
+    /// The local address the underlying TCP listener is bound to
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// Waits for the next incoming connection that has completed the handshake
+    ///
+    /// An `Err` is returned if accepting the socket or the handshake failed. This never returns
+    /// `None` unless the background task accepting the sockets has stopped.
+    pub async fn next_connection(&mut self) -> Option<Result<ListenerConnectionHandle, OpenError>> {
+        self.connections.recv().await
+    }
+}
+
+impl Stream for IncomingConnections {
+    type Item = Result<ListenerConnectionHandle, OpenError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.connections.poll_recv(cx)
+    }
+}
+
+impl Drop for IncomingConnections {
+    fn drop(&mut self) {
+        self.accept_loop.abort();
+    }
+}
@@ -20,7 +20,8 @@ use crate::{
         receiver::{CreditMode, ReceiverInner},
         state::{LinkFlowState, LinkFlowStateInner, LinkState},
         target_archetype::TargetArchetypeExt,
-        LinkFrame, LinkIncomingItem, LinkRelay, ReceiverAttachError, ReceiverLink,
+        LinkFrame, LinkIncomingItem, LinkRelay, ReceiverAttachError, ReceiverLink, SettledNotifier,
+        SettledNotify,
     },
     session::SessionHandle,
     Receiver,
@@ -156,12 +157,14 @@ where
 
         // Comparing unsettled should be taken care of in `on_incoming_attach`
         let unsettled = Arc::new(RwLock::new(None));
+        let settled = Arc::new(SettledNotify::default());
         let link_handle = LinkRelay::Receiver {
             tx: incoming_tx,
             output_handle: (),
             flow_state: flow_state_producer,
             unsettled: unsettled.clone(),
             receiver_settle_mode: rcv_settle_mode.clone(),
+            settled: SettledNotifier(settled.clone()),
             more: false,
         };
 
@@ -251,6 +254,9 @@ where
             credit_mode: self.credit_mode.clone(),
             processed: AtomicU32::new(0),
             auto_accept: self.auto_accept,
+            drop_expired: false,
+            drained: false,
+            settled,
             session: control.clone(),
             outgoing,
             incoming: incoming_rx,
@@ -194,6 +194,7 @@ where
         let inner = SenderInner {
             link,
             buffer_size: shared.buffer_size,
+            settlement_timeout: None,
             session: session.control.clone(),
             outgoing,
             incoming: incoming_rx,
@@ -3,6 +3,7 @@
 pub mod builder;
 pub mod connection;
 pub mod error;
+pub mod incoming;
 pub mod link;
 pub mod local_receiver_link;
 pub mod local_sender_link;
@@ -13,14 +14,21 @@ cfg_scram! {
     pub mod scram;
 }
 
+cfg_test_server! {
+    pub mod test_server;
+}
+
 use fe2o3_amqp_types::{
     definitions::{ReceiverSettleMode, SenderSettleMode},
     performatives::Begin,
 };
 
 pub use self::connection::{ConnectionAcceptor, ListenerConnectionHandle};
+pub use self::incoming::IncomingConnections;
 pub use self::link::{LinkAcceptor, LinkEndpoint};
-pub use self::sasl_acceptor::{SaslAcceptor, SaslAnonymousMechanism, SaslPlainMechanism};
+pub use self::sasl_acceptor::{
+    SaslAcceptor, SaslAnonymousMechanism, SaslCallbackAcceptor, SaslPlainMechanism,
+};
 pub use self::session::{ListenerSessionHandle, SessionAcceptor};
 
 /// A half established session that is initiated by the remote peer
@@ -1,13 +1,18 @@
 //! Supported SASL mechanisms
 
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 use fe2o3_amqp_types::{
     primitives::{Array, Symbol},
     sasl::{SaslChallenge, SaslCode, SaslInit, SaslMechanisms, SaslOutcome, SaslResponse},
 };
 
-use crate::sasl_profile::{ANONYMOUS, PLAIN};
+use crate::{
+    sasl_profile::{ANONYMOUS, PLAIN},
+    util::Initialized,
+};
+
+use super::builder::Builder;
 
 /// SASL frames sent by server, excluding the initial mechanism frame
 #[derive(Debug)]
@@ -83,11 +88,7 @@ impl SaslPlainMechanism {
 impl SaslPlainMechanism {
     fn validate_init(&self, init: SaslInit) -> Option<SaslCode> {
         let response = init.initial_response?.into_vec();
-
-        let mut split = response.split(|b| *b == 0u8);
-        let _authzid = split.next()?;
-        let authcid = split.next()?;
-        let passwd = split.next()?;
+        let (authcid, passwd) = split_plain_initial_response(&response)?;
         Some(self.validate_credential(authcid, passwd))
     }
 
@@ -100,6 +101,16 @@ impl SaslPlainMechanism {
     }
 }
 
+/// Splits the initial response of the PLAIN mechanism `[authzid] NUL authcid NUL passwd` into
+/// `authcid` and `passwd`
+fn split_plain_initial_response(response: &[u8]) -> Option<(&[u8], &[u8])> {
+    let mut split = response.split(|b| *b == 0u8);
+    let _authzid = split.next()?;
+    let authcid = split.next()?;
+    let passwd = split.next()?;
+    Some((authcid, passwd))
+}
+
 impl SaslAcceptor for SaslPlainMechanism {
     fn mechanisms(&self) -> Array<Symbol> {
         Array::from(vec![Symbol::from(PLAIN)])
@@ -164,3 +175,166 @@ impl SaslAcceptor for SaslAnonymousMechanism {
         SaslServerFrame::Outcome(outcome)
     }
 }
+
+type PlainCallback = Arc<dyn Fn(&str, &str) -> bool + Send + Sync>;
+type AnonymousCallback = Arc<dyn Fn() -> bool + Send + Sync>;
+
+/// A SASL acceptor that offers the configured mechanisms and validates the credentials with
+/// user supplied callbacks
+///
+/// The mechanisms are offered in the order they are added to the builder. A client that picks a
+/// mechanism that is not offered is rejected with [`SaslCode::Auth`].
+///
+/// # Example
+///
+/// ```rust
+/// use fe2o3_amqp::acceptor::{ConnectionAcceptor, SaslCallbackAcceptor};
+///
+/// let sasl_acceptor = SaslCallbackAcceptor::builder()
+///     .plain(|username, password| username == "guest" && password == "guest")
+///     .anonymous(|| false)
+///     .build();
+/// let connection_acceptor = ConnectionAcceptor::builder()
+///     .container_id("example-listener")
+///     .sasl_acceptor(sasl_acceptor)
+///     .build();
+/// ```
+#[derive(Clone, Default)]
+pub struct SaslCallbackAcceptor {
+    pub(crate) mechanisms: Vec<Symbol>,
+    pub(crate) plain: Option<PlainCallback>,
+    pub(crate) anonymous: Option<AnonymousCallback>,
+}
+
+impl fmt::Debug for SaslCallbackAcceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SaslCallbackAcceptor")
+            .field("mechanisms", &self.mechanisms)
+            .finish()
+    }
+}
+
+impl SaslCallbackAcceptor {
+    /// Creates a builder for [`SaslCallbackAcceptor`] with no mechanism
+    pub fn builder() -> Builder<Self, Initialized> {
+        Builder::<Self, Initialized>::new()
+    }
+
+    fn validate_init(&self, init: SaslInit) -> SaslCode {
+        match init.mechanism.as_str() {
+            PLAIN => {
+                let validate = match &self.plain {
+                    Some(validate) => validate,
+                    None => return SaslCode::Auth,
+                };
+                let response = match init.initial_response {
+                    Some(response) => response.into_vec(),
+                    None => return SaslCode::Auth,
+                };
+                let credential =
+                    split_plain_initial_response(&response).and_then(|(authcid, passwd)| {
+                        let username = std::str::from_utf8(authcid).ok()?;
+                        let password = std::str::from_utf8(passwd).ok()?;
+                        Some((username, password))
+                    });
+                match credential {
+                    Some((username, password)) if validate(username, password) => SaslCode::Ok,
+                    _ => SaslCode::Auth,
+                }
+            }
+            ANONYMOUS => match &self.anonymous {
+                Some(allow) if allow() => SaslCode::Ok,
+                _ => SaslCode::Auth,
+            },
+            _ => SaslCode::Auth,
+        }
+    }
+}
+
+impl SaslAcceptor for SaslCallbackAcceptor {
+    fn mechanisms(&self) -> Array<Symbol> {
+        Array::from(self.mechanisms.clone())
+    }
+
+    fn on_init(&mut self, init: SaslInit) -> SaslServerFrame {
+        let outcome = SaslOutcome {
+            code: self.validate_init(init),
+            additional_data: None,
+        };
+        SaslServerFrame::Outcome(outcome)
+    }
+
+    fn on_response(&mut self, _response: SaslResponse) -> SaslServerFrame {
+        // Neither PLAIN nor ANONYMOUS sends a challenge
+        let outcome = SaslOutcome {
+            code: SaslCode::Sys,
+            additional_data: None,
+        };
+        SaslServerFrame::Outcome(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        primitives::{Binary, Symbol},
+        sasl::{SaslCode, SaslInit},
+    };
+
+    use super::{SaslAcceptor, SaslCallbackAcceptor, SaslServerFrame};
+
+    fn outcome_code(frame: SaslServerFrame) -> SaslCode {
+        match frame {
+            SaslServerFrame::Outcome(outcome) => outcome.code,
+            SaslServerFrame::Challenge(_) => panic!("Expecting outcome"),
+        }
+    }
+
+    fn init(mechanism: &str, initial_response: Option<&[u8]>) -> SaslInit {
+        SaslInit {
+            mechanism: Symbol::from(mechanism),
+            initial_response: initial_response.map(|r| Binary::from(r.to_vec())),
+            hostname: None,
+        }
+    }
+
+    #[test]
+    fn test_mechanisms_are_offered_in_order_added() {
+        let acceptor = SaslCallbackAcceptor::builder()
+            .anonymous(|| true)
+            .plain(|_, _| true)
+            .plain(|_, _| false)
+            .build();
+        assert_eq!(
+            acceptor.mechanisms().0,
+            vec![Symbol::from("ANONYMOUS"), Symbol::from("PLAIN")]
+        );
+    }
+
+    #[test]
+    fn test_plain_credential_is_passed_to_callback() {
+        let mut acceptor = SaslCallbackAcceptor::builder()
+            .plain(|username, password| username == "user" && password == "pass")
+            .build();
+
+        let frame = acceptor.on_init(init("PLAIN", Some(b"\0user\0pass")));
+        assert_eq!(outcome_code(frame), SaslCode::Ok);
+
+        let frame = acceptor.on_init(init("PLAIN", Some(b"\0user\0wrong")));
+        assert_eq!(outcome_code(frame), SaslCode::Auth);
+
+        let frame = acceptor.on_init(init("PLAIN", None));
+        assert_eq!(outcome_code(frame), SaslCode::Auth);
+    }
+
+    #[test]
+    fn test_mechanism_not_offered_is_rejected() {
+        let mut acceptor = SaslCallbackAcceptor::builder().plain(|_, _| true).build();
+
+        let frame = acceptor.on_init(init("ANONYMOUS", None));
+        assert_eq!(outcome_code(frame), SaslCode::Auth);
+
+        let frame = acceptor.on_init(init("SCRAM-SHA-256", Some(b"n,,n=user,r=nonce")));
+        assert_eq!(outcome_code(frame), SaslCode::Auth);
+    }
+}
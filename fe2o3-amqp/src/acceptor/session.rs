@@ -285,6 +285,7 @@ impl SessionAcceptor {
             control: session_control_tx,
             engine_handle,
             outcome,
+            remnant: None,
             outgoing: outgoing_tx,
             link_listener: link_listener_rx,
         };
@@ -500,6 +501,10 @@ impl endpoint::Session for ListenerSession {
         self.session.on_outgoing_attach(attach)
     }
 
+    fn replenish_incoming_window(&mut self) -> Option<SessionFrame> {
+        self.session.replenish_incoming_window()
+    }
+
     fn on_outgoing_flow(&mut self, flow: LinkFlow) -> Result<SessionFrame, Self::Error> {
         self.session.on_outgoing_flow(flow)
     }
@@ -521,7 +526,7 @@ impl endpoint::Session for ListenerSession {
         self.session.on_outgoing_disposition(disposition)
     }
 
-    fn on_outgoing_detach(&mut self, detach: Detach) -> SessionFrame {
+    fn on_outgoing_detach(&mut self, detach: Detach) -> Option<SessionFrame> {
         self.session.on_outgoing_detach(detach)
     }
 }
@@ -531,6 +536,7 @@ cfg_transaction! {
         // This should be unreachable, but an error is probably a better way
         fn allocate_transaction_id(
             &mut self,
+            _global_id: Option<fe2o3_amqp_types::transaction::TransactionId>,
         ) -> Result<fe2o3_amqp_types::transaction::TransactionId, AllocTxnIdError> {
             Err(AllocTxnIdError::NotImplemented)
         }
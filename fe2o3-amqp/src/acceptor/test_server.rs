@@ -0,0 +1,347 @@
+//! A minimal scripted AMQP 1.0 server for testing client code without a broker
+//!
+//! The [`TestServer`] accepts a single connection with a single session. It replies to the
+//! Open, Begin and Attach performatives automatically and then runs a script of [`Step`]s
+//! against the links attached by the client.
+//!
+//! - A [`Step::Transfer`] sends a message over the link attached by a client [`Receiver`] and
+//!   checks the outcome the client settles it with.
+//! - A [`Step::Receive`] waits for a message over the link attached by a client [`Sender`] and
+//!   settles it with the scripted outcome.
+//!
+//! Once the script is exhausted, the server keeps accepting links and auto-accepts incoming
+//! messages until the client closes the connection.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use fe2o3_amqp::acceptor::test_server::TestServer;
+//! use fe2o3_amqp::types::{
+//!     messaging::{Accepted, Body, Outcome},
+//!     primitives::Value,
+//! };
+//! use fe2o3_amqp::{Connection, Receiver, Session};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let server = TestServer::builder()
+//!         .transfer(Body::from(Value::from("hello")), Outcome::Accepted(Accepted {}))
+//!         .bind("127.0.0.1:0")
+//!         .await
+//!         .unwrap();
+//!
+//!     let mut connection = Connection::open("test-client", &server.url()[..])
+//!         .await
+//!         .unwrap();
+//!     let mut session = Session::begin(&mut connection).await.unwrap();
+//!     let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+//!         .await
+//!         .unwrap();
+//!
+//!     // The code under test
+//!     let delivery = receiver.recv::<Value>().await.unwrap();
+//!     receiver.accept(&delivery).await.unwrap();
+//!
+//!     receiver.close().await.unwrap();
+//!     session.end().await.unwrap();
+//!     connection.close().await.unwrap();
+//!
+//!     server.finish().await.unwrap();
+//! }
+//! ```
+
+use std::net::SocketAddr;
+
+use fe2o3_amqp_types::{
+    messaging::{Body, Message, Outcome, Source, Target},
+    primitives::Value,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, ToSocketAddrs},
+    task::{JoinError, JoinHandle},
+};
+
+use crate::{
+    connection::OpenError,
+    link::{receiver::TerminalDeliveryState, DispositionError, RecvError, SendError},
+    session::BeginError,
+    Receiver, Sender,
+};
+
+use super::{
+    error::AcceptorAttachError, link::LinkEndpoint, ConnectionAcceptor, LinkAcceptor,
+    ListenerSessionHandle, SessionAcceptor,
+};
+
+/// Default container id of the test server
+pub const DEFAULT_TEST_SERVER_CONTAINER_ID: &str = "fe2o3-amqp-test-server";
+
+/// A step in the script executed by the [`TestServer`]
+#[derive(Debug)]
+pub enum Step {
+    /// Send a message over the link attached by a client receiver and expect the client to
+    /// settle it with the same kind of outcome. Only the variant of the outcome is compared.
+    Transfer {
+        /// The message to send
+        message: Box<Message<Body<Value>>>,
+
+        /// The expected outcome
+        expected: Outcome,
+    },
+
+    /// Wait for a message over the link attached by a client sender and settle it with the
+    /// given state
+    Receive {
+        /// The state the message is settled with
+        state: TerminalDeliveryState,
+    },
+}
+
+/// Errors with running the script of a [`TestServer`]
+#[derive(Debug, thiserror::Error)]
+pub enum TestServerError {
+    /// IO error
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// Error accepting the connection
+    #[error(transparent)]
+    Open(#[from] OpenError),
+
+    /// Error accepting the session
+    #[error(transparent)]
+    Begin(#[from] BeginError),
+
+    /// Error accepting a link
+    #[error(transparent)]
+    Attach(#[from] AcceptorAttachError),
+
+    /// Error sending a scripted message
+    #[error(transparent)]
+    Send(#[from] SendError),
+
+    /// Error receiving a message from the client
+    #[error(transparent)]
+    Recv(#[from] RecvError),
+
+    /// Error settling a message from the client
+    #[error(transparent)]
+    Disposition(#[from] DispositionError),
+
+    /// The client settled a scripted message with an unexpected outcome
+    #[error("Expecting outcome {expected:?}, found {found:?}")]
+    UnexpectedOutcome {
+        /// The scripted outcome
+        expected: Outcome,
+
+        /// The outcome the client settled the message with
+        found: Outcome,
+    },
+
+    /// The server task has panicked or was cancelled
+    #[error(transparent)]
+    Join(#[from] JoinError),
+}
+
+/// Builder of a [`TestServer`]
+#[derive(Debug)]
+pub struct Builder {
+    /// The container id of the server
+    pub container_id: String,
+
+    /// The script executed by the server
+    pub steps: Vec<Step>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            container_id: DEFAULT_TEST_SERVER_CONTAINER_ID.to_string(),
+            steps: Vec::new(),
+        }
+    }
+}
+
+impl Builder {
+    /// Creates a builder with an empty script
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The container id of the server
+    pub fn container_id(mut self, id: impl Into<String>) -> Self {
+        self.container_id = id.into();
+        self
+    }
+
+    /// Append a step to the script
+    pub fn step(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Append a [`Step::Transfer`] to the script
+    pub fn transfer(self, message: impl Into<Message<Body<Value>>>, expected: Outcome) -> Self {
+        self.step(Step::Transfer {
+            message: Box::new(message.into()),
+            expected,
+        })
+    }
+
+    /// Append a [`Step::Receive`] to the script
+    pub fn receive(self, state: impl Into<TerminalDeliveryState>) -> Self {
+        self.step(Step::Receive {
+            state: state.into(),
+        })
+    }
+
+    /// Binds to the address and runs the script against the first incoming connection
+    pub async fn bind(self, addr: impl ToSocketAddrs) -> Result<TestServer, std::io::Error> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            run(self, stream).await
+        });
+        Ok(TestServer { local_addr, handle })
+    }
+}
+
+/// A minimal AMQP 1.0 server that runs a script against a single client connection
+///
+/// See the [module level documentation](self) for an example
+#[derive(Debug)]
+pub struct TestServer {
+    local_addr: SocketAddr,
+    handle: JoinHandle<Result<Vec<Message<Body<Value>>>, TestServerError>>,
+}
+
+impl TestServer {
+    /// Creates a builder for the [`TestServer`]
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
+    /// The local address the server is bound to
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The url the client can connect to
+    pub fn url(&self) -> String {
+        format!("amqp://{}", self.local_addr)
+    }
+
+    /// Waits for the client to close the connection and returns the messages received by the
+    /// [`Step::Receive`] steps in order
+    pub async fn finish(self) -> Result<Vec<Message<Body<Value>>>, TestServerError> {
+        self.handle.await?
+    }
+}
+
+type DefaultLinkAcceptor = LinkAcceptor<fn(Source) -> Option<Source>, fn(Target) -> Option<Target>>;
+
+/// The links attached by the client that are not yet used by the script
+struct Links {
+    acceptor: DefaultLinkAcceptor,
+    sender: Option<Sender>,
+    receiver: Option<Receiver>,
+    idle: Vec<LinkEndpoint>,
+}
+
+impl Links {
+    async fn sender(
+        &mut self,
+        session: &mut ListenerSessionHandle,
+    ) -> Result<&mut Sender, AcceptorAttachError> {
+        while self.sender.is_none() {
+            match self.acceptor.accept(session).await? {
+                LinkEndpoint::Sender(sender) => self.sender = Some(sender),
+                link => self.idle.push(link),
+            }
+        }
+        Ok(self.sender.as_mut().expect("sender is attached"))
+    }
+
+    async fn receiver(
+        &mut self,
+        session: &mut ListenerSessionHandle,
+    ) -> Result<&mut Receiver, AcceptorAttachError> {
+        while self.receiver.is_none() {
+            match self.acceptor.accept(session).await? {
+                LinkEndpoint::Receiver(receiver) => self.receiver = Some(receiver),
+                link => self.idle.push(link),
+            }
+        }
+        Ok(self.receiver.as_mut().expect("receiver is attached"))
+    }
+}
+
+async fn run<Io>(builder: Builder, stream: Io) -> Result<Vec<Message<Body<Value>>>, TestServerError>
+where
+    Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
+{
+    let mut connection = ConnectionAcceptor::new(builder.container_id)
+        .accept(stream)
+        .await?;
+    let mut session = SessionAcceptor::new().accept(&mut connection).await?;
+    let mut links = Links {
+        acceptor: LinkAcceptor::new(),
+        sender: None,
+        receiver: None,
+        idle: Vec::new(),
+    };
+
+    let mut received = Vec::new();
+    for step in builder.steps {
+        match step {
+            Step::Transfer { message, expected } => {
+                let sender = links.sender(&mut session).await?;
+                let found = sender.send(*message).await?;
+                if std::mem::discriminant(&expected) != std::mem::discriminant(&found) {
+                    return Err(TestServerError::UnexpectedOutcome { expected, found });
+                }
+            }
+            Step::Receive { state } => {
+                let receiver = links.receiver(&mut session).await?;
+                let delivery = receiver.recv::<Body<Value>>().await?;
+                receiver.dispose(&delivery, state).await?;
+                received.push(delivery.into_message());
+            }
+        }
+    }
+
+    let idle = links
+        .sender
+        .map(LinkEndpoint::Sender)
+        .into_iter()
+        .chain(links.receiver.map(LinkEndpoint::Receiver))
+        .chain(links.idle);
+    for link in idle {
+        tokio::spawn(drive(link));
+    }
+    while let Ok(link) = links.acceptor.accept(&mut session).await {
+        tokio::spawn(drive(link));
+    }
+
+    let _ = session.on_end().await;
+    let _ = connection.on_close().await;
+    Ok(received)
+}
+
+/// Keeps a link open until the client detaches it, accepting all incoming messages
+async fn drive(link: LinkEndpoint) {
+    match link {
+        LinkEndpoint::Sender(mut sender) => {
+            let _ = sender.on_detach().await;
+            let _ = sender.close().await;
+        }
+        LinkEndpoint::Receiver(mut receiver) => {
+            while let Ok(delivery) = receiver.recv::<Body<Value>>().await {
+                let _ = receiver.accept(&delivery).await;
+            }
+            let _ = receiver.close().await;
+        }
+    }
+}
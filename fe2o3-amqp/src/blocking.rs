@@ -0,0 +1,318 @@
+//! Blocking wrappers of [`Connection`](crate::Connection), [`Session`](crate::Session),
+//! [`Sender`](crate::Sender) and [`Receiver`](crate::Receiver) for synchronous code
+//!
+//! Every blocking call runs the corresponding async method on a current-thread runtime that is
+//! shared by the whole process. The runtime is driven by a background thread so that the
+//! connection, session and link event loops keep running between blocking calls.
+//!
+//! The blocking calls must not be made from within an async context, otherwise they will panic.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use fe2o3_amqp::blocking::{Connection, Receiver, Sender, Session};
+//! use fe2o3_amqp::types::primitives::Value;
+//!
+//! let mut connection = Connection::open("connection-1", "amqp://localhost:5672").unwrap();
+//! let mut session = Session::begin(&mut connection).unwrap();
+//!
+//! let mut sender = Sender::attach(&mut session, "rust-sender-link-1", "q1").unwrap();
+//! let outcome = sender.send("hello AMQP").unwrap();
+//! outcome.accepted_or_else(|state| state).unwrap();
+//!
+//! let mut receiver = Receiver::attach(&mut session, "rust-receiver-link-1", "q1").unwrap();
+//! let delivery = receiver.recv::<Value>().unwrap();
+//! receiver.accept(&delivery).unwrap();
+//!
+//! sender.close().unwrap();
+//! receiver.close().unwrap();
+//! session.end().unwrap();
+//! connection.close().unwrap();
+//! ```
+//!
+//! Anything that is not wrapped can be run with [`block_on`], and the handles returned by the
+//! async API can be converted into the blocking wrappers with `From`.
+//!
+//! ```rust,ignore
+//! use fe2o3_amqp::{blocking, Connection};
+//!
+//! let connection = blocking::block_on(
+//!     Connection::builder()
+//!         .container_id("connection-1")
+//!         .max_frame_size(64 * 1024)
+//!         .open("amqp://localhost:5672"),
+//! )
+//! .unwrap();
+//! let mut connection = blocking::Connection::from(connection);
+//! ```
+
+use std::{convert::TryInto, future::Future, sync::OnceLock, time::Duration};
+
+use fe2o3_amqp_types::{
+    definitions,
+    messaging::{Address, FromBody, Outcome, SerializableBody},
+};
+use tokio::runtime::Runtime;
+use url::Url;
+
+use crate::{
+    connection::{self, ConnectionHandle, OpenError},
+    link::{
+        delivery::DeliveryInfo, DetachError, DispositionError, ReceiverAttachError, RecvError,
+        SendError, SenderAttachError,
+    },
+    session::{self, BeginError, SessionHandle},
+    Delivery, Sendable,
+};
+
+/// Name of the thread that drives the shared runtime
+const RUNTIME_THREAD_NAME: &str = "fe2o3-amqp-blocking";
+
+/// Returns the shared runtime, which is created along with the thread that drives it on the
+/// first call
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<&'static Runtime> = OnceLock::new();
+
+    RUNTIME.get_or_init(|| {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Unable to create the runtime for blocking calls");
+        let runtime: &'static Runtime = Box::leak(Box::new(runtime));
+
+        // Tasks spawned by the event loops only make progress while a thread is driving the
+        // current-thread runtime
+        std::thread::Builder::new()
+            .name(RUNTIME_THREAD_NAME.to_string())
+            .spawn(move || runtime.block_on(std::future::pending::<()>()))
+            .expect("Unable to spawn the thread that drives the runtime for blocking calls");
+        runtime
+    })
+}
+
+/// Runs a future to completion on the shared runtime, blocking the current thread
+///
+/// # Panics
+///
+/// This will panic if called from within an async context.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    runtime().block_on(future)
+}
+
+/// Blocking wrapper of [`ConnectionHandle`]
+#[derive(Debug)]
+pub struct Connection {
+    inner: ConnectionHandle<()>,
+}
+
+impl From<ConnectionHandle<()>> for Connection {
+    fn from(inner: ConnectionHandle<()>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Connection {
+    /// Opens a connection with the default configuration. See
+    /// [`Connection::open`](crate::Connection::open)
+    pub fn open(
+        container_id: impl Into<String>,
+        url: impl TryInto<Url, Error = impl Into<OpenError>>,
+    ) -> Result<Self, OpenError> {
+        block_on(crate::Connection::open(container_id, url)).map(Self::from)
+    }
+
+    /// Closes the connection. See [`ConnectionHandle::close`]
+    pub fn close(&mut self) -> Result<(), connection::Error> {
+        block_on(self.inner.close())
+    }
+
+    /// Closes the connection with an error. See [`ConnectionHandle::close_with_error`]
+    pub fn close_with_error(
+        &mut self,
+        error: impl Into<definitions::Error>,
+    ) -> Result<(), connection::Error> {
+        block_on(self.inner.close_with_error(error))
+    }
+
+    /// Checks if the underlying event loop has stopped
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Returns the async connection handle
+    pub fn into_inner(self) -> ConnectionHandle<()> {
+        self.inner
+    }
+}
+
+/// Blocking wrapper of [`SessionHandle`]
+#[derive(Debug)]
+pub struct Session {
+    inner: SessionHandle<()>,
+}
+
+impl From<SessionHandle<()>> for Session {
+    fn from(inner: SessionHandle<()>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Session {
+    /// Begins a session with the default configuration. See
+    /// [`Session::begin`](crate::Session::begin)
+    pub fn begin(connection: &mut Connection) -> Result<Self, BeginError> {
+        block_on(crate::Session::begin(&mut connection.inner)).map(Self::from)
+    }
+
+    /// Ends the session. See [`SessionHandle::end`]
+    pub fn end(&mut self) -> Result<(), session::Error> {
+        block_on(self.inner.end())
+    }
+
+    /// Ends the session with an error. See [`SessionHandle::end_with_error`]
+    pub fn end_with_error(
+        &mut self,
+        error: impl Into<definitions::Error>,
+    ) -> Result<(), session::Error> {
+        block_on(self.inner.end_with_error(error))
+    }
+
+    /// Checks if the underlying event loop has stopped
+    pub fn is_ended(&self) -> bool {
+        self.inner.is_ended()
+    }
+
+    /// Returns the async session handle
+    pub fn into_inner(self) -> SessionHandle<()> {
+        self.inner
+    }
+}
+
+/// Blocking wrapper of [`Sender`](crate::Sender)
+#[derive(Debug)]
+pub struct Sender {
+    inner: crate::Sender,
+}
+
+impl From<crate::Sender> for Sender {
+    fn from(inner: crate::Sender) -> Self {
+        Self { inner }
+    }
+}
+
+impl Sender {
+    /// Attaches a sender link with the default configuration. See
+    /// [`Sender::attach`](crate::Sender::attach)
+    pub fn attach(
+        session: &mut Session,
+        name: impl Into<String>,
+        addr: impl Into<Address>,
+    ) -> Result<Self, SenderAttachError> {
+        block_on(crate::Sender::attach(&mut session.inner, name, addr)).map(Self::from)
+    }
+
+    /// Sends a message and waits for its outcome. See [`Sender::send`](crate::Sender::send)
+    #[allow(clippy::result_large_err)]
+    pub fn send<T: SerializableBody>(
+        &mut self,
+        sendable: impl Into<Sendable<T>>,
+    ) -> Result<Outcome, SendError> {
+        block_on(self.inner.send(sendable))
+    }
+
+    /// Sends a message and waits for its outcome for at most `duration`. See
+    /// [`Sender::send_with_timeout`](crate::Sender::send_with_timeout)
+    #[allow(clippy::result_large_err)]
+    pub fn send_with_timeout<T: SerializableBody>(
+        &mut self,
+        sendable: impl Into<Sendable<T>>,
+        duration: Duration,
+    ) -> Result<Outcome, SendError> {
+        block_on(self.inner.send_with_timeout(sendable, duration))
+    }
+
+    /// Closes the link. See [`Sender::close`](crate::Sender::close)
+    pub fn close(self) -> Result<(), DetachError> {
+        block_on(self.inner.close())
+    }
+
+    /// Returns the async sender
+    pub fn into_inner(self) -> crate::Sender {
+        self.inner
+    }
+}
+
+/// Blocking wrapper of [`Receiver`](crate::Receiver)
+#[derive(Debug)]
+pub struct Receiver {
+    inner: crate::Receiver,
+}
+
+impl From<crate::Receiver> for Receiver {
+    fn from(inner: crate::Receiver) -> Self {
+        Self { inner }
+    }
+}
+
+impl Receiver {
+    /// Attaches a receiver link with the default configuration. See
+    /// [`Receiver::attach`](crate::Receiver::attach)
+    pub fn attach(
+        session: &mut Session,
+        name: impl Into<String>,
+        addr: impl Into<Address>,
+    ) -> Result<Self, ReceiverAttachError> {
+        block_on(crate::Receiver::attach(&mut session.inner, name, addr)).map(Self::from)
+    }
+
+    /// Receives a message. See [`Receiver::recv`](crate::Receiver::recv)
+    #[allow(clippy::result_large_err)]
+    pub fn recv<T>(&mut self) -> Result<Delivery<T>, RecvError>
+    where
+        for<'de> T: FromBody<'de> + Send,
+    {
+        block_on(self.inner.recv())
+    }
+
+    /// Receives a message, or returns `None` if no message arrives within `duration`. See
+    /// [`Receiver::recv_with_timeout`](crate::Receiver::recv_with_timeout)
+    #[allow(clippy::result_large_err)]
+    pub fn recv_with_timeout<T>(
+        &mut self,
+        duration: Duration,
+    ) -> Result<Option<Delivery<T>>, RecvError>
+    where
+        for<'de> T: FromBody<'de> + Send,
+    {
+        block_on(self.inner.recv_with_timeout(duration))
+    }
+
+    /// Accepts the message. See [`Receiver::accept`](crate::Receiver::accept)
+    pub fn accept(&self, delivery_info: impl Into<DeliveryInfo>) -> Result<(), DispositionError> {
+        block_on(self.inner.accept(delivery_info))
+    }
+
+    /// Rejects the message. See [`Receiver::reject`](crate::Receiver::reject)
+    pub fn reject(
+        &self,
+        delivery_info: impl Into<DeliveryInfo>,
+        error: impl Into<Option<definitions::Error>>,
+    ) -> Result<(), DispositionError> {
+        block_on(self.inner.reject(delivery_info, error))
+    }
+
+    /// Releases the message. See [`Receiver::release`](crate::Receiver::release)
+    pub fn release(&self, delivery_info: impl Into<DeliveryInfo>) -> Result<(), DispositionError> {
+        block_on(self.inner.release(delivery_info))
+    }
+
+    /// Closes the link. See [`Receiver::close`](crate::Receiver::close)
+    pub fn close(self) -> Result<(), DetachError> {
+        block_on(self.inner.close())
+    }
+
+    /// Returns the async receiver
+    pub fn into_inner(self) -> crate::Receiver {
+        self.inner
+    }
+}
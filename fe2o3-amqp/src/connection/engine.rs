@@ -1,13 +1,13 @@
 //! The engine handles incoming and outgoing frames and messages to reduce
 //! transferring frames/messages over channels
 
-use std::io;
-use std::time::Duration;
+use std::{io, sync::Arc, time::Duration};
 
-use fe2o3_amqp_types::definitions::{self, AmqpError};
-use fe2o3_amqp_types::performatives::Close;
+use fe2o3_amqp_types::definitions::{self, AmqpError, ConnectionError};
+use fe2o3_amqp_types::performatives::{Close, Open};
 use futures_util::{SinkExt, StreamExt};
 use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::broadcast;
 use tokio::sync::mpsc::Receiver;
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
@@ -16,12 +16,21 @@ use crate::control::ConnectionControl;
 use crate::endpoint::{IncomingChannel, OutgoingChannel};
 use crate::frames::amqp::{self, Frame, FrameBody};
 use crate::session::frame::{SessionFrame, SessionFrameBody};
-use crate::transport::Transport;
-use crate::util::Running;
+use crate::transport::{metrics::TransportCounters, Transport};
+use crate::util::{IdleTimeout, Running};
 use crate::{endpoint, transport, SendBound};
 
-use super::{heartbeat::HeartBeat, ConnectionState};
-use super::{AllocSessionError, ConnectionInnerError, ConnectionStateError, Error, OpenError};
+use super::{heartbeat::HeartBeat, BoxedIo, ConnectionEvent, ConnectionState};
+use super::{
+    AllocSessionError, ConnectionInnerError, ConnectionStateError, Error, OpenError,
+    DEFAULT_EVENT_CHAN_BUF,
+};
+
+/// Maximum number of frames that are taken from the sessions before sending Close
+const MAX_DRAINED_SESSION_FRAMES: usize = 4096;
+
+/// How long to wait for the next frame from the sessions before sending Close
+const DRAIN_IDLE_TIMEOUT: Duration = Duration::from_millis(50);
 
 #[derive(Debug)]
 pub(crate) struct ConnectionEngine<Io, C> {
@@ -30,6 +39,7 @@ pub(crate) struct ConnectionEngine<Io, C> {
     control: Receiver<ConnectionControl>,
     outgoing_session_frames: Receiver<SessionFrame>,
     heartbeat: HeartBeat,
+    events: broadcast::Sender<ConnectionEvent>,
 }
 
 cfg_not_wasm32! {
@@ -44,7 +54,7 @@ cfg_not_wasm32! {
         ConnectionStateError: From<C::OpenError> + From<C::CloseError>,
         OpenError: From<C::OpenError>,
     {
-        pub fn spawn(self) -> (JoinHandle<()>, oneshot::Receiver<Result<(), Error>>) {
+        pub fn spawn(self) -> (JoinHandle<BoxedIo>, oneshot::Receiver<Result<(), Error>>) {
             let (tx, rx) = oneshot::channel();
             let handle = tokio::spawn(self.event_loop(tx));
             (handle, rx)
@@ -66,7 +76,7 @@ cfg_wasm32! {
     {
         pub fn spawn_local(
             self
-        ) -> (JoinHandle<()>, oneshot::Receiver<Result<(), Error>>) {
+        ) -> (JoinHandle<BoxedIo>, oneshot::Receiver<Result<(), Error>>) {
             let (tx, rx) = oneshot::channel();
             let handle = tokio::task::spawn_local(self.event_loop(tx));
             (handle, rx)
@@ -75,7 +85,7 @@ cfg_wasm32! {
         pub fn spawn_on_local_set(
             self,
             local_set: &tokio::task::LocalSet,
-        ) -> (JoinHandle<()>, oneshot::Receiver<Result<(), Error>>) {
+        ) -> (JoinHandle<BoxedIo>, oneshot::Receiver<Result<(), Error>>) {
             let (tx, rx) = oneshot::channel();
             let handle = local_set.spawn_local(self.event_loop(tx));
             (handle, rx)
@@ -108,6 +118,7 @@ where
             | ConnectionState::OpenReceived
             | ConnectionState::OpenSent
             | ConnectionState::Opened => {
+                self.publish_closing();
                 self.connection
                     .send_close(&mut self.transport, error)
                     .await?;
@@ -198,17 +209,40 @@ where
 
         // Set heartbeat here because in pipelined-open, the Open frame
         // may be recved after mux loop is started
-        match &remote_idle_timeout {
-            Some(0) | None => self.heartbeat = HeartBeat::never(),
-            Some(millis) => {
-                let period = Duration::from_millis(*millis as u64);
-                self.heartbeat = HeartBeat::new(period);
-            }
-        };
+        self.heartbeat = HeartBeat::for_remote_idle_timeout(remote_idle_timeout);
 
         Ok(())
     }
 
+    pub(crate) fn transport_metrics(&self) -> Arc<TransportCounters> {
+        self.transport.metrics_handle()
+    }
+
+    pub(crate) fn local_open(&self) -> &Open {
+        self.connection.local_open()
+    }
+
+    pub(crate) fn remote_open(&self) -> Option<&Open> {
+        self.connection.remote_open()
+    }
+
+    /// Subscribes to the events that are published once the event loop is started
+    pub(crate) fn subscribe_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publishes an event to the subscribers if there is any
+    fn publish(&self, event: ConnectionEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Publishes `Closing` only once, when the first Close frame is sent or received
+    fn publish_closing(&self) {
+        if let ConnectionState::Opened = self.connection.local_state() {
+            self.publish(ConnectionEvent::Closing);
+        }
+    }
+
     /// Open Connection without starting the Engine::event_loop()
     pub(crate) async fn open(
         transport: Transport<Io, amqp::Frame>,
@@ -222,12 +256,19 @@ where
             control,
             outgoing_session_frames,
             heartbeat: HeartBeat::never(),
+            events: broadcast::channel(DEFAULT_EVENT_CHAN_BUF).0,
         };
 
         match engine.open_inner().await {
             Ok(_) => Ok(engine),
             Err(error) => {
-                match engine.close_connection(None).await {
+                let close_error = match &error {
+                    OpenError::TransportError(transport::Error::FramingError) => Some(
+                        definitions::Error::new(ConnectionError::FramingError, None, None),
+                    ),
+                    _ => None,
+                };
+                match engine.close_connection(close_error).await {
                     Ok(_) => Err(error),
                     Err(error) => match error {
                         ConnectionInnerError::TransportError(e) => {
@@ -262,6 +303,9 @@ where
     ) -> Result<(), ConnectionInnerError> {
         match &self.connection.local_state() {
             ConnectionState::Opened => {}
+            // Frames that were in flight when the local Close was sent are discarded while
+            // waiting for the remote Close
+            ConnectionState::CloseSent | ConnectionState::Discarding => return Ok(()),
             _ => return Err(ConnectionInnerError::IllegalState),
         };
 
@@ -283,21 +327,32 @@ where
         let channel = IncomingChannel(channel);
         match body {
             FrameBody::Open(open) => {
+                // Only one Open frame can be received from the remote peer
+                match self.connection.local_state() {
+                    ConnectionState::OpenReceived
+                    | ConnectionState::Opened
+                    | ConnectionState::CloseReceived
+                    | ConnectionState::CloseSent
+                    | ConnectionState::Discarding
+                    | ConnectionState::End => {
+                        return Err(transport::Error::FramingError.into());
+                    }
+                    _ => {}
+                }
+
                 let remote_idle_timeout = open.idle_time_out;
                 self.connection.on_incoming_open(channel, open)?;
+                if let ConnectionState::Opened = self.connection.local_state() {
+                    self.publish(ConnectionEvent::Opened);
+                }
 
                 // Set heartbeat here because in pipelined-open, the Open frame
                 // may be recved after mux loop is started
-                match &remote_idle_timeout {
-                    Some(millis) => {
-                        let period = Duration::from_millis(*millis as u64);
-                        self.heartbeat = HeartBeat::new(period);
-                    }
-                    None => self.heartbeat = HeartBeat::never(),
-                };
+                self.heartbeat = HeartBeat::for_remote_idle_timeout(remote_idle_timeout);
             }
             FrameBody::Begin(begin) => {
                 self.connection.on_incoming_begin(channel, begin).await?;
+                self.publish(ConnectionEvent::SessionBegun { channel: channel.0 });
             }
             FrameBody::Attach(attach) => {
                 let sframe = SessionFrame::new(channel, SessionFrameBody::Attach(attach));
@@ -330,8 +385,10 @@ where
             }
             FrameBody::End(end) => {
                 self.connection.on_incoming_end(channel, end).await?;
+                self.publish(ConnectionEvent::SessionEnded { channel: channel.0 });
             }
             FrameBody::Close(close) => {
+                self.publish_closing();
                 let result = self.connection.on_incoming_close(channel, close);
                 if matches!(
                     self.connection.local_state(),
@@ -371,10 +428,8 @@ where
         log::debug!("{}", control);
         match control {
             ConnectionControl::Close(error) => {
-                self.outgoing_session_frames.close();
-                while let Some(frame) = self.outgoing_session_frames.recv().await {
-                    self.on_outgoing_session_frames(frame).await?;
-                }
+                self.publish_closing();
+                self.drain_outgoing_session_frames().await?;
 
                 self.connection
                     .send_close(&mut self.transport, error)
@@ -399,6 +454,9 @@ where
                     log::error!("{:?}", error);
                 }
             }
+            ConnectionControl::SetIdleTimeout(duration) => {
+                self.transport.set_idle_timeout(duration);
+            }
         }
 
         match self.connection.local_state() {
@@ -407,6 +465,36 @@ where
         }
     }
 
+    /// Writes the frames that the sessions have enqueued so far before a Close is sent.
+    ///
+    /// Sessions that are not ended yet may still be forwarding frames from their links, so the
+    /// channel is kept open until it stays idle for [`DRAIN_IDLE_TIMEOUT`] or
+    /// [`MAX_DRAINED_SESSION_FRAMES`] frames are written.
+    async fn drain_outgoing_session_frames(&mut self) -> Result<(), ConnectionInnerError> {
+        let mut idle = IdleTimeout::new(DRAIN_IDLE_TIMEOUT);
+        let mut drained = 0;
+        while drained < MAX_DRAINED_SESSION_FRAMES && self.connection.has_allocated_sessions() {
+            tokio::select! {
+                frame = self.outgoing_session_frames.recv() => match frame {
+                    Some(frame) => {
+                        self.on_outgoing_session_frames(frame).await?;
+                        drained += 1;
+                        idle.reset();
+                    }
+                    None => break,
+                },
+                _ = &mut idle => break,
+            }
+        }
+
+        self.outgoing_session_frames.close();
+        while let Some(frame) = self.outgoing_session_frames.recv().await {
+            self.on_outgoing_session_frames(frame).await?;
+        }
+        self.transport.flush().await?;
+        Ok(())
+    }
+
     #[inline]
     #[cfg_attr(feature = "tracing", tracing::instrument(name = "SEND", skip_all))]
     async fn on_outgoing_session_frames(
@@ -468,7 +556,37 @@ where
         error: &ConnectionInnerError,
     ) -> Result<Running, ConnectionInnerError> {
         match error {
-            ConnectionInnerError::TransportError(_) => Ok(Running::Stop),
+            ConnectionInnerError::TransportError(error) => {
+                let error = match error {
+                    transport::Error::DecodeError(description) => definitions::Error::new(
+                        AmqpError::DecodeError,
+                        description.clone(),
+                        None,
+                    ),
+                    transport::Error::FramingError => {
+                        definitions::Error::new(ConnectionError::FramingError, None, None)
+                    }
+                    transport::Error::NotImplemented(description) => definitions::Error::new(
+                        AmqpError::NotImplemented,
+                        description.clone(),
+                        None,
+                    ),
+                    transport::Error::IdleTimeoutElapsed => {
+                        self.publish(ConnectionEvent::RemoteIdleTimeout);
+                        return Ok(Running::Stop);
+                    }
+                    transport::Error::Io(_) => return Ok(Running::Stop),
+                };
+
+                // The incoming bytes can no longer be trusted, so the remote Close frame is
+                // not awaited
+                if let ConnectionState::Opened = self.connection.local_state() {
+                    self.connection
+                        .send_close(&mut self.transport, Some(error))
+                        .await?;
+                }
+                Ok(Running::Stop)
+            }
             ConnectionInnerError::IllegalState => {
                 let error = definitions::Error::new(AmqpError::IllegalState, None, None);
                 self.close_connection(Some(error)).await?;
@@ -492,7 +610,12 @@ where
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(name = "Connection::event_loop", skip(self), fields(container_id = %self.connection.local_open().container_id)))]
-    async fn event_loop(mut self, tx: oneshot::Sender<Result<(), Error>>) {
+    async fn event_loop(mut self, tx: oneshot::Sender<Result<(), Error>>) -> BoxedIo {
+        // Open frames are usually exchanged before the event loop is started
+        if let ConnectionState::Opened = self.connection.local_state() {
+            self.publish(ConnectionEvent::Opened);
+        }
+
         let mut outcome = Ok(());
         loop {
             let result = tokio::select! {
@@ -619,6 +742,10 @@ where
             }
         }
 
+        // The event loop may have stopped because of an error before the close frames are
+        // exchanged
+        *self.connection.local_state_mut() = ConnectionState::End;
+
         // Clean Shutdown
         //
         // When the Receiver is dropped, it is possible for unprocessed messages to remain
@@ -635,7 +762,10 @@ where
         #[cfg(feature = "log")]
         log::debug!("Stopped");
 
+        self.publish(ConnectionEvent::closed(&outcome));
         let result = outcome.and(close).map_err(Into::into);
         let _ = tx.send(result);
+
+        Box::new(self.transport.into_inner_io())
     }
 }
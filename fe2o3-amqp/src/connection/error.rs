@@ -6,7 +6,9 @@ use bytes::Bytes;
 use fe2o3_amqp_types::{definitions, primitives::Binary, sasl::SaslCode};
 use tokio::{sync::mpsc, task::JoinError};
 
-use crate::transport::{self, error::NegotiationError};
+use crate::transport::{self, error::NegotiationError, protocol_header::ProtocolHeader};
+
+use super::RedirectInfo;
 
 cfg_scram! {
     use crate::auth::error::ScramErrorKind;
@@ -32,13 +34,25 @@ pub enum OpenError {
     TlsConnectorNotFound,
 
     /// Scheme is invalid or not found
-    #[error(r#"Invalid scheme. Only "amqp" and "amqps" are supported."#)]
+    #[error(r#"Invalid scheme. Only "amqp" and "amqps", and "ws" and "wss" with feature "websocket", are supported."#)]
     InvalidScheme,
 
     /// Protocol negotiation failed due to protocol header mismatch
     #[error("Protocol header mismatch. Found {0:?}")]
     ProtocolHeaderMismatch(Bytes),
 
+    /// The remote peer answered with the protocol header of a different protocol version
+    ///
+    /// The caller may decide whether to retry with the version that is `found`
+    #[error("Protocol version mismatch. Expecting {expected:?}, found {found:?}")]
+    ProtocolVersionMismatch {
+        /// Protocol header sent by the local peer
+        expected: ProtocolHeader,
+
+        /// Protocol header received from the remote peer
+        found: ProtocolHeader,
+    },
+
     /// SASL negotiation failed
     #[error("SASL error code {:?}, additional data: {:?}", .code, .additional_data)]
     SaslError {
@@ -77,6 +91,68 @@ pub enum OpenError {
     /// Remote peer closed connection with error during openning process
     #[error("Remote peer closed connection with error {}", .0)]
     RemoteClosedWithError(definitions::Error),
+
+    /// Error with the WebSocket binding
+    ///
+    /// The error is boxed to keep the size of `OpenError` small
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+    #[error(transparent)]
+    WebSocketError(Box<fe2o3_amqp_ws::Error>),
+
+    /// The connection was not opened within the duration set by
+    /// [`open_timeout`](crate::connection::Builder::open_timeout), or an incoming connection did
+    /// not complete the handshake in time
+    #[error("Timed out opening the connection")]
+    OpenTimeout,
+}
+
+impl OpenError {
+    /// Returns the address that the remote peer redirects the connection to if the remote peer
+    /// closed the connection with an `amqp:connection:redirect` error during the opening process
+    pub fn redirect(&self) -> Option<RedirectInfo> {
+        match self {
+            Self::RemoteClosedWithError(error) => RedirectInfo::from_error(error),
+            _ => None,
+        }
+    }
+
+    /// Whether opening the connection may succeed if it is attempted again later
+    ///
+    /// This is the case if the remote peer is not reachable (yet) or does not respond in time.
+    /// Errors like a failed SASL negotiation or an invalid url are not transient.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Io(error) | Self::TransportError(transport::Error::Io(error)) => {
+                is_transient_io_error(error)
+            }
+            #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+            Self::WebSocketError(error) => match error.as_ref() {
+                fe2o3_amqp_ws::Error::Io(error) => is_transient_io_error(error),
+                _ => false,
+            },
+            Self::TransportError(transport::Error::IdleTimeoutElapsed) | Self::OpenTimeout => true,
+            _ => false,
+        }
+    }
+}
+
+fn is_transient_io_error(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::TimedOut
+    )
+}
+
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+impl From<fe2o3_amqp_ws::Error> for OpenError {
+    fn from(err: fe2o3_amqp_ws::Error) -> Self {
+        Self::WebSocketError(Box::new(err))
+    }
 }
 
 impl From<NegotiationError> for OpenError {
@@ -84,6 +160,9 @@ impl From<NegotiationError> for OpenError {
         match err {
             NegotiationError::Io(err) => Self::Io(err),
             NegotiationError::ProtocolHeaderMismatch(buf) => Self::ProtocolHeaderMismatch(buf),
+            NegotiationError::ProtocolVersionMismatch { expected, found } => {
+                Self::ProtocolVersionMismatch { expected, found }
+            }
             NegotiationError::InvalidDomain => Self::InvalidDomain,
             NegotiationError::SaslError {
                 code,
@@ -225,6 +304,17 @@ pub enum Error {
     JoinError(#[from] JoinError),
 }
 
+impl Error {
+    /// Returns the address that the remote peer redirects the connection to if the remote peer
+    /// closed the connection with an `amqp:connection:redirect` error
+    pub fn redirect(&self) -> Option<RedirectInfo> {
+        match self {
+            Self::RemoteClosedWithError(error) => RedirectInfo::from_error(error),
+            _ => None,
+        }
+    }
+}
+
 impl From<ConnectionInnerError> for Error {
     fn from(error: ConnectionInnerError) -> Self {
         match error {
@@ -275,3 +365,21 @@ pub enum TryCloseError {
     #[error("The connection has not received a close frame from the remote peer")]
     RemoteCloseNotReceived,
 }
+
+/// Error associated with recovering the IO of a closed connection
+#[derive(Debug, thiserror::Error)]
+pub enum IntoInnerIoError {
+    /// The connection has not been closed with [`close`](super::ConnectionHandle::close),
+    /// [`close_with_error`](super::ConnectionHandle::close_with_error) or
+    /// [`on_close`](super::ConnectionHandle::on_close)
+    #[error("The connection is not closed")]
+    NotClosed,
+
+    /// The underlying IO is not of the requested type
+    #[error("The underlying IO is not of the requested type")]
+    TypeMismatch,
+
+    /// The connection event loop has panicked or has been cancelled
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
+}
@@ -0,0 +1,116 @@
+//! Lifecycle events published by the connection event loop
+
+use fe2o3_amqp_types::definitions::{self, AmqpError, ConnectionError};
+use futures_util::Stream;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::transport;
+
+use super::ConnectionInnerError;
+
+/// Number of events that are buffered for each subscriber before the oldest ones are dropped
+pub(crate) const DEFAULT_EVENT_CHAN_BUF: usize = 64;
+
+/// A lifecycle event of a connection
+///
+/// The events can be subscribed to with
+/// [`ConnectionHandle::events`](crate::connection::ConnectionHandle::events)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionEvent {
+    /// Open frames are exchanged with the remote peer
+    Opened,
+
+    /// The remote peer has not sent any frame within the local idle timeout
+    ///
+    /// The connection is closed right after this event
+    RemoteIdleTimeout,
+
+    /// A session is begun by the remote peer on the `channel`
+    SessionBegun {
+        /// The channel used by the remote peer
+        channel: u16,
+    },
+
+    /// A session is ended by the remote peer on the `channel`
+    SessionEnded {
+        /// The channel used by the remote peer
+        channel: u16,
+    },
+
+    /// A Close frame is sent or received
+    Closing,
+
+    /// The connection is closed without an error
+    Closed,
+
+    /// The connection is closed with an error
+    ClosedWithError(definitions::Error),
+}
+
+impl ConnectionEvent {
+    /// Whether this is the last event published by the connection
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Closed | Self::ClosedWithError(_))
+    }
+
+    pub(crate) fn closed(outcome: &Result<(), ConnectionInnerError>) -> Self {
+        let error = match outcome {
+            Ok(_) | Err(ConnectionInnerError::RemoteClosed) => return Self::Closed,
+            Err(ConnectionInnerError::RemoteClosedWithError(error)) => error.clone(),
+            Err(ConnectionInnerError::IllegalState) => {
+                definitions::Error::new(AmqpError::IllegalState, None, None)
+            }
+            Err(ConnectionInnerError::NotImplemented(description)) => {
+                definitions::Error::new(AmqpError::NotImplemented, description.clone(), None)
+            }
+            Err(ConnectionInnerError::NotFound(description)) => {
+                definitions::Error::new(AmqpError::NotFound, description.clone(), None)
+            }
+            Err(ConnectionInnerError::TransportError(error)) => match error {
+                transport::Error::DecodeError(description) => {
+                    definitions::Error::new(AmqpError::DecodeError, description.clone(), None)
+                }
+                transport::Error::FramingError => {
+                    definitions::Error::new(ConnectionError::FramingError, None, None)
+                }
+                transport::Error::NotImplemented(description) => {
+                    definitions::Error::new(AmqpError::NotImplemented, description.clone(), None)
+                }
+                transport::Error::Io(_) | transport::Error::IdleTimeoutElapsed => {
+                    definitions::Error::new(
+                        ConnectionError::ConnectionForced,
+                        error.to_string(),
+                        None,
+                    )
+                }
+            },
+        };
+        Self::ClosedWithError(error)
+    }
+}
+
+/// Turns the receiver into a stream that skips the events lost to lagging and ends after the
+/// terminal event
+pub(crate) fn event_stream(
+    receiver: broadcast::Receiver<ConnectionEvent>,
+) -> impl Stream<Item = ConnectionEvent> + Unpin {
+    Box::pin(futures_util::stream::unfold(
+        Some(receiver),
+        |receiver| async move {
+            let mut receiver = receiver?;
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        let receiver = match event.is_terminal() {
+                            true => None,
+                            false => Some(receiver),
+                        };
+                        return Some((event, receiver));
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    ))
+}
@@ -2,9 +2,13 @@
 
 use std::{io, pin::Pin, task::Poll, time::Duration};
 
+use fe2o3_amqp_types::definitions::Milliseconds;
 use futures_util::Stream;
 use pin_project_lite::pin_project;
 
+/// Heartbeats are not sent more often than this regardless of the remote idle timeout
+const MIN_HEARTBEAT_PERIOD: Duration = Duration::from_millis(100);
+
 cfg_not_wasm32! {
     use tokio_stream::wrappers::IntervalStream;
 
@@ -91,6 +95,33 @@ impl HeartBeat {
         let interval = Some(InnerStream::new(period));
         Self { interval }
     }
+
+    /// A [`HeartBeat`] that keeps the connection alive given the idle timeout advertised by the
+    /// remote peer.
+    ///
+    /// Empty frames are sent at half the remote idle timeout so that the remote peer does not
+    /// consider the connection idle because of network latency. A remote idle timeout of `None`
+    /// or zero means that the remote peer does not require heartbeats.
+    ///
+    /// The period is never shorter than 100 milliseconds so that an unreasonably small remote idle
+    /// timeout does not flood the connection with empty frames. A remote peer that advertises an
+    /// idle timeout below 200 milliseconds may therefore still close the connection.
+    pub fn for_remote_idle_timeout(remote_idle_timeout: Option<Milliseconds>) -> Self {
+        match remote_idle_timeout {
+            Some(0) | None => Self::never(),
+            Some(millis) => Self::new(heartbeat_period(millis)),
+        }
+    }
+
+    /// Returns `true` if the [`HeartBeat`] never ticks
+    #[cfg(test)]
+    fn is_never(&self) -> bool {
+        self.interval.is_none()
+    }
+}
+
+fn heartbeat_period(remote_idle_timeout: Milliseconds) -> Duration {
+    Duration::from_millis(u64::from(remote_idle_timeout / 2)).max(MIN_HEARTBEAT_PERIOD)
 }
 
 impl Stream for HeartBeat {
@@ -107,3 +138,34 @@ impl Stream for HeartBeat {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{heartbeat_period, HeartBeat, MIN_HEARTBEAT_PERIOD};
+
+    #[test]
+    fn test_no_heartbeat_without_remote_idle_timeout() {
+        assert!(HeartBeat::for_remote_idle_timeout(None).is_never());
+        assert!(HeartBeat::for_remote_idle_timeout(Some(0)).is_never());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_for_smallest_remote_idle_timeout() {
+        // Half of 1 ms would be a zero period, which is not a valid interval
+        assert!(!HeartBeat::for_remote_idle_timeout(Some(1)).is_never());
+    }
+
+    #[test]
+    fn test_heartbeat_period_is_half_of_remote_idle_timeout() {
+        assert_eq!(heartbeat_period(60_000), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_heartbeat_period_is_clamped_to_minimum() {
+        assert_eq!(heartbeat_period(1), MIN_HEARTBEAT_PERIOD);
+        assert_eq!(heartbeat_period(199), MIN_HEARTBEAT_PERIOD);
+        assert_eq!(heartbeat_period(200), MIN_HEARTBEAT_PERIOD);
+    }
+}
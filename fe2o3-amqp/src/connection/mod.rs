@@ -1,16 +1,18 @@
 //! Implements AMQP1.0 Connection
 
-use std::{cmp::min, collections::HashMap, sync::Arc};
+use std::{cmp::min, collections::HashMap, sync::Arc, time::Duration};
 
 use fe2o3_amqp_types::{
-    definitions::{self},
+    definitions::{self, Fields, MIN_MAX_FRAME_SIZE},
     performatives::{Begin, Close, End, Open},
+    primitives::{Array, Symbol},
     states::ConnectionState,
 };
-use futures_util::{Sink, SinkExt};
+use futures_util::{Sink, SinkExt, Stream};
 use slab::Slab;
 use tokio::{
     sync::{
+        broadcast,
         mpsc::Sender,
         oneshot::{self, error::TryRecvError},
     },
@@ -28,6 +30,7 @@ use crate::{
     frames::amqp::{Frame, FrameBody},
     session::frame::{SessionFrame, SessionFrameBody, SessionIncomingItem},
     session::Session,
+    transport::{self, metrics::TransportCounters, TransportMetrics},
     SendBound,
 };
 
@@ -40,6 +43,18 @@ mod error;
 pub mod heartbeat;
 pub use error::*;
 
+mod event;
+pub use event::ConnectionEvent;
+pub(crate) use event::DEFAULT_EVENT_CHAN_BUF;
+
+mod redirect;
+pub use redirect::{RedirectInfo, SOLE_CONNECTION_FOR_CONTAINER};
+
+cfg_not_wasm32! {
+    mod reconnect;
+    pub use reconnect::*;
+}
+
 /// Default max-frame-size.
 ///
 /// Please note that this is different from `MaxFrameSize::default()`.
@@ -53,6 +68,14 @@ pub const DEFAULT_CHANNEL_MAX: u16 = 255;
 
 type SessionRelay = Arc<Sender<SessionIncomingItem>>;
 
+/// The IO returned by the event loop once it has stopped
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) type BoxedIo = Box<dyn std::any::Any + Send>;
+
+/// The IO returned by the event loop once it has stopped
+#[cfg(target_arch = "wasm32")]
+pub(crate) type BoxedIo = Box<dyn std::any::Any>;
+
 /// A handle to the [`Connection`] event loop.
 ///
 /// Dropping the handle will also stop the [`Connection`] event loop.
@@ -61,12 +84,27 @@ pub struct ConnectionHandle<R> {
     /// Only change this value in `on_close` method
     pub(crate) is_closed: bool,
     pub(crate) control: Sender<ConnectionControl>,
-    pub(crate) handle: JoinHandle<()>,
+    pub(crate) handle: JoinHandle<BoxedIo>,
     pub(crate) outcome: oneshot::Receiver<Result<(), Error>>,
 
     // outgoing channel for session
     pub(crate) outgoing: Sender<SessionFrame>,
     pub(crate) session_listener: R,
+
+    pub(crate) transport_metrics: Arc<TransportCounters>,
+
+    /// The Open frame sent to the remote peer
+    pub(crate) local_open: Open,
+
+    /// The Open frame received from the remote peer
+    pub(crate) remote_open: Option<Open>,
+
+    /// Only used to subscribe to the events that are published from now on
+    pub(crate) events: broadcast::Receiver<ConnectionEvent>,
+
+    /// Receives the events published since the connection was opened until the first call
+    /// to `events()`
+    pub(crate) pending_events: Option<broadcast::Receiver<ConnectionEvent>>,
 }
 
 impl<R> std::fmt::Debug for ConnectionHandle<R> {
@@ -82,6 +120,110 @@ impl<R> Drop for ConnectionHandle<R> {
 }
 
 impl<R> ConnectionHandle<R> {
+    /// Get a snapshot of the number of frames and bytes sent and received over the underlying
+    /// transport
+    pub fn transport_metrics(&self) -> TransportMetrics {
+        self.transport_metrics.snapshot()
+    }
+
+    /// The `offered-capabilities` of the Open frame received from the remote peer
+    pub fn remote_offered_capabilities(&self) -> Option<&Array<Symbol>> {
+        self.remote_open.as_ref()?.offered_capabilities.as_ref()
+    }
+
+    /// Whether the `capability` is listed in the `offered-capabilities` of the Open frame
+    /// received from the remote peer
+    ///
+    /// A capability that is offered by the remote peer can be used by the local peer.
+    pub fn remote_offers_capability(&self, capability: &str) -> bool {
+        self.remote_offered_capabilities()
+            .is_some_and(|capabilities| capabilities.iter().any(|c| c.as_str() == capability))
+    }
+
+    /// The `desired-capabilities` of the Open frame received from the remote peer
+    pub fn remote_desired_capabilities(&self) -> Option<&Array<Symbol>> {
+        self.remote_open.as_ref()?.desired_capabilities.as_ref()
+    }
+
+    /// The connection `properties` of the Open frame received from the remote peer
+    pub fn remote_properties(&self) -> Option<&Fields> {
+        self.remote_open.as_ref()?.properties.as_ref()
+    }
+
+    /// The max frame size agreed with the remote peer, which is the smaller of the
+    /// `max-frame-size` of the local and remote Open frames
+    pub fn negotiated_max_frame_size(&self) -> u32 {
+        let local = self.local_open.max_frame_size.0;
+        match &self.remote_open {
+            Some(remote) => min(local, remote.max_frame_size.0),
+            None => local,
+        }
+    }
+
+    /// The channel max agreed with the remote peer, which is the smaller of the `channel-max` of
+    /// the local and remote Open frames
+    pub fn negotiated_channel_max(&self) -> u16 {
+        let local = self.local_open.channel_max.0;
+        match &self.remote_open {
+            Some(remote) => min(local, remote.channel_max.0),
+            None => local,
+        }
+    }
+
+    /// The idle timeout agreed with the remote peer, which is the smaller of the `idle-time-out`
+    /// of the local and remote Open frames
+    ///
+    /// An unset or zero `idle-time-out` means that the peer does not have an idle timeout, and
+    /// `None` is returned if neither peer has one. Please note that the connection builder
+    /// advertises half of the configured `idle_time_out`.
+    pub fn negotiated_idle_timeout(&self) -> Option<Duration> {
+        let local = self.local_open.idle_time_out.filter(|ms| *ms > 0);
+        let remote = self
+            .remote_open
+            .as_ref()
+            .and_then(|open| open.idle_time_out)
+            .filter(|ms| *ms > 0);
+        let millis = match (local, remote) {
+            (Some(local), Some(remote)) => min(local, remote),
+            (Some(millis), None) | (None, Some(millis)) => millis,
+            (None, None) => return None,
+        };
+        Some(Duration::from_millis(millis as u64))
+    }
+
+    /// Changes the local idle timeout of the connection
+    ///
+    /// The connection is closed if no frame is received from the remote peer within
+    /// `idle_timeout`, and a zero duration disables the idle timeout. This only changes how the
+    /// local idle timeout is tracked and the timer restarts from now. The `idle-time-out` sent
+    /// to the remote peer in the Open frame cannot be renegotiated on an open connection, so
+    /// the remote peer keeps sending empty frames at the interval it has derived from that value.
+    /// Setting a timeout shorter than that interval may therefore close an idle connection
+    /// that is otherwise healthy.
+    ///
+    /// An `Error::IllegalState` is returned if the underlying event loop has stopped.
+    pub async fn set_idle_timeout(&self, idle_timeout: Duration) -> Result<(), Error> {
+        self.control
+            .send(ConnectionControl::SetIdleTimeout(idle_timeout))
+            .await
+            .map_err(|_| Error::IllegalState)
+    }
+
+    /// Subscribes to the lifecycle events of the connection
+    ///
+    /// The first stream also yields the events published since the connection was opened, and
+    /// the following streams only yield the events published after they are subscribed. The
+    /// stream ends after [`ConnectionEvent::Closed`] or [`ConnectionEvent::ClosedWithError`], or
+    /// right away if the event loop has already stopped. Events are dropped for a subscriber
+    /// that falls too far behind.
+    pub fn events(&mut self) -> impl Stream<Item = ConnectionEvent> + Unpin {
+        let receiver = self
+            .pending_events
+            .take()
+            .unwrap_or_else(|| self.events.resubscribe());
+        event::event_stream(receiver)
+    }
+
     /// Checks if the underlying event loop has stopped
     pub fn is_closed(&self) -> bool {
         match self.is_closed {
@@ -121,6 +263,9 @@ impl<R> ConnectionHandle<R> {
     cfg_not_wasm32! {
         /// Close the connection
         ///
+        /// If the remote peer has already closed the connection with an error, the error sent by
+        /// the remote peer is returned as [`Error::RemoteClosedWithError`].
+        ///
         /// An `Error::IllegalState` will be returned if this is called after executing any of
         /// [`close`](#method.close), [`close_with_error`](#method.close_with_error) or
         /// [`on_close`](#method.on_close). This will cause the JoinHandle to be polled after
@@ -162,6 +307,9 @@ impl<R> ConnectionHandle<R> {
 
     /// Returns when the underlying event loop has stopped
     ///
+    /// If the remote peer closed the connection with an error, the error sent by the remote peer
+    /// is returned as [`Error::RemoteClosedWithError`].
+    ///
     /// An `Error::IllegalState` will be returned if this is called after executing any of
     /// [`close`](#method.close), [`close_with_error`](#method.close_with_error) or
     /// [`on_close`](#method.on_close). This will cause the JoinHandle to be polled after
@@ -182,6 +330,29 @@ impl<R> ConnectionHandle<R> {
         }
     }
 
+    /// Consume the closed connection and return the underlying IO
+    ///
+    /// `Io` must be the type of the stream the connection is opened with (ie. the stream after
+    /// the TLS and SASL negotiation if any). The write half of the IO has been shut down when
+    /// the connection was closed.
+    ///
+    /// [`IntoInnerIoError::NotClosed`] will be returned if the connection is not yet closed with
+    /// [`close`](#method.close), [`close_with_error`](#method.close_with_error) or
+    /// [`on_close`](#method.on_close), in which case the connection is closed when the handle is
+    /// dropped.
+    pub async fn into_inner_io<Io>(mut self) -> Result<Io, IntoInnerIoError>
+    where
+        Io: 'static,
+    {
+        if !self.is_closed {
+            return Err(IntoInnerIoError::NotClosed);
+        }
+        let io = (&mut self.handle).await?;
+        io.downcast::<Io>()
+            .map(|io| *io)
+            .map_err(|_| IntoInnerIoError::TypeMismatch)
+    }
+
     /// Allocte (channel, session_id) for a new session
     pub(crate) async fn allocate_session(
         &mut self,
@@ -502,6 +673,40 @@ impl Connection {
                 .open(url)
                 .await
         }
+
+        /// Same as [`open`](#method.open) but retries with `backoff` if the connection cannot
+        /// be opened because of a [transient](OpenError::is_transient) error, for example if
+        /// the remote peer refuses the connection because it is not up yet
+        ///
+        /// Other errors, like a failed SASL negotiation, are returned right away. Otherwise the
+        /// error of the last attempt is returned if all attempts fail.
+        ///
+        /// # Example
+        ///
+        /// ```rust, ignore
+        /// let backoff = Backoff {
+        ///     initial_delay: Duration::from_millis(500),
+        ///     multiplier: 2,
+        ///     max_attempts: Some(5),
+        ///     ..Default::default()
+        /// };
+        /// let connection = Connection::open_with_retry("connection-1", "amqp://localhost:5672", backoff)
+        ///     .await
+        ///     .unwrap();
+        /// ```
+        pub async fn open_with_retry(
+            container_id: impl Into<String>,
+            url: impl TryInto<Url, Error = impl Into<OpenError>>,
+            backoff: Backoff,
+        ) -> Result<ConnectionHandle<()>, OpenError> {
+            let container_id = container_id.into();
+            let url: Url = url.try_into().map_err(Into::into)?;
+            let mut connect = || Connection::open(container_id.clone(), url.clone());
+            let (connection, _) =
+                reconnect::open_with_backoff(&mut connect, &backoff, OpenError::is_transient)
+                    .await?;
+            Ok(connection)
+        }
     }
 }
 
@@ -546,6 +751,10 @@ impl endpoint::Connection for Connection {
         &self.local_open
     }
 
+    fn remote_open(&self) -> Option<&Open> {
+        self.remote_open.as_ref()
+    }
+
     fn allocate_session(
         &mut self,
         tx: Sender<SessionIncomingItem>,
@@ -580,6 +789,10 @@ impl endpoint::Connection for Connection {
             .remove(outgoing_channel.0 as usize);
     }
 
+    fn has_allocated_sessions(&self) -> bool {
+        !self.session_by_outgoing_channel.is_empty()
+    }
+
     /// Reacting to remote Open frame
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn on_incoming_open(
@@ -599,6 +812,14 @@ impl endpoint::Connection for Connection {
             _ => return Err(Self::OpenError::IllegalState),
         }
 
+        // The remote peer must not advertise a max-frame-size below the minimum. Otherwise the
+        // connection would be unusable
+        if (open.max_frame_size.0 as usize) < MIN_MAX_FRAME_SIZE {
+            return Err(Self::OpenError::TransportError(
+                transport::Error::FramingError,
+            ));
+        }
+
         // set channel_max to mutually acceptable
         self.agreed_channel_max = min(self.local_open.channel_max.0, open.channel_max.0);
         self.remote_open = Some(open);
@@ -0,0 +1,159 @@
+//! Redirection of a connection to another container host
+
+use std::convert::TryFrom;
+
+use fe2o3_amqp_types::{
+    definitions::{self, ConnectionError, ErrorCondition},
+    primitives::Value,
+};
+
+/// Connection capability indicating that a container only allows one connection per
+/// `container-id`
+///
+/// A peer that offers this capability may close an existing connection of the same container,
+/// or redirect it with [`RedirectInfo`].
+pub const SOLE_CONNECTION_FOR_CONTAINER: &str = "sole-connection-for-container";
+
+/// Address that a remote peer redirects a connection to when it closes the connection with an
+/// `amqp:connection:redirect` error
+///
+/// See 2.8.15 Connection Error in the AMQP1.0 specification
+///
+/// # Example
+///
+/// ```rust,ignore
+/// match connection.on_close().await {
+///     Err(error) => match error.redirect() {
+///         Some(redirect) => {
+///             let url = format!("amqp://{}:{}", redirect.network_host, redirect.port);
+///             let connection = Connection::builder()
+///                 .container_id("connection-1")
+///                 .hostname(redirect.hostname.as_deref())
+///                 .open(&url[..])
+///                 .await?;
+///         }
+///         None => { /* ... */ }
+///     },
+///     Ok(_) => { /* ... */ }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectInfo {
+    /// The hostname of the container, which should be supplied in the `hostname` field of the
+    /// Open frame and during the SASL and TLS negotiation
+    pub hostname: Option<String>,
+
+    /// The DNS hostname or IP address of the machine hosting the container
+    pub network_host: String,
+
+    /// The port number on the machine hosting the container
+    pub port: u16,
+}
+
+impl RedirectInfo {
+    /// Extracts the redirect address from an `amqp:connection:redirect` error
+    ///
+    /// `None` is returned if the error has a different condition, or if the `network-host` or
+    /// `port` is missing from the info map.
+    pub fn from_error(error: &definitions::Error) -> Option<Self> {
+        match &error.condition {
+            ErrorCondition::ConnectionError(ConnectionError::Redirect) => {}
+            _ => return None,
+        }
+        let info = error.info.as_ref()?;
+        let hostname = info.get("hostname").and_then(as_string);
+        let network_host = info.get("network-host").and_then(as_string)?;
+        let port = info.get("port").and_then(as_port)?;
+        Some(Self {
+            hostname,
+            network_host,
+            port,
+        })
+    }
+}
+
+fn as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Symbol(s) => Some(s.as_str().to_string()),
+        _ => None,
+    }
+}
+
+/// The port is a ushort in the specification, but some peers send it with a wider type or as
+/// a string
+fn as_port(value: &Value) -> Option<u16> {
+    match value {
+        Value::Ushort(port) => Some(*port),
+        Value::Uint(port) => u16::try_from(*port).ok(),
+        Value::Ulong(port) => u16::try_from(*port).ok(),
+        Value::Int(port) => u16::try_from(*port).ok(),
+        Value::Long(port) => u16::try_from(*port).ok(),
+        Value::String(port) => port.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        definitions::{self, AmqpError, ConnectionError, Fields},
+        primitives::{Symbol, Value},
+    };
+
+    use super::RedirectInfo;
+
+    fn redirect_error(info: Vec<(&str, Value)>) -> definitions::Error {
+        let info: Fields = info
+            .into_iter()
+            .map(|(key, value)| (Symbol::from(key), value))
+            .collect();
+        definitions::Error::new(ConnectionError::Redirect, None, info)
+    }
+
+    #[test]
+    fn redirect_close_is_parsed_into_redirect_info() {
+        let error = redirect_error(vec![
+            ("hostname", Value::from("container-1")),
+            ("network-host", Value::from("10.0.0.2")),
+            ("port", Value::Ushort(5673)),
+        ]);
+        assert_eq!(
+            RedirectInfo::from_error(&error),
+            Some(RedirectInfo {
+                hostname: Some("container-1".to_string()),
+                network_host: "10.0.0.2".to_string(),
+                port: 5673,
+            })
+        );
+
+        // The hostname is optional and the port may be sent with a wider type
+        let error = redirect_error(vec![
+            ("network-host", Value::from("broker.example.net")),
+            ("port", Value::Uint(5671)),
+        ]);
+        assert_eq!(
+            RedirectInfo::from_error(&error),
+            Some(RedirectInfo {
+                hostname: None,
+                network_host: "broker.example.net".to_string(),
+                port: 5671,
+            })
+        );
+    }
+
+    #[test]
+    fn incomplete_or_other_errors_are_not_redirects() {
+        let error = redirect_error(vec![("network-host", Value::from("10.0.0.2"))]);
+        assert_eq!(RedirectInfo::from_error(&error), None);
+
+        let error = redirect_error(vec![
+            ("network-host", Value::from("10.0.0.2")),
+            ("port", Value::Uint(70000)),
+        ]);
+        assert_eq!(RedirectInfo::from_error(&error), None);
+
+        let error = definitions::Error::new(AmqpError::ResourceLimitExceeded, None, None);
+        assert_eq!(RedirectInfo::from_error(&error), None);
+    }
+}
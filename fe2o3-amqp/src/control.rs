@@ -4,6 +4,8 @@ use fe2o3_amqp_types::{
     definitions::{self, ConnectionError},
     performatives::Disposition,
 };
+use std::time::Duration;
+
 use tokio::sync::{mpsc::Sender, oneshot};
 
 use crate::{
@@ -31,6 +33,7 @@ pub(crate) enum ConnectionControl {
     },
     DeallocateSession(OutgoingChannel),
     GetMaxFrameSize(oneshot::Sender<usize>),
+    SetIdleTimeout(Duration),
 }
 
 impl std::fmt::Display for ConnectionControl {
@@ -43,6 +46,7 @@ impl std::fmt::Display for ConnectionControl {
             } => write!(f, "AllocateSession"),
             Self::DeallocateSession(id) => write!(f, "DeallocateSession({})", id.0),
             Self::GetMaxFrameSize(_) => write!(f, "GetMaxFrameSize"),
+            Self::SetIdleTimeout(duration) => write!(f, "SetIdleTimeout({:?})", duration),
         }
     }
 }
@@ -69,6 +73,7 @@ pub(crate) enum SessionControl {
     // Transaction related controls
     #[cfg(feature = "transaction")]
     AllocateTransactionId {
+        global_id: Option<TransactionId>,
         resp: oneshot::Sender<Result<TransactionId, AllocTxnIdError>>,
     },
     #[cfg(feature = "transaction")]
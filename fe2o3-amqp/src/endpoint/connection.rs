@@ -25,6 +25,7 @@ pub(crate) trait Connection {
     fn local_state(&self) -> &Self::State;
     fn local_state_mut(&mut self) -> &mut Self::State;
     fn local_open(&self) -> &Open;
+    fn remote_open(&self) -> Option<&Open>;
 
     // Allocate outgoing channel id and session id to a new session
     fn allocate_session(
@@ -33,6 +34,8 @@ pub(crate) trait Connection {
     ) -> Result<OutgoingChannel, Self::AllocError>;
     // Remove outgoing id and session id association
     fn deallocate_session(&mut self, outgoing_channel: OutgoingChannel);
+    // Whether there is any session that is not deallocated yet
+    fn has_allocated_sessions(&self) -> bool;
 
     // async fn forward_to_session(&mut self, incoming_channel: u16, frame: SessionFrame) -> Result<(), Self::Error>;
 
@@ -98,6 +98,10 @@ pub(crate) trait Session {
     // Intercepting LinkFrames
     fn on_outgoing_attach(&mut self, attach: Attach) -> Result<SessionFrame, Self::Error>;
 
+    /// Restores the incoming-window once it is running low, returning the session Flow that
+    /// informs the remote peer
+    fn replenish_incoming_window(&mut self) -> Option<SessionFrame>;
+
     fn on_outgoing_flow(&mut self, flow: LinkFlow) -> Result<SessionFrame, Self::Error>;
 
     fn on_outgoing_transfer(
@@ -112,7 +116,7 @@ pub(crate) trait Session {
         disposition: Disposition,
     ) -> Result<SessionFrame, Self::Error>;
 
-    fn on_outgoing_detach(&mut self, detach: Detach) -> SessionFrame;
+    fn on_outgoing_detach(&mut self, detach: Detach) -> Option<SessionFrame>;
 }
 
 pub(crate) trait SessionExt: Session {
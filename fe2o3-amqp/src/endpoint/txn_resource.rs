@@ -10,7 +10,10 @@ use crate::transaction::AllocTxnIdError;
 use super::Session;
 
 pub(crate) trait HandleDeclare: Session {
-    fn allocate_transaction_id(&mut self) -> Result<TransactionId, AllocTxnIdError>;
+    fn allocate_transaction_id(
+        &mut self,
+        global_id: Option<TransactionId>,
+    ) -> Result<TransactionId, AllocTxnIdError>;
 }
 
 
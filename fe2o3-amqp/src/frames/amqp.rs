@@ -1,5 +1,7 @@
 //! AMQP frame type and corresponding encoder and decoder
 
+use std::io;
+
 use bytes::{Buf, BufMut, BytesMut};
 use fe2o3_amqp_types::performatives::{
     Attach, Begin, Close, Detach, Disposition, End, Flow, Open, Performative, Transfer,
@@ -101,6 +103,13 @@ impl FrameEncoder {
             let writer = (&mut buf).writer();
             let mut serializer = Serializer::from(writer);
             transfer.serialize(&mut serializer)?;
+            // At least one byte of the payload must fit in each frame
+            if buf.len() >= self.max_frame_body_size {
+                return Err(serde_amqp::Error::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Transfer performative exceeds the max frame size",
+                )));
+            }
             let split_index = self.max_frame_body_size - buf.len();
 
             // Send first frame
@@ -356,4 +365,114 @@ mod tests {
         let mut src = BytesMut::from(&[0x02, 0x00, 0x00, 0x00][..]);
         let _frame = decoder.decode(&mut src).unwrap();
     }
+
+    #[test]
+    fn test_encode_transfer_spanning_three_frames() {
+        use bytes::Bytes;
+        use fe2o3_amqp_types::{
+            definitions::{DeliveryTag, Handle},
+            messaging::MESSAGE_FORMAT,
+            performatives::Transfer,
+        };
+
+        use super::FrameBody;
+
+        let max_frame_size = 512;
+        let transfer = Transfer {
+            handle: Handle::from(0),
+            delivery_id: Some(0),
+            delivery_tag: Some(DeliveryTag::from(vec![0u8; 4])),
+            message_format: Some(MESSAGE_FORMAT),
+            settled: Some(false),
+            more: false,
+            rcv_settle_mode: None,
+            state: None,
+            resume: false,
+            aborted: false,
+            batchable: false,
+        };
+        let payload = Bytes::from((0..1200u32).map(|i| i as u8).collect::<Vec<u8>>());
+        let frame = Frame::new(
+            1u16,
+            FrameBody::Transfer {
+                performative: transfer,
+                payload: payload.clone(),
+            },
+        );
+
+        let mut encoder = FrameEncoder::new(max_frame_size);
+        let mut dst = BytesMut::new();
+        encoder.encode(frame, &mut dst).unwrap();
+
+        let mut decoder = FrameDecoder {};
+        let mut frames = Vec::new();
+        while !dst.is_empty() {
+            let len = std::cmp::min(dst.len(), max_frame_size);
+            let mut src = dst.split_to(len);
+            let frame = decoder.decode(&mut src).unwrap().unwrap();
+            assert_eq!(frame.channel, 1);
+            match frame.body {
+                FrameBody::Transfer {
+                    performative,
+                    payload,
+                } => frames.push((performative, payload)),
+                _ => panic!("Expecting a transfer"),
+            }
+        }
+
+        assert_eq!(frames.len(), 3);
+        let (first, _) = &frames[0];
+        assert!(first.more);
+        assert!(first.delivery_tag.is_some());
+        let (middle, _) = &frames[1];
+        assert!(middle.more);
+        assert!(middle.delivery_tag.is_none());
+        let (last, _) = &frames[2];
+        assert!(!last.more);
+
+        let reassembled: Vec<u8> = frames
+            .iter()
+            .flat_map(|(_, partial)| partial.iter().copied())
+            .collect();
+        assert_eq!(&reassembled[..], &payload[..]);
+    }
+
+    #[test]
+    fn test_encode_transfer_with_performative_exceeding_max_frame_size() {
+        use bytes::Bytes;
+        use fe2o3_amqp_types::{
+            definitions::{self, AmqpError, DeliveryTag, Handle},
+            messaging::{DeliveryState, Rejected, MESSAGE_FORMAT},
+            performatives::Transfer,
+        };
+
+        use super::FrameBody;
+
+        let error = definitions::Error::new(AmqpError::InternalError, "x".repeat(600), None);
+        let state = Rejected { error: Some(error) };
+        let transfer = Transfer {
+            handle: Handle::from(0),
+            delivery_id: Some(0),
+            delivery_tag: Some(DeliveryTag::from(vec![0u8; 4])),
+            message_format: Some(MESSAGE_FORMAT),
+            settled: Some(false),
+            more: false,
+            rcv_settle_mode: None,
+            state: Some(DeliveryState::Rejected(state)),
+            resume: false,
+            aborted: false,
+            batchable: false,
+        };
+        let frame = Frame::new(
+            1u16,
+            FrameBody::Transfer {
+                performative: transfer,
+                payload: Bytes::from_static(b"payload"),
+            },
+        );
+
+        let mut encoder = FrameEncoder::new(512);
+        let mut dst = BytesMut::new();
+        assert!(encoder.encode(frame, &mut dst).is_err());
+    }
 }
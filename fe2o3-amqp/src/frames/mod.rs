@@ -9,5 +9,8 @@ pub const FRAME_TYPE_AMQP: u8 = 0x00;
 /// Type byte of SASL frame
 pub const FRAME_TYPE_SASL: u8 = 0x01;
 
+/// Size in bytes of the frame size and the rest of the frame header without extended header
+pub(crate) const FRAME_HEADER_SIZE: usize = 8;
+
 mod error;
 pub use error::Error;
@@ -25,8 +25,12 @@
 //! |`"rustls"`| enables TLS integration with `tokio-rustls` and `rustls` |
 //! |`"native-tls"`| enables TLS integration with `tokio-native-tls` and `native-tls`|
 //! |`"acceptor"`| enables `ConnectionAcceptor`, `SessionAcceptor`, and `LinkAcceptor`|
+//! |`"test-server"`| enables `acceptor::test_server::TestServer`, a scripted server for testing client code |
 //! |`"transaction"`| enables `Controller`, `Transaction`, `OwnedTransaction` and `control_link_acceptor` |
 //! |`"scram"`| enables SCRAM auth |
+//! |`"blocking"`| enables `blocking`, which wraps `Connection`, `Session`, `Sender` and `Receiver` with blocking calls |
+//! |`"websocket"`| enables opening a `Connection` with `"ws"` and `"wss"` urls using `fe2o3-amqp-ws` |
+//! |`"uuid"`| enables conversion from `uuid::Uuid` to `MessageId` |
 //! |`"tracing"`| enables logging with `tracing` |
 //! |`"log"`| enables logging with `log` |
 //!
@@ -207,6 +211,14 @@ cfg_transaction! {
     pub mod transaction;
 }
 
+cfg_blocking! {
+    pub mod blocking;
+}
+
+cfg_not_wasm32! {
+    pub mod request_reply;
+}
+
 pub mod types {
     //! Re-exporting `fe2o3-amqp-types`
     pub use fe2o3_amqp_types::*;
@@ -3,20 +3,22 @@
 use std::{
     marker::PhantomData,
     sync::{atomic::AtomicU32, Arc},
+    time::Duration,
 };
 
 use fe2o3_amqp_types::{
     definitions::{Fields, ReceiverSettleMode, SenderSettleMode, SequenceNo},
-    messaging::{Source, Target, TargetArchetype},
-    primitives::{Symbol, Ulong},
+    messaging::{FilterSet, Source, Target, TargetArchetype},
+    primitives::{Symbol, Ulong, Value},
 };
 use parking_lot::RwLock;
+use serde_amqp::described::Described;
 use tokio::sync::{mpsc, Notify};
 
 use crate::{
     connection::DEFAULT_OUTGOING_BUFFER_SIZE,
     endpoint::{LinkExt, OutputHandle},
-    link::{Link, LinkIncomingItem, LinkRelay},
+    link::{Link, LinkIncomingItem, LinkRelay, SettledNotify},
     session::{self, SessionHandle},
     util::{Consumer, Producer},
 };
@@ -112,6 +114,26 @@ pub struct Builder<Role, T, NameState, SS, TS> {
     /// `false`
     pub auto_accept: bool,
 
+    /// Whether the receiver will automatically settle incoming deliveries whose
+    /// `absolute-expiry-time` has passed instead of returning them to the user
+    ///
+    /// This field has no effect on Sender
+    ///
+    /// # Default
+    ///
+    /// `false`
+    pub drop_expired: bool,
+
+    /// How long `Sender::send` waits for the remote peer to settle a delivery before it fails
+    /// with `SendError::SettlementTimeout`
+    ///
+    /// This field has no effect on Receiver
+    ///
+    /// # Default
+    ///
+    /// `None`
+    pub settlement_timeout: Option<Duration>,
+
     /// Whether to verify the `source` field of the incoming Attach frame
     ///
     /// Default to true
@@ -151,6 +173,8 @@ impl<Role, T> Default for Builder<Role, T, WithoutName, WithoutSource, WithoutTa
             target_state: PhantomData,
 
             auto_accept: false,
+            drop_expired: false,
+            settlement_timeout: None,
             verify_incoming_source: true,
             verify_incoming_target: true,
         }
@@ -173,15 +197,35 @@ impl Builder<role::ReceiverMarker, Target, WithoutName, WithoutSource, WithTarge
 impl<T, NameState, SS, TS> Builder<role::ReceiverMarker, T, NameState, SS, TS> {
     /// Sets the `auto_accept` field.
     ///
+    /// If enabled, every delivery is accepted right after it is returned by `recv()`, which is
+    /// only meaningful in `ReceiverSettleMode::First`. Attaching a receiver with both
+    /// `auto_accept` and `ReceiverSettleMode::Second` fails with
+    /// `ReceiverAttachError::AutoAcceptWithRcvSettleModeSecond`.
+    ///
     /// Default value: `false`
     pub fn auto_accept(mut self, value: bool) -> Self {
         self.auto_accept = value;
         self
     }
+
+    /// Sets the `drop_expired` field.
+    ///
+    /// If enabled, an incoming delivery whose `absolute-expiry-time` has passed is settled with
+    /// a `Modified` outcome with `delivery-failed` set and is not returned to the user. The
+    /// expiry time is compared against the system clock, and this has no effect on wasm32 targets.
+    ///
+    /// Default value: `false`
+    pub fn drop_expired(mut self, value: bool) -> Self {
+        self.drop_expired = value;
+        self
+    }
 }
 
 impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
     /// The name of the link
+    ///
+    /// A unique name is generated when the link is attached if the name is empty, which can be
+    /// retrieved with `name()` of the attached link.
     pub fn name(self, name: impl Into<String>) -> Builder<Role, T, WithName, SS, TS> {
         Builder {
             name: name.into(),
@@ -195,7 +239,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             desired_capabilities: self.desired_capabilities,
             buffer_size: self.buffer_size,
             credit_mode: self.credit_mode,
-            properties: Default::default(),
+            properties: self.properties,
 
             role: self.role,
             name_state: PhantomData,
@@ -203,6 +247,8 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: self.target_state,
 
             auto_accept: self.auto_accept,
+            drop_expired: self.drop_expired,
+            settlement_timeout: self.settlement_timeout,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
         }
@@ -222,7 +268,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             desired_capabilities: self.desired_capabilities,
             buffer_size: self.buffer_size,
             credit_mode: self.credit_mode,
-            properties: Default::default(),
+            properties: self.properties,
 
             role: PhantomData,
             name_state: self.name_state,
@@ -230,6 +276,8 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: self.target_state,
 
             auto_accept: self.auto_accept,
+            drop_expired: self.drop_expired,
+            settlement_timeout: self.settlement_timeout,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
         }
@@ -249,7 +297,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             desired_capabilities: self.desired_capabilities,
             buffer_size: self.buffer_size,
             credit_mode: self.credit_mode,
-            properties: Default::default(),
+            properties: self.properties,
 
             role: PhantomData,
             name_state: self.name_state,
@@ -257,6 +305,8 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: self.target_state,
 
             auto_accept: self.auto_accept,
+            drop_expired: self.drop_expired,
+            settlement_timeout: self.settlement_timeout,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
         }
@@ -296,6 +346,8 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: self.target_state,
 
             auto_accept: self.auto_accept,
+            drop_expired: self.drop_expired,
+            settlement_timeout: self.settlement_timeout,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
         }
@@ -318,7 +370,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             desired_capabilities: self.desired_capabilities,
             buffer_size: self.buffer_size,
             credit_mode: self.credit_mode,
-            properties: Default::default(),
+            properties: self.properties,
 
             role: self.role,
             name_state: self.name_state,
@@ -326,6 +378,8 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: PhantomData,
 
             auto_accept: self.auto_accept,
+            drop_expired: self.drop_expired,
+            settlement_timeout: self.settlement_timeout,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
         }
@@ -349,7 +403,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
                 desired_capabilities: self.desired_capabilities,
                 buffer_size: self.buffer_size,
                 credit_mode: self.credit_mode,
-                properties: Default::default(),
+                properties: self.properties,
 
                 role: self.role,
                 name_state: self.name_state,
@@ -357,6 +411,8 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
                 target_state: PhantomData,
 
                 auto_accept: self.auto_accept,
+                drop_expired: self.drop_expired,
+                settlement_timeout: self.settlement_timeout,
                 verify_incoming_source: self.verify_incoming_source,
                 verify_incoming_target: self.verify_incoming_target,
             }
@@ -463,6 +519,19 @@ impl<T, NameState, SS, TS> Builder<role::SenderMarker, T, NameState, SS, TS> {
         self.initial_delivery_count = count;
         self
     }
+
+    /// Sets the `settlement_timeout` field.
+    ///
+    /// If set, [`Sender::send`] and [`Sender::send_ref`] fail with
+    /// `SendError::SettlementTimeout` if the remote peer does not settle the delivery within the
+    /// duration after it is transferred. The delivery stays in the unsettled map, so that a late
+    /// disposition is still handled. The timeout is not applied on wasm32 targets.
+    ///
+    /// Default value: `None`
+    pub fn settlement_timeout(mut self, duration: impl Into<Option<Duration>>) -> Self {
+        self.settlement_timeout = duration.into();
+        self
+    }
 }
 
 impl<T, NameState, SS, TS> Builder<role::ReceiverMarker, T, NameState, SS, TS> {
@@ -477,6 +546,29 @@ impl<T, NameState, SS, TS> Builder<role::ReceiverMarker, T, NameState, SS, TS> {
     }
 }
 
+impl<T, NameState, TS> Builder<role::ReceiverMarker, T, NameState, WithSource, TS> {
+    /// Adds an entry to the `filter` field of the source, ie. a JMS selector with the key
+    /// `"apache.org:selector-filter:string"` or `"apache.org:no-local-filter:list"`.
+    ///
+    /// A `None` value adds the key with a null value. The filters actually in place are set by
+    /// the remote peer on the source of its Attach, which can be found with
+    /// [`Receiver::source_filter`] once attached.
+    pub fn source_filter(
+        mut self,
+        key: impl Into<Symbol>,
+        value: impl Into<Option<Described<Value>>>,
+    ) -> Self {
+        if let Some(source) = &mut self.source {
+            let filter = source.filter.get_or_insert_with(FilterSet::new);
+            match value.into() {
+                Some(described) => filter.insert(key.into(), Value::Described(Box::new(described))),
+                None => filter.insert_null(key),
+            };
+        }
+        self
+    }
+}
+
 impl Builder<role::SenderMarker, Target, WithName, WithSource, WithTarget> {
     /// Attach the link as a sender
     ///
@@ -496,6 +588,15 @@ impl Builder<role::SenderMarker, Target, WithName, WithSource, WithTarget> {
         self,
         session: &mut SessionHandle<R>,
     ) -> Result<Sender, SenderAttachError> {
+        if let Some(target) = &self.target {
+            if target.dynamic && target.address.is_some() {
+                return Err(SenderAttachError::TargetAddressIsSomeWhenDynamicIsTrue);
+            }
+            if !target.dynamic && target.dynamic_node_properties.is_some() {
+                return Err(SenderAttachError::DynamicNodePropertiesIsSomeWhenDynamicIsFalse);
+            }
+        }
+
         self.attach_inner(session)
             .await
             .map(|inner| Sender { inner })
@@ -533,11 +634,15 @@ where
         session: &mut SessionHandle<R>,
     ) -> Result<SenderInner<SenderLink<T>>, SenderAttachError> {
         let buffer_size = self.buffer_size;
+        let settlement_timeout = self.settlement_timeout;
         let (incoming_tx, mut incoming_rx) = mpsc::channel::<LinkIncomingItem>(self.buffer_size);
         let outgoing = session.outgoing.clone();
         let (producer, consumer) = self.create_flow_state_containers();
         let unsettled = Arc::new(RwLock::new(None));
 
+        if self.name.is_empty() {
+            self.name = super::generate_link_name();
+        }
         let link_relay = LinkRelay::new_sender(incoming_tx, producer, unsettled.clone());
         let output_handle =
             session::allocate_link(&session.control, self.name.clone(), link_relay).await?;
@@ -575,6 +680,7 @@ where
         let inner = SenderInner {
             link,
             buffer_size,
+            settlement_timeout,
             session: session.control.clone(),
             outgoing,
             incoming: incoming_rx,
@@ -635,6 +741,18 @@ where
         mut self,
         session: &mut SessionHandle<R>,
     ) -> Result<ReceiverInner<ReceiverLink<T>>, ReceiverAttachError> {
+        if self.auto_accept && self.rcv_settle_mode == ReceiverSettleMode::Second {
+            return Err(ReceiverAttachError::AutoAcceptWithRcvSettleModeSecond);
+        }
+        if let Some(source) = &self.source {
+            if source.dynamic && source.address.is_some() {
+                return Err(ReceiverAttachError::SourceAddressIsSomeWhenDynamicIsTrue);
+            }
+            if !source.dynamic && source.dynamic_node_properties.is_some() {
+                return Err(ReceiverAttachError::DynamicNodePropertiesIsSomeWhenDynamicIsFalse);
+            }
+        }
+
         // TODO: how to avoid clone?
         let buffer_size = self.buffer_size;
         let credit_mode = self.credit_mode.clone();
@@ -642,14 +760,20 @@ where
         let outgoing = session.outgoing.clone();
         let (relay_flow_state, flow_state) = self.create_flow_state_containers();
         let unsettled = Arc::new(RwLock::new(None));
+        let settled = Arc::new(SettledNotify::default());
         let auto_accept = self.auto_accept;
+        let drop_expired = self.drop_expired;
 
         let link_relay = LinkRelay::new_receiver(
             incoming_tx,
             relay_flow_state,
             unsettled.clone(),
             self.rcv_settle_mode.clone(),
+            settled.clone(),
         );
+        if self.name.is_empty() {
+            self.name = super::generate_link_name();
+        }
         // Create Link in Session
         // Any error here will be on the Session level and thus it should immediately return with an error
         let output_handle =
@@ -680,6 +804,9 @@ where
             credit_mode,
             processed: AtomicU32::new(0),
             auto_accept,
+            drop_expired,
+            drained: false,
+            settled,
             session: session.control.clone(),
             outgoing,
             incoming: incoming_rx,
@@ -709,3 +836,36 @@ cfg_transaction! {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::primitives::{OrderedMap, Symbol, Value};
+
+    use crate::{Receiver, Sender};
+
+    #[test]
+    fn test_named_builder_keeps_fields() {
+        let mut properties = OrderedMap::new();
+        properties.insert(Symbol::from("key"), Value::from("value"));
+
+        let builder = Sender::builder()
+            .properties(properties.clone())
+            .max_message_size(1024u64)
+            .name("sender-link")
+            .source("source-addr")
+            .target("target-addr");
+        assert_eq!(builder.name, "sender-link");
+        assert_eq!(builder.source.unwrap().address.unwrap(), "source-addr");
+        assert_eq!(builder.target.unwrap().address.unwrap(), "target-addr");
+        assert_eq!(builder.max_message_size, Some(1024));
+        assert_eq!(builder.properties, Some(properties.clone()));
+
+        let builder = Receiver::builder()
+            .properties(properties.clone())
+            .name("receiver-link")
+            .receiver()
+            .source("source-addr");
+        assert_eq!(builder.name, "receiver-link");
+        assert_eq!(builder.properties, Some(properties));
+    }
+}
@@ -2,7 +2,10 @@
 
 use fe2o3_amqp_types::{
     definitions::{DeliveryNumber, DeliveryTag, Handle, MessageFormat, ReceiverSettleMode},
-    messaging::{Accepted, DeliveryState, Message, Outcome, SerializableBody, MESSAGE_FORMAT},
+    messaging::{
+        Accepted, DeliveryAnnotations, DeliveryState, Footer, Message, MessageAnnotations, Outcome,
+        SerializableBody, MESSAGE_FORMAT,
+    },
     primitives::BinaryRef,
 };
 use futures_util::FutureExt;
@@ -108,8 +111,8 @@ impl<T> Delivery<T> {
     }
 
     /// Get the delivery ID
-    pub fn delivery_id(&self) -> &DeliveryNumber {
-        &self.delivery_id
+    pub fn delivery_id(&self) -> DeliveryNumber {
+        self.delivery_id
     }
 
     /// Get the delivery tag
@@ -137,6 +140,21 @@ impl<T> Delivery<T> {
         self.message.body
     }
 
+    /// Get a reference to the delivery annotations of the message, if any
+    pub fn delivery_annotations(&self) -> Option<&DeliveryAnnotations> {
+        self.message.delivery_annotations.as_ref()
+    }
+
+    /// Get a reference to the message annotations of the message, if any
+    pub fn message_annotations(&self) -> Option<&MessageAnnotations> {
+        self.message.message_annotations.as_ref()
+    }
+
+    /// Get a reference to the footer of the message, if any
+    pub fn footer(&self) -> Option<&Footer> {
+        self.message.footer.as_ref()
+    }
+
     /// Consume the delivery into the delivery info and message.
     /// The message format will be lost.
     pub fn into_parts(self) -> (DeliveryInfo, Message<T>) {
@@ -1,4 +1,7 @@
-use fe2o3_amqp_types::definitions::{self, AmqpError, ErrorCondition, SessionError};
+use fe2o3_amqp_types::{
+    definitions::{self, AmqpError, ErrorCondition, SessionError},
+    messaging::Outcome,
+};
 use serde_amqp::primitives::Symbol;
 use tokio::sync::TryLockError;
 
@@ -48,9 +51,10 @@ pub enum SenderAttachError {
     #[error("Illegal session state. Session might have stopped.")]
     IllegalSessionState,
 
-    /// Link name duplicated
-    #[error("Link name is not unique.")]
-    DuplicatedLinkName,
+    /// Link name duplicated. This is checked locally before the Attach frame is sent, and the
+    /// conflicting link name is carried in the error
+    #[error("Link name {0:?} is already in use in this session")]
+    DuplicatedLinkName(String),
 
     /// Illegal link state
     #[error("Illegal session state")]
@@ -116,6 +120,12 @@ pub enum SenderAttachError {
     /// Remote peer closed the link with an error
     #[error("Remote peer closed with error {:?}", .0)]
     RemoteClosedWithError(definitions::Error),
+
+    /// When dynamic is set to true by the sending link endpoint, this field constitutes a request
+    /// for the receiving peer to dynamically create a node at the target. In this case the address
+    /// field MUST NOT be set. This is checked locally before the Attach frame is sent.
+    #[error("Target address must not be set when dynamic is set by the sending endpoint")]
+    TargetAddressIsSomeWhenDynamicIsTrue,
 }
 
 /// Error associated with sending a message
@@ -141,6 +151,50 @@ pub enum SendError {
     /// Error serializing message
     #[error("Error encoding message")]
     MessageEncodeError,
+
+    /// The message is not settled by the remote peer within the given duration
+    #[error("Message is not settled within the timeout")]
+    SettlementTimeout,
+
+    /// The transfer performative does not fit within the max frame size of the connection.
+    ///
+    /// Only the message payload can be split across multiple transfer frames, so this is
+    /// usually caused by a large delivery state.
+    #[error("Transfer frame size {size} exceeds the max frame size {max}")]
+    MaxFrameSizeExceeded {
+        /// Size of the first transfer frame of the delivery, including at least one byte of the
+        /// payload
+        size: usize,
+
+        /// Max frame size negotiated with the remote peer
+        max: usize,
+    },
+
+    /// The encoded message is larger than the max message size of the link
+    #[error("Message size {size} exceeds the max message size {max}")]
+    MessageSizeExceeded {
+        /// Size of the encoded message
+        size: u64,
+
+        /// Max message size negotiated with the remote peer
+        max: u64,
+    },
+
+    /// A pre-settled message is sent on a link whose negotiated `SenderSettleMode` is
+    /// `Unsettled`
+    #[error("Pre-settled message is not allowed with SenderSettleMode::Unsettled")]
+    IllegalSenderSettleMode,
+}
+
+/// Error with sending a batch of messages with [`Sender::send_batch`](super::Sender::send_batch)
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to send {} of the batch of messages", .results.iter().filter(|r| r.is_err()).count())]
+pub struct SendBatchError {
+    /// The result of each message that has been attempted, in the same order as the batch.
+    ///
+    /// Sending stops at the first message that fails to be transferred, so the messages after
+    /// the last entry have not been sent.
+    pub results: Vec<Result<Outcome, SendError>>,
 }
 
 impl From<serde_amqp::Error> for SendError {
@@ -155,6 +209,32 @@ impl From<DetachError> for SendError {
     }
 }
 
+impl From<MaxFrameSizeExceeded> for SendError {
+    fn from(MaxFrameSizeExceeded { size, max }: MaxFrameSizeExceeded) -> Self {
+        Self::MaxFrameSizeExceeded { size, max }
+    }
+}
+
+/// The transfer performative does not fit within the max frame size of the connection
+#[derive(Debug)]
+pub(crate) struct MaxFrameSizeExceeded {
+    pub size: usize,
+    pub max: usize,
+}
+
+impl From<MessageSizeExceeded> for SendError {
+    fn from(MessageSizeExceeded { size, max }: MessageSizeExceeded) -> Self {
+        Self::MessageSizeExceeded { size, max }
+    }
+}
+
+/// The encoded message is larger than the max message size of the link
+#[derive(Debug)]
+pub(crate) struct MessageSizeExceeded {
+    pub size: u64,
+    pub max: u64,
+}
+
 /// Error with the sender trying consume link credit
 ///
 /// This is only used in
@@ -202,9 +282,10 @@ pub enum ReceiverAttachError {
     #[error("Illegal session state. Session might have stopped.")]
     IllegalSessionState,
 
-    /// Link name is already in use
-    #[error("Link name is not unique.")]
-    DuplicatedLinkName,
+    /// Link name is already in use. This is checked locally before the Attach frame is sent, and
+    /// the conflicting link name is carried in the error
+    #[error("Link name {0:?} is already in use in this session")]
+    DuplicatedLinkName(String),
 
     /// Illegal link state
     #[error("Illegal session state")]
@@ -272,13 +353,24 @@ pub enum ReceiverAttachError {
     /// The desired filter(s) on the receiver is not supported by the remote peer
     #[error("{:?}", .0)]
     DesiredFilterNotSupported(#[from] DesiredFilterNotSupported),
+
+    /// `auto_accept` is enabled on a receiver in `ReceiverSettleMode::Second`. This is checked
+    /// locally before the Attach frame is sent.
+    #[error("Auto accept is not supported with ReceiverSettleMode::Second")]
+    AutoAcceptWithRcvSettleModeSecond,
+
+    /// When set to true by the receiving link endpoint, this field constitutes a request for the
+    /// sending peer to dynamically create a node at the source. In this case the address field
+    /// MUST NOT be set. This is checked locally before the Attach frame is sent.
+    #[error("Source address must not be set when dynamic is set by the receiving endpoint")]
+    SourceAddressIsSomeWhenDynamicIsTrue,
 }
 
 impl From<AllocLinkError> for ReceiverAttachError {
     fn from(value: AllocLinkError) -> Self {
         match value {
             AllocLinkError::IllegalSessionState => Self::IllegalSessionState,
-            AllocLinkError::DuplicatedLinkName => Self::DuplicatedLinkName,
+            AllocLinkError::DuplicatedLinkName(name) => Self::DuplicatedLinkName(name),
         }
     }
 }
@@ -289,7 +381,7 @@ impl<'a> TryFrom<&'a ReceiverAttachError> for definitions::Error {
     fn try_from(value: &'a ReceiverAttachError) -> Result<Self, Self::Error> {
         let condition: ErrorCondition = match value {
             ReceiverAttachError::IllegalSessionState => AmqpError::IllegalState.into(),
-            ReceiverAttachError::DuplicatedLinkName => SessionError::HandleInUse.into(),
+            ReceiverAttachError::DuplicatedLinkName(_) => SessionError::HandleInUse.into(),
             ReceiverAttachError::IllegalState => AmqpError::IllegalState.into(),
             ReceiverAttachError::NonAttachFrameReceived => AmqpError::NotAllowed.into(),
             ReceiverAttachError::ExpectImmediateDetach => AmqpError::NotAllowed.into(),
@@ -315,7 +407,7 @@ impl From<AllocLinkError> for SenderAttachError {
     fn from(value: AllocLinkError) -> Self {
         match value {
             AllocLinkError::IllegalSessionState => Self::IllegalSessionState,
-            AllocLinkError::DuplicatedLinkName => Self::DuplicatedLinkName,
+            AllocLinkError::DuplicatedLinkName(name) => Self::DuplicatedLinkName(name),
         }
     }
 }
@@ -362,7 +454,7 @@ impl<'a> TryFrom<&'a SenderAttachError> for definitions::Error {
     fn try_from(value: &'a SenderAttachError) -> Result<Self, Self::Error> {
         let condition: ErrorCondition = match value {
             SenderAttachError::IllegalSessionState => AmqpError::IllegalState.into(),
-            SenderAttachError::DuplicatedLinkName => SessionError::HandleInUse.into(),
+            SenderAttachError::DuplicatedLinkName(_) => SessionError::HandleInUse.into(),
             SenderAttachError::IllegalState => AmqpError::IllegalState.into(),
             SenderAttachError::NonAttachFrameReceived => AmqpError::NotAllowed.into(),
             SenderAttachError::ExpectImmediateDetach => AmqpError::NotAllowed.into(),
@@ -570,6 +662,12 @@ impl From<ReceiverTransferError> for RecvError {
 }
 
 /// Type alias for disposition error
+///
+/// Settling a delivery with [`Receiver::accept`](crate::Receiver::accept),
+/// [`Receiver::reject`](crate::Receiver::reject), [`Receiver::release`](crate::Receiver::release)
+/// or [`Receiver::modify`](crate::Receiver::modify) returns
+/// [`IllegalLinkStateError::IllegalSessionState`] if the session has already ended, in which case
+/// the disposition was not sent to the remote peer.
 pub type DispositionError = IllegalLinkStateError;
 
 /// Type alias for flow error
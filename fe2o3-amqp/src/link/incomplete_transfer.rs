@@ -63,15 +63,14 @@ impl IncompleteTransfer {
         // value of the settled flag on any of the preceding transfers was true;
         // if no preceding transfer was sent with settled being true then the
         // value when unset MUST be taken as false.
-        match &self.performative.settled {
-            Some(value) => {
-                if let Some(other_value) = other.settled {
-                    if !value {
-                        self.performative.settled = Some(other_value);
-                    }
-                }
+        //
+        // A delivery that is settled by the sender cannot be unsettled by a later transfer
+        match (self.performative.settled, other.settled) {
+            (Some(true), Some(false)) => {
+                return Err(ReceiverTransferError::InconsistentFieldInMultiFrameDelivery)
             }
-            None => self.performative.settled = other.settled,
+            (Some(true), _) | (_, None) => {}
+            (_, Some(other_value)) => self.performative.settled = Some(other_value),
         }
 
         if let Some(other_state) = other.state {
@@ -163,3 +162,61 @@ impl IncompleteTransfer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use fe2o3_amqp_types::{definitions::Handle, performatives::Transfer, primitives::Binary};
+
+    use crate::link::ReceiverTransferError;
+
+    use super::IncompleteTransfer;
+
+    fn transfer(delivery_tag: Option<&[u8]>, settled: Option<bool>) -> Transfer {
+        Transfer {
+            handle: Handle(0),
+            delivery_id: delivery_tag.map(|_| 0),
+            delivery_tag: delivery_tag.map(|tag| Binary::from(tag.to_vec())),
+            message_format: delivery_tag.map(|_| 0),
+            settled,
+            more: true,
+            rcv_settle_mode: None,
+            state: None,
+            resume: false,
+            aborted: false,
+            batchable: false,
+        }
+    }
+
+    #[test]
+    fn continuation_may_omit_or_repeat_fields() {
+        let mut incomplete = IncompleteTransfer::new(transfer(Some(b"tag"), None), Bytes::new());
+        incomplete.or_assign(transfer(None, None)).unwrap();
+        incomplete
+            .or_assign(transfer(Some(b"tag"), Some(true)))
+            .unwrap();
+        incomplete.or_assign(transfer(None, None)).unwrap();
+        assert_eq!(incomplete.performative.settled, Some(true));
+    }
+
+    #[test]
+    fn continuation_with_different_delivery_tag_is_inconsistent() {
+        let mut incomplete = IncompleteTransfer::new(transfer(Some(b"tag"), None), Bytes::new());
+        let result = incomplete.or_assign(transfer(Some(b"other"), None));
+        assert!(matches!(
+            result,
+            Err(ReceiverTransferError::InconsistentFieldInMultiFrameDelivery)
+        ));
+    }
+
+    #[test]
+    fn continuation_cannot_unsettle_a_settled_delivery() {
+        let mut incomplete =
+            IncompleteTransfer::new(transfer(Some(b"tag"), Some(true)), Bytes::new());
+        let result = incomplete.or_assign(transfer(None, Some(false)));
+        assert!(matches!(
+            result,
+            Err(ReceiverTransferError::InconsistentFieldInMultiFrameDelivery)
+        ));
+    }
+}
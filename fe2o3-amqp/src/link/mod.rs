@@ -1,6 +1,12 @@
 //! Implements AMQP1.0 Link
 
-use std::{marker::PhantomData, sync::Arc};
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use bytes::{BufMut, BytesMut};
 use fe2o3_amqp_types::{
@@ -20,7 +26,7 @@ pub use receiver::Receiver;
 pub use sender::Sender;
 use serde::Serialize;
 use serde_amqp::ser::Serializer;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{futures::Notified, mpsc, oneshot, Notify};
 
 use crate::{
     control::SessionControl,
@@ -526,6 +532,50 @@ where
     }
 }
 
+/// Wakes up a receiver that waits for the remote sender to settle a delivery
+///
+/// It is closed once the session no longer relays frames to the receiver, ie. when the link is
+/// detached or the session ends, so that the receiver stops waiting
+#[derive(Debug, Default)]
+pub(crate) struct SettledNotify {
+    notify: Notify,
+    closed: AtomicBool,
+}
+
+impl SettledNotify {
+    pub(crate) fn notified(&self) -> Notified<'_> {
+        self.notify.notified()
+    }
+
+    pub(crate) fn notify_waiters(&self) {
+        self.notify.notify_waiters()
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.notify.notify_waiters()
+    }
+
+    pub(crate) fn reopen(&self) {
+        self.closed.store(false, Ordering::Release);
+    }
+}
+
+/// The relay's handle to the [`SettledNotify`] of a receiver, which closes it when the relay is
+/// dropped
+#[derive(Debug)]
+pub(crate) struct SettledNotifier(pub(crate) Arc<SettledNotify>);
+
+impl Drop for SettledNotifier {
+    fn drop(&mut self) {
+        self.0.close()
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum LinkRelay<O> {
     Sender {
@@ -543,6 +593,7 @@ pub(crate) enum LinkRelay<O> {
         flow_state: ReceiverRelayFlowState,
         unsettled: ArcReceiverUnsettledMap,
         receiver_settle_mode: ReceiverSettleMode,
+        settled: SettledNotifier,
         more: bool,
     },
 }
@@ -567,6 +618,7 @@ impl LinkRelay<()> {
         flow_state: ReceiverRelayFlowState,
         unsettled: ArcReceiverUnsettledMap,
         receiver_settle_mode: ReceiverSettleMode,
+        settled: Arc<SettledNotify>,
     ) -> Self {
         Self::Receiver {
             tx,
@@ -574,6 +626,7 @@ impl LinkRelay<()> {
             flow_state,
             unsettled,
             receiver_settle_mode,
+            settled: SettledNotifier(settled),
             more: false,
         }
     }
@@ -598,6 +651,7 @@ impl LinkRelay<()> {
                 flow_state,
                 unsettled,
                 receiver_settle_mode,
+                settled,
                 more,
                 ..
             } => LinkRelay::Receiver {
@@ -606,6 +660,7 @@ impl LinkRelay<()> {
                 flow_state,
                 unsettled,
                 receiver_settle_mode,
+                settled,
                 more,
             },
         }
@@ -613,12 +668,12 @@ impl LinkRelay<()> {
 }
 
 impl LinkRelay<OutputHandle> {
-    // pub(crate) fn output_handle(&self) -> &OutputHandle {
-    //     match self {
-    //         Self::Sender { output_handle, .. } => output_handle,
-    //         Self::Receiver { output_handle, .. } => output_handle,
-    //     }
-    // }
+    pub(crate) fn output_handle(&self) -> &OutputHandle {
+        match self {
+            Self::Sender { output_handle, .. } => output_handle,
+            Self::Receiver { output_handle, .. } => output_handle,
+        }
+    }
 
     pub(crate) async fn send(
         &mut self,
@@ -660,10 +715,29 @@ impl LinkRelay<OutputHandle> {
                 Ok(ret)
             }
             LinkRelay::Receiver {
+                tx,
                 flow_state,
                 output_handle,
                 ..
             } => {
+                // While draining, the flow is forwarded to the receiver, which applies the
+                // delivery-count of the remote sender after the transfers that precede the flow
+                // have consumed link credit
+                if flow_state.drain() {
+                    let ret = match flow.echo {
+                        true => Some(
+                            flow_state
+                                .lock
+                                .read()
+                                .as_link_flow(output_handle.clone(), false),
+                        ),
+                        false => None,
+                    };
+                    tx.send(LinkFrame::Flow(flow))
+                        .await
+                        .map_err(|_| LinkRelayError::UnattachedHandle)?;
+                    return Ok(ret);
+                }
                 let ret = flow_state.on_incoming_flow(flow, output_handle.clone());
                 Ok(ret)
             }
@@ -744,11 +818,17 @@ impl LinkRelay<OutputHandle> {
 
                 echo
             }
-            LinkRelay::Receiver { unsettled, .. } => {
+            LinkRelay::Receiver {
+                unsettled,
+                settled: settled_notify,
+                ..
+            } => {
                 if settled {
                     let mut guard = unsettled.write();
                     // let _state = remove_from_unsettled(unsettled, &delivery_tag).await;
                     let _state = guard.as_mut().and_then(|m| m.swap_remove(&delivery_tag));
+                    // Wake up those waiting for the sender to settle in mode Second
+                    settled_notify.0.notify_waiters();
                 } else {
                     let mut guard = unsettled.write();
                     if let Some(msg_state) = guard.as_mut().and_then(|m| m.get_mut(&delivery_tag)) {
@@ -849,10 +929,37 @@ pub(crate) fn get_max_message_size(local: u64, remote: Option<u64>) -> u64 {
     }
 }
 
+/// Generates a link name for a link that is attached with an empty name
+///
+/// The counter makes the name unique within the process, and the random suffix makes it
+/// unlikely to collide with the names generated by other processes.
+pub(crate) fn generate_link_name() -> String {
+    use std::{
+        collections::hash_map::RandomState,
+        hash::{BuildHasher, Hasher},
+        sync::atomic::{AtomicU64, Ordering},
+    };
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(count);
+    format!("fe2o3-amqp-link-{}-{:016x}", count, hasher.finish())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::link::state::LinkFlowStateInner;
 
+    #[test]
+    fn generated_link_names_are_distinct() {
+        let first = super::generate_link_name();
+        let second = super::generate_link_name();
+        assert!(first.starts_with("fe2o3-amqp-link-"));
+        assert_ne!(first, second);
+    }
+
     #[tokio::test]
     async fn test_producer_notify() {
         use std::sync::Arc;
@@ -1,17 +1,27 @@
 //! Implementation of AMQP1.0 receiver
 
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
 
 use fe2o3_amqp_types::{
-    definitions::{self, DeliveryTag, Fields, SequenceNo},
+    definitions::{self, DeliveryTag, Fields, Handle, SequenceNo},
     messaging::{
-        Accepted, Address, DeliveryState, FromBody, Modified, Rejected, Released, Source, Target,
+        Accepted, Address, DeliveryState, FilterSet, FromBody, Modified, Rejected, Released,
+        Source, Target,
     },
     performatives::{Attach, Detach, Transfer},
 };
-use tokio::sync::mpsc;
+use futures_util::{future::BoxFuture, stream::FusedStream, Stream};
+use tokio::sync::mpsc::{self, error::TryRecvError};
 
 cfg_not_wasm32! {
+    use fe2o3_amqp_types::primitives::Timestamp;
     use std::time::Duration;
     use tokio::time::{error::Elapsed, timeout};
 }
@@ -34,7 +44,8 @@ use super::{
     ArcReceiverUnsettledMap, DetachThenResumeReceiverError, DispositionError,
     IllegalLinkStateError, LinkFrame, LinkRelay, LinkStateError, ReceiverAttachError,
     ReceiverAttachExchange, ReceiverFlowState, ReceiverLink, ReceiverResumeError,
-    ReceiverResumeErrorKind, ReceiverTransferError, RecvError, DEFAULT_CREDIT,
+    ReceiverResumeErrorKind, ReceiverTransferError, RecvError, SettledNotifier, SettledNotify,
+    DEFAULT_CREDIT,
 };
 
 cfg_transaction! {
@@ -96,6 +107,7 @@ impl Default for CreditMode {
 /// |`buffer_size`| `u16::MAX` |
 /// |`role`| `role::Sender` |
 /// |`auto_accept`|`false`|
+/// |`drop_expired`|`false`|
 ///
 /// # Customize configuration with [`builder::Builder`]
 ///
@@ -126,13 +138,46 @@ impl Receiver {
         self.inner.link.name()
     }
 
+    /// Returns the handle that the local peer uses to refer to the link, or `None` if the link
+    /// is detached
+    pub fn output_handle(&self) -> Option<Handle> {
+        self.inner.link.output_handle.clone().map(Into::into)
+    }
+
+    /// Returns the handle that the remote peer uses to refer to the link, or `None` if the link
+    /// is detached
+    pub fn input_handle(&self) -> Option<Handle> {
+        self.inner.link.input_handle.clone().map(Into::into)
+    }
+
     /// Returns the `max_message_size` of the link. A value of zero indicates that the link has no
     /// maximum message size, and thus a zero value is turned into a `None`
     pub fn max_message_size(&self) -> Option<u64> {
         self.inner.link.max_message_size()
     }
 
-    /// Get the current credit of the link
+    /// Returns the link credit currently issued to the remote sender
+    ///
+    /// This decreases as messages arrive and is updated when the link credit is set or the
+    /// remote sender consumes the link credit while draining.
+    pub fn credit(&self) -> u32 {
+        self.inner.link.flow_state().link_credit()
+    }
+
+    /// Returns the current delivery count of the link
+    pub fn delivery_count(&self) -> SequenceNo {
+        self.inner.link.flow_state().delivery_count()
+    }
+
+    /// Returns the number of messages that the remote sender last advertised as available
+    ///
+    /// This is the `available` field of the last flow received from the remote sender, which is
+    /// decreased as messages arrive. The value is only advisory.
+    pub fn available(&self) -> u32 {
+        self.inner.link.flow_state().available()
+    }
+
+    /// Get the credit mode of the link
     pub fn credit_mode(&self) -> &CreditMode {
         &self.inner.credit_mode
     }
@@ -150,10 +195,22 @@ impl Receiver {
     }
 
     /// Set `auto_accept` to `value`
+    ///
+    /// Auto accept is only meaningful in `ReceiverSettleMode::First`.
     pub fn set_auto_accept(&mut self, value: bool) {
         self.inner.auto_accept = value;
     }
 
+    /// Get the `drop_expired` field of receiver
+    pub fn drop_expired(&self) -> bool {
+        self.inner.drop_expired
+    }
+
+    /// Set `drop_expired` to `value`
+    pub fn set_drop_expired(&mut self, value: bool) {
+        self.inner.drop_expired = value;
+    }
+
     /// Get a reference to the link's source field
     pub fn source(&self) -> &Option<Source> {
         &self.inner.link.source
@@ -164,6 +221,12 @@ impl Receiver {
         &mut self.inner.link.source
     }
 
+    /// Get a reference to the `filter` of the link's source, which holds the filters actually
+    /// in place at the remote peer once the link is attached
+    pub fn source_filter(&self) -> Option<&FilterSet> {
+        self.inner.link.source.as_ref()?.filter.as_ref()
+    }
+
     /// Get a reference to the link's target field
     pub fn target(&self) -> &Option<Target> {
         &self.inner.link.target
@@ -193,6 +256,9 @@ impl Receiver {
     /// Attach the receiver link to a session with the default configuration
     /// with the `name` and `source` address set the specified value
     ///
+    /// A unique link name is generated if the `name` is empty, which can be retrieved with
+    /// [`name`](#method.name).
+    ///
     /// # Default configuration
     ///
     /// | Field | Default Value |
@@ -210,6 +276,7 @@ impl Receiver {
     /// |`buffer_size`| `u16::MAX` |
     /// |`role`| `role::Sender` |
     /// |`auto_accept`|`false`|
+    /// |`drop_expired`|`false`|
     ///  
     /// # Example
     ///
@@ -232,6 +299,52 @@ impl Receiver {
             .await
     }
 
+    /// Attach a receiver link that asks the remote peer to dynamically create a node at the
+    /// source
+    ///
+    /// The local source is sent with `dynamic` set to `true`, no address and the given
+    /// `dynamic_node_properties`. On success, the address of the node created by the remote
+    /// peer is returned along with the receiver.
+    ///
+    /// If the remote peer does not create a node, it will respond with a null source and this
+    /// returns [`ReceiverAttachError::IncomingSourceIsNone`].
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// let (mut receiver, address) = Receiver::attach_dynamic(
+    ///     &mut session,           // mutable reference to SessionHandle
+    ///     "rust-receiver-link-1", // link name
+    ///     None                    // dynamic node properties
+    /// ).await.unwrap();
+    /// ```
+    pub async fn attach_dynamic<R>(
+        session: &mut SessionHandle<R>,
+        name: impl Into<String>,
+        dynamic_node_properties: impl Into<Option<Fields>>,
+    ) -> Result<(Receiver, Address), ReceiverAttachError> {
+        let source = Source {
+            dynamic: true,
+            dynamic_node_properties: dynamic_node_properties.into(),
+            ..Default::default()
+        };
+        let receiver = Self::builder()
+            .name(name)
+            .source(source)
+            .attach(session)
+            .await?;
+
+        // When set to true by the sending link endpoint this field indicates creation of a
+        // dynamically created node. In this case the address field will contain the address of
+        // the created node
+        let address = receiver
+            .source()
+            .as_ref()
+            .and_then(|source| source.address.clone())
+            .ok_or(ReceiverAttachError::SourceAddressIsNoneWhenDynamicIsTrue)?;
+        Ok((receiver, address))
+    }
+
     /// Receive a message from the link
     ///
     /// # Example
@@ -305,10 +418,97 @@ impl Receiver {
     ///
     /// This will send a `Flow` performative with the `drain` field set to true.
     /// Setting the credit will set the `drain` field to false and stop draining
+    ///
+    /// The remote sender will send all available messages and then consume the remaining link
+    /// credit. Use [`recv_or_drained`](Self::recv_or_drained) to receive the messages until the
+    /// drain cycle completes. Credit is not automatically replenished in `CreditMode::Auto`
+    /// while draining, and [`set_credit`](Self::set_credit) needs to be called to receive more
+    /// messages afterwards.
     pub async fn drain(&mut self) -> Result<(), IllegalLinkStateError> {
         self.inner.drain().await
     }
 
+    /// Receive a message or wait for the ongoing drain cycle to complete
+    ///
+    /// This behaves like [`recv`](Self::recv) except that it returns `Ok(None)` once the remote
+    /// sender has consumed all link credit in response to [`drain`](Self::drain), which means
+    /// there are no more messages currently available.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// receiver.drain().await.unwrap();
+    /// while let Some(delivery) = receiver.recv_or_drained::<Value>().await.unwrap() {
+    ///     receiver.accept(&delivery).await.unwrap();
+    /// }
+    /// ```
+    ///
+    /// # Cancel safety
+    ///
+    /// This function is cancel-safe.
+    pub async fn recv_or_drained<T>(&mut self) -> Result<Option<Delivery<T>>, RecvError>
+    where
+        for<'de> T: FromBody<'de> + Send,
+    {
+        self.inner.recv_or_drained().await
+    }
+
+    /// Receive a message if a complete delivery is already buffered
+    ///
+    /// This returns `Ok(None)` immediately instead of waiting for the next transfer if no
+    /// complete delivery has arrived yet. A multi-frame delivery whose remaining transfer frames
+    /// have not arrived is not considered ready.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// if let Some(delivery) = receiver.try_recv::<Value>().await.unwrap() {
+    ///     receiver.accept(&delivery).await.unwrap();
+    /// }
+    /// ```
+    ///
+    /// # Cancel safety
+    ///
+    /// This function is cancel-safe.
+    pub async fn try_recv<T>(&mut self) -> Result<Option<Delivery<T>>, RecvError>
+    where
+        for<'de> T: FromBody<'de> + Send,
+    {
+        self.inner.try_recv().await
+    }
+
+    /// Turn the receiver into a [`Stream`](futures_util::Stream) of deliveries with the body type
+    /// `T`
+    ///
+    /// Each item is the result of [`recv`](Self::recv). The stream ends after yielding a
+    /// [`RecvError::LinkStateError`], for example when the link is detached by the remote peer.
+    /// The deliveries still need to be disposed explicitly through the receiver returned by
+    /// [`RecvStream::get_ref`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use futures_util::StreamExt;
+    ///
+    /// let mut stream = receiver.into_stream::<Value>();
+    /// while let Some(delivery) = stream.next().await {
+    ///     let delivery = delivery.unwrap();
+    ///     stream.get_ref().unwrap().accept(&delivery).await.unwrap();
+    /// }
+    /// let receiver = stream.into_inner().unwrap();
+    /// receiver.close().await.unwrap();
+    /// ```
+    pub fn into_stream<T>(self) -> RecvStream<T>
+    where
+        for<'de> T: FromBody<'de> + Send + Sync + 'static,
+    {
+        RecvStream {
+            receiver: Some(self),
+            recv: None,
+            terminated: false,
+        }
+    }
+
     /// Detach the link.
     ///
     /// This will send a `Detach` performative with the `closed` field set to false. If the remote
@@ -346,6 +546,40 @@ impl Receiver {
         ) -> Result<Result<DetachedReceiver, (DetachedReceiver, DetachError)>, Elapsed> {
             timeout(duration, self.detach()).await
         }
+
+        /// Receive a message or return `Ok(None)` if no message arrives within the `duration`
+        ///
+        /// This simply wraps [`recv`](#method.recv) with a `timeout`. The link credit and the
+        /// state of a partially received delivery are left untouched when the timeout elapses,
+        /// and a message that is already available at the deadline is returned instead of
+        /// `Ok(None)`.
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// match receiver.recv_with_timeout::<Value>(Duration::from_millis(500)).await.unwrap() {
+        ///     Some(delivery) => receiver.accept(&delivery).await.unwrap(),
+        ///     None => println!("No message is available"),
+        /// }
+        /// ```
+        ///
+        /// # Cancel safety
+        ///
+        /// This function is cancel-safe.
+        pub async fn recv_with_timeout<T>(
+            &mut self,
+            duration: Duration,
+        ) -> Result<Option<Delivery<T>>, RecvError>
+        where
+            for<'de> T: FromBody<'de> + Send,
+        {
+            // `timeout` polls the inner future before checking the deadline, and `recv` is
+            // cancel-safe, so no message is lost when the timeout elapses
+            match timeout(duration, self.inner.recv()).await {
+                Ok(result) => result.map(Some),
+                Err(_) => Ok(None),
+            }
+        }
     }
 
     /// Detach the link and then resume on a new session.
@@ -407,6 +641,11 @@ impl Receiver {
     ///
     /// This will not send disposition if the delivery is not found in the local unsettled map.
     ///
+    /// # Errors
+    ///
+    /// [`IllegalLinkStateError::IllegalSessionState`](crate::link::IllegalLinkStateError) is
+    /// returned if the session has ended and the disposition could not be sent.
+    ///
     /// # Example
     ///
     /// The code of the example below can be found in the [GitHub repo](https://github.com/minghuaw/fe2o3-amqp/blob/main/examples/receiver/src/main.rs)
@@ -502,7 +741,22 @@ impl Receiver {
     /// Modify the message by sending a disposition with the `delivery_state` field set
     /// to `Modify`
     ///
+    /// The `delivery_failed`, `undeliverable_here` and `message_annotations` fields of the
+    /// `Modified` outcome are sent to the remote peer as they are given.
+    ///
     /// This will not send disposition if the delivery is not found in the local unsettled map.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Ask the broker to redeliver the message to a different receiver
+    /// let modified = Modified {
+    ///     delivery_failed: Some(true),
+    ///     undeliverable_here: Some(true),
+    ///     message_annotations: None,
+    /// };
+    /// receiver.modify(&delivery, modified).await.unwrap();
+    /// ```
     pub async fn modify(
         &self,
         delivery_info: impl Into<DeliveryInfo>,
@@ -525,6 +779,45 @@ impl Receiver {
         self.dispose_all(deliveries, state).await
     }
 
+    /// Wait until the delivery is settled by the remote sender
+    ///
+    /// In `ReceiverSettleMode::Second`, the disposition sent by [`accept`](Self::accept) or the
+    /// other disposition methods does not settle the delivery. The delivery stays in the local
+    /// unsettled map until the remote sender settles it with a second disposition, and this
+    /// resolves once that happens. It resolves immediately if the delivery is not found in the
+    /// local unsettled map, which is always the case after a disposition in
+    /// `ReceiverSettleMode::First`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IllegalLinkStateError::IllegalState`] if the link is detached, or
+    /// [`IllegalLinkStateError::IllegalSessionState`] if the session ends, before the remote
+    /// sender settles the delivery. This still waits indefinitely if the remote sender never
+    /// settles the delivery while the link stays attached.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut receiver = Receiver::builder()
+    ///     .name("rust-receiver-link-1")
+    ///     .source("q1")
+    ///     .receiver_settle_mode(ReceiverSettleMode::Second)
+    ///     .attach(&mut session)
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// let delivery: Delivery<Value> = receiver.recv().await.unwrap();
+    /// receiver.accept(&delivery).await.unwrap();
+    /// receiver.on_settled(&delivery).await.unwrap();
+    /// ```
+    pub async fn on_settled(
+        &self,
+        delivery_info: impl Into<DeliveryInfo>,
+    ) -> Result<(), IllegalLinkStateError> {
+        let delivery_info: DeliveryInfo = delivery_info.into();
+        self.inner.on_settled(&delivery_info.delivery_tag).await
+    }
+
     /// Dispose the message by sending a disposition with the provided state
     ///
     /// This will not send disposition if the delivery is not found in the local unsettled map.
@@ -540,6 +833,8 @@ impl Receiver {
     /// Dispose the message by sending one or more disposition(s) with the provided state
     ///
     /// Only deliveries that are found in the local unsettled map will be included in the disposition frame(s).
+    /// Deliveries with consecutive delivery ids are disposed with a single disposition frame that
+    /// has both `first` and `last` set.
     pub async fn dispose_all(
         &self,
         deliveries: impl IntoIterator<Item = impl Into<DeliveryInfo>>,
@@ -553,6 +848,97 @@ impl Receiver {
     }
 }
 
+type RecvFuture<T> = BoxFuture<'static, (Receiver, Result<Delivery<T>, RecvError>)>;
+
+/// A [`Stream`](futures_util::Stream) of deliveries created by [`Receiver::into_stream`]
+pub struct RecvStream<T> {
+    receiver: Option<Receiver>,
+    recv: Option<RecvFuture<T>>,
+    terminated: bool,
+}
+
+impl<T> std::fmt::Debug for RecvStream<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecvStream")
+            .field("receiver", &self.receiver)
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+impl<T> RecvStream<T> {
+    /// Get a reference to the receiver
+    ///
+    /// This returns `None` only if the stream has been polled but has not yielded the next item
+    /// yet
+    pub fn get_ref(&self) -> Option<&Receiver> {
+        self.receiver.as_ref()
+    }
+
+    /// Get a mutable reference to the receiver
+    ///
+    /// This returns `None` only if the stream has been polled but has not yielded the next item
+    /// yet
+    pub fn get_mut(&mut self) -> Option<&mut Receiver> {
+        self.receiver.as_mut()
+    }
+
+    /// Consume the stream and get back the receiver
+    ///
+    /// This returns `None` only if the stream has been polled but has not yielded the next item
+    /// yet
+    pub fn into_inner(self) -> Option<Receiver> {
+        self.receiver
+    }
+}
+
+impl<T> Stream for RecvStream<T>
+where
+    for<'de> T: FromBody<'de> + Send + Sync + 'static,
+{
+    type Item = Result<Delivery<T>, RecvError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+
+        // The receiver is moved into the future and handed back once the future completes, which
+        // makes it safe to keep polling the same future across multiple calls
+        let mut recv = match self.recv.take() {
+            Some(recv) => recv,
+            None => match self.receiver.take() {
+                Some(mut receiver) => Box::pin(async move {
+                    let result = receiver.recv::<T>().await;
+                    (receiver, result)
+                }),
+                None => return Poll::Ready(None),
+            },
+        };
+
+        match recv.as_mut().poll(cx) {
+            Poll::Ready((receiver, result)) => {
+                self.receiver = Some(receiver);
+                self.terminated = matches!(result, Err(RecvError::LinkStateError(_)));
+                Poll::Ready(Some(result))
+            }
+            Poll::Pending => {
+                self.recv = Some(recv);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> FusedStream for RecvStream<T>
+where
+    for<'de> T: FromBody<'de> + Send + Sync + 'static,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
 #[derive(Debug, Clone)]
 /// Terminal delivery states that can be used by the receiver to dispose of a delivery
 pub enum TerminalDeliveryState {
@@ -618,6 +1004,39 @@ impl From<Modified> for TerminalDeliveryState {
     }
 }
 
+/// Checks whether the `absolute-expiry-time` of the message has passed
+#[cfg(not(target_arch = "wasm32"))]
+fn is_expired<T>(delivery: &Delivery<T>) -> bool {
+    match delivery
+        .message()
+        .properties
+        .as_ref()
+        .and_then(|properties| properties.absolute_expiry_time.as_ref())
+    {
+        Some(expiry) => now_as_timestamp()
+            .map(|now| *expiry <= now)
+            .unwrap_or(false),
+        None => false,
+    }
+}
+
+/// Returns the current time, or `None` if the system clock is before the unix epoch
+#[cfg(not(target_arch = "wasm32"))]
+fn now_as_timestamp() -> Option<Timestamp> {
+    let since_epoch = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    i64::try_from(since_epoch.as_millis())
+        .ok()
+        .map(Timestamp::from_milliseconds)
+}
+
+/// The system clock is not available on wasm32 targets
+#[cfg(target_arch = "wasm32")]
+fn is_expired<T>(_delivery: &Delivery<T>) -> bool {
+    false
+}
+
 #[derive(Debug)]
 pub(crate) struct ReceiverInner<L: endpoint::ReceiverLink> {
     pub(crate) link: L,
@@ -625,6 +1044,13 @@ pub(crate) struct ReceiverInner<L: endpoint::ReceiverLink> {
     pub(crate) credit_mode: CreditMode,
     pub(crate) processed: AtomicU32, // SequenceNo,
     pub(crate) auto_accept: bool,
+    pub(crate) drop_expired: bool,
+
+    // Whether a flow completing the drain cycle has been received
+    pub(crate) drained: bool,
+
+    // Notified by the link relay when the remote sender settles a delivery
+    pub(crate) settled: Arc<SettledNotify>,
 
     // Control sender to the session
     pub(crate) session: mpsc::Sender<SessionControl>,
@@ -681,12 +1107,15 @@ where
     }
 
     fn as_new_link_relay(&self, tx: mpsc::Sender<LinkFrame>) -> LinkRelay<()> {
+        // The previous relay is dropped when the link is detached
+        self.settled.reopen();
         LinkRelay::Receiver {
             tx,
             output_handle: (),
             flow_state: self.link.flow_state().clone(),
             unsettled: self.link.unsettled().clone(),
             receiver_settle_mode: self.link.rcv_settle_mode().clone(),
+            settled: SettledNotifier(self.settled.clone()),
             // This only controls whether a multi-transfer delivery id
             // will be added to sessions map
             more: false,
@@ -782,7 +1211,57 @@ where
             match self.recv_inner().await? // FIXME: cancel safe? if oneshot channel is cancel safe
             {
                 Some(delivery) => return Ok(delivery),
-                None => continue, // Incomplete transfer, there are more transfer frames coming
+                // Incomplete transfer, there are more transfer frames coming, an expired
+                // delivery has been dropped, or a drain cycle has completed
+                None => {
+                    self.drained = false;
+                    continue;
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn on_settled(
+        &self,
+        delivery_tag: &DeliveryTag,
+    ) -> Result<(), IllegalLinkStateError> {
+        loop {
+            // The `Notified` future is created before checking the unsettled map so that a
+            // settlement in between is not missed
+            let notified = self.settled.notified();
+            let is_unsettled = self
+                .link
+                .unsettled()
+                .read()
+                .as_ref()
+                .map(|map| map.contains_key(delivery_tag))
+                .unwrap_or(false);
+            if !is_unsettled {
+                return Ok(());
+            }
+            if self.settled.is_closed() {
+                // The remote sender can no longer settle the delivery
+                return match self.session.is_closed() {
+                    true => Err(IllegalLinkStateError::IllegalSessionState),
+                    false => Err(IllegalLinkStateError::IllegalState),
+                };
+            }
+            notified.await;
+        }
+    }
+
+    pub(crate) async fn recv_or_drained<T>(&mut self) -> Result<Option<Delivery<T>>, RecvError>
+    where
+        for<'de> T: FromBody<'de> + Send,
+    {
+        loop {
+            match self.recv_inner().await? {
+                Some(delivery) => return Ok(Some(delivery)),
+                None => {
+                    if std::mem::take(&mut self.drained) {
+                        return Ok(None);
+                    }
+                }
             }
         }
     }
@@ -800,7 +1279,41 @@ where
             .recv()
             .await // cancel safe
             .ok_or(LinkStateError::IllegalSessionState)?;
+        self.on_incoming_frame(frame).await
+    }
 
+    /// Process the frames that are already buffered until a complete delivery is found
+    ///
+    /// Returns `Ok(None)` if the buffered frames do not complete a delivery, which includes the
+    /// case of a multi-frame delivery that is still being received.
+    pub(crate) async fn try_recv<T>(&mut self) -> Result<Option<Delivery<T>>, RecvError>
+    where
+        for<'de> T: FromBody<'de> + Send,
+    {
+        loop {
+            let frame = match self.incoming.try_recv() {
+                Ok(frame) => frame,
+                Err(TryRecvError::Empty) => return Ok(None),
+                Err(TryRecvError::Disconnected) => {
+                    return Err(LinkStateError::IllegalSessionState.into())
+                }
+            };
+            if let Some(delivery) = self.on_incoming_frame(frame).await? {
+                return Ok(Some(delivery));
+            }
+        }
+    }
+
+    /// # Cancel safety
+    ///
+    /// This should be cancel safe if oneshot channel is cancel safe
+    async fn on_incoming_frame<T>(
+        &mut self,
+        frame: LinkFrame,
+    ) -> Result<Option<Delivery<T>>, RecvError>
+    where
+        for<'de> T: FromBody<'de> + Send,
+    {
         match frame {
             LinkFrame::Detach(detach) => {
                 let closed = detach.closed;
@@ -819,9 +1332,16 @@ where
                 payload,
             } => self.on_incoming_transfer(performative, payload).await, // cancel safe
             LinkFrame::Attach(_) => Err(LinkStateError::IllegalState.into()),
-            LinkFrame::Flow(_) | LinkFrame::Disposition(_) => {
-                // Flow and Disposition are handled by LinkRelay which runs
-                // in the session loop
+            LinkFrame::Flow(flow) => {
+                // Flow is handled by LinkRelay which runs in the session loop and is only
+                // forwarded while draining
+                if self.link.flow_state().on_drain_flow(&flow) {
+                    self.drained = true;
+                }
+                Ok(None)
+            }
+            LinkFrame::Disposition(_) => {
+                // Disposition is handled by LinkRelay which runs in the session loop
                 unreachable!()
             }
             #[cfg(feature = "transaction")]
@@ -860,6 +1380,58 @@ where
             .map_err(Into::into)
     }
 
+    /// Drops the buffered partial delivery together with its entry in the unsettled map
+    fn discard_incomplete_transfer(&mut self) {
+        if let Some(incomplete) = self.incomplete_transfer.take() {
+            self.remove_unsettled(&incomplete.performative.delivery_tag);
+        }
+    }
+
+    /// Discards the aborted delivery without yielding it to the user. A delivery that was
+    /// started on this link still counts towards the delivery-count like on the sender side,
+    /// and it is counted as processed so that credit is replenished in `CreditMode::Auto`.
+    ///
+    /// # Cancel safety
+    ///
+    /// This is cancel safe because all internal `.await` point(s) are cancel safe
+    async fn on_aborted_transfer(&mut self, transfer: &Transfer) -> Result<(), RecvError> {
+        let incomplete = self.incomplete_transfer.take();
+        let is_started = incomplete.is_some() || transfer.delivery_id.is_some();
+        let delivery_tag = match incomplete {
+            Some(incomplete) => incomplete.performative.delivery_tag,
+            None => transfer.delivery_tag.clone(),
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            "Discarding aborted delivery: delivery_tag: {:?}",
+            delivery_tag
+        );
+        #[cfg(feature = "log")]
+        log::debug!(
+            "Discarding aborted delivery: delivery_tag: {:?}",
+            delivery_tag
+        );
+
+        self.remove_unsettled(&delivery_tag);
+
+        // A resumed delivery has already been counted before the link was re-attached
+        if is_started && !transfer.resume {
+            self.link.flow_state().consume(1)?;
+            let prev = self.processed.fetch_add(1, Ordering::Release);
+            self.update_credit_if_auto(prev + 1).await?; // cancel safe
+        }
+        Ok(())
+    }
+
+    fn remove_unsettled(&self, delivery_tag: &Option<DeliveryTag>) {
+        if let Some(delivery_tag) = delivery_tag {
+            if let Some(map) = self.link.unsettled().write().as_mut() {
+                map.swap_remove(delivery_tag);
+            }
+        }
+    }
+
     fn on_incomplete_transfer(
         &mut self,
         transfer: Transfer,
@@ -868,7 +1440,10 @@ where
         // Partial transfer of the delivery
         match &mut self.incomplete_transfer {
             Some(incomplete) => {
-                incomplete.or_assign(transfer)?;
+                if let Err(err) = incomplete.or_assign(transfer) {
+                    self.discard_incomplete_transfer();
+                    return Err(err.into());
+                }
                 incomplete.append(payload);
 
                 if let Some(delivery_tag) = incomplete.performative.delivery_tag.clone() {
@@ -926,14 +1501,7 @@ where
                         section_number,
                         section_offset,
                     )?;
-
-                    // Auto accept the message and leave settled to be determined based on rcv_settle_mode
-                    if self.auto_accept {
-                        self.dispose(&delivery, None, Accepted {}.into()).await?;
-                        // cancel safe
-                    }
-
-                    Ok(Some(delivery))
+                    self.on_delivery(delivery).await // cancel safe
                 } else {
                     // The new Transfer belongs to the buffered incomplete transfer
                     self.on_complete_transfer(transfer, payload).await // cancel safe
@@ -959,7 +1527,10 @@ where
     {
         let delivery = match self.incomplete_transfer.take() {
             Some(mut incomplete) => {
-                incomplete.or_assign(transfer)?;
+                if let Err(err) = incomplete.or_assign(transfer) {
+                    self.remove_unsettled(&incomplete.performative.delivery_tag);
+                    return Err(err.into());
+                }
                 incomplete.append(payload); // This also computes the section number and offset incrementally
 
                 self.link.on_complete_transfer(
@@ -977,6 +1548,49 @@ where
             }
         };
 
+        self.on_delivery(delivery).await // cancel safe
+    }
+
+    /// Settles the delivery if it has expired and `drop_expired` is enabled, otherwise accepts
+    /// the delivery if `auto_accept` is enabled
+    ///
+    /// Deliveries pre-settled by the sender are never disposed, so they are counted towards
+    /// replenishing the credit in `CreditMode::Auto` upon arrival.
+    ///
+    /// # Cancel safety
+    ///
+    /// This is cancel safe because all internal `.await` point(s) are cancel safe
+    async fn on_delivery<T>(
+        &mut self,
+        delivery: Delivery<T>,
+    ) -> Result<Option<Delivery<T>>, RecvError> {
+        let is_unsettled = self
+            .link
+            .unsettled()
+            .read()
+            .as_ref()
+            .map(|map| map.contains_key(&delivery.delivery_tag))
+            .unwrap_or(false);
+        if !is_unsettled {
+            let prev = self.processed.fetch_add(1, Ordering::Release);
+            self.update_credit_if_auto(prev + 1).await?; // cancel safe
+
+            if self.drop_expired && is_expired(&delivery) {
+                return Ok(None);
+            }
+            return Ok(Some(delivery));
+        }
+
+        if self.drop_expired && is_expired(&delivery) {
+            let modified = Modified {
+                delivery_failed: Some(true),
+                undeliverable_here: None,
+                message_annotations: None,
+            };
+            self.dispose(&delivery, None, modified.into()).await?; // cancel safe
+            return Ok(None);
+        }
+
         // Auto accept the message and leave settled to be determined based on rcv_settle_mode
         if self.auto_accept {
             self.dispose(&delivery, None, Accepted {}.into()).await?; // cancel safe
@@ -1001,7 +1615,7 @@ where
         // within the frame carrying the performative MUST be ignored). An aborted
         // message is implicitly settled
         if transfer.aborted {
-            let _ = self.incomplete_transfer.take();
+            self.on_aborted_transfer(&transfer).await?; // cancel safe
             return Ok(None);
         }
 
@@ -1085,7 +1699,8 @@ where
     #[inline]
     async fn update_credit_if_auto(&self, processed: u32) -> Result<(), DispositionError> {
         if let CreditMode::Auto(max_credit) = self.credit_mode {
-            if processed >= max_credit / 2 {
+            // Topping up the credit would stop an ongoing drain cycle
+            if processed >= max_credit / 2 && !self.link.flow_state().drain() {
                 // Reset link credit
                 self.processed.swap(0, Ordering::Release);
                 self.link
@@ -311,6 +311,8 @@ where
     ) -> Result<(), Self::DispositionError> {
         // sorting before filtering may be more cache/branch-prediction friendly?
         delivery_infos.sort_by(|left, right| left.delivery_id.cmp(&right.delivery_id));
+        // The same delivery passed more than once would otherwise split a consecutive range
+        delivery_infos.dedup_by(|left, right| left.delivery_id == right.delivery_id);
         {
             let reader = self.unsettled.read();
             delivery_infos.retain(|info| {
@@ -839,7 +841,7 @@ where
             | ReceiverAttachError::ExpectImmediateDetach
             | ReceiverAttachError::RemoteClosedWithError(_) => attach_error,
 
-            ReceiverAttachError::DuplicatedLinkName => {
+            ReceiverAttachError::DuplicatedLinkName(_) => {
                 let error = definitions::Error::new(
                     SessionError::HandleInUse,
                     "Link name is in use".to_string(),
@@ -907,19 +909,115 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use fe2o3_amqp_types::{
+        definitions::{DeliveryNumber, DeliveryTag},
         messaging::{
             message::{Body, __private::Serializable},
-            AmqpValue, DeliveryAnnotations, Header, Message, MessageAnnotations,
+            Accepted, AmqpValue, DeliveryAnnotations, DeliveryState, Header, Message,
+            MessageAnnotations, Target,
         },
+        performatives::Disposition,
         primitives::{OrderedMap, Value},
     };
+    use parking_lot::RwLock;
     use serde_amqp::to_vec;
-
-    use crate::link::receiver_link::count_number_of_sections_and_offset;
+    use tokio::sync::mpsc;
+
+    use crate::{
+        endpoint::{OutputHandle, ReceiverLink as _},
+        link::{
+            delivery::DeliveryInfo,
+            receiver_link::count_number_of_sections_and_offset,
+            state::{LinkFlowState, LinkFlowStateInner},
+            LinkFrame, Receiver, ReceiverLink,
+        },
+        util::Sealed,
+    };
 
     use super::is_consecutive;
 
+    fn delivery_info(delivery_id: DeliveryNumber) -> DeliveryInfo {
+        DeliveryInfo {
+            delivery_id,
+            delivery_tag: DeliveryTag::from(delivery_id.to_be_bytes().to_vec()),
+            rcv_settle_mode: None,
+            _sealed: Sealed {},
+        }
+    }
+
+    /// Creates a receiver link with the given deliveries in its unsettled map
+    fn receiver_link_with_unsettled(delivery_ids: &[DeliveryNumber]) -> ReceiverLink<Target> {
+        let flow_state = LinkFlowState::receiver(LinkFlowStateInner {
+            initial_delivery_count: 0,
+            delivery_count: 0,
+            link_credit: 0,
+            available: 0,
+            drain: false,
+            properties: None,
+        });
+        let unsettled = delivery_ids
+            .iter()
+            .map(|id| (delivery_info(*id).delivery_tag, None))
+            .collect();
+        Receiver::builder()
+            .name("receiver-link")
+            .source("q1")
+            .create_link(
+                Arc::new(RwLock::new(Some(unsettled))),
+                OutputHandle(0),
+                Arc::new(flow_state),
+            )
+    }
+
+    async fn dispose_all(
+        link: &ReceiverLink<Target>,
+        delivery_ids: &[DeliveryNumber],
+    ) -> Vec<Disposition> {
+        let (writer, mut rx) = mpsc::channel(10);
+        let delivery_infos = delivery_ids.iter().map(|id| delivery_info(*id)).collect();
+        link.dispose_all(
+            &writer,
+            delivery_infos,
+            None,
+            DeliveryState::Accepted(Accepted {}),
+            false,
+        )
+        .await
+        .unwrap();
+        drop(writer);
+
+        let mut dispositions = Vec::new();
+        while let Some(frame) = rx.recv().await {
+            match frame {
+                LinkFrame::Disposition(disposition) => dispositions.push(disposition),
+                _ => panic!("Expecting a disposition frame"),
+            }
+        }
+        dispositions
+    }
+
+    #[tokio::test]
+    async fn test_dispose_all_coalesces_consecutive_deliveries() {
+        let link = receiver_link_with_unsettled(&[1, 2, 3, 4, 5]);
+
+        let dispositions = dispose_all(&link, &[3, 1, 5, 2, 4]).await;
+        assert_eq!(dispositions.len(), 1);
+        assert_eq!(dispositions[0].first, 1);
+        assert_eq!(dispositions[0].last, Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_dispose_all_splits_non_consecutive_deliveries() {
+        let link = receiver_link_with_unsettled(&[1, 2, 4, 5, 7]);
+
+        // Duplicates and deliveries not found in the unsettled map are ignored
+        let dispositions = dispose_all(&link, &[1, 2, 2, 4, 5, 6, 7]).await;
+        let ranges: Vec<_> = dispositions.iter().map(|d| (d.first, d.last)).collect();
+        assert_eq!(ranges, vec![(1, Some(2)), (4, Some(5)), (7, Some(7))]);
+    }
+
     #[test]
     fn test_section_numbers() {
         let message = Message {
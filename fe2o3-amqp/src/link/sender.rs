@@ -1,15 +1,27 @@
 //! Implementation of AMQP1.0 sender
 
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
 use bytes::{Bytes, BytesMut};
+use futures_util::{future::BoxFuture, Sink};
+use serde_amqp::to_vec;
+use std::time::Duration;
 use tokio::sync::{mpsc, oneshot};
 
 cfg_not_wasm32! {
-    use std::time::Duration;
     use tokio::time::{error::Elapsed, timeout};
+
+    use super::{state::LinkState, FlowError};
 }
 
 use fe2o3_amqp_types::{
-    definitions::{self, DeliveryTag, Fields, MessageFormat, SenderSettleMode},
+    definitions::{
+        self, DeliveryTag, Fields, Handle, MessageFormat, ReceiverSettleMode, SenderSettleMode,
+        SequenceNo, MIN_MAX_FRAME_SIZE,
+    },
     messaging::{
         message::__private::Serializable, Address, DeliveryState, Outcome, SerializableBody,
         Source, Target,
@@ -21,6 +33,7 @@ use fe2o3_amqp_types::{
 use crate::{
     control::SessionControl,
     endpoint::{self, LinkAttach, LinkDetach, LinkExt, Settlement},
+    frames::FRAME_HEADER_SIZE,
     session::SessionHandle,
     Payload,
 };
@@ -28,15 +41,16 @@ use crate::{
 use super::{
     builder::{self, WithSource, WithoutName, WithoutTarget},
     delivery::{DeliveryFut, Sendable, UnsettledMessage},
-    error::DetachError,
+    error::{DetachError, MaxFrameSizeExceeded, MessageSizeExceeded},
+    get_max_frame_size,
     resumption::ResumingDelivery,
     role,
     shared_inner::{
         recv_remote_detach, LinkEndpointInner, LinkEndpointInnerDetach, LinkEndpointInnerReattach,
     },
     ArcSenderUnsettledMap, DetachThenResumeSenderError, LinkFrame, LinkRelay, LinkStateError,
-    SendError, SenderAttachError, SenderAttachExchange, SenderFlowState, SenderLink,
-    SenderResumeError, SenderResumeErrorKind,
+    SendBatchError, SendError, SenderAttachError, SenderAttachExchange, SenderFlowState,
+    SenderLink, SenderResumeError, SenderResumeErrorKind,
 };
 
 #[cfg(docsrs)]
@@ -83,6 +97,7 @@ use fe2o3_amqp_types::messaging::{
 /// |`Properties`| `None` |
 /// |`buffer_size`| `u16::MAX` |
 /// |`role`| `role::Sender` |
+/// |`settlement_timeout`| `None` |
 ///
 /// # Customize configuration with [`builder::Builder`]
 ///
@@ -117,12 +132,47 @@ impl Sender {
         self.inner.link.name()
     }
 
+    /// Returns the handle that the local peer uses to refer to the link, or `None` if the link
+    /// is detached
+    pub fn output_handle(&self) -> Option<Handle> {
+        self.inner.link.output_handle.clone().map(Into::into)
+    }
+
+    /// Returns the handle that the remote peer uses to refer to the link, or `None` if the link
+    /// is detached
+    pub fn input_handle(&self) -> Option<Handle> {
+        self.inner.link.input_handle.clone().map(Into::into)
+    }
+
     /// Returns the `max_message_size` of the link. A value of zero indicates that the link has no
     /// maximum message size, and thus a zero value is turned into a `None`
     pub fn max_message_size(&self) -> Option<u64> {
         self.inner.link.max_message_size()
     }
 
+    /// Returns the link credit currently available to send messages
+    ///
+    /// This is the last value granted by the remote receiver minus the messages that have been
+    /// sent since then.
+    pub fn available_credit(&self) -> u32 {
+        self.inner.link.flow_state().state().link_credit()
+    }
+
+    /// Returns the current delivery count of the link
+    pub fn delivery_count(&self) -> SequenceNo {
+        self.inner.link.flow_state().state().delivery_count()
+    }
+
+    /// Get the `settlement_timeout` field of sender
+    pub fn settlement_timeout(&self) -> Option<Duration> {
+        self.inner.settlement_timeout
+    }
+
+    /// Set `settlement_timeout` to `value`
+    pub fn set_settlement_timeout(&mut self, value: impl Into<Option<Duration>>) {
+        self.inner.settlement_timeout = value.into();
+    }
+
     /// Get a reference to the link's source field
     pub fn source(&self) -> &Option<Source> {
         &self.inner.link.source
@@ -162,6 +212,9 @@ impl Sender {
     /// Attach the sender link to a session with default configuration
     /// with the `name` and `target` address set to the specified values
     ///
+    /// A unique link name is generated if the `name` is empty, which can be retrieved with
+    /// [`name`](#method.name).
+    ///
     /// ## Default configuration
     ///
     /// | Field | Default Value |
@@ -178,6 +231,7 @@ impl Sender {
     /// |`Properties`| `None` |
     /// |`buffer_size`| `u16::MAX` |
     /// |`role`| `role::Sender` |
+    /// |`settlement_timeout`| `None` |
     ///
     /// # Example
     ///
@@ -201,6 +255,52 @@ impl Sender {
             .await
     }
 
+    /// Attach a sender link that asks the remote peer to dynamically create a node at the
+    /// target
+    ///
+    /// The local target is sent with `dynamic` set to `true`, no address and the given
+    /// `dynamic_node_properties`. On success, the address of the node created by the remote
+    /// peer is returned along with the sender.
+    ///
+    /// If the remote peer does not create a node, it will respond with a null target and this
+    /// returns [`SenderAttachError::IncomingTargetIsNone`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (sender, address) = Sender::attach_dynamic(
+    ///     &mut session,           // mutable reference to SessionHandle
+    ///     "rust-sender-link-1",   // link name
+    ///     None                    // dynamic node properties
+    /// ).await.unwrap();
+    /// ```
+    pub async fn attach_dynamic<R>(
+        session: &mut SessionHandle<R>,
+        name: impl Into<String>,
+        dynamic_node_properties: impl Into<Option<Fields>>,
+    ) -> Result<(Sender, Address), SenderAttachError> {
+        let target = Target {
+            dynamic: true,
+            dynamic_node_properties: dynamic_node_properties.into(),
+            ..Default::default()
+        };
+        let sender = Self::builder()
+            .name(name)
+            .target(target)
+            .attach(session)
+            .await?;
+
+        // When set to true by the receiving link endpoint this field indicates creation of a
+        // dynamically created node. In this case the address field will contain the address of
+        // the created node
+        let address = sender
+            .target()
+            .as_ref()
+            .and_then(|target| target.address.clone())
+            .ok_or(SenderAttachError::TargetAddressIsNoneWhenDynamicIsTrue)?;
+        Ok((sender, address))
+    }
+
     /// Detach the link
     ///
     /// The Sender will send a detach frame with closed field set to false,
@@ -394,7 +494,7 @@ impl Sender {
             .send_with_state::<T, SendError>(sendable.into(), None, false)
             .await
             .map(DeliveryFut::from)?;
-        fut.await
+        self.settle_within_timeout(fut).await
     }
 
     /// Like [`send()`](#method.send) but takes a reference to the message
@@ -410,19 +510,146 @@ impl Sender {
             .send_ref_with_state::<T, SendError>(sendable, None, false)
             .await
             .map(DeliveryFut::from)?;
+        self.settle_within_timeout(fut).await
+    }
+
+    /// Waits for the outcome of a delivery, bounded by the `settlement_timeout` of the sender
+    async fn settle_within_timeout(
+        &self,
+        fut: DeliveryFut<Result<Outcome, SendError>>,
+    ) -> Result<Outcome, SendError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(duration) = self.inner.settlement_timeout {
+            return timeout(duration, fut)
+                .await
+                .map_err(|_| SendError::SettlementTimeout)?;
+        }
         fut.await
     }
 
+    /// Send a message pre-settled without waiting for a disposition
+    ///
+    /// The transfer is always sent with `settled` set to `true`, and this returns once the
+    /// transfer is written to the session. The delivery is never added to the unsettled map, so
+    /// the message is lost if the link fails before the remote peer receives it.
+    ///
+    /// [`SendError::IllegalSenderSettleMode`] is returned if the negotiated
+    /// [`SenderSettleMode`] is [`SenderSettleMode::Unsettled`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut sender = Sender::builder()
+    ///     .name("rust-sender-link-1")
+    ///     .target("q1")
+    ///     .sender_settle_mode(SenderSettleMode::Settled)
+    ///     .attach(&mut session)
+    ///     .await
+    ///     .unwrap();
+    /// sender.send_settled("hello AMQP").await.unwrap();
+    /// ```
+    pub async fn send_settled<T: SerializableBody>(
+        &mut self,
+        sendable: impl Into<Sendable<T>>,
+    ) -> Result<(), SendError> {
+        if let SenderSettleMode::Unsettled = self.inner.link.snd_settle_mode {
+            return Err(SendError::IllegalSenderSettleMode);
+        }
+
+        let mut sendable = sendable.into();
+        sendable.settled = Some(true);
+        match self
+            .inner
+            .send_with_state::<T, SendError>(sendable, None, false)
+            .await?
+        {
+            Settlement::Settled(_) => Ok(()),
+            Settlement::Unsettled { .. } => Err(SendError::IllegalSenderSettleMode),
+        }
+    }
+
     cfg_not_wasm32! {
         /// Send a message and wait for acknowledgement (disposition) with a timeout.
         ///
-        /// This simply wraps [`send`](#method.send) inside a [`tokio::time::timeout`]
+        /// This wraps [`send`](#method.send) inside a [`tokio::time::timeout`] and returns
+        /// [`SendError::SettlementTimeout`] if the message is not settled by the remote peer
+        /// within the `duration`, including the time spent waiting for link credit. A message
+        /// that has already been transferred stays in the unsettled map, and a disposition that
+        /// arrives after the timeout is still handled by the link.
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// match sender.send_with_timeout("hello", Duration::from_secs(1)).await {
+        ///     Ok(outcome) => println!("Settled with {:?}", outcome),
+        ///     Err(SendError::SettlementTimeout) => println!("No disposition in time"),
+        ///     Err(error) => return Err(error),
+        /// }
+        /// ```
         pub async fn send_with_timeout<T: SerializableBody>(
             &mut self,
             sendable: impl Into<Sendable<T>>,
             duration: Duration,
-        ) -> Result<Result<Outcome, SendError>, Elapsed> {
-            timeout(duration, self.send(sendable)).await
+        ) -> Result<Outcome, SendError> {
+            timeout(duration, self.send(sendable))
+                .await
+                .map_err(|_| SendError::SettlementTimeout)?
+        }
+    }
+
+    /// Send a batch of messages and wait for the acknowledgement of all of them
+    ///
+    /// All transfers are written first, waiting for more link credit whenever the available
+    /// link credit runs out, and the outcomes are only awaited afterwards so that the round trips
+    /// to the remote peer overlap. The outcomes are returned in the same order as the messages.
+    ///
+    /// If any message fails, a [`SendBatchError`] is returned with the result of each message
+    /// that has been attempted. Sending stops at the first message that fails to be
+    /// transferred.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let outcomes = sender.send_batch(vec!["hello", "world"]).await.unwrap();
+    /// assert_eq!(outcomes.len(), 2);
+    /// ```
+    pub async fn send_batch<T, I>(&mut self, sendables: I) -> Result<Vec<Outcome>, SendBatchError>
+    where
+        T: SerializableBody,
+        I: IntoIterator,
+        I::Item: Into<Sendable<T>>,
+    {
+        let mut futs = Vec::new();
+        let mut transfer_error = None;
+        let mut sendables = sendables.into_iter().peekable();
+        while let Some(sendable) = sendables.next() {
+            // The last transfer is not batchable so that the remote peer does not hold back the
+            // dispositions that are awaited below
+            let batchable = sendables.peek().is_some();
+            let result = self
+                .inner
+                .send_with_state::<T, SendError>(sendable.into(), None, batchable)
+                .await
+                .map(DeliveryFut::from);
+            match result {
+                Ok(fut) => futs.push(fut),
+                Err(error) => {
+                    transfer_error = Some(error);
+                    break;
+                }
+            }
+        }
+
+        let mut results = Vec::with_capacity(futs.len() + 1);
+        for fut in futs {
+            results.push(fut.await);
+        }
+        results.extend(transfer_error.map(Err));
+
+        if results.iter().all(Result::is_ok) {
+            Ok(results.into_iter().filter_map(Result::ok).collect())
+        } else {
+            Err(SendBatchError { results })
         }
     }
 
@@ -461,6 +688,54 @@ impl Sender {
             .map(DeliveryFut::from)
     }
 
+    cfg_not_wasm32! {
+        /// Wait until at least `min` link credit is available and returns the available link
+        /// credit.
+        ///
+        /// This allows producers to wait for credit before building a large batch of messages.
+        /// If a `duration` is given and it elapses before enough credit is granted by the remote
+        /// receiver, the currently available link credit, which is less than `min`, is returned.
+        ///
+        /// An error is returned if the link is not attached or if the remote peer detaches the
+        /// link while waiting.
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// let credit = sender.await_credit(10, Some(Duration::from_secs(1))).await.unwrap();
+        /// if credit >= 10 {
+        ///     // Send a batch of 10 messages
+        /// }
+        /// ```
+        pub async fn await_credit(
+            &mut self,
+            min: u32,
+            duration: Option<Duration>,
+        ) -> Result<u32, FlowError> {
+            match self.inner.link.local_state {
+                LinkState::Attached | LinkState::IncompleteAttachExchanged => {}
+                _ => return Err(FlowError::IllegalState),
+            }
+
+            let detached_fut = self.inner.incoming.recv(); // cancel safe
+            let fut = self
+                .inner
+                .link
+                .wait_for_credit_or_detached(&self.inner.outgoing, min, detached_fut);
+            let result = match duration {
+                Some(duration) => match timeout(duration, fut).await {
+                    Ok(result) => result,
+                    Err(_) => return Ok(self.inner.link.flow_state().state().link_credit()),
+                },
+                None => fut.await,
+            };
+            result.map_err(|err| match err {
+                LinkStateError::IllegalSessionState => FlowError::IllegalSessionState,
+                _ => FlowError::IllegalState,
+            })
+        }
+    }
+
     /// Returns when the remote peer detach/close the link
     pub async fn on_detach(&mut self) -> DetachError {
         match recv_remote_detach(&mut self.inner).await {
@@ -480,6 +755,145 @@ impl Sender {
             Err(err) => err,
         }
     }
+
+    /// Turn the sender into a [`Sink`](futures_util::Sink) of [`Sendable`]s
+    ///
+    /// - `poll_ready` is pending while there is no link credit available.
+    /// - `start_send` starts sending the message in the same way as
+    ///   [`send_batchable`](Self::send_batchable), and the outcome of the delivery is not
+    ///   awaited.
+    /// - `poll_flush` and `poll_close` wait until the last message is written to the session.
+    ///   The link is not closed by `poll_close`, and the sender can be taken back with
+    ///   [`SendSink::into_inner`] to close the link.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use futures_util::StreamExt;
+    ///
+    /// let mut sink = sender.into_sink();
+    /// futures_util::stream::iter(["hello", "world"])
+    ///     .map(|body| Ok(Sendable::from(body)))
+    ///     .forward(&mut sink)
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// let sender = sink.into_inner().unwrap();
+    /// sender.close().await.unwrap();
+    /// ```
+    pub fn into_sink(self) -> SendSink {
+        SendSink {
+            sender: Some(self),
+            pending: None,
+        }
+    }
+}
+
+type PendingFuture = BoxFuture<'static, (Sender, Result<(), SendError>)>;
+
+/// A [`Sink`](futures_util::Sink) of [`Sendable`]s created by [`Sender::into_sink`]
+pub struct SendSink {
+    sender: Option<Sender>,
+    pending: Option<PendingFuture>,
+}
+
+impl std::fmt::Debug for SendSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendSink")
+            .field("sender", &self.sender)
+            .finish()
+    }
+}
+
+impl SendSink {
+    /// Get a reference to the sender
+    ///
+    /// This returns `None` only if an operation started by the sink has not completed yet
+    pub fn get_ref(&self) -> Option<&Sender> {
+        self.sender.as_ref()
+    }
+
+    /// Get a mutable reference to the sender
+    ///
+    /// This returns `None` only if an operation started by the sink has not completed yet
+    pub fn get_mut(&mut self) -> Option<&mut Sender> {
+        self.sender.as_mut()
+    }
+
+    /// Consume the sink and get back the sender
+    ///
+    /// This returns `None` only if an operation started by the sink has not completed yet
+    pub fn into_inner(self) -> Option<Sender> {
+        self.sender
+    }
+
+    /// Drives the pending operation to completion and returns the sender
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<&mut Sender, SendError>> {
+        if let Some(pending) = self.pending.as_mut() {
+            let (sender, result) = ready!(pending.as_mut().poll(cx));
+            self.pending = None;
+            self.sender = Some(sender);
+            result?;
+        }
+        match self.sender.as_mut() {
+            Some(sender) => Poll::Ready(Ok(sender)),
+            // The sender is only taken out while there is a pending operation
+            None => Poll::Ready(Err(LinkStateError::IllegalState.into())),
+        }
+    }
+}
+
+impl<T> Sink<Sendable<T>> for SendSink
+where
+    T: SerializableBody + Send + 'static,
+{
+    type Error = SendError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let sender = ready!(self.poll_pending(cx))?;
+        if sender.inner.link.flow_state().state().link_credit() > 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        // Wait for the remote receiver to grant more link credit
+        let mut sender = self.sender.take().expect("sender is checked above");
+        self.pending = Some(Box::pin(async move {
+            let detached_fut = sender.inner.incoming.recv(); // cancel safe
+            let result = sender
+                .inner
+                .link
+                .wait_for_credit_or_detached(&sender.inner.outgoing, 1, detached_fut)
+                .await
+                .map(|_| ())
+                .map_err(SendError::from);
+            (sender, result)
+        }));
+        self.poll_pending(cx).map_ok(|_| ())
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Sendable<T>) -> Result<(), Self::Error> {
+        let mut sender = self
+            .sender
+            .take()
+            .ok_or(SendError::LinkStateError(LinkStateError::IllegalState))?;
+        self.pending = Some(Box::pin(async move {
+            let result = sender
+                .inner
+                .send_with_state::<T, SendError>(item, None, true)
+                .await
+                .map(|_| ());
+            (sender, result)
+        }));
+        Ok(())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_pending(cx).map_ok(|_| ())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        <Self as Sink<Sendable<T>>>::poll_flush(self, cx)
+    }
 }
 
 /// This is so that the transaction controller can re-use
@@ -489,6 +903,7 @@ pub(crate) struct SenderInner<L: endpoint::SenderLink> {
     // The SenderLink manages the state
     pub(crate) link: L,
     pub(crate) buffer_size: usize,
+    pub(crate) settlement_timeout: Option<Duration>,
 
     // Control sender to the session
     pub(crate) session: mpsc::Sender<SessionControl>,
@@ -637,7 +1052,10 @@ where
     ) -> Result<Settlement, E>
     where
         T: SerializableBody,
-        E: From<L::TransferError> + From<serde_amqp::Error>,
+        E: From<L::TransferError>
+            + From<serde_amqp::Error>
+            + From<MaxFrameSizeExceeded>
+            + From<MessageSizeExceeded>,
     {
         use bytes::BufMut;
         use serde::Serialize;
@@ -667,7 +1085,10 @@ where
     ) -> Result<Settlement, E>
     where
         T: SerializableBody,
-        E: From<L::TransferError> + From<serde_amqp::Error>,
+        E: From<L::TransferError>
+            + From<serde_amqp::Error>
+            + From<MaxFrameSizeExceeded>
+            + From<MessageSizeExceeded>,
     {
         use bytes::BufMut;
         use serde::Serialize;
@@ -698,8 +1119,23 @@ where
         batchable: bool,
     ) -> Result<Settlement, E>
     where
-        E: From<L::TransferError> + From<serde_amqp::Error>,
+        E: From<L::TransferError>
+            + From<serde_amqp::Error>
+            + From<MaxFrameSizeExceeded>
+            + From<MessageSizeExceeded>,
     {
+        // If the max message size is zero or unset, there is no maximum size imposed by the link
+        // endpoint. No link credit is consumed by a message that is too large.
+        let size = payload.len() as u64;
+        if let Some(max) = self.link.max_message_size() {
+            if size > max {
+                return Err(MessageSizeExceeded { size, max }.into());
+            }
+        }
+
+        self.check_transfer_frame_size::<E>(message_format, &state, batchable, !payload.is_empty())
+            .await?;
+
         // send a transfer, checking state will be implemented in SenderLink
         let detached_fut = self.incoming.recv(); // cancel safe
         let settlement = self
@@ -716,6 +1152,51 @@ where
             .await?;
         Ok(settlement)
     }
+
+    /// Checks that the first transfer frame of a delivery fits within the max frame size.
+    ///
+    /// Only the payload is split across multiple transfer frames, so the performative and at
+    /// least one byte of the payload must fit in a single frame. The max frame size is only
+    /// queried if the frame could exceed the smallest max frame size allowed.
+    async fn check_transfer_frame_size<E>(
+        &self,
+        message_format: MessageFormat,
+        state: &Option<DeliveryState>,
+        batchable: bool,
+        has_payload: bool,
+    ) -> Result<(), E>
+    where
+        E: From<LinkStateError> + From<serde_amqp::Error> + From<MaxFrameSizeExceeded>,
+    {
+        // Upper bound of the performative, whose fields are only known once credit is consumed
+        let transfer = Transfer {
+            handle: Handle(u32::MAX),
+            delivery_id: Some(u32::MAX),
+            delivery_tag: Some(DeliveryTag::from([u8::MAX; 4])),
+            message_format: Some(message_format),
+            settled: Some(false),
+            more: true,
+            rcv_settle_mode: Some(ReceiverSettleMode::Second),
+            state: state.clone(),
+            resume: false,
+            aborted: false,
+            batchable,
+        };
+        let size = FRAME_HEADER_SIZE + to_vec(&transfer)?.len() + usize::from(has_payload);
+        if size <= MIN_MAX_FRAME_SIZE {
+            return Ok(());
+        }
+
+        // The max frame size of the encoder excludes the 4 bytes frame size
+        let max = get_max_frame_size(&self.session)
+            .await
+            .map_err(|_| LinkStateError::IllegalSessionState)?
+            + 4;
+        match size > max {
+            true => Err(MaxFrameSizeExceeded { size, max }.into()),
+            false => Ok(()),
+        }
+    }
 }
 
 impl SenderInner<SenderLink<Target>> {
@@ -961,11 +1442,12 @@ impl SenderInner<SenderLink<Target>> {
         mut initial_remote_attach: Option<Attach>,
         is_reattaching: bool,
     ) -> Result<(), SenderResumeErrorKind> {
-        self.reallocate_output_handle().await?;
-
         let mut resend_buf = Vec::new();
 
         loop {
+            // The output handle is released whenever the link is suspended
+            self.reallocate_output_handle().await?;
+
             let attach_exchange = match initial_remote_attach.take() {
                 Some(remote_attach) => {
                     self.link
@@ -24,52 +24,32 @@ where
         &mut self,
         writer: &mpsc::Sender<LinkFrame>,
         mut transfer: Transfer,
-        mut payload: Payload,
+        payload: Payload,
     ) -> Result<bool, LinkStateError> {
-        let settled = transfer.settled.unwrap_or(match self.snd_settle_mode {
-            SenderSettleMode::Settled => true,
-            SenderSettleMode::Unsettled => false,
-            SenderSettleMode::Mixed => false,
-        });
+        let settled = self.is_transfer_settled(&transfer);
         let input_handle = self
             .input_handle
             .clone()
             .ok_or(LinkStateError::IllegalState)?;
 
-        // Check message size
-        // If this field is zero or unset, there is no maximum size imposed by the link endpoint.
-        let more = (self.max_message_size != 0) && (payload.len() as u64 > self.max_message_size);
-        if !more {
-            transfer.more = false;
-            send_transfer(writer, input_handle, transfer, payload.clone()).await?;
-        // cancel safe
-        } else {
-            // Send the first frame
-            let partial = payload.split_to(self.max_message_size as usize);
-            transfer.more = true;
-            send_transfer(writer, input_handle.clone(), transfer.clone(), partial).await?; // cancel safe
-
-            // Send the transfers in the middle
-            while payload.len() > self.max_message_size as usize {
-                let partial = payload.split_to(self.max_message_size as usize);
-                transfer.delivery_tag = None;
-                transfer.message_format = None;
-                transfer.settled = None;
-                send_transfer(writer, input_handle.clone(), transfer.clone(), partial).await?;
-                // cancel safe
-            }
-
-            // Send the last transfer
-            // For messages that are too large to fit within the maximum frame size, additional
-            // data MAY be trans- ferred in additional transfer frames by setting the more flag on
-            // all but the last transfer frame
-            transfer.more = false;
-            send_transfer(writer, input_handle, transfer, payload).await?; // cancel safe
-        }
+        // Messages that are too large to fit within the maximum frame size are split into
+        // multiple transfer frames by the `FrameEncoder`
+        transfer.more = false;
+        send_transfer(writer, input_handle, transfer, payload).await?; // cancel safe
 
         Ok(settled)
     }
 
+    /// Whether the transfer is settled, falling back to the `snd_settle_mode` of the link if the
+    /// `settled` field is not set
+    fn is_transfer_settled(&self, transfer: &Transfer) -> bool {
+        transfer.settled.unwrap_or(match self.snd_settle_mode {
+            SenderSettleMode::Settled => true,
+            SenderSettleMode::Unsettled => false,
+            SenderSettleMode::Mixed => false,
+        })
+    }
+
     pub(crate) async fn get_delivery_tag_or_detached<Fut>(
         &mut self,
         writer: &mpsc::Sender<LinkFrame>,
@@ -91,35 +71,81 @@ where
                 Ok(tag)
             },
             frame = detached => { // cancel safe
-                match frame {
-                    // If remote has detached the link
-                    Some(LinkFrame::Detach(detach)) => {
-                        // FIXME: if the sender is not trying to send anything, this is
-                        // probably not responsive enough
-                        let closed = detach.closed;
-                        self.send_detach(writer, closed, None).await?;
-                        let result = self.on_incoming_detach(detach);
-
-                        match (result, closed) {
-                            (Ok(_), true) => Err(LinkStateError::RemoteClosed),
-                            (Ok(_), false) => Err(LinkStateError::RemoteDetached),
-                            (Err(err), _) => Err(LinkStateError::from(err)),
-                        }
-                    },
-                    Some(_frame) => {
-                        // Other frames should not forwarded to the sender by the session
-                        #[cfg(feature = "tracing")]
-                        tracing::error!("Unexpected frame: {:?}", _frame);
-                        #[cfg(feature = "log")]
-                        log::error!("Unexpected frame: {:?}", _frame);
-
-                        Err(LinkStateError::ExpectImmediateDetach)
-                    }
-                    None => {
-                        // Other frames should not forwarded to the sender by the session
-                        Err(LinkStateError::ExpectImmediateDetach)
-                    }
+                Err(self.on_frame_while_waiting_for_credit(writer, frame).await)
+            }
+        }
+    }
+
+    /// Waits until at least `min` link credit is available and returns the available credit
+    ///
+    /// # Cancel safety
+    ///
+    /// This is cancel safe because it only `.await` on `Notify` and the cancel safe `detached`
+    pub(crate) async fn wait_for_credit_or_detached<Fut>(
+        &mut self,
+        writer: &mpsc::Sender<LinkFrame>,
+        min: u32,
+        detached: Fut,
+    ) -> Result<u32, LinkStateError>
+    where
+        Fut: Future<Output = Option<LinkFrame>> + Send,
+    {
+        let flow_state = &self.flow_state;
+        let credit = async move {
+            loop {
+                // The `Notified` future is created before checking the credit so that a flow
+                // arriving in between is not missed
+                let notified = flow_state.notifier.notified();
+                let link_credit = flow_state.state().link_credit();
+                if link_credit >= min {
+                    return link_credit;
                 }
+                notified.await;
+            }
+        };
+
+        tokio::select! {
+            link_credit = credit => Ok(link_credit),
+            frame = detached => { // cancel safe
+                Err(self.on_frame_while_waiting_for_credit(writer, frame).await)
+            }
+        }
+    }
+
+    async fn on_frame_while_waiting_for_credit(
+        &mut self,
+        writer: &mpsc::Sender<LinkFrame>,
+        frame: Option<LinkFrame>,
+    ) -> LinkStateError {
+        match frame {
+            // If remote has detached the link
+            Some(LinkFrame::Detach(detach)) => {
+                // FIXME: if the sender is not trying to send anything, this is
+                // probably not responsive enough
+                let closed = detach.closed;
+                if let Err(err) = self.send_detach(writer, closed, None).await {
+                    return err.into();
+                }
+                let result = self.on_incoming_detach(detach);
+
+                match (result, closed) {
+                    (Ok(_), true) => LinkStateError::RemoteClosed,
+                    (Ok(_), false) => LinkStateError::RemoteDetached,
+                    (Err(err), _) => LinkStateError::from(err),
+                }
+            }
+            Some(_frame) => {
+                // Other frames should not forwarded to the sender by the session
+                #[cfg(feature = "tracing")]
+                tracing::error!("Unexpected frame: {:?}", _frame);
+                #[cfg(feature = "log")]
+                log::error!("Unexpected frame: {:?}", _frame);
+
+                LinkStateError::ExpectImmediateDetach
+            }
+            None => {
+                // Other frames should not forwarded to the sender by the session
+                LinkStateError::ExpectImmediateDetach
             }
         }
     }
@@ -217,6 +243,18 @@ where
     where
         Fut: Future<Output = Option<LinkFrame>> + Send,
     {
+        // A link that is detached or closed must not accept new transfers
+        match self.local_state {
+            LinkState::Attached | LinkState::IncompleteAttachExchanged => {}
+            LinkState::DetachReceived | LinkState::Detached => {
+                return Err(LinkStateError::RemoteDetached)
+            }
+            LinkState::CloseReceived | LinkState::Closed => {
+                return Err(LinkStateError::RemoteClosed)
+            }
+            _ => return Err(LinkStateError::IllegalState),
+        }
+
         let tag = self.get_delivery_tag_or_detached(writer, detached).await?;
         // Delivery count is incremented when consuming credit
         let delivery_tag = DeliveryTag::from(tag);
@@ -250,29 +288,40 @@ where
             .delivery_tag
             .clone()
             .ok_or(LinkStateError::IllegalState)?;
-        let settled = self
-            .send_transfer_without_modifying_unsettled_map(writer, transfer, payload)
-            .await?;
-        match settled {
-            true => Ok(Settlement::Settled(delivery_tag)),
-            // If not set on the first (or only) transfer for a (multi-transfer)
-            // delivery, then the settled flag MUST be interpreted as being false.
-            false => {
-                let (tx, rx) = oneshot::channel();
-                let unsettled = UnsettledMessage::new(payload_copy, None, message_format, tx);
-                {
-                    let mut guard = self.unsettled.write();
-                    guard
-                        .get_or_insert(OrderedMap::new())
-                        .insert(delivery_tag.clone(), unsettled);
-                }
+        if self.is_transfer_settled(&transfer) {
+            self.send_transfer_without_modifying_unsettled_map(writer, transfer, payload)
+                .await?;
+            return Ok(Settlement::Settled(delivery_tag));
+        }
+
+        // If not set on the first (or only) transfer for a (multi-transfer)
+        // delivery, then the settled flag MUST be interpreted as being false.
+        //
+        // The delivery is added to the unsettled map before the transfer is sent so that a
+        // disposition that arrives right after the transfer is not lost
+        let (tx, rx) = oneshot::channel();
+        let unsettled = UnsettledMessage::new(payload_copy, None, message_format, tx);
+        {
+            let mut guard = self.unsettled.write();
+            guard
+                .get_or_insert(OrderedMap::new())
+                .insert(delivery_tag.clone(), unsettled);
+        }
 
-                Ok(Settlement::Unsettled {
-                    delivery_tag,
-                    outcome: rx,
-                })
+        if let Err(error) = self
+            .send_transfer_without_modifying_unsettled_map(writer, transfer, payload)
+            .await
+        {
+            if let Some(map) = self.unsettled.write().as_mut() {
+                map.swap_remove(&delivery_tag);
             }
+            return Err(error);
         }
+
+        Ok(Settlement::Unsettled {
+            delivery_tag,
+            outcome: rx,
+        })
     }
 
     async fn dispose(
@@ -813,7 +862,7 @@ where
             | SenderAttachError::ExpectImmediateDetach
             | SenderAttachError::RemoteClosedWithError(_) => attach_error,
 
-            SenderAttachError::DuplicatedLinkName => {
+            SenderAttachError::DuplicatedLinkName(_) => {
                 let error = definitions::Error::new(
                     SessionError::HandleInUse,
                     "Link name is in use".to_string(),
@@ -903,3 +952,277 @@ where
         None => SenderAttachError::IllegalSessionState,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use fe2o3_amqp_types::{
+        definitions::{self, AmqpError, DeliveryTag, SenderSettleMode},
+        messaging::{Target, MESSAGE_FORMAT},
+        performatives::{Detach, Transfer},
+    };
+    use parking_lot::RwLock;
+    use tokio::sync::{mpsc, Notify};
+
+    use crate::{
+        endpoint::{self, InputHandle, OutputHandle, Settlement},
+        link::{
+            state::{LinkFlowState, LinkFlowStateInner, LinkState},
+            DetachError, LinkFrame, LinkStateError, Sender, SenderLink,
+        },
+        util::Consumer,
+    };
+
+    fn attached_sender_link(
+        snd_settle_mode: SenderSettleMode,
+        link_credit: u32,
+        max_message_size: u64,
+    ) -> SenderLink<Target> {
+        let flow_state = LinkFlowState::sender(LinkFlowStateInner {
+            initial_delivery_count: 0,
+            delivery_count: 0,
+            link_credit,
+            available: 0,
+            drain: false,
+            properties: None,
+        });
+        let consumer = Consumer::new(Arc::new(Notify::new()), Arc::new(flow_state));
+        let mut link = Sender::builder()
+            .name("sender-link")
+            .target("q1")
+            .sender_settle_mode(snd_settle_mode)
+            .max_message_size(max_message_size)
+            .create_link(Arc::new(RwLock::new(None)), OutputHandle(0), consumer);
+
+        // Simulate a successful attach exchange with the remote peer
+        link.input_handle = Some(InputHandle(1));
+        link.local_state = LinkState::Attached;
+        link
+    }
+
+    fn recv_transfer(rx: &mut mpsc::Receiver<LinkFrame>) -> (InputHandle, Transfer, Bytes) {
+        match rx.try_recv().unwrap() {
+            LinkFrame::Transfer {
+                input_handle,
+                performative,
+                payload,
+            } => (input_handle, performative, payload),
+            _ => panic!("Expecting a transfer frame"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_payload_emits_settled_transfer() {
+        use endpoint::SenderLink;
+
+        let mut link = attached_sender_link(SenderSettleMode::Settled, 2, 0);
+        let (writer, mut rx) = mpsc::channel(10);
+
+        let payload = Bytes::from_static(b"hello");
+        let settlement = link
+            .send_payload(
+                &writer,
+                std::future::pending(),
+                payload.clone(),
+                MESSAGE_FORMAT,
+                None,
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(
+            matches!(settlement, Settlement::Settled(tag) if tag == DeliveryTag::from([0u8; 4]))
+        );
+
+        let (input_handle, transfer, sent) = recv_transfer(&mut rx);
+        assert_eq!(input_handle, InputHandle(1));
+        assert_eq!(sent, payload);
+        let expected = [
+            0x00, 0x53, 0x14, // descriptor
+            0xc0, 0x0b, 0x05, // list8, size, count (trailing default fields are omitted)
+            0x43, // handle: uint0
+            0x40, // delivery-id: null, assigned by the session
+            0xa0, 0x04, 0x00, 0x00, 0x00, 0x00, // delivery-tag: vbin8
+            0x43, // message-format: uint0
+            0x41, // settled: true
+        ];
+        assert_eq!(serde_amqp::to_vec(&transfer).unwrap(), expected);
+        assert!(rx.try_recv().is_err());
+
+        // Consuming a credit advances the delivery count
+        let state = link.flow_state.state().lock.read();
+        assert_eq!(state.delivery_count, 1);
+        assert_eq!(state.link_credit, 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_payload_sends_single_transfer_within_max_message_size() {
+        use endpoint::SenderLink;
+
+        let mut link = attached_sender_link(SenderSettleMode::Unsettled, 1, 10);
+        let (writer, mut rx) = mpsc::channel(10);
+
+        let payload = Bytes::from_static(b"0123456789");
+        let settlement = link
+            .send_payload(
+                &writer,
+                std::future::pending(),
+                payload.clone(),
+                MESSAGE_FORMAT,
+                None,
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(settlement, Settlement::Unsettled { .. }));
+
+        // Splitting into multiple frames is left to the frame encoder
+        let (_, transfer, partial) = recv_transfer(&mut rx);
+        assert!(!transfer.more);
+        assert!(transfer.delivery_tag.is_some());
+        assert_eq!(partial, payload);
+        assert!(rx.try_recv().is_err());
+
+        assert_eq!(link.unsettled.read().as_ref().map(|m| m.len()), Some(1));
+    }
+
+    fn closing_detach_with_error() -> Detach {
+        Detach {
+            handle: 1.into(),
+            closed: true,
+            error: Some(definitions::Error::new(
+                AmqpError::ResourceLimitExceeded,
+                None,
+                None,
+            )),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_incoming_closing_detach_with_error() {
+        use endpoint::{LinkDetach, SenderLink};
+
+        let mut link = attached_sender_link(SenderSettleMode::Unsettled, 1, 0);
+        let (writer, mut rx) = mpsc::channel(10);
+
+        let err = link
+            .on_incoming_detach(closing_detach_with_error())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DetachError::RemoteClosedWithError(error)
+                if error.condition == AmqpError::ResourceLimitExceeded.into()
+        ));
+        assert!(matches!(link.local_state, LinkState::CloseReceived));
+
+        // No new transfer is accepted once the remote peer has closed the link
+        let err = link
+            .send_payload(
+                &writer,
+                std::future::pending(),
+                Bytes::from_static(b"hello"),
+                MESSAGE_FORMAT,
+                None,
+                None,
+                false,
+            )
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, LinkStateError::RemoteClosed));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_payload_interrupted_by_closing_detach() {
+        use endpoint::SenderLink;
+
+        // Without link credit the sender waits until the remote peer detaches the link
+        let mut link = attached_sender_link(SenderSettleMode::Unsettled, 0, 0);
+        let (writer, mut rx) = mpsc::channel(10);
+
+        let detached = async { Some(LinkFrame::Detach(closing_detach_with_error())) };
+        let err = link
+            .send_payload(
+                &writer,
+                detached,
+                Bytes::from_static(b"hello"),
+                MESSAGE_FORMAT,
+                None,
+                None,
+                false,
+            )
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            LinkStateError::RemoteClosedWithError(error)
+                if error.condition == AmqpError::ResourceLimitExceeded.into()
+        ));
+        assert!(matches!(link.local_state, LinkState::Closed));
+
+        // The closing detach is echoed back to the remote peer
+        match rx.try_recv().unwrap() {
+            LinkFrame::Detach(detach) => assert!(detach.closed),
+            _ => panic!("Expecting a detach frame"),
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_credit_resolves_once_enough_credit_is_granted() {
+        use futures_util::poll;
+
+        use crate::{endpoint::LinkFlow, util::Produce};
+
+        let mut link = attached_sender_link(SenderSettleMode::Unsettled, 0, 0);
+        let mut producer = link.flow_state.producer();
+        let (writer, mut rx) = mpsc::channel(10);
+
+        let credit = {
+            let wait = link.wait_for_credit_or_detached(&writer, 3, std::future::pending());
+            tokio::pin!(wait);
+            assert!(poll!(&mut wait).is_pending());
+
+            // Not enough credit is granted
+            let link_flow = LinkFlow {
+                link_credit: Some(2),
+                ..Default::default()
+            };
+            producer.produce((link_flow, OutputHandle(0))).await;
+            assert!(poll!(&mut wait).is_pending());
+
+            let link_flow = LinkFlow {
+                link_credit: Some(5),
+                ..Default::default()
+            };
+            producer.produce((link_flow, OutputHandle(0))).await;
+            wait.await.unwrap()
+        };
+        assert_eq!(credit, 5);
+
+        // Waiting for credit does not consume any credit
+        assert_eq!(link.flow_state.state().link_credit(), 5);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_credit_interrupted_by_closing_detach() {
+        let mut link = attached_sender_link(SenderSettleMode::Unsettled, 0, 0);
+        let (writer, mut rx) = mpsc::channel(10);
+
+        let detached = async { Some(LinkFrame::Detach(closing_detach_with_error())) };
+        let err = link
+            .wait_for_credit_or_detached(&writer, 1, detached)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LinkStateError::RemoteClosedWithError(_)));
+        assert!(matches!(link.local_state, LinkState::Closed));
+        assert!(matches!(rx.try_recv().unwrap(), LinkFrame::Detach(_)));
+    }
+}
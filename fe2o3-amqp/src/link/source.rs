@@ -34,9 +34,10 @@ impl VerifySource for Source {
         // spec where the value is not a described type.
         verify_filter(&self.filter, &other.filter)?;
 
-        if other.dynamic && other.address.is_none() {
+        if (self.dynamic || other.dynamic) && other.address.is_none() {
             // When set to true by the sending link endpoint this field indicates creation of a dynamically created
-            // node. In this case the address field will contain the address of the created node
+            // node. In this case the address field will contain the address of the created node.
+            // A dynamic node requested locally must also come back with an address
             Err(ReceiverAttachError::SourceAddressIsNoneWhenDynamicIsTrue)
         } else if !other.dynamic && other.dynamic_node_properties.is_some() {
             Err(ReceiverAttachError::DynamicNodePropertiesIsSomeWhenDynamicIsFalse)
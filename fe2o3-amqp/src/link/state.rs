@@ -228,6 +228,30 @@ impl LinkFlowState<role::ReceiverMarker> {
             false => None,
         }
     }
+
+    /// Handles a Flow frame that is forwarded to the receiver while draining
+    ///
+    /// This must be called after the transfers that precede the flow have consumed link credit.
+    /// Returns whether the flow completes the drain cycle, in which case the local drain flag is
+    /// reset
+    pub(crate) fn on_drain_flow(&self, flow: &LinkFlow) -> bool {
+        let mut state = self.lock.write();
+
+        // The sender advances the delivery-count without sending any message when it consumes
+        // the remaining link-credit while draining. The delivery limit (delivery-count +
+        // link-credit) stays the same.
+        if let Some(delivery_count) = flow.delivery_count {
+            let advanced = delivery_count.wrapping_sub(state.delivery_count);
+            state.link_credit = state.link_credit.saturating_sub(advanced);
+            state.delivery_count = delivery_count;
+        }
+
+        let drained = state.drain && flow.link_credit == Some(0) && state.link_credit == 0;
+        if drained {
+            state.drain = false;
+        }
+        drained
+    }
 }
 
 impl<R> LinkFlowState<R> {
@@ -235,10 +259,18 @@ impl<R> LinkFlowState<R> {
         self.lock.read().link_credit
     }
 
+    pub fn delivery_count(&self) -> SequenceNo {
+        self.lock.read().delivery_count
+    }
+
     pub fn drain(&self) -> bool {
         self.lock.read().drain
     }
 
+    pub fn available(&self) -> u32 {
+        self.lock.read().available
+    }
+
     pub fn initial_delivery_count(&self) -> SequenceNo {
         self.lock.read().initial_delivery_count
     }
@@ -271,6 +303,9 @@ impl LinkFlowState<role::ReceiverMarker> {
         } else {
             state.delivery_count = state.delivery_count.wrapping_add(count);
             state.link_credit = state.link_credit.saturating_sub(count);
+            // The receiver MUST maintain a floor of zero in its calculation of the value of
+            // available
+            state.available = state.available.saturating_sub(count);
             Ok(())
         }
     }
@@ -497,4 +532,56 @@ mod tests {
         // All credits have been consumed already
         assert_pending!(consumer.consume(1));
     }
+
+    fn create_receiver_flow_state(
+        link_credit: u32,
+        drain: bool,
+    ) -> LinkFlowState<role::ReceiverMarker> {
+        LinkFlowState::receiver(LinkFlowStateInner {
+            initial_delivery_count: 0,
+            delivery_count: 0,
+            link_credit,
+            available: 0,
+            drain,
+            properties: None,
+        })
+    }
+
+    #[test]
+    fn test_receiver_flow_state_completes_drain_cycle() {
+        let flow_state = create_receiver_flow_state(5, true);
+
+        // Two messages have arrived before the sender consumes the remaining credit
+        flow_state.consume(2).unwrap();
+        assert_eq!(flow_state.link_credit(), 3);
+
+        let link_flow = LinkFlow {
+            delivery_count: Some(5),
+            link_credit: Some(0),
+            drain: true,
+            ..Default::default()
+        };
+        assert!(flow_state.on_drain_flow(&link_flow));
+        assert_eq!(flow_state.link_credit(), 0);
+        assert_eq!(flow_state.delivery_count(), 5);
+        assert!(!flow_state.drain());
+    }
+
+    #[test]
+    fn test_receiver_flow_state_keeps_draining_until_credit_is_consumed() {
+        let flow_state = create_receiver_flow_state(5, true);
+
+        // The sender has sent two messages and still holds the remaining credit
+        flow_state.consume(2).unwrap();
+        let link_flow = LinkFlow {
+            delivery_count: Some(2),
+            link_credit: Some(3),
+            drain: true,
+            ..Default::default()
+        };
+        assert!(!flow_state.on_drain_flow(&link_flow));
+        assert_eq!(flow_state.link_credit(), 3);
+        assert_eq!(flow_state.delivery_count(), 2);
+        assert!(flow_state.drain());
+    }
 }
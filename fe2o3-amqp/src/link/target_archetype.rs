@@ -19,8 +19,9 @@ impl VerifyTargetArchetype for Target {
         // The address of the source MUST be set when sent on a attach frame sent by the receiving
         // link endpoint where the dynamic flag is set to true (that is where the receiver has
         // created an addressable node at the request of the sender and is now communicating the
-        // address of that created node).
-        if other.dynamic && other.address.is_none() {
+        // address of that created node). A dynamic node requested locally must also come back with
+        // an address.
+        if (self.dynamic || other.dynamic) && other.address.is_none() {
             Err(SenderAttachError::TargetAddressIsNoneWhenDynamicIsTrue)
         } else if !other.dynamic && other.dynamic_node_properties.is_some() {
             // If the dynamic field is not set to true this field MUST be left unset.
@@ -32,6 +32,31 @@ macro_rules! cfg_acceptor {
     }
 }
 
+/// The test server builds on the acceptor and is not supported in wasm32 targets
+macro_rules! cfg_test_server {
+    ($($item:item)*) => {
+        $(
+            #[cfg_attr(docsrs, doc(cfg(feature = "test-server")))]
+            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(feature = "test-server")]
+            $item
+        )*
+    }
+}
+
+/// The blocking wrappers rely on a tokio runtime driven by a thread, which is not supported in
+/// wasm32 targets
+macro_rules! cfg_blocking {
+    ($($item:item)*) => {
+        $(
+            #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(feature = "blocking")]
+            $item
+        )*
+    }
+}
+
 macro_rules! cfg_transaction {
     ($($item:item)*) => {
         $(
@@ -0,0 +1,241 @@
+//! Request/reply over a pair of links
+//!
+//! A [`RequestReplyClient`] attaches a sender to the address of a service and a receiver to a
+//! reply node that is dynamically created by the remote peer. Each request is sent with the
+//! `reply-to` set to the address of the reply node and with a `message-id`, and the response is
+//! the first message received on the reply node whose `correlation-id` is the `message-id` of the
+//! request. Messages with any other `correlation-id` are accepted and discarded without being
+//! decoded as the response type.
+//!
+//! ```rust, ignore
+//! let mut client = RequestReplyClient::attach(&mut session, "client", "service").await.unwrap();
+//! let response: Delivery<String> = client.request(Message::from("ping")).await.unwrap();
+//! client.close().await.unwrap();
+//! ```
+
+use std::time::Duration;
+
+use fe2o3_amqp_types::{
+    definitions::{self, AmqpError},
+    messaging::{
+        message::__private::{Deserializable, Serializable},
+        Address, Body, FromBody, Message, MessageId, Outcome, Properties, SerializableBody,
+    },
+    primitives::Value,
+};
+use serde_amqp::{from_slice, to_vec};
+use tokio::time::{timeout_at, Instant};
+
+use crate::{
+    link::{
+        delivery::DeliveryInfo, DetachError, DispositionError, MessageDecodeError,
+        ReceiverAttachError, RecvError, SendError, SenderAttachError,
+    },
+    session::SessionHandle,
+    Delivery, Receiver, Sender,
+};
+
+/// Default time to wait for the response of a request
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Error attaching a [`RequestReplyClient`]
+#[derive(Debug, thiserror::Error)]
+pub enum RequestReplyAttachError {
+    /// Error attaching the sender link
+    #[error(transparent)]
+    Sender(#[from] SenderAttachError),
+
+    /// Error attaching the receiver link to the dynamic reply node
+    #[error(transparent)]
+    Receiver(#[from] ReceiverAttachError),
+}
+
+/// Error sending a request or receiving its response
+#[derive(Debug, thiserror::Error)]
+pub enum RequestReplyError {
+    /// Error sending the request
+    #[error(transparent)]
+    Send(#[from] SendError),
+
+    /// The request is not accepted by the remote peer
+    #[error("Request is not accepted: {:?}", .0)]
+    NotAccepted(Outcome),
+
+    /// Error receiving the response
+    #[error(transparent)]
+    Recv(#[from] RecvError),
+
+    /// Error settling the response
+    #[error(transparent)]
+    Disposition(#[from] DispositionError),
+
+    /// The response did not arrive within the timeout
+    #[error("Timed out waiting for the response")]
+    Timeout,
+}
+
+/// A client that sends requests and correlates the responses by `correlation-id`
+///
+/// Requests are sent one at a time. A response that arrives after its request has timed out is
+/// accepted and discarded when the next request waits for its own response, even if it cannot be
+/// decoded as the response type of the next request.
+#[derive(Debug)]
+pub struct RequestReplyClient {
+    sender: Sender,
+    receiver: Receiver,
+    reply_to: Address,
+    next_message_id: u64,
+    timeout: Duration,
+}
+
+impl RequestReplyClient {
+    /// Attach a sender to `target` and a receiver to a reply node dynamically created by the
+    /// remote peer
+    ///
+    /// The links are named `"{name}-sender"` and `"{name}-receiver"`.
+    pub async fn attach<R>(
+        session: &mut SessionHandle<R>,
+        name: impl Into<String>,
+        target: impl Into<Address>,
+    ) -> Result<Self, RequestReplyAttachError> {
+        let name = name.into();
+        let sender = Sender::attach(session, format!("{}-sender", name), target).await?;
+        let (receiver, reply_to) =
+            match Receiver::attach_dynamic(session, format!("{}-receiver", name), None).await {
+                Ok(attached) => attached,
+                Err(err) => {
+                    let _ = sender.close().await;
+                    return Err(err.into());
+                }
+            };
+
+        Ok(Self {
+            sender,
+            receiver,
+            reply_to,
+            next_message_id: 0,
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+        })
+    }
+
+    /// Address of the dynamic reply node that is set as the `reply-to` of every request
+    pub fn reply_to(&self) -> &Address {
+        &self.reply_to
+    }
+
+    /// Time to wait for the response of a request
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Set the time to wait for the response of a request
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send a request and wait for its response
+    ///
+    /// The `reply-to` of the request is set to the address of the reply node. A `message-id` is
+    /// generated if the request doesn't have one. The returned response is accepted. A response
+    /// that cannot be decoded as `T` is rejected with an `amqp:decode-error`, and
+    /// [`RecvError::MessageDecode`] is returned.
+    ///
+    /// [`RequestReplyError::Timeout`] is returned if the request is not settled and its response
+    /// received within the timeout. A request that has already been transferred stays in the
+    /// unsettled map of the sender.
+    pub async fn request<B, T>(
+        &mut self,
+        mut message: Message<B>,
+    ) -> Result<Delivery<T>, RequestReplyError>
+    where
+        B: SerializableBody,
+        for<'de> T: FromBody<'de> + Send,
+    {
+        let properties = message.properties.get_or_insert(Properties::default());
+        let message_id = properties
+            .message_id
+            .get_or_insert_with(|| {
+                let message_id = MessageId::from(self.next_message_id);
+                self.next_message_id = self.next_message_id.wrapping_add(1);
+                message_id
+            })
+            .clone();
+        properties.reply_to = Some(self.reply_to.clone());
+
+        let deadline = Instant::now() + self.timeout;
+        let outcome = timeout_at(deadline, self.sender.send(message))
+            .await
+            .map_err(|_| RequestReplyError::Timeout)??;
+        let _accepted = outcome.accepted_or_else(RequestReplyError::NotAccepted)?;
+
+        loop {
+            // `recv` is cancel-safe, so no response is lost if the deadline is reached. The body is
+            // only decoded as `T` once the response is known to belong to this request
+            let delivery: Delivery<Body<Value>> = timeout_at(deadline, self.receiver.recv())
+                .await
+                .map_err(|_| RequestReplyError::Timeout)??;
+
+            let correlation_id = delivery
+                .message()
+                .properties
+                .as_ref()
+                .and_then(|p| p.correlation_id.as_ref());
+            if correlation_id == Some(&message_id) {
+                return match decode_delivery(delivery) {
+                    Ok(delivery) => {
+                        self.receiver.accept(&delivery).await?;
+                        Ok(delivery)
+                    }
+                    Err(err) => {
+                        let error = definitions::Error::new(
+                            AmqpError::DecodeError,
+                            err.source.to_string(),
+                            None,
+                        );
+                        self.receiver.reject(err.info.clone(), error).await?;
+                        Err(RecvError::MessageDecode(err).into())
+                    }
+                };
+            }
+
+            // Response of a request that has timed out
+            self.receiver.accept(&delivery).await?;
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?correlation_id, "Discarding uncorrelated response");
+            #[cfg(feature = "log")]
+            log::debug!("Discarding uncorrelated response {:?}", correlation_id);
+        }
+    }
+
+    /// Close the sender and the receiver
+    pub async fn close(self) -> Result<(), DetachError> {
+        self.sender.close().await?;
+        self.receiver.close().await?;
+        Ok(())
+    }
+}
+
+/// Decodes the message of a delivery received with a generic body as `T`
+fn decode_delivery<T>(delivery: Delivery<Body<Value>>) -> Result<Delivery<T>, MessageDecodeError>
+where
+    for<'de> T: FromBody<'de>,
+{
+    let message = to_vec(&Serializable(&delivery.message)).and_then(|buf| {
+        from_slice::<Deserializable<Message<T>>>(&buf).map(|message| message.0)
+    });
+    match message {
+        Ok(message) => Ok(Delivery {
+            link_output_handle: delivery.link_output_handle,
+            delivery_id: delivery.delivery_id,
+            delivery_tag: delivery.delivery_tag,
+            message_format: delivery.message_format,
+            rcv_settle_mode: delivery.rcv_settle_mode,
+            message,
+        }),
+        Err(source) => Err(MessageDecodeError {
+            info: DeliveryInfo::from(delivery),
+            source,
+        }),
+    }
+}
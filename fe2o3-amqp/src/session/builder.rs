@@ -1,6 +1,6 @@
 //! Session builder
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use fe2o3_amqp_types::definitions::{Fields, Handle, TransferNumber};
 use serde_amqp::primitives::Symbol;
@@ -49,6 +49,10 @@ pub struct Builder {
     /// that are used by links attached to the session
     pub buffer_size: usize,
 
+    /// Whether the session retains its states after the underlying connection stops so that it
+    /// can be rebound to a new connection with [`SessionHandle::rebind`]
+    pub rebindable: bool,
+
     /// Acceptor for incoming transaction control links
     #[cfg(not(target_arch = "wasm32"))]
     #[cfg(all(feature = "transaction", feature = "acceptor"))]
@@ -66,6 +70,7 @@ impl Default for Builder {
             desired_capabilities: None,
             properties: None,
             buffer_size: DEFAULT_SESSION_MUX_BUFFER_SIZE,
+            rebindable: false,
 
             #[cfg(not(target_arch = "wasm32"))]
             #[cfg(all(feature = "transaction", feature = "acceptor"))]
@@ -96,6 +101,7 @@ cfg_transaction! {
                     local_state,
                     initial_outgoing_id: Constant::new(self.next_outgoing_id),
                     next_outgoing_id: self.next_outgoing_id,
+                    initial_incoming_window: Constant::new(self.incoming_window),
                     incoming_window: self.incoming_window,
                     outgoing_window: self.outgoing_window,
                     handle_max: self.handle_max,
@@ -112,6 +118,9 @@ cfg_transaction! {
                     link_by_name: HashMap::new(),
                     link_by_input_handle: HashMap::new(),
                     delivery_tag_by_id: HashMap::new(),
+                    local_attach_by_name: self.rebindable.then(HashMap::new),
+                    rebound_input_handles: HashMap::new(),
+                    refused_output_handles: HashSet::new(),
                 };
 
                 TxnSession {
@@ -141,6 +150,7 @@ impl Builder {
             local_state,
             initial_outgoing_id: Constant::new(self.next_outgoing_id),
             next_outgoing_id: self.next_outgoing_id,
+            initial_incoming_window: Constant::new(self.incoming_window),
             incoming_window: self.incoming_window,
             outgoing_window: self.outgoing_window,
             handle_max: self.handle_max,
@@ -157,6 +167,9 @@ impl Builder {
             link_by_name: HashMap::new(),
             link_by_input_handle: HashMap::new(),
             delivery_tag_by_id: HashMap::new(),
+            local_attach_by_name: self.rebindable.then(HashMap::new),
+            rebound_input_handles: HashMap::new(),
+            refused_output_handles: HashSet::new(),
         }
     }
 
@@ -222,11 +235,31 @@ impl Builder {
 
     /// Buffer size of the underlying [`tokio::sync::mpsc::channel`]
     /// that are used by links attached to the session
+    ///
+    /// This also sizes the channel that relays incoming frames from the connection engine to the
+    /// session. When this channel is full, the connection engine waits until the session has
+    /// processed some of the buffered frames, which in turn blocks the frames of all other
+    /// sessions on the same connection. A larger buffer allows the session to absorb bursts of
+    /// incoming frames at the cost of memory. The default is `u16::MAX`.
+    ///
+    /// # Panics
+    ///
+    /// Beginning the session panics if `buffer_size` is zero
     pub fn buffer_size(mut self, buffer_size: usize) -> Self {
         self.buffer_size = buffer_size;
         self
     }
 
+    /// Retain the session states after the underlying connection stops so that the session and
+    /// its links can be rebound to a new connection with [`SessionHandle::rebind`].
+    ///
+    /// While a rebindable session is waiting to be rebound, outgoing frames from its links are
+    /// buffered instead of failing.
+    pub fn rebindable(mut self, rebindable: bool) -> Self {
+        self.rebindable = rebindable;
+        self
+    }
+
     // TODO
     // /// Enable handling remotely initiated control link and transaction by setting the
     // /// `control_link_acceptor` field
@@ -274,7 +307,9 @@ impl Builder {
             };
 
             #[cfg(not(all(feature = "transaction", feature = "acceptor")))]
-            let (engine_handle, outcome) = {
+            let (engine_handle, outcome, remnant) = {
+                let rebindable = self.rebindable;
+                let buffer_size = self.buffer_size;
                 let session = self.into_session(outgoing_channel, local_state);
                 let engine = SessionEngine::begin_client_session(
                     connection.control.clone(),
@@ -285,11 +320,11 @@ impl Builder {
                     outgoing_rx,
                 )
                 .await?;
-                engine.spawn()
+                spawn_client_engine(engine, rebindable, buffer_size)
             };
 
             #[cfg(all(feature = "transaction", feature = "acceptor"))]
-            let (engine_handle, outcome) = {
+            let (engine_handle, outcome, remnant) = {
                 let mut this = self;
                 match this.control_link_acceptor.take() {
                     Some(control_link_acceptor) => {
@@ -309,9 +344,12 @@ impl Builder {
                             outgoing_rx,
                         )
                         .await?;
-                        engine.spawn()
+                        let (engine_handle, outcome) = engine.spawn();
+                        (engine_handle, outcome, None)
                     }
                     None => {
+                        let rebindable = this.rebindable;
+                        let buffer_size = this.buffer_size;
                         let session = this.into_session(outgoing_channel, local_state);
                         let engine = SessionEngine::begin_client_session(
                             connection.control.clone(),
@@ -322,7 +360,7 @@ impl Builder {
                             outgoing_rx,
                         )
                         .await?;
-                        engine.spawn()
+                        spawn_client_engine(engine, rebindable, buffer_size)
                     }
                 }
             };
@@ -332,6 +370,7 @@ impl Builder {
                 control: session_control_tx,
                 engine_handle,
                 outcome,
+                remnant,
                 outgoing: outgoing_tx,
                 link_listener: (),
             };
@@ -393,6 +432,7 @@ impl Builder {
                 control: session_control_tx,
                 engine_handle,
                 outcome,
+                remnant: None,
                 outgoing: outgoing_tx,
                 link_listener: (),
             };
@@ -453,6 +493,7 @@ impl Builder {
                 control: session_control_tx,
                 engine_handle,
                 outcome,
+                remnant: None,
                 outgoing: outgoing_tx,
                 link_listener: (),
             };
@@ -460,3 +501,31 @@ impl Builder {
         }
     }
 }
+
+cfg_not_wasm32! {
+    use tokio::{sync::oneshot, task::JoinHandle};
+
+    use super::{engine::SessionRemnant, error::Error};
+
+    #[allow(clippy::type_complexity)]
+    fn spawn_client_engine(
+        engine: SessionEngine<Session>,
+        rebindable: bool,
+        buffer_size: usize,
+    ) -> (
+        JoinHandle<()>,
+        oneshot::Receiver<Result<(), Error>>,
+        Option<oneshot::Receiver<SessionRemnant>>,
+    ) {
+        match rebindable {
+            true => {
+                let (engine_handle, outcome, remnant) = engine.spawn_rebindable(buffer_size);
+                (engine_handle, outcome, Some(remnant))
+            }
+            false => {
+                let (engine_handle, outcome) = engine.spawn();
+                (engine_handle, outcome, None)
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use fe2o3_amqp_types::{
-    definitions::{self, AmqpError, SessionError},
-    performatives::End,
+    definitions::{self, AmqpError, LinkError, SessionError},
+    performatives::{Attach, Detach, End},
 };
 use tokio::{
     sync::{mpsc, oneshot},
@@ -8,10 +10,10 @@ use tokio::{
 };
 
 use crate::{
-    connection::{self},
+    connection::{self, AllocSessionError, ConnectionHandle},
     control::{ConnectionControl, SessionControl},
-    endpoint::{self, IncomingChannel, Session},
-    link::LinkFrame,
+    endpoint::{self, IncomingChannel, InputHandle, OutputHandle, Session},
+    link::{LinkFrame, LinkRelay},
     util::Running,
     SendBound,
 };
@@ -115,7 +117,9 @@ cfg_not_wasm32! {
     {
         pub fn spawn(self) -> (JoinHandle<()>, oneshot::Receiver<Result<(), Error>>) {
             let (tx, rx) = oneshot::channel();
-            let handle = tokio::spawn(self.event_loop(tx));
+            let handle = tokio::spawn(async move {
+                let _ = self.event_loop(tx).await;
+            });
             (handle, rx)
         }
     }
@@ -130,13 +134,17 @@ cfg_wasm32! {
     {
         pub fn spawn_local(self) -> (JoinHandle<()>, oneshot::Receiver<Result<(), Error>>) {
             let (tx, rx) = oneshot::channel();
-            let handle = tokio::task::spawn_local(self.event_loop(tx));
+            let handle = tokio::task::spawn_local(async move {
+                let _ = self.event_loop(tx).await;
+            });
             (handle, rx)
         }
 
         pub fn spawn_on_local_set(self, local_set: &tokio::task::LocalSet) -> (JoinHandle<()>, oneshot::Receiver<Result<(), Error>>) {
             let (tx, rx) = oneshot::channel();
-            let handle = local_set.spawn_local(self.event_loop(tx));
+            let handle = local_set.spawn_local(async move {
+                let _ = self.event_loop(tx).await;
+            });
             (handle, rx)
         }
     }
@@ -175,6 +183,12 @@ where
                 self.session
                     .on_incoming_transfer(performative, payload)
                     .await?;
+                if let Some(flow) = self.session.replenish_incoming_window() {
+                    self.outgoing
+                        .send(flow)
+                        .await
+                        .map_err(|_| SessionInnerError::IllegalConnectionState)?;
+                }
             }
             SessionFrameBody::Disposition(disposition) => {
                 if let Some(dispositions) = self.session.on_incoming_disposition(disposition)? {
@@ -283,8 +297,8 @@ where
             }
 
             #[cfg(feature = "transaction")]
-            SessionControl::AllocateTransactionId { resp } => {
-                let result = self.session.allocate_transaction_id();
+            SessionControl::AllocateTransactionId { global_id, resp } => {
+                let result = self.session.allocate_transaction_id(global_id);
                 resp.send(result)
                     .map_err(|_| SessionInnerError::UnattachedHandle)?;
             }
@@ -345,9 +359,10 @@ where
                 .on_outgoing_disposition(disposition)
                 .map(SessionOutgoingItem::SingleFrame)
                 .map(Some)?,
-            LinkFrame::Detach(detach) => Some(SessionOutgoingItem::SingleFrame(
-                self.session.on_outgoing_detach(detach),
-            )),
+            LinkFrame::Detach(detach) => self
+                .session
+                .on_outgoing_detach(detach)
+                .map(SessionOutgoingItem::SingleFrame),
 
             #[cfg(feature = "transaction")]
             LinkFrame::Acquisition(_) => {
@@ -404,6 +419,10 @@ where
                 );
                 self.end_session(Some(error)).await
             }
+            SessionInnerError::WindowViolation => {
+                let error = Error::new(SessionError::WindowViolation, None, None);
+                self.end_session(Some(error)).await
+            }
             SessionInnerError::RemoteEnded | SessionInnerError::RemoteEndedWithError(_) => {
                 self.end_session(None).await
             }
@@ -485,7 +504,7 @@ where
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(name = "Session::event_loop", skip(self), fields(outgoing_channel = %self.session.outgoing_channel().0)))]
-    async fn event_loop(mut self, tx: oneshot::Sender<Result<(), Error>>) {
+    async fn event_loop(mut self, tx: oneshot::Sender<Result<(), Error>>) -> Self {
         let mut outcome = Ok(());
         loop {
             let result = tokio::select! {
@@ -594,5 +613,224 @@ where
                 .await;
         let result = outcome.map_err(Into::into);
         let _ = tx.send(result);
+        self
+    }
+}
+
+/// States retained after the event loop of a rebindable session stops, which allows the session
+/// to be begun again on a new connection
+pub(crate) struct SessionRemnant {
+    pub session: super::Session,
+    pub control: mpsc::Receiver<SessionControl>,
+    pub outgoing_link_frames: mpsc::Receiver<LinkFrame>,
+    pub buffer_size: usize,
+}
+
+cfg_not_wasm32! {
+    impl SessionEngine<super::Session> {
+        pub fn spawn_rebindable(
+            self,
+            buffer_size: usize,
+        ) -> (
+            JoinHandle<()>,
+            oneshot::Receiver<Result<(), Error>>,
+            oneshot::Receiver<SessionRemnant>,
+        ) {
+            let (tx, rx) = oneshot::channel();
+            let (remnant_tx, remnant_rx) = oneshot::channel();
+            let handle = tokio::spawn(async move {
+                let engine = self.event_loop(tx).await;
+                // The relays are retained for the rebind, and the receivers waiting for the
+                // remote senders to settle deliveries need to be woken up
+                for relay in engine.session.link_by_input_handle.values() {
+                    if let LinkRelay::Receiver { settled, .. } = relay {
+                        settled.0.close();
+                    }
+                }
+                let remnant = SessionRemnant {
+                    session: engine.session,
+                    control: engine.control,
+                    outgoing_link_frames: engine.outgoing_link_frames,
+                    buffer_size,
+                };
+                let _ = remnant_tx.send(remnant);
+            });
+            (handle, rx, remnant_rx)
+        }
+
+        /// Begins the retained session on a new connection and re-attaches the links that were
+        /// attached on the previous connection
+        pub(crate) async fn rebind_client_session(
+            connection: &mut ConnectionHandle<()>,
+            remnant: SessionRemnant,
+        ) -> Result<Self, BeginError> {
+            let SessionRemnant {
+                mut session,
+                control,
+                outgoing_link_frames,
+                buffer_size,
+            } = remnant;
+            let (incoming_tx, incoming_rx) = mpsc::channel(buffer_size);
+
+            let outgoing_channel = match connection.allocate_session(incoming_tx).await {
+                Ok(channel) => channel,
+                Err(AllocSessionError::IllegalState) => {
+                    return Err(BeginError::IllegalConnectionState)
+                }
+                Err(AllocSessionError::ChannelMaxReached) => {
+                    return Err(BeginError::LocalChannelMaxReached)
+                }
+            };
+            let reattaching = session.reset_for_rebind(outgoing_channel);
+
+            let mut engine = Self::begin_client_session(
+                connection.control.clone(),
+                session,
+                control,
+                incoming_rx,
+                connection.outgoing.clone(),
+                outgoing_link_frames,
+            )
+            .await?;
+            engine.reattach_links(reattaching).await?;
+            Ok(engine)
+        }
+
+        async fn reattach_links(
+            &mut self,
+            reattaching: Vec<(Attach, LinkRelay<OutputHandle>)>,
+        ) -> Result<(), BeginError> {
+            let mut pending = HashMap::with_capacity(reattaching.len());
+            for (attach, relay) in reattaching {
+                pending.insert(attach.name.clone(), relay);
+                let frame = self
+                    .session
+                    .on_outgoing_attach(attach)
+                    .map_err(|_| BeginError::IllegalState)?;
+                self.outgoing
+                    .send(frame)
+                    .await
+                    .map_err(|_| BeginError::IllegalConnectionState)?;
+            }
+
+            while !pending.is_empty() {
+                let SessionFrame { channel, body } = self
+                    .incoming
+                    .recv()
+                    .await
+                    .ok_or(BeginError::IllegalConnectionState)?;
+                match body {
+                    SessionFrameBody::Attach(attach) if pending.contains_key(&attach.name) => {
+                        if let Some(relay) = pending.remove(&attach.name) {
+                            self.on_reattach(attach, relay).await?;
+                        }
+                    }
+                    SessionFrameBody::End(end) => match end.error {
+                        Some(error) => return Err(BeginError::RemoteEndedWithError(error)),
+                        None => return Err(BeginError::RemoteEnded),
+                    },
+                    body => {
+                        self.on_incoming(SessionFrame { channel, body })
+                            .await
+                            .map_err(|_| BeginError::IllegalState)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        async fn on_reattach(
+            &mut self,
+            attach: Attach,
+            mut relay: LinkRelay<OutputHandle>,
+        ) -> Result<(), BeginError> {
+            let input_handle = InputHandle::from(attach.handle.clone());
+
+            // The remote peer refuses to resume the link by responding with a null terminus. The
+            // link is closed right away in the same way as `IncomingTargetIsNone` is handled when
+            // attaching, and the error is relayed to the link as if the link were closed by the
+            // remote peer
+            let refused = match &relay {
+                LinkRelay::Sender { .. } => attach.target.is_none(),
+                LinkRelay::Receiver { .. } => attach.source.is_none(),
+            };
+            if refused {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(link = %attach.name, "Re-attach is refused by the remote peer");
+                #[cfg(feature = "log")]
+                log::debug!("Re-attach of link {} is refused by the remote peer", attach.name);
+
+                let output_handle = relay.output_handle().clone();
+                let detach = Detach {
+                    handle: output_handle.clone().into(),
+                    closed: true,
+                    error: None,
+                };
+                let frame = SessionFrame::new(
+                    self.session.outgoing_channel,
+                    SessionFrameBody::Detach(detach),
+                );
+                self.outgoing
+                    .send(frame)
+                    .await
+                    .map_err(|_| BeginError::IllegalConnectionState)?;
+                if let Some(attaches) = &mut self.session.local_attach_by_name {
+                    let _ = attaches.remove(&attach.name);
+                }
+                self.session.refused_output_handles.insert(output_handle);
+
+                let error = definitions::Error::new(
+                    LinkError::DetachForced,
+                    "Re-attach is refused by the remote peer".to_string(),
+                    None,
+                );
+                let detach = Detach {
+                    handle: attach.handle,
+                    closed: true,
+                    error: Some(error),
+                };
+                // The link may have been dropped already
+                let _ = relay.on_incoming_detach(detach).await;
+                self.session.link_by_input_handle.insert(input_handle, relay);
+                return Ok(());
+            }
+
+            match &mut relay {
+                LinkRelay::Sender {
+                    receiver_settle_mode,
+                    ..
+                } => {
+                    *receiver_settle_mode = attach.rcv_settle_mode.clone();
+                }
+                LinkRelay::Receiver {
+                    flow_state,
+                    output_handle,
+                    ..
+                } => {
+                    // The delivery-count of the receiver is initialized by the remote sender, and
+                    // the remaining link credit needs to be granted again
+                    let flow = {
+                        let mut state = flow_state.lock.write();
+                        if let Some(delivery_count) = attach.initial_delivery_count {
+                            state.delivery_count = delivery_count;
+                        }
+                        state.as_link_flow(output_handle.clone(), false)
+                    };
+                    let frame = self
+                        .session
+                        .on_outgoing_flow(flow)
+                        .map_err(|_| BeginError::IllegalState)?;
+                    self.outgoing
+                        .send(frame)
+                        .await
+                        .map_err(|_| BeginError::IllegalConnectionState)?;
+                }
+            }
+            self.session
+                .rebound_input_handles
+                .insert(relay.output_handle().clone(), input_handle.clone());
+            self.session.link_by_input_handle.insert(input_handle, relay);
+            Ok(())
+        }
     }
 }
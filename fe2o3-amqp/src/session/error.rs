@@ -44,7 +44,8 @@ pub enum BeginError {
     #[error("Remote ended with error")]
     RemoteEndedWithError(definitions::Error),
 
-    /// Channel max reached
+    /// No channel is available for the session because the channel-max negotiated with the
+    /// remote peer is reached
     #[error("Local channel-max reached")]
     LocalChannelMaxReached,
 }
@@ -90,6 +91,10 @@ pub(crate) enum SessionInnerError {
     #[error("Remote session ended")]
     RemoteEnded,
 
+    /// The remote peer sent a transfer when the incoming-window is exhausted
+    #[error("Transfer received when the incoming-window is exhausted")]
+    WindowViolation,
+
     /// Remote session ended with error
     #[error("Remote ended with error")]
     RemoteEndedWithError(definitions::Error),
@@ -153,6 +158,10 @@ pub enum Error {
     #[error("Remote session ended")]
     RemoteEnded,
 
+    /// The remote peer sent a transfer when the incoming-window is exhausted
+    #[error("Transfer received when the incoming-window is exhausted")]
+    WindowViolation,
+
     /// Remote session ended with error
     #[error("Remote ended with error")]
     RemoteEndedWithError(definitions::Error),
@@ -180,6 +189,7 @@ impl From<SessionInnerError> for Error {
             SessionInnerError::IllegalConnectionState => Self::IllegalConnectionState,
             SessionInnerError::TransferFrameToSender => Self::TransferFrameToSender,
             SessionInnerError::RemoteEnded => Self::RemoteEnded,
+            SessionInnerError::WindowViolation => Self::WindowViolation,
             SessionInnerError::RemoteEndedWithError(err) => Self::RemoteEndedWithError(err),
 
             #[cfg(not(target_arch = "wasm32"))]
@@ -216,8 +226,25 @@ pub(crate) enum AllocLinkError {
     #[error("Illegal session state")]
     IllegalSessionState,
 
-    #[error("Link name must be unique")]
-    DuplicatedLinkName,
+    #[error("Link name {0:?} is already in use in this session")]
+    DuplicatedLinkName(String),
+}
+
+/// Error with rebinding a session to a new connection
+#[derive(Debug, thiserror::Error)]
+pub enum RebindError {
+    /// The session is still running on the previous connection
+    #[error("The session is still running on the previous connection")]
+    StillRunning,
+
+    /// The session did not retain its states, either because it was not begun as rebindable or
+    /// because a previous rebind has failed
+    #[error("The session cannot be rebound")]
+    NotRebindable,
+
+    /// Error with beginning the session on the new connection
+    #[error(transparent)]
+    Begin(#[from] BeginError),
 }
 
 /// Error with attempting to end a session
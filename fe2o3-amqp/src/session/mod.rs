@@ -1,6 +1,6 @@
 //! Implements AMQP1.0 Session
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use fe2o3_amqp_types::{
     definitions::{
@@ -41,12 +41,15 @@ pub(crate) mod frame;
 
 pub mod error;
 use error::{AllocLinkError, SessionInnerError, SessionStateError};
-pub use error::{BeginError, Error, TryEndError};
+pub use error::{BeginError, Error, RebindError, TryEndError};
 
 mod builder;
 pub use builder::*;
 
-use self::frame::{SessionFrame, SessionFrameBody, SessionOutgoingItem};
+use self::{
+    engine::{SessionEngine, SessionRemnant},
+    frame::{SessionFrame, SessionFrameBody, SessionOutgoingItem},
+};
 
 /// Default incoming_window and outgoing_window
 pub const DEFAULT_WINDOW: Uint = 2048;
@@ -61,6 +64,8 @@ pub struct SessionHandle<R> {
     pub(crate) control: mpsc::Sender<SessionControl>,
     pub(crate) engine_handle: JoinHandle<()>,
     pub(crate) outcome: oneshot::Receiver<Result<(), Error>>,
+    // States retained by a rebindable session after the event loop stops
+    pub(crate) remnant: Option<oneshot::Receiver<SessionRemnant>>,
 
     // outgoing for Link
     pub(crate) outgoing: mpsc::Sender<LinkFrame>,
@@ -81,10 +86,13 @@ impl<R> Drop for SessionHandle<R> {
 
 impl<R> SessionHandle<R> {
     /// Checks if the underlying event loop has stopped
+    ///
+    /// This also returns `true` once the event loop of a rebindable session has stopped, even
+    /// though the session may still be rebound to a new connection
     pub fn is_ended(&self) -> bool {
         match self.is_ended {
             true => true,
-            false => self.control.is_closed(),
+            false => self.control.is_closed() || self.engine_handle.is_finished(),
         }
     }
 
@@ -187,6 +195,61 @@ impl<R> SessionHandle<R> {
     }
 }
 
+cfg_not_wasm32! {
+    impl SessionHandle<()> {
+        /// Rebinds the session to a new connection after the previous connection has stopped
+        ///
+        /// The session is begun again on `connection` with its original configuration, and the
+        /// links that were attached are re-attached without being re-created. Deliveries that
+        /// were unsettled on the previous connection are not resumed.
+        ///
+        /// The session must have been begun with [`Builder::rebindable`] set to `true`. If
+        /// beginning the session on the new connection fails, the session can no longer be
+        /// rebound.
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// let mut session = Session::builder()
+        ///     .rebindable(true)
+        ///     .begin(&mut connection)
+        ///     .await.unwrap();
+        /// let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+        ///
+        /// // The connection is lost
+        /// let _ = session.on_end().await;
+        ///
+        /// let mut connection = Connection::open("connection-2", "amqp://localhost:5672").await.unwrap();
+        /// session.rebind(&mut connection).await.unwrap();
+        /// sender.send("hello").await.unwrap();
+        /// ```
+        pub async fn rebind(
+            &mut self,
+            connection: &mut crate::connection::ConnectionHandle<()>,
+        ) -> Result<(), RebindError> {
+            if !self.is_ended && !self.engine_handle.is_finished() {
+                return Err(RebindError::StillRunning);
+            }
+
+            let remnant = self
+                .remnant
+                .take()
+                .ok_or(RebindError::NotRebindable)?
+                .await
+                .map_err(|_| RebindError::NotRebindable)?;
+            let buffer_size = remnant.buffer_size;
+            let engine = SessionEngine::rebind_client_session(connection, remnant).await?;
+            let (engine_handle, outcome, remnant) = engine.spawn_rebindable(buffer_size);
+
+            self.is_ended = false;
+            self.engine_handle = engine_handle;
+            self.outcome = outcome;
+            self.remnant = Some(remnant);
+            Ok(())
+        }
+    }
+}
+
 /// # Cancel safety
 ///
 /// It internally `.await` on a send on `tokio::mpsc::Sender` and on a `oneshot::Receiver`.
@@ -260,6 +323,8 @@ pub struct Session {
     pub(crate) local_state: SessionState,
     pub(crate) initial_outgoing_id: Constant<TransferNumber>,
     pub(crate) next_outgoing_id: TransferNumber,
+    // The incoming-window is restored to this value once half of it is used
+    pub(crate) initial_incoming_window: Constant<TransferNumber>,
     pub(crate) incoming_window: TransferNumber,
     pub(crate) outgoing_window: TransferNumber,
     pub(crate) handle_max: Handle,
@@ -291,6 +356,15 @@ pub struct Session {
     pub(crate) link_by_input_handle: HashMap<InputHandle, LinkRelay<OutputHandle>>,
     // Maps from DeliveryId to link.DeliveryCount
     pub(crate) delivery_tag_by_id: HashMap<(Role, DeliveryNumber), (InputHandle, DeliveryTag)>, // Role must be the remote peer's role
+    // The last Attach sent by each local link, which is used to re-attach the link when the
+    // session is rebound to a new connection. This is `None` unless the session is rebindable
+    pub(crate) local_attach_by_name: Option<HashMap<String, Attach>>,
+    // Input handles of links that are resumed after the session is rebound. The links are not
+    // aware of the input handles assigned by the new remote peer
+    pub(crate) rebound_input_handles: HashMap<OutputHandle, InputHandle>,
+    // Output handles of links whose re-attach is refused by the new remote peer. The closing
+    // detach is already sent by the session, so the detach later sent by the link is dropped
+    pub(crate) refused_output_handles: HashSet<OutputHandle>,
 }
 
 impl Session {
@@ -328,6 +402,78 @@ impl Session {
         }
     }
 
+    /// Resets the session states so that the session can be begun again on a new connection.
+    ///
+    /// Links that were attached are taken out of the registry and returned along with the Attach
+    /// frames that re-attach them. The link credit of senders is cleared until the remote receiver
+    /// grants new credit.
+    pub(crate) fn reset_for_rebind(
+        &mut self,
+        outgoing_channel: OutgoingChannel,
+    ) -> Vec<(Attach, LinkRelay<OutputHandle>)> {
+        self.outgoing_channel = outgoing_channel;
+        self.local_state = SessionState::Unmapped;
+        self.next_outgoing_id = *self.initial_outgoing_id.value();
+        self.incoming_window = *self.initial_incoming_window.value();
+        self.incoming_channel = None;
+        self.next_incoming_id = 0;
+        self.remote_incoming_window = 0;
+        self.remote_outgoing_window = 0;
+        self.remote_incoming_window_exhausted_buffer.clear();
+        self.delivery_tag_by_id.clear();
+        self.rebound_input_handles.clear();
+
+        let mut reattaching = Vec::with_capacity(self.link_by_input_handle.len());
+        for (_, relay) in self.link_by_input_handle.drain() {
+            let name = match self
+                .link_name_by_output_handle
+                .get(relay.output_handle().0 as usize)
+            {
+                Some(name) => name,
+                None => continue,
+            };
+            let mut attach = match self
+                .local_attach_by_name
+                .as_ref()
+                .and_then(|attaches| attaches.get(name))
+            {
+                Some(attach) => attach.clone(),
+                None => continue,
+            };
+
+            // Deliveries that are unsettled on the previous connection cannot be resumed.
+            // Dropping the unsettled messages of a sender resolves the pending send futures with
+            // an error
+            attach.unsettled = None;
+            attach.incomplete_unsettled = false;
+            if let LinkRelay::Sender {
+                flow_state,
+                unsettled,
+                ..
+            } = &relay
+            {
+                let mut state = flow_state.state().lock.write();
+                state.link_credit = 0;
+                attach.initial_delivery_count = Some(state.delivery_count);
+                if let Some(map) = unsettled.write().as_mut() {
+                    map.clear();
+                }
+            }
+            // The deliveries received on the previous connection can no longer be settled
+            if let LinkRelay::Receiver {
+                unsettled, settled, ..
+            } = &relay
+            {
+                if let Some(map) = unsettled.write().as_mut() {
+                    map.clear();
+                }
+                settled.0.reopen();
+            }
+            reattaching.push((attach, relay));
+        }
+        reattaching
+    }
+
     fn on_outgoing_transfer_inner(
         &mut self,
         input_handle: InputHandle,
@@ -511,7 +657,7 @@ impl endpoint::Session for Session {
 
         // check whether link name is duplciated
         if self.link_by_name.contains_key(&link_name) {
-            return Err(AllocLinkError::DuplicatedLinkName);
+            return Err(AllocLinkError::DuplicatedLinkName(link_name));
         }
 
         // get a new entry index
@@ -542,11 +688,15 @@ impl endpoint::Session for Session {
 
     /// This should only deallocate the output handle
     fn deallocate_link(&mut self, output_handle: OutputHandle) {
+        let _ = self.rebound_input_handles.remove(&output_handle);
         if let Some(name) = self
             .link_name_by_output_handle
             .try_remove(output_handle.0 as usize)
         {
             let _ = self.link_by_name.remove(&name);
+            if let Some(attaches) = &mut self.local_attach_by_name {
+                let _ = attaches.remove(&name);
+            }
         }
     }
 
@@ -643,7 +793,11 @@ impl endpoint::Session for Session {
         self.next_incoming_id = self.next_incoming_id.wrapping_add(1);
         self.remote_outgoing_window = self.remote_outgoing_window.saturating_sub(1);
 
-        // TODO: allow user to define whether the incoming window should be decremented
+        // The remote peer must not send a transfer when the incoming-window is exhausted
+        if self.incoming_window == 0 {
+            return Err(SessionInnerError::WindowViolation);
+        }
+        self.incoming_window -= 1;
 
         let input_handle = InputHandle::from(transfer.handle.clone());
         match self.link_by_input_handle.get_mut(&input_handle) {
@@ -710,12 +864,20 @@ impl endpoint::Session for Session {
                     }
                 }
             }
+            // The echoed dispositions settle the deliveries, which are no longer tracked
+            for delivery_id in &delivery_ids {
+                self.delivery_tag_by_id
+                    .remove(&(disposition.role.clone(), *delivery_id));
+            }
 
             let chunk_inds = consecutive_chunk_indices(&delivery_ids[..]);
 
-            let mut dispositions = Vec::with_capacity(chunk_inds.len());
+            let mut dispositions = Vec::with_capacity(chunk_inds.len() + 1);
             let mut prev_ind = 0;
-            for ind in chunk_inds {
+            // The chunk indices only mark where the consecutive runs are split, so the final
+            // run ends at the end of the delivery ids
+            let final_ind = (!delivery_ids.is_empty()).then_some(delivery_ids.len());
+            for ind in chunk_inds.into_iter().chain(final_ind) {
                 let slice = &delivery_ids[prev_ind..ind];
                 let disposition = Disposition {
                     role: Role::Sender,
@@ -851,11 +1013,38 @@ impl endpoint::Session for Session {
     }
 
     fn on_outgoing_attach(&mut self, attach: Attach) -> Result<SessionFrame, Self::Error> {
+        if let Some(attaches) = &mut self.local_attach_by_name {
+            attaches.insert(attach.name.clone(), attach.clone());
+        }
         let body = SessionFrameBody::Attach(attach);
         let frame = SessionFrame::new(self.outgoing_channel, body);
         Ok(frame)
     }
 
+    fn replenish_incoming_window(&mut self) -> Option<SessionFrame> {
+        let initial_incoming_window = *self.initial_incoming_window.value();
+        if self.incoming_window > initial_incoming_window / 2 {
+            return None;
+        }
+
+        self.incoming_window = initial_incoming_window;
+        let flow = Flow {
+            next_incoming_id: Some(self.next_incoming_id),
+            incoming_window: self.incoming_window,
+            next_outgoing_id: self.next_outgoing_id,
+            outgoing_window: self.outgoing_window,
+            handle: None,
+            delivery_count: None,
+            link_credit: None,
+            available: None,
+            drain: false,
+            echo: false,
+            properties: None,
+        };
+        let body = SessionFrameBody::Flow(flow);
+        Some(SessionFrame::new(self.outgoing_channel, body))
+    }
+
     fn on_outgoing_flow(&mut self, flow: LinkFlow) -> Result<SessionFrame, Self::Error> {
         let flow = Flow {
             // Session flow states
@@ -884,6 +1073,15 @@ impl endpoint::Session for Session {
         transfer: Transfer,
         payload: Payload,
     ) -> Result<Option<SessionOutgoingItem>, Self::Error> {
+        let input_handle = match self.rebound_input_handles.is_empty() {
+            true => input_handle,
+            false => self
+                .rebound_input_handles
+                .get(&OutputHandle::from(transfer.handle.clone()))
+                .cloned()
+                .unwrap_or(input_handle),
+        };
+
         // Check if remote-incoming-window is exhausted
         if self.remote_incoming_window == 0 {
             // exhausted
@@ -942,10 +1140,20 @@ impl endpoint::Session for Session {
         Ok(frame)
     }
 
-    fn on_outgoing_detach(&mut self, detach: Detach) -> SessionFrame {
-        self.deallocate_link(detach.handle.clone().into());
+    fn on_outgoing_detach(&mut self, detach: Detach) -> Option<SessionFrame> {
+        // Transfers that are held back by the remote-incoming-window must not be sent after the
+        // handle is detached
+        self.remote_incoming_window_exhausted_buffer
+            .retain(|(_, transfer, _)| transfer.handle != detach.handle);
+
+        let output_handle = OutputHandle::from(detach.handle.clone());
+        let refused = self.refused_output_handles.remove(&output_handle);
+        self.deallocate_link(output_handle);
+        if refused {
+            return None;
+        }
         let body = SessionFrameBody::Detach(detach);
-        SessionFrame::new(self.outgoing_channel, body)
+        Some(SessionFrame::new(self.outgoing_channel, body))
     }
 }
 
@@ -958,6 +1166,7 @@ cfg_transaction! {
         // This should be unreachable, but an error is probably a better way
         fn allocate_transaction_id(
             &mut self,
+            _global_id: Option<fe2o3_amqp_types::transaction::TransactionId>,
         ) -> Result<fe2o3_amqp_types::transaction::TransactionId, AllocTxnIdError> {
             // Err(Error::amqp_error(AmqpError::NotImplemented, "Resource side transaction is not enabled".to_string()))
             Err(AllocTxnIdError::NotImplemented)
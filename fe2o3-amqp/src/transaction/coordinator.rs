@@ -1,6 +1,6 @@
 //! Control link coordinator
 
-use std::collections::HashSet;
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use fe2o3_amqp_types::{
     definitions::{self, AmqpError, LinkError},
@@ -11,6 +11,7 @@ use fe2o3_amqp_types::{
     },
 };
 use tokio::sync::mpsc;
+use uuid::Uuid;
 
 use crate::{
     acceptor::{link::SharedLinkAcceptorFields, local_receiver_link::LocalReceiverLinkAcceptor},
@@ -29,6 +30,26 @@ use super::{control_link_frame::ControlMessageBody, CoordinatorError};
 
 pub(crate) type CoordinatorLink = ReceiverLink<Coordinator>;
 
+/// Allocates the transaction ID for a newly declared transaction
+///
+/// This allows the transactional resource to control how transaction IDs are generated, e.g. to
+/// embed routing information in the transaction ID. The allocated transaction ID must not be in use
+/// by any active transaction on the same session, otherwise the `Declare` will be rejected.
+pub trait TxnIdAllocator: Debug + Send + Sync {
+    /// Allocates a new transaction ID
+    fn allocate(&self) -> TransactionId;
+}
+
+/// The default [`TxnIdAllocator`] which allocates a random UUID (v4) as the transaction ID
+#[derive(Debug, Clone, Default)]
+pub struct UuidTxnIdAllocator;
+
+impl TxnIdAllocator for UuidTxnIdAllocator {
+    fn allocate(&self) -> TransactionId {
+        TransactionId::from(Uuid::new_v4().into_bytes())
+    }
+}
+
 /// An acceptor that handles incoming control links
 #[derive(Debug, Clone)]
 pub struct ControlLinkAcceptor {
@@ -38,6 +59,8 @@ pub struct ControlLinkAcceptor {
         Coordinator,
         fn(Coordinator) -> Option<Coordinator>,
     >,
+    pub(crate) accept_global_id: bool,
+    pub(crate) txn_id_allocator: Arc<dyn TxnIdAllocator>,
 }
 
 fn unreachable_dynamic_coordinator(_: Coordinator) -> Option<Coordinator> {
@@ -60,6 +83,8 @@ impl Default for ControlLinkAcceptor {
                 verify_incoming_source: true,
                 verify_incoming_target: true,
             },
+            accept_global_id: true,
+            txn_id_allocator: Arc::new(UuidTxnIdAllocator),
         }
     }
 }
@@ -77,7 +102,8 @@ impl ControlLinkAcceptor {
             .await
             .map(|inner| TxnCoordinator {
                 inner,
-                txn_ids: HashSet::new(),
+                txn_ids: HashMap::new(),
+                accept_global_id: self.accept_global_id,
             })
     }
 
@@ -106,33 +132,54 @@ impl From<SuccessfulOutcome> for DeliveryState {
 #[derive(Debug)]
 pub(crate) struct TxnCoordinator {
     inner: ReceiverInner<CoordinatorLink>,
-    txn_ids: HashSet<TransactionId>,
+    /// Active transactions declared on this control link, mapping the local transaction ID to the
+    /// global transaction ID if one was supplied in the `Declare`
+    txn_ids: HashMap<TransactionId, Option<TransactionId>>,
+    accept_global_id: bool,
 }
 
 impl TxnCoordinator {
     async fn on_declare(&mut self, declare: &Declare) -> Result<Declared, CoordinatorError> {
-        match declare.global_id {
-            Some(_) => Err(CoordinatorError::GlobalIdNotImplemented),
-            None => {
-                let txn_id =
-                    super::session::allocate_transaction_id(self.inner.session_control()).await?;
-
-                // The TxnManager has the authoratitive version of all active txns, so
-                // the txn-id obtained from the TxnManager should be "guaranteed" to be unique
-                self.txn_ids.insert(txn_id.clone());
-                Ok(Declared { txn_id })
-            }
+        if declare.global_id.is_some() && !self.accept_global_id {
+            return Err(CoordinatorError::GlobalIdNotSupported);
         }
+
+        let global_id = declare.global_id.clone();
+        let txn_id = super::session::allocate_transaction_id(
+            self.inner.session_control(),
+            global_id.clone(),
+        )
+        .await?;
+
+        // The TxnManager has the authoratitive version of all active txns, so
+        // the txn-id obtained from the TxnManager should be "guaranteed" to be unique
+        self.txn_ids.insert(txn_id.clone(), global_id);
+        Ok(Declared { txn_id })
     }
 
     async fn on_discharge(&mut self, discharge: &Discharge) -> Result<Accepted, CoordinatorError> {
-        if !self.txn_ids.remove(&discharge.txn_id) {
-            return Err(CoordinatorError::TransactionError(
-                TransactionError::UnknownId,
-            ));
-        }
+        // The controller may discharge a transaction by either the local or the global
+        // transaction ID
+        let txn_id = match self.txn_ids.contains_key(&discharge.txn_id) {
+            true => Some(discharge.txn_id.clone()),
+            false => self
+                .txn_ids
+                .iter()
+                .find(|(_, global_id)| global_id.as_ref() == Some(&discharge.txn_id))
+                .map(|(txn_id, _)| txn_id.clone()),
+        };
+        let txn_id = match txn_id {
+            Some(txn_id) => {
+                self.txn_ids.remove(&txn_id);
+                txn_id
+            }
+            None => {
+                return Err(CoordinatorError::TransactionError(
+                    TransactionError::UnknownId,
+                ))
+            }
+        };
 
-        let txn_id = discharge.txn_id.clone();
         match discharge.fail {
             Some(true) => {
                 super::session::rollback_transaction(self.inner.session_control(), txn_id)
@@ -257,9 +304,23 @@ impl TxnCoordinator {
                 #[cfg(feature = "log")]
                 log::error!("error = {:?}", error);
                 match error {
-                    CoordinatorError::GlobalIdNotImplemented => {
+                    CoordinatorError::GlobalIdNotSupported => {
                         let error = TransactionError::UnknownId;
-                        let description = "Global transaction ID is not implemented".to_string();
+                        let description = "Global transaction ID is not supported".to_string();
+                        self.reject(delivery_info, error, description).await
+                    }
+                    CoordinatorError::GlobalIdInUse => {
+                        let error = AmqpError::NotAllowed;
+                        let description =
+                            "Global transaction ID is already associated with an active transaction"
+                                .to_string();
+                        self.reject(delivery_info, error, description).await
+                    }
+                    CoordinatorError::TxnIdInUse => {
+                        let error = AmqpError::InternalError;
+                        let description =
+                            "Allocated transaction ID is already associated with an active transaction"
+                                .to_string();
                         self.reject(delivery_info, error, description).await
                     }
                     CoordinatorError::InvalidSessionState => {
@@ -299,7 +360,7 @@ impl TxnCoordinator {
     async fn reject(
         &mut self,
         delivery_info: DeliveryInfo,
-        error: TransactionError,
+        error: impl Into<definitions::ErrorCondition>,
         description: impl Into<Option<String>>,
     ) -> Result<(), IllegalLinkStateError> {
         let error = definitions::Error::new(error, description, None);
@@ -344,7 +405,7 @@ impl TxnCoordinator {
 
 impl Drop for TxnCoordinator {
     fn drop(&mut self) {
-        for txn_id in self.txn_ids.drain() {
+        for (txn_id, _) in self.txn_ids.drain() {
             if self
                 .inner
                 .session_control()
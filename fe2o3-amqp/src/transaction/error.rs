@@ -5,7 +5,8 @@ use fe2o3_amqp_types::{
 
 use crate::link::{
     delivery::{FromDeliveryState, FromOneshotRecvError, FromPreSettled},
-    DetachError, IllegalLinkStateError, LinkStateError, SendError, SenderAttachError,
+    DetachError, IllegalLinkStateError, LinkStateError, MaxFrameSizeExceeded, MessageSizeExceeded, SendError,
+    SenderAttachError,
 };
 
 /// Errors with allocation of new transacation ID
@@ -20,6 +21,16 @@ pub(crate) enum AllocTxnIdError {
     #[cfg(not(target_arch = "wasm32"))]
     #[cfg(feature = "acceptor")]
     InvalidSessionState,
+
+    /// The global transaction ID is already associated with an active transaction
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(feature = "acceptor")]
+    GlobalIdInUse,
+
+    /// The allocated transaction ID is already associated with an active transaction
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(feature = "acceptor")]
+    TxnIdInUse,
 }
 
 /// Errors with discharging a transaction at the transaction manager
@@ -45,10 +56,20 @@ impl From<TransactionError> for DischargeError {
 /// Errors on the transacitonal resource side
 #[derive(Debug)]
 pub(crate) enum CoordinatorError {
-    /// The global transaction ID is not implemented yet
+    /// The global transaction ID is not supported by the coordinator
     #[cfg(not(target_arch = "wasm32"))]
     #[cfg(feature = "acceptor")]
-    GlobalIdNotImplemented,
+    GlobalIdNotSupported,
+
+    /// The global transaction ID is already associated with an active transaction
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(feature = "acceptor")]
+    GlobalIdInUse,
+
+    /// The allocated transaction ID is already associated with an active transaction
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(feature = "acceptor")]
+    TxnIdInUse,
 
     /// Session must have dropped
     #[cfg(not(target_arch = "wasm32"))]
@@ -71,6 +92,12 @@ impl From<AllocTxnIdError> for CoordinatorError {
             #[cfg(not(target_arch = "wasm32"))]
             #[cfg(feature = "acceptor")]
             AllocTxnIdError::InvalidSessionState => Self::InvalidSessionState,
+            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(feature = "acceptor")]
+            AllocTxnIdError::GlobalIdInUse => Self::GlobalIdInUse,
+            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(feature = "acceptor")]
+            AllocTxnIdError::TxnIdInUse => Self::TxnIdInUse,
         }
     }
 }
@@ -113,6 +140,36 @@ pub enum ControllerSendError {
     /// Error serializing message
     #[error("Error encoding message")]
     MessageEncodeError,
+
+    /// The message is not settled by the remote peer within the given duration
+    #[error("Message is not settled within the timeout")]
+    SettlementTimeout,
+
+    /// The transfer performative does not fit within the max frame size of the connection
+    #[error("Transfer frame size {size} exceeds the max frame size {max}")]
+    MaxFrameSizeExceeded {
+        /// Size of the first transfer frame of the delivery, including at least one byte of the
+        /// payload
+        size: usize,
+
+        /// Max frame size negotiated with the remote peer
+        max: usize,
+    },
+
+    /// The encoded message is larger than the max message size of the link
+    #[error("Message size {size} exceeds the max message size {max}")]
+    MessageSizeExceeded {
+        /// Size of the encoded message
+        size: u64,
+
+        /// Max message size negotiated with the remote peer
+        max: u64,
+    },
+
+    /// A pre-settled message is sent on a link whose negotiated `SenderSettleMode` is
+    /// `Unsettled`
+    #[error("Pre-settled message is not allowed with SenderSettleMode::Unsettled")]
+    IllegalSenderSettleMode,
 }
 
 impl From<SendError> for ControllerSendError {
@@ -123,6 +180,12 @@ impl From<SendError> for ControllerSendError {
             SendError::NonTerminalDeliveryState => Self::NonTerminalDeliveryState,
             SendError::IllegalDeliveryState => Self::IllegalDeliveryState,
             SendError::MessageEncodeError => Self::MessageEncodeError,
+            SendError::SettlementTimeout => Self::SettlementTimeout,
+            SendError::MaxFrameSizeExceeded { size, max } => {
+                Self::MaxFrameSizeExceeded { size, max }
+            }
+            SendError::MessageSizeExceeded { size, max } => Self::MessageSizeExceeded { size, max },
+            SendError::IllegalSenderSettleMode => Self::IllegalSenderSettleMode,
         }
     }
 }
@@ -201,6 +264,27 @@ pub enum PostError {
     /// Error serializing message
     #[error("Error encoding message")]
     MessageEncodeError,
+
+    /// The transfer performative does not fit within the max frame size of the connection
+    #[error("Transfer frame size {size} exceeds the max frame size {max}")]
+    MaxFrameSizeExceeded {
+        /// Size of the first transfer frame of the delivery, including at least one byte of the
+        /// payload
+        size: usize,
+
+        /// Max frame size negotiated with the remote peer
+        max: usize,
+    },
+
+    /// The encoded message is larger than the max message size of the link
+    #[error("Message size {size} exceeds the max message size {max}")]
+    MessageSizeExceeded {
+        /// Size of the encoded message
+        size: u64,
+
+        /// Max message size negotiated with the remote peer
+        max: u64,
+    },
 }
 
 impl From<serde_amqp::Error> for PostError {
@@ -215,6 +299,18 @@ impl From<DetachError> for PostError {
     }
 }
 
+impl From<MaxFrameSizeExceeded> for PostError {
+    fn from(MaxFrameSizeExceeded { size, max }: MaxFrameSizeExceeded) -> Self {
+        Self::MaxFrameSizeExceeded { size, max }
+    }
+}
+
+impl From<MessageSizeExceeded> for PostError {
+    fn from(MessageSizeExceeded { size, max }: MessageSizeExceeded) -> Self {
+        Self::MessageSizeExceeded { size, max }
+    }
+}
+
 impl From<IllegalLinkStateError> for PostError {
     fn from(value: IllegalLinkStateError) -> Self {
         match value {
@@ -1,6 +1,6 @@
 //! Manages incoming transaction on the resource side
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 
 use fe2o3_amqp_types::{
@@ -28,6 +28,8 @@ pub(crate) trait HandleControlLink {
 pub(crate) struct TransactionManager {
     pub control_link_outgoing: mpsc::Sender<LinkFrame>,
     pub txns: OrderedMap<TransactionId, ResourceTransaction>,
+    /// Maps the global transaction ID supplied in a `Declare` to the local transaction ID
+    pub global_ids: HashMap<TransactionId, TransactionId>,
     pub control_link_acceptor: Arc<ControlLinkAcceptor>,
 }
 
@@ -39,9 +41,27 @@ impl TransactionManager {
         Self {
             control_link_outgoing,
             txns: OrderedMap::new(),
+            global_ids: HashMap::new(),
             control_link_acceptor: Arc::new(control_link_acceptor),
         }
     }
+
+    /// Resolves a transaction ID, which may be either a local or a global transaction ID,
+    /// to the local transaction ID
+    pub(crate) fn resolve_txn_id<'a>(&'a self, txn_id: &'a TransactionId) -> &'a TransactionId {
+        match self.txns.contains_key(txn_id) {
+            true => txn_id,
+            false => self.global_ids.get(txn_id).unwrap_or(txn_id),
+        }
+    }
+
+    /// Removes the transaction and its global transaction ID mapping if there is any
+    pub(crate) fn remove_txn(&mut self, txn_id: &TransactionId) -> Option<ResourceTransaction> {
+        let local_id = self.resolve_txn_id(txn_id).clone();
+        let txn = self.txns.swap_remove(&local_id)?;
+        self.global_ids.retain(|_, local| *local != local_id);
+        Some(txn)
+    }
 }
 
 #[derive(Debug)]
@@ -100,6 +120,10 @@ mod tests {
     use fe2o3_amqp_types::transaction::TransactionId;
     use uuid::Uuid;
 
+    use crate::transaction::coordinator::ControlLinkAcceptor;
+
+    use super::{ResourceTransaction, TransactionManager};
+
     #[test]
     fn test_recover_key_from_txn_id() {
         let uuid = Uuid::new_v4();
@@ -107,4 +131,26 @@ mod tests {
         let uuid2 = Uuid::from_slice(txn_id.as_ref()).unwrap();
         assert_eq!(uuid, uuid2);
     }
+
+    #[test]
+    fn test_resolve_and_remove_txn_by_global_id() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let mut manager = TransactionManager::new(tx, ControlLinkAcceptor::default());
+        let local_id = TransactionId::from(Uuid::new_v4().into_bytes());
+        let global_id = TransactionId::from(b"global".to_vec());
+        manager
+            .txns
+            .insert(local_id.clone(), ResourceTransaction::new());
+        manager
+            .global_ids
+            .insert(global_id.clone(), local_id.clone());
+
+        assert_eq!(manager.resolve_txn_id(&local_id), &local_id);
+        assert_eq!(manager.resolve_txn_id(&global_id), &local_id);
+
+        assert!(manager.remove_txn(&global_id).is_some());
+        assert!(manager.txns.is_empty());
+        assert!(manager.global_ids.is_empty());
+        assert!(manager.remove_txn(&local_id).is_none());
+    }
 }
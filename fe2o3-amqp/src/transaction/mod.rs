@@ -245,6 +245,10 @@ pub trait TransactionExt: TransactionDischarge + TransactionalRetirement {
 ///
 /// ## Transactional retirement
 ///
+/// The outcome is sent in a [`TransactionalState`] and only takes effect at the resource when the
+/// transaction is committed. If the transaction is rolled back, the delivery is left unsettled at
+/// the resource.
+///
 /// ```rust,ignore
 /// let controller = Controller::attach(&mut session, "controller").await.unwrap();
 /// let mut receiver = Receiver::attach(&mut session, "rust-recver-1", "q1")
@@ -258,6 +262,12 @@ pub trait TransactionExt: TransactionDischarge + TransactionalRetirement {
 /// txn.accept(&mut receiver, &delivery).await.unwrap();
 /// txn.commit().await.unwrap();
 ///
+/// // Rollback
+/// let delivery: Delivery<Value> = receiver.recv().await.unwrap();
+/// let mut txn = Transaction::declare(&controller, None).await.unwrap();
+/// txn.reject(&mut receiver, &delivery, None).await.unwrap();
+/// txn.rollback().await.unwrap();
+///
 /// controller.close().await.unwrap();
 /// receiver.close().await.unwrap();
 /// ```
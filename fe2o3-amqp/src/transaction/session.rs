@@ -8,7 +8,6 @@ use fe2o3_amqp_types::{
     transaction::{TransactionError, TransactionId},
 };
 use tokio::sync::{mpsc, oneshot};
-use uuid::Uuid;
 
 use crate::{
     control::SessionControl,
@@ -29,11 +28,12 @@ use super::{
 
 pub(crate) async fn allocate_transaction_id(
     control: &mpsc::Sender<SessionControl>,
+    global_id: Option<TransactionId>,
 ) -> Result<TransactionId, AllocTxnIdError> {
     let (resp, result) = oneshot::channel();
 
     control
-        .send(SessionControl::AllocateTransactionId { resp })
+        .send(SessionControl::AllocateTransactionId { global_id, resp })
         .await
         .map_err(|_| AllocTxnIdError::InvalidSessionState)?;
     result
@@ -122,16 +122,36 @@ impl<S> endpoint::HandleDeclare for TxnSession<S>
 where
     S: endpoint::Session<Error = session::error::SessionInnerError> + endpoint::SessionExt + Send + Sync,
 {
-    fn allocate_transaction_id(&mut self) -> Result<TransactionId, AllocTxnIdError> {
-        let mut txn_id = TransactionId::from(Uuid::new_v4().into_bytes());
-        while self.txn_manager.txns.contains_key(&txn_id) {
-            txn_id = TransactionId::from(Uuid::new_v4().into_bytes());
+    fn allocate_transaction_id(
+        &mut self,
+        global_id: Option<TransactionId>,
+    ) -> Result<TransactionId, AllocTxnIdError> {
+        if let Some(global_id) = &global_id {
+            if self.txn_manager.global_ids.contains_key(global_id)
+                || self.txn_manager.txns.contains_key(global_id)
+            {
+                return Err(AllocTxnIdError::GlobalIdInUse);
+            }
+        }
+
+        let txn_id = self
+            .txn_manager
+            .control_link_acceptor
+            .txn_id_allocator
+            .allocate();
+        if self.txn_manager.txns.contains_key(&txn_id)
+            || self.txn_manager.global_ids.contains_key(&txn_id)
+        {
+            return Err(AllocTxnIdError::TxnIdInUse);
         }
 
         let _ = self
             .txn_manager
             .txns
             .insert(txn_id.clone(), ResourceTransaction::new());
+        if let Some(global_id) = global_id {
+            self.txn_manager.global_ids.insert(global_id, txn_id.clone());
+        }
         Ok(txn_id)
     }
 }
@@ -146,7 +166,7 @@ where
         &mut self,
         txn_id: TransactionId,
     ) -> Result<Result<Accepted, TransactionError>, Self::Error> {
-        let txn = match self.txn_manager.txns.swap_remove(&txn_id) {
+        let txn = match self.txn_manager.remove_txn(&txn_id) {
             Some(txn) => txn,
             None => return Ok(Err(TransactionError::UnknownId)),
         };
@@ -200,7 +220,7 @@ where
         &mut self,
         txn_id: TransactionId,
     ) -> Result<Result<Accepted, TransactionError>, Self::Error> {
-        match self.txn_manager.txns.swap_remove(&txn_id) {
+        match self.txn_manager.remove_txn(&txn_id) {
             Some(_) => {
                 // TODO: Simply drop the frames?
                 Ok(Ok(Accepted {}))
@@ -294,10 +314,10 @@ where
     ) -> Result<Option<Disposition>, Self::Error> {
         let (txn, txn_id) = match &transfer.state {
             Some(DeliveryState::TransactionalState(state)) => {
-                let txn_id = &state.txn_id;
+                let txn_id = self.txn_manager.resolve_txn_id(&state.txn_id).clone();
                 self.txn_manager
                     .txns
-                    .get_mut(txn_id)
+                    .get_mut(&txn_id)
                     .map(|txn| (txn, txn_id.clone()))
                     .ok_or(S::Error::UnknownTxnId)?
             }
@@ -313,8 +333,8 @@ where
     ) -> Result<Option<Vec<Disposition>>, Self::Error> {
         match &disposition.state {
             Some(DeliveryState::TransactionalState(state)) => {
-                let txn_id = &state.txn_id;
-                match self.txn_manager.txns.get_mut(txn_id) {
+                let txn_id = self.txn_manager.resolve_txn_id(&state.txn_id).clone();
+                match self.txn_manager.txns.get_mut(&txn_id) {
                     Some(txn) => {
                         txn.frames.push(TxnWorkFrame::Retire(disposition));
                         Ok(None) // TODO: need to consider the receiver settle mode?
@@ -362,6 +382,10 @@ where
         self.session.on_outgoing_attach(attach)
     }
 
+    fn replenish_incoming_window(&mut self) -> Option<SessionFrame> {
+        self.session.replenish_incoming_window()
+    }
+
     fn on_outgoing_flow(&mut self, flow: LinkFlow) -> Result<SessionFrame, Self::Error> {
         self.session.on_outgoing_flow(flow)
     }
@@ -383,7 +407,7 @@ where
         self.session.on_outgoing_disposition(disposition)
     }
 
-    fn on_outgoing_detach(&mut self, detach: Detach) -> SessionFrame {
+    fn on_outgoing_detach(&mut self, detach: Detach) -> Option<SessionFrame> {
         self.session.on_outgoing_detach(detach)
     }
 }
@@ -5,6 +5,8 @@ use fe2o3_amqp_types::{primitives::Binary, sasl::SaslCode};
 
 use crate::{frames, sasl_profile};
 
+use super::protocol_header::ProtocolHeader;
+
 cfg_scram! {
     use crate::auth::error::ScramErrorKind;
 }
@@ -60,6 +62,12 @@ pub enum NegotiationError {
     #[error("Protocol header mismatch {0:?}")]
     ProtocolHeaderMismatch(Bytes),
 
+    #[error("Protocol version mismatch. Expecting {expected:?}, found {found:?}")]
+    ProtocolVersionMismatch {
+        expected: ProtocolHeader,
+        found: ProtocolHeader,
+    },
+
     #[error("Invalid domain")]
     InvalidDomain,
 
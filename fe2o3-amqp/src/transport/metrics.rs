@@ -0,0 +1,55 @@
+//! Counters of the frames and bytes sent and received over the transport
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A snapshot of the number of AMQP frames and bytes sent and received over the transport
+///
+/// The byte counts include the frame headers but not the protocol headers and SASL frames
+/// exchanged before the connection is opened
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransportMetrics {
+    /// Number of frames received
+    pub frames_in: u64,
+
+    /// Number of frames sent
+    pub frames_out: u64,
+
+    /// Number of bytes received
+    pub bytes_in: u64,
+
+    /// Number of bytes sent
+    pub bytes_out: u64,
+}
+
+/// Counters shared between the transport and the connection handle
+#[derive(Debug, Default)]
+pub(crate) struct TransportCounters {
+    frames_in: AtomicU64,
+    frames_out: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
+impl TransportCounters {
+    pub(crate) fn on_frame_in(&self, bytes: usize) {
+        self.frames_in.fetch_add(1, Ordering::Relaxed);
+        self.bytes_in.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn on_frame_out(&self) {
+        self.frames_out.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn on_bytes_out(&self, bytes: usize) {
+        self.bytes_out.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> TransportMetrics {
+        TransportMetrics {
+            frames_in: self.frames_in.load(Ordering::Relaxed),
+            frames_out: self.frames_out.load(Ordering::Relaxed),
+            bytes_in: self.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.bytes_out.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -9,12 +9,9 @@
 
 /* -------------------------------- Transport ------------------------------- */
 
-use fe2o3_amqp_types::{
-    definitions::{MAJOR, MINOR, MIN_MAX_FRAME_SIZE, REVISION},
-    states::ConnectionState,
-};
+use fe2o3_amqp_types::{definitions::MIN_MAX_FRAME_SIZE, states::ConnectionState};
 
-use std::{io, marker::PhantomData, task::Poll, time::Duration};
+use std::{io, marker::PhantomData, sync::Arc, task::Poll, time::Duration};
 
 use bytes::BytesMut;
 use futures_util::{Future, Sink, SinkExt, Stream, StreamExt};
@@ -29,10 +26,14 @@ use crate::{
 
 use protocol_header::ProtocolHeader;
 
-use self::{error::NegotiationError, protocol_header::ProtocolHeaderCodec};
+use self::{
+    error::NegotiationError, metrics::TransportCounters, protocol_header::ProtocolHeaderCodec,
+};
 
 pub(crate) mod error;
 pub use error::Error;
+pub(crate) mod metrics;
+pub use metrics::TransportMetrics;
 pub mod protocol_header;
 
 pin_project! {
@@ -47,6 +48,12 @@ pin_project! {
 
         #[pin]
         idle_timeout: Option<IdleTimeout>,
+
+        metrics: Arc<TransportCounters>,
+
+        // Buffer that outgoing frames are encoded into, which is reused across frames
+        encode_buf: BytesMut,
+
         // frame type
         ftype: PhantomData<Ftype>,
     }
@@ -66,6 +73,13 @@ where
         (self.framed_write, self.framed_read)
     }
 
+    /// Consume the transport and return the underlying IO
+    pub fn into_inner_io(self) -> Io {
+        let reader = self.framed_read.into_inner();
+        let writer = self.framed_write.into_inner();
+        reader.unsplit(writer)
+    }
+
     /// Bind to an IO
     pub fn bind(io: Io, max_frame_size: usize, idle_timeout: Option<Duration>) -> Self {
         let (reader, writer) = tokio::io::split(io);
@@ -97,9 +111,20 @@ where
             framed_write,
             framed_read,
             idle_timeout,
+            metrics: Arc::new(TransportCounters::default()),
+            encode_buf: BytesMut::new(),
             ftype: PhantomData,
         }
     }
+
+    /// Get a snapshot of the number of frames and bytes sent and received
+    pub fn metrics(&self) -> TransportMetrics {
+        self.metrics.snapshot()
+    }
+
+    pub(crate) fn metrics_handle(&self) -> Arc<TransportCounters> {
+        self.metrics.clone()
+    }
 }
 
 impl<Io> Transport<Io, ()>
@@ -182,7 +207,7 @@ where
         tracing::event!(parent: &span, tracing::Level::TRACE, ?proto_header);
         #[cfg(feature = "log")]
         log::trace!("SEND proto_header = {:?}", proto_header);
-        framed_write.send(proto_header).await?;
+        framed_write.send(proto_header.clone()).await?;
 
         #[cfg(feature = "tracing")]
         let span = tracing::span!(tracing::Level::TRACE, "RECV");
@@ -197,14 +222,8 @@ where
         #[cfg(feature = "log")]
         log::trace!("RECV incoming_header = {:?}", incoming_header);
 
-        if !incoming_header.is_sasl()
-            || incoming_header.major != MAJOR
-            || incoming_header.minor != MINOR
-            || incoming_header.revision != REVISION
-        {
-            return Err(NegotiationError::ProtocolHeaderMismatch(
-                incoming_header.into(),
-            ));
+        if incoming_header != proto_header {
+            return Err(header_mismatch(&proto_header, incoming_header));
         }
 
         let encoder = length_delimited_encoder(MIN_MAX_FRAME_SIZE);
@@ -275,6 +294,37 @@ where
         self.idle_timeout = idle_timeout;
         self
     }
+
+    /// Restarts the idle timeout as if a frame was received from the remote peer
+    ///
+    /// This is also done whenever a non-empty frame is written, so that a transport which is busy
+    /// writing is not timed out while the remote peer has nothing to send back. Empty frames
+    /// (heartbeats) do not restart the idle timeout.
+    pub fn reset_idle_timeout(&mut self) -> &mut Self {
+        if let Some(idle_timeout) = &mut self.idle_timeout {
+            idle_timeout.reset();
+        }
+        self
+    }
+}
+
+/// Upper bound of the capacity that is reserved up front for encoding frames. The negotiated max
+/// frame size may be as large as `u32::MAX`, and the buffer would only grow beyond this bound
+/// when a frame that large is actually encoded
+const MAX_RESERVED_ENCODE_BUF_CAPACITY: usize = 64 * 1024;
+
+/// Prepares the encode buffer for the next frame
+///
+/// The frames encoded previously are split off the buffer and copied by the length delimited
+/// codec, so the allocation is reclaimed here instead of allocating a new one once they are
+/// dropped. The allocation grows to the max frame size (bounded by
+/// [`MAX_RESERVED_ENCODE_BUF_CAPACITY`]) the first time.
+fn reserve_encode_buf(encode_buf: &mut BytesMut, max_frame_size: usize) {
+    encode_buf.clear();
+    encode_buf.reserve(std::cmp::min(
+        max_frame_size,
+        MAX_RESERVED_ENCODE_BUF_CAPACITY,
+    ));
 }
 
 /// Creates a LengthDelimitedCodec that can handle the AMQP and SASL frames
@@ -408,17 +458,32 @@ where
             io::ErrorKind::UnexpectedEof,
             "Waiting for header exchange",
         ))
-    })??;
+    })?;
+    let incoming_header = match incoming_header {
+        Ok(header) => header,
+        Err(error) => {
+            *local_state = ConnectionState::End;
+            return Err(error);
+        }
+    };
     if incoming_header != *proto_header {
         *local_state = ConnectionState::End;
-        return Err(NegotiationError::NotImplemented(Some(format!(
-            "Expecting {:?}, found {:?}",
-            proto_header, incoming_header
-        ))));
+        return Err(header_mismatch(proto_header, incoming_header));
     }
     Ok(incoming_header)
 }
 
+/// Distinguishes a different version of the same protocol from a different protocol
+fn header_mismatch(expected: &ProtocolHeader, found: ProtocolHeader) -> NegotiationError {
+    match found.id == expected.id {
+        true => NegotiationError::ProtocolVersionMismatch {
+            expected: expected.clone(),
+            found,
+        },
+        false => NegotiationError::ProtocolHeaderMismatch(found.into()),
+    }
+}
+
 impl<Io> Sink<amqp::Frame> for Transport<Io, amqp::Frame>
 where
     Io: AsyncWrite + Unpin,
@@ -441,17 +506,30 @@ where
     ) -> Result<(), Self::Error> {
         use std::pin::Pin;
 
-        let mut bytesmut = BytesMut::new();
+        // Empty frames are only sent to keep the remote peer from timing out, and must not
+        // hide a remote peer that has gone silent
+        if !matches!(item.body(), amqp::FrameBody::Empty) {
+            if let Some(idle_timeout) = &mut self.idle_timeout {
+                idle_timeout.reset();
+            }
+        }
+
         let max_frame_size = self.framed_write.encoder().max_frame_length();
+        reserve_encode_buf(&mut self.encode_buf, max_frame_size);
         let mut encoder = amqp::FrameEncoder::new(max_frame_size);
-        encoder.encode(item, &mut bytesmut)?;
+        encoder.encode(item, &mut self.encode_buf)?;
+        let mut bytesmut = self.encode_buf.split();
+        self.metrics.on_frame_out();
 
+        // The length delimited codec prepends the 4 bytes frame size
         while bytesmut.len() > max_frame_size {
             let partial = bytesmut.split_to(max_frame_size);
+            self.metrics.on_bytes_out(partial.len() + 4);
             let writer = Pin::new(&mut self.framed_write);
             writer.start_send(partial.freeze())?;
         }
 
+        self.metrics.on_bytes_out(bytesmut.len() + 4);
         let writer = Pin::new(&mut self.framed_write);
         writer
             .start_send(bytesmut.freeze()) // Result<_, std::io::Error>
@@ -504,6 +582,8 @@ where
                             Ok(b) => b,
                             Err(err) => return Poll::Ready(Some(Err(err.into()))),
                         };
+                        // The 4 bytes frame size is stripped by the length delimited codec
+                        this.metrics.on_frame_in(src.len() + 4);
                         // tracing::debug!("raw bytes {:#x?}", &src[..]);
                         let mut decoder = amqp::FrameDecoder {};
                         Poll::Ready(decoder.decode(&mut src).map_err(Into::into).transpose())
@@ -547,14 +627,16 @@ where
     fn start_send(self: std::pin::Pin<&mut Self>, item: sasl::Frame) -> Result<(), Self::Error> {
         // (frame=?item);
 
+        let this = self.project();
+
         // Needs to know the length, and thus cannot write directly to the IO
-        let mut bytesmut = BytesMut::new();
+        let max_frame_size = this.framed_write.encoder().max_frame_length();
+        reserve_encode_buf(this.encode_buf, max_frame_size);
         let mut encoder = sasl::FrameCodec {};
-        encoder.encode(item, &mut bytesmut)?;
+        encoder.encode(item, this.encode_buf)?;
 
-        let this = self.project();
         this.framed_write
-            .start_send(bytesmut.freeze())
+            .start_send(this.encode_buf.split().freeze())
             .map_err(Into::into)
     }
 
@@ -618,7 +700,8 @@ mod tests {
 
     use super::{
         amqp::{Frame, FrameBody},
-        protocol_header::ProtocolHeaderCodec,
+        error::NegotiationError,
+        protocol_header::{ProtocolHeader, ProtocolHeaderCodec, ProtocolId},
         Transport,
     };
 
@@ -673,6 +756,43 @@ mod tests {
         assert_eq!(&writer[..], &[0x0u8, 0x0, 0x0, 0x8, 0x2, 0x0, 0x0, 0x0]);
     }
 
+    #[tokio::test]
+    async fn encode_buf_allocation_is_reused_across_frames() {
+        let (client, _server) = tokio::io::duplex(64 * 1024);
+        let mut transport = Transport::<_, Frame>::bind(client, 512, None);
+
+        // Enough frames for the encoded bytes to exceed the capacity reserved for the buffer
+        let mut allocation_ends = Vec::new();
+        for _ in 0..64 {
+            let open = Open {
+                container_id: "1234".into(),
+                hostname: Some("127.0.0.1".into()),
+                max_frame_size: 1000.into(),
+                channel_max: 9.into(),
+                idle_time_out: Some(10),
+                outgoing_locales: None,
+                incoming_locales: None,
+                offered_capabilities: None,
+                desired_capabilities: None,
+                properties: None,
+            };
+            transport
+                .send(Frame::new(0u16, FrameBody::Open(open)))
+                .await
+                .unwrap();
+            transport.send(Frame::empty()).await.unwrap();
+
+            // The encoded frames have been copied by the length delimited codec, so the same
+            // allocation is expected to be reclaimed instead of allocating again, which keeps
+            // the end of the allocation unchanged
+            super::reserve_encode_buf(&mut transport.encode_buf, 512);
+            let end = transport.encode_buf.as_ptr() as usize + transport.encode_buf.capacity();
+            allocation_ends.push(end);
+        }
+
+        assert!(allocation_ends.iter().all(|end| *end == allocation_ends[0]));
+    }
+
     #[tokio::test]
     async fn test_header_exchange() {
         let mock = Builder::new()
@@ -692,6 +812,58 @@ mod tests {
             .unwrap();
     }
 
+    async fn negotiate_amqp_header_with_incoming(incoming: &[u8]) -> NegotiationError {
+        let mock = Builder::new()
+            .write(b"AMQP")
+            .write(&[0, 1, 0, 0])
+            .read(incoming)
+            .build();
+
+        let (reader, writer) = tokio::io::split(mock);
+        let framed_read = FramedRead::new(reader, ProtocolHeaderCodec::new());
+        let framed_write = FramedWrite::new(writer, ProtocolHeaderCodec::new());
+
+        let mut local_state = ConnectionState::Start;
+        let error =
+            Transport::negotiate_amqp_header(framed_write, framed_read, &mut local_state, None)
+                .await
+                .unwrap_err();
+        assert!(matches!(local_state, ConnectionState::End));
+        error
+    }
+
+    #[tokio::test]
+    async fn test_header_exchange_with_version_mismatch() {
+        let error = negotiate_amqp_header_with_incoming(b"AMQP\x00\x00\x09\x01").await;
+        match error {
+            NegotiationError::ProtocolVersionMismatch { expected, found } => {
+                assert_eq!(expected, ProtocolHeader::amqp());
+                assert_eq!(found, ProtocolHeader::new(ProtocolId::Amqp, 0, 9, 1));
+            }
+            error => panic!("Expecting ProtocolVersionMismatch, found {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_header_exchange_with_different_protocol_id() {
+        let error = negotiate_amqp_header_with_incoming(b"AMQP\x03\x01\x00\x00").await;
+        match error {
+            NegotiationError::ProtocolHeaderMismatch(buf) => {
+                assert_eq!(&buf[..], b"AMQP\x03\x01\x00\x00")
+            }
+            error => panic!("Expecting ProtocolHeaderMismatch, found {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_header_exchange_with_non_amqp_header() {
+        let error = negotiate_amqp_header_with_incoming(b"HTTP/1.1").await;
+        match error {
+            NegotiationError::ProtocolHeaderMismatch(buf) => assert_eq!(&buf[..], b"HTTP/1.1"),
+            error => panic!("Expecting ProtocolHeaderMismatch, found {:?}", error),
+        }
+    }
+
     #[tokio::test]
     async fn test_empty_frame_with_length_delimited_codec() {
         let mock = Builder::new()
@@ -703,6 +875,27 @@ mod tests {
         transport.send(frame).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_metrics_count_frames_and_bytes() {
+        let mock = Builder::new()
+            .write(&[0x00, 0x00, 0x00, 0x08])
+            .write(&[0x02, 0x00, 0x00, 0x00])
+            .write(&[0x00, 0x00, 0x00, 0x08])
+            .write(&[0x02, 0x00, 0x00, 0x00])
+            .read(&[0x00, 0x00, 0x00, 0x08, 0x02, 0x00, 0x00, 0x00])
+            .build();
+        let mut transport = Transport::bind(mock, 512, None);
+        transport.send(Frame::empty()).await.unwrap();
+        transport.send(Frame::empty()).await.unwrap();
+        let _frame = transport.next().await.unwrap().unwrap();
+
+        let metrics = transport.metrics();
+        assert_eq!(metrics.frames_out, 2);
+        assert_eq!(metrics.bytes_out, 16);
+        assert_eq!(metrics.frames_in, 1);
+        assert_eq!(metrics.bytes_in, 8);
+    }
+
     #[tokio::test]
     async fn test_frame_sink() {
         // use std::io::Cursor;
@@ -1,3 +1,10 @@
+//! Helpers shared by the integration tests
+//!
+//! The tests either run against a broker in a container or against an in-process peer that is
+//! built with the acceptor over an in-memory duplex stream
+
+#![allow(dead_code)]
+
 use testcontainers::{clients::Cli, core::WaitFor, Container, GenericImage};
 use tokio::sync::OnceCell;
 
@@ -49,3 +56,360 @@ pub async fn setup_rabbitmq_amqp10(
     let port = node.get_host_port_ipv4(5672);
     (node, port)
 }
+
+#[cfg(feature = "acceptor")]
+#[allow(unused_imports)]
+pub use self::in_process::*;
+
+#[cfg(feature = "acceptor")]
+mod in_process {
+    use fe2o3_amqp::{
+        acceptor::{
+            error::AcceptorAttachError,
+            link::{LinkAcceptor, LinkEndpoint},
+            session::SessionAcceptor,
+            ConnectionAcceptor,
+        },
+        connection::ConnectionHandle,
+        frames::amqp::{Frame, FrameBody},
+        transport::Transport,
+        types::{
+            definitions::{self, AmqpError, Handle},
+            messaging::{Source, Target},
+            performatives::{Flow, Transfer},
+            primitives::Binary,
+            primitives::Value,
+        },
+        Connection, Receiver, Sender,
+    };
+    use futures_util::StreamExt;
+    use serde::Serialize;
+    use std::sync::Arc;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt, DuplexStream},
+        sync::mpsc::{UnboundedReceiver, UnboundedSender},
+        task::JoinHandle,
+    };
+
+    /// Spawns an in-process peer with the default acceptors. See [`spawn_peer_with`]
+    pub fn spawn_peer(stream: DuplexStream) -> (JoinHandle<()>, UnboundedReceiver<LinkEndpoint>) {
+        spawn_peer_with(
+            stream,
+            ConnectionAcceptor::new("in-process-peer"),
+            SessionAcceptor::new(),
+            LinkAcceptor::new(),
+        )
+    }
+
+    /// Spawns an in-process peer that accepts a single connection, every session begun on it, and
+    /// keeps accepting links until the sessions end. Refused links are skipped. The accepted links
+    /// are sent over the returned channel so that each test can decide how the remote end behaves
+    pub fn spawn_peer_with<FS, FT>(
+        stream: DuplexStream,
+        connection_acceptor: ConnectionAcceptor<(), ()>,
+        session_acceptor: SessionAcceptor,
+        link_acceptor: LinkAcceptor<FS, FT>,
+    ) -> (JoinHandle<()>, UnboundedReceiver<LinkEndpoint>)
+    where
+        FS: Fn(Source) -> Option<Source> + Send + Sync + 'static,
+        FT: Fn(Target) -> Option<Target> + Send + Sync + 'static,
+    {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let link_acceptor = Arc::new(link_acceptor);
+        let handle = tokio::spawn(async move {
+            let mut connection = connection_acceptor.accept(stream).await.unwrap();
+            while let Ok(mut session) = session_acceptor.accept(&mut connection).await {
+                let tx = tx.clone();
+                let link_acceptor = link_acceptor.clone();
+                tokio::spawn(async move {
+                    loop {
+                        match link_acceptor.accept(&mut session).await {
+                            Ok(link) => {
+                                if tx.send(link).is_err() {
+                                    break;
+                                }
+                            }
+                            // A refused link does not stop the session
+                            Err(AcceptorAttachError::IllegalSessionState) => break,
+                            Err(_) => continue,
+                        }
+                    }
+                    let _ = session.on_end().await;
+                });
+            }
+            let _ = connection.on_close().await;
+        });
+        (handle, rx)
+    }
+
+    /// Opens a connection to an in-process peer with the default acceptors
+    pub async fn connect() -> (
+        ConnectionHandle<()>,
+        JoinHandle<()>,
+        UnboundedReceiver<LinkEndpoint>,
+    ) {
+        let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+        let (peer, links) = spawn_peer(peer_io);
+        (open(client_io).await, peer, links)
+    }
+
+    /// Opens a connection to an in-process peer with the given acceptors
+    pub async fn connect_with<FS, FT>(
+        connection_acceptor: ConnectionAcceptor<(), ()>,
+        session_acceptor: SessionAcceptor,
+        link_acceptor: LinkAcceptor<FS, FT>,
+    ) -> (
+        ConnectionHandle<()>,
+        JoinHandle<()>,
+        UnboundedReceiver<LinkEndpoint>,
+    )
+    where
+        FS: Fn(Source) -> Option<Source> + Send + Sync + 'static,
+        FT: Fn(Target) -> Option<Target> + Send + Sync + 'static,
+    {
+        let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+        let (peer, links) = spawn_peer_with(
+            peer_io,
+            connection_acceptor,
+            session_acceptor,
+            link_acceptor,
+        );
+        (open(client_io).await, peer, links)
+    }
+
+    /// Opens a connection to an in-process peer through a relay, returning the channel to write
+    /// raw frames to the client in between the frames from the peer
+    pub async fn connect_with_injection() -> (
+        ConnectionHandle<()>,
+        JoinHandle<()>,
+        UnboundedReceiver<LinkEndpoint>,
+        UnboundedSender<Vec<u8>>,
+    ) {
+        let (client_io, relay_client_io) = tokio::io::duplex(64 * 1024);
+        let (relay_peer_io, peer_io) = tokio::io::duplex(64 * 1024);
+        let (inject_tx, inject_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(relay_and_inject_frames(
+            relay_client_io,
+            relay_peer_io,
+            inject_rx,
+        ));
+        let (peer, links) = spawn_peer(peer_io);
+        (open(client_io).await, peer, links, inject_tx)
+    }
+
+    /// Opens a connection to an in-process peer through a relay, returning the channel that
+    /// receives the bytes written by the client
+    pub async fn connect_with_recording() -> (
+        ConnectionHandle<()>,
+        JoinHandle<()>,
+        UnboundedReceiver<LinkEndpoint>,
+        UnboundedReceiver<Vec<u8>>,
+    ) {
+        let (client_io, relay_client_io) = tokio::io::duplex(64 * 1024);
+        let (relay_peer_io, peer_io) = tokio::io::duplex(64 * 1024);
+        let (record_tx, record_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(relay_and_record_frames(
+            relay_client_io,
+            relay_peer_io,
+            record_tx,
+        ));
+        let (peer, links) = spawn_peer(peer_io);
+        (open(client_io).await, peer, links, record_rx)
+    }
+
+    async fn open(client_io: DuplexStream) -> ConnectionHandle<()> {
+        Connection::builder()
+            .container_id("in-process-client")
+            .open_with_stream(client_io)
+            .await
+            .unwrap()
+    }
+
+    /// Relays frames between the client and the peer, and writes the injected raw frames to the
+    /// client in between the frames from the peer
+    async fn relay_and_inject_frames(
+        client: DuplexStream,
+        peer: DuplexStream,
+        mut inject: UnboundedReceiver<Vec<u8>>,
+    ) {
+        let (mut client_read, mut client_write) = tokio::io::split(client);
+        let (mut peer_read, mut peer_write) = tokio::io::split(peer);
+        tokio::spawn(async move {
+            let _ = tokio::io::copy(&mut client_read, &mut peer_write).await;
+        });
+
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            tokio::select! {
+                read = peer_read.read(&mut buf) => match read {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => client_write.write_all(&buf[..n]).await.unwrap(),
+                },
+                Some(frame) = inject.recv() => client_write.write_all(&frame).await.unwrap(),
+            }
+        }
+    }
+
+    /// Relays frames between the client and the peer, and records the bytes written by the
+    /// client
+    async fn relay_and_record_frames(
+        client: DuplexStream,
+        peer: DuplexStream,
+        record: UnboundedSender<Vec<u8>>,
+    ) {
+        let (mut client_read, mut client_write) = tokio::io::split(client);
+        let (mut peer_read, mut peer_write) = tokio::io::split(peer);
+        tokio::spawn(async move {
+            let _ = tokio::io::copy(&mut peer_read, &mut client_write).await;
+        });
+
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            match client_read.read(&mut buf).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => {
+                    let _ = record.send(buf[..n].to_vec());
+                    if peer_write.write_all(&buf[..n]).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Decodes the AMQP frames recorded by [`connect_with_recording`], skipping the protocol
+    /// header
+    pub async fn recorded_frames(record: &mut UnboundedReceiver<Vec<u8>>) -> Vec<FrameBody> {
+        let mut bytes = Vec::new();
+        while let Ok(chunk) = record.try_recv() {
+            bytes.extend(chunk);
+        }
+
+        let (mut writer, reader) = tokio::io::duplex(bytes.len() + 1);
+        writer.write_all(&bytes[8..]).await.unwrap();
+        drop(writer);
+        let transport = Transport::<_, Frame>::bind(reader, u32::MAX as usize, None);
+        transport
+            .filter_map(|frame| async move { frame.ok().map(Frame::into_body) })
+            .collect()
+            .await
+    }
+
+    /// Waits for the next link attached by the client, which is expected to be a remote sender
+    pub async fn next_remote_sender(links: &mut UnboundedReceiver<LinkEndpoint>) -> Sender {
+        match links.recv().await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a remote sender"),
+        }
+    }
+
+    /// Waits for the next link attached by the client, which is expected to be a remote receiver
+    pub async fn next_remote_receiver(links: &mut UnboundedReceiver<LinkEndpoint>) -> Receiver {
+        match links.recv().await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a remote receiver"),
+        }
+    }
+
+    /// Drives a remote link until it is detached by the client, returning the link name
+    pub fn drive(link: LinkEndpoint) -> String {
+        match link {
+            LinkEndpoint::Sender(sender) => {
+                let name = sender.name().to_string();
+                tokio::spawn(drive_sender(sender));
+                name
+            }
+            LinkEndpoint::Receiver(receiver) => {
+                let name = receiver.name().to_string();
+                tokio::spawn(drive_receiver(receiver));
+                name
+            }
+        }
+    }
+
+    pub async fn drive_sender(mut sender: Sender) {
+        let _ = sender.on_detach().await;
+        let _ = sender.close().await;
+    }
+
+    pub async fn drive_receiver(mut receiver: Receiver) {
+        while let Ok(delivery) = receiver.recv::<Value>().await {
+            let _ = receiver.accept(&delivery).await;
+        }
+        let _ = receiver.close().await;
+    }
+
+    /// Encodes an AMQP frame on channel 0
+    fn amqp_frame(performative: &impl Serialize, payload: &[u8]) -> Vec<u8> {
+        let body = serde_amqp::to_vec(performative).unwrap();
+        let size = (8 + body.len() + payload.len()) as u32;
+
+        // doff = 2, type = AMQP, channel = 0
+        let mut frame = size.to_be_bytes().to_vec();
+        frame.extend_from_slice(&[0x02, 0x00, 0x00, 0x00]);
+        frame.extend_from_slice(&body);
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    /// Encodes a transfer frame on channel 0 for the link with handle 0
+    pub fn transfer_frame(
+        delivery: Option<(u32, &[u8])>,
+        settled: Option<bool>,
+        more: bool,
+        aborted: bool,
+        payload: &[u8],
+    ) -> Vec<u8> {
+        let transfer = Transfer {
+            handle: Handle(0),
+            delivery_id: delivery.map(|(id, _)| id),
+            delivery_tag: delivery.map(|(_, tag)| Binary::from(tag.to_vec())),
+            message_format: delivery.map(|_| 0),
+            settled,
+            more,
+            rcv_settle_mode: None,
+            state: None,
+            resume: false,
+            aborted,
+            batchable: false,
+        };
+        amqp_frame(&transfer, payload)
+    }
+
+    /// Encodes a flow frame on channel 0 for the link with handle 0
+    pub fn flow_frame(delivery_count: u32, link_credit: u32, available: u32) -> Vec<u8> {
+        let flow = Flow {
+            next_incoming_id: Some(0),
+            incoming_window: 2048,
+            next_outgoing_id: 0,
+            outgoing_window: 2048,
+            handle: Some(Handle(0)),
+            delivery_count: Some(delivery_count),
+            link_credit: Some(link_credit),
+            available: Some(available),
+            drain: false,
+            echo: false,
+            properties: None,
+        };
+        amqp_frame(&flow, &[])
+    }
+
+    /// amqp-value("hi") split into two parts at the string constructor
+    pub const SPLIT_MESSAGE: (&[u8], &[u8]) = (&[0x00, 0x53, 0x77, 0xa1], &[0x02, b'h', b'i']);
+
+    pub fn application_error() -> definitions::Error {
+        definitions::Error::new(
+            AmqpError::NotImplemented,
+            Some("application fault".to_string()),
+            None,
+        )
+    }
+
+    pub fn assert_application_error(error: &definitions::Error) {
+        assert_eq!(
+            error.condition,
+            definitions::ErrorCondition::AmqpError(AmqpError::NotImplemented)
+        );
+        assert_eq!(error.description.as_deref(), Some("application fault"));
+    }
+}
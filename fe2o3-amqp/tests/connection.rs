@@ -0,0 +1,1175 @@
+//! Tests of connections against an in-process peer built with the acceptor over an in-memory duplex
+//! stream
+
+#![cfg(all(feature = "acceptor", not(target_arch = "wasm32")))]
+
+mod common;
+
+use common::{application_error, assert_application_error, connect, drive, spawn_peer};
+use fe2o3_amqp::{
+    acceptor::{
+        link::LinkAcceptor, sasl_acceptor::SaslServerFrame, session::SessionAcceptor,
+        ConnectionAcceptor, SaslAcceptor,
+    },
+    connection::{ConnectionHandle, IntoInnerIoError, OpenError},
+    sasl_profile::SaslProfile,
+    types::{
+        definitions::{self, AmqpError, SenderSettleMode},
+        primitives::{Array, Symbol, Value},
+        sasl::{SaslCode, SaslInit, SaslOutcome, SaslResponse},
+    },
+    Connection, Receiver, Sender, Session,
+};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+#[tokio::test]
+async fn connection_transport_metrics_count_frames_and_bytes() {
+    let (mut connection, _peer, mut links) = connect().await;
+
+    // Open frames are exchanged
+    let opened = connection.transport_metrics();
+    assert_eq!(opened.frames_out, 1);
+    assert_eq!(opened.frames_in, 1);
+    assert!(opened.bytes_out >= 8 && opened.bytes_in >= 8);
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    drive(links.recv().await.unwrap());
+    let payload = "x".repeat(1000);
+    sender.send(payload).await.unwrap();
+
+    // Begin, attach and transfer are sent, and begin, attach, flow and disposition are received
+    let sent = connection.transport_metrics();
+    assert!(sent.frames_out >= opened.frames_out + 3);
+    assert!(sent.frames_in >= opened.frames_in + 4);
+    assert!(sent.bytes_out >= opened.bytes_out + 1000);
+
+    sender.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+/// Relays bytes between the client and the in-process peer. Once `inject` fires, the bytes from
+/// the peer are no longer relayed and a frame that cannot be decoded is sent to the client
+/// instead.
+async fn relay_then_inject_malformed_frame(
+    client: tokio::io::DuplexStream,
+    peer: tokio::io::DuplexStream,
+    inject: tokio::sync::oneshot::Receiver<()>,
+) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut client_read, mut client_write) = tokio::io::split(client);
+    let (mut peer_read, mut peer_write) = tokio::io::split(peer);
+    tokio::spawn(async move {
+        let _ = tokio::io::copy(&mut client_read, &mut peer_write).await;
+    });
+
+    let mut inject = inject;
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        tokio::select! {
+            read = peer_read.read(&mut buf) => match read {
+                Ok(0) | Err(_) => return,
+                Ok(n) => client_write.write_all(&buf[..n]).await.unwrap(),
+            },
+            _ = &mut inject => break,
+        }
+    }
+
+    // size = 12, doff = 2, type = AMQP, channel = 0, followed by an invalid performative
+    let malformed = [
+        0x00, 0x00, 0x00, 0x0c, 0x02, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+    ];
+    client_write.write_all(&malformed).await.unwrap();
+    client_write.flush().await.unwrap();
+
+    // Keep the stream open so that the client stops because of the malformed frame
+    std::future::pending::<()>().await
+}
+
+/// A raw peer that advertises a max-frame-size of 100 in its Open and returns the frames sent
+/// by the client
+async fn raw_peer_with_tiny_max_frame_size(peer: tokio::io::DuplexStream) -> Vec<u8> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut read, mut write) = tokio::io::split(peer);
+    let mut header = [0u8; 8];
+    read.read_exact(&mut header).await.unwrap();
+    assert_eq!(&header, b"AMQP\x00\x01\x00\x00");
+    write.write_all(&header).await.unwrap();
+
+    // size = 23, doff = 2, type = AMQP, channel = 0, followed by
+    // open(container-id = "peer", hostname = null, max-frame-size = 100)
+    let open = [
+        0x00, 0x00, 0x00, 0x17, 0x02, 0x00, 0x00, 0x00, 0x00, 0x53, 0x10, 0xc0, 0x0a, 0x03, 0xa1,
+        0x04, b'p', b'e', b'e', b'r', 0x40, 0x52, 0x64,
+    ];
+    write.write_all(&open).await.unwrap();
+
+    // Read until the client sends a Close with an error
+    let mut received = Vec::new();
+    let mut buf = vec![0u8; 1024];
+    let condition = b"amqp:connection:framing-error";
+    while !received
+        .windows(condition.len())
+        .any(|window| window == condition)
+    {
+        match read.read(&mut buf).await.unwrap() {
+            0 => break,
+            n => received.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    // size = 12, doff = 2, type = AMQP, channel = 0, followed by close()
+    let close = [
+        0x00, 0x00, 0x00, 0x0c, 0x02, 0x00, 0x00, 0x00, 0x00, 0x53, 0x18, 0x45,
+    ];
+    write.write_all(&close).await.unwrap();
+    received
+}
+
+#[tokio::test]
+async fn remote_open_with_max_frame_size_below_minimum_is_rejected() {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(raw_peer_with_tiny_max_frame_size(peer_io));
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        Connection::builder()
+            .container_id("in-process-client")
+            .open_with_stream(client_io),
+    )
+    .await
+    .expect("open did not complete");
+    assert!(matches!(
+        result,
+        Err(OpenError::TransportError(
+            fe2o3_amqp::transport::Error::FramingError
+        ))
+    ));
+
+    let received = tokio::time::timeout(Duration::from_secs(5), peer)
+        .await
+        .expect("close frame is not sent to the peer")
+        .unwrap();
+    let condition = b"amqp:connection:framing-error";
+    assert!(received
+        .windows(condition.len())
+        .any(|window| window == condition));
+}
+
+#[tokio::test]
+async fn remote_protocol_header_of_other_version_is_reported() {
+    use fe2o3_amqp::transport::protocol_header::{ProtocolHeader, ProtocolId};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (client_io, mut peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let mut header = [0u8; 8];
+        peer_io.read_exact(&mut header).await.unwrap();
+        // AMQP 0-9-1
+        peer_io.write_all(b"AMQP\x00\x00\x09\x01").await.unwrap();
+    });
+
+    let result = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await;
+    match result {
+        Err(OpenError::ProtocolVersionMismatch { expected, found }) => {
+            assert_eq!(expected, ProtocolHeader::amqp());
+            assert_eq!(found, ProtocolHeader::new(ProtocolId::Amqp, 0, 9, 1));
+        }
+        result => panic!("Expecting ProtocolVersionMismatch, found {:?}", result),
+    }
+    peer.await.unwrap();
+}
+
+#[tokio::test]
+async fn malformed_frame_stops_connection_with_error_instead_of_panicking() {
+    let (client_io, relay_client_io) = tokio::io::duplex(64 * 1024);
+    let (relay_peer_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let (inject_tx, inject_rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(relay_then_inject_malformed_frame(
+        relay_client_io,
+        relay_peer_io,
+        inject_rx,
+    ));
+    let peer = tokio::spawn(async move {
+        let mut connection = ConnectionAcceptor::new("in-process-peer")
+            .accept(peer_io)
+            .await
+            .unwrap();
+        let mut session = SessionAcceptor::new()
+            .accept(&mut connection)
+            .await
+            .unwrap();
+        let _link = LinkAcceptor::new().accept(&mut session).await.unwrap();
+        connection.on_close().await
+    });
+
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+
+    inject_tx.send(()).unwrap();
+
+    // Outstanding operations on the session fail instead of hanging
+    let result = tokio::time::timeout(Duration::from_secs(5), receiver.recv::<Value>())
+        .await
+        .expect("receiver is not notified");
+    assert!(result.is_err());
+
+    let result = tokio::time::timeout(Duration::from_secs(5), connection.on_close())
+        .await
+        .expect("connection engine did not stop");
+    assert!(matches!(
+        result,
+        Err(fe2o3_amqp::connection::Error::TransportError(_))
+    ));
+    assert!(connection.is_closed());
+
+    let result = tokio::time::timeout(Duration::from_secs(5), session.on_end())
+        .await
+        .expect("session engine did not stop");
+    assert!(result.is_err());
+
+    // The client closes the connection with a decode error
+    let result = tokio::time::timeout(Duration::from_secs(5), peer)
+        .await
+        .expect("close frame is not sent to the peer");
+    match result.unwrap() {
+        Err(fe2o3_amqp::connection::Error::RemoteClosedWithError(error)) => {
+            assert_eq!(
+                error.condition,
+                definitions::ErrorCondition::AmqpError(AmqpError::DecodeError)
+            );
+        }
+        result => panic!("Expecting remote close with an error, found {:?}", result),
+    }
+}
+
+async fn remote_closes_connection_with_error() -> (ConnectionHandle<()>, JoinHandle<()>) {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let mut connection = ConnectionAcceptor::new("in-process-peer")
+            .accept(peer_io)
+            .await
+            .unwrap();
+        let error = definitions::Error::new(
+            AmqpError::ResourceLimitExceeded,
+            Some("too many connections".to_string()),
+            None,
+        );
+        let _ = connection.close_with_error(error).await;
+    });
+    let connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    (connection, peer)
+}
+
+fn assert_resource_limit_exceeded(error: &definitions::Error) {
+    assert_eq!(
+        error.condition,
+        definitions::ErrorCondition::AmqpError(AmqpError::ResourceLimitExceeded)
+    );
+    assert_eq!(error.description.as_deref(), Some("too many connections"));
+}
+
+#[tokio::test]
+async fn remote_close_error_is_returned_by_on_close() {
+    let (mut connection, peer) = remote_closes_connection_with_error().await;
+
+    match connection.on_close().await {
+        Err(fe2o3_amqp::connection::Error::RemoteClosedWithError(error)) => {
+            assert_resource_limit_exceeded(&error)
+        }
+        result => panic!("Expecting remote close with an error, found {:?}", result),
+    }
+    peer.await.unwrap();
+}
+
+#[tokio::test]
+async fn remote_close_error_is_returned_by_close() {
+    let (mut connection, peer) = remote_closes_connection_with_error().await;
+    peer.await.unwrap();
+
+    match connection.close().await {
+        Err(fe2o3_amqp::connection::Error::RemoteClosedWithError(error)) => {
+            assert_resource_limit_exceeded(&error)
+        }
+        result => panic!("Expecting remote close with an error, found {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn redirect_close_exposes_redirect_info() {
+    use fe2o3_amqp::{
+        connection::{RedirectInfo, SOLE_CONNECTION_FOR_CONTAINER},
+        types::{definitions::ConnectionError, primitives::OrderedMap},
+    };
+
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let mut acceptor = ConnectionAcceptor::new("in-process-peer");
+        acceptor.local_open.offered_capabilities = Some(Array::from(vec![Symbol::from(
+            SOLE_CONNECTION_FOR_CONTAINER,
+        )]));
+        let mut connection = acceptor.accept(peer_io).await.unwrap();
+
+        let mut info = OrderedMap::new();
+        info.insert(Symbol::from("hostname"), Value::from("container-1"));
+        info.insert(Symbol::from("network-host"), Value::from("10.0.0.2"));
+        info.insert(Symbol::from("port"), Value::Ushort(5673));
+        let error = definitions::Error::new(ConnectionError::Redirect, None, info);
+        let _ = connection.close_with_error(error).await;
+    });
+
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .add_desired_capabilities(SOLE_CONNECTION_FOR_CONTAINER)
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    assert!(connection.remote_offers_capability(SOLE_CONNECTION_FOR_CONTAINER));
+
+    let error = connection.on_close().await.unwrap_err();
+    assert_eq!(
+        error.redirect(),
+        Some(RedirectInfo {
+            hostname: Some("container-1".to_string()),
+            network_host: "10.0.0.2".to_string(),
+            port: 5673,
+        })
+    );
+    peer.await.unwrap();
+}
+
+#[tokio::test]
+async fn close_with_error_sends_error_to_remote() {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let mut connection = ConnectionAcceptor::new("in-process-peer")
+            .accept(peer_io)
+            .await
+            .unwrap();
+        connection.on_close().await
+    });
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+
+    connection
+        .close_with_error(application_error())
+        .await
+        .unwrap();
+    match peer.await.unwrap() {
+        Err(fe2o3_amqp::connection::Error::RemoteClosedWithError(error)) => {
+            assert_application_error(&error)
+        }
+        result => panic!("Expecting remote close with an error, found {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn remote_open_capabilities_and_properties_are_exposed() {
+    use fe2o3_amqp::types::{definitions::Fields, primitives::OrderedMap};
+
+    fn properties(product: &str) -> Fields {
+        let mut properties = OrderedMap::new();
+        properties.insert(Symbol::from("product"), Value::from(product));
+        properties.insert(Symbol::from("version"), Value::from("1.0"));
+        properties
+    }
+
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let mut acceptor = ConnectionAcceptor::new("in-process-peer");
+        acceptor.local_open.offered_capabilities =
+            Some(Array::from(vec![Symbol::from("ANONYMOUS-RELAY")]));
+        acceptor.local_open.desired_capabilities =
+            Some(Array::from(vec![Symbol::from("DELAYED_DELIVERY")]));
+        acceptor.local_open.properties = Some(properties("in-process-peer"));
+        let mut connection = acceptor.accept(peer_io).await.unwrap();
+
+        assert_eq!(
+            connection.remote_offered_capabilities(),
+            Some(&Array::from(vec![Symbol::from("client-offered")]))
+        );
+        assert_eq!(connection.remote_desired_capabilities(), None);
+        assert_eq!(
+            connection.remote_properties(),
+            Some(&properties("in-process-client"))
+        );
+        let _ = connection.on_close().await;
+    });
+
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .add_offered_capabilities("client-offered")
+        .properties(properties("in-process-client"))
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        connection.remote_offered_capabilities(),
+        Some(&Array::from(vec![Symbol::from("ANONYMOUS-RELAY")]))
+    );
+    assert_eq!(
+        connection.remote_desired_capabilities(),
+        Some(&Array::from(vec![Symbol::from("DELAYED_DELIVERY")]))
+    );
+    assert_eq!(
+        connection.remote_properties(),
+        Some(&properties("in-process-peer"))
+    );
+
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+}
+
+#[tokio::test]
+async fn open_carries_local_capabilities_and_exposes_remote_capabilities() {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let mut acceptor = ConnectionAcceptor::new("in-process-peer");
+        acceptor.local_open.offered_capabilities = Some(Array::from(vec![
+            Symbol::from("sole-connection-for-container"),
+            Symbol::from("ANONYMOUS-RELAY"),
+        ]));
+        let mut connection = acceptor.accept(peer_io).await.unwrap();
+        let capabilities = (
+            connection.remote_offered_capabilities().cloned(),
+            connection.remote_desired_capabilities().cloned(),
+        );
+        let _ = connection.on_close().await;
+        capabilities
+    });
+
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .set_offered_capabilities(vec![Symbol::from("vendor-capability")])
+        .add_desired_capabilities("sole-connection-for-container")
+        .add_desired_capabilities("DELAYED_DELIVERY")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+
+    assert!(connection.remote_offers_capability("sole-connection-for-container"));
+    assert!(connection.remote_offers_capability("ANONYMOUS-RELAY"));
+    assert!(!connection.remote_offers_capability("DELAYED_DELIVERY"));
+    connection.close().await.unwrap();
+
+    let (offered, desired) = peer.await.unwrap();
+    assert_eq!(
+        offered,
+        Some(Array::from(vec![Symbol::from("vendor-capability")]))
+    );
+    assert_eq!(
+        desired,
+        Some(Array::from(vec![
+            Symbol::from("sole-connection-for-container"),
+            Symbol::from("DELAYED_DELIVERY"),
+        ]))
+    );
+}
+
+#[tokio::test]
+async fn outgoing_open_carries_product_version_and_platform_properties() {
+    use fe2o3_amqp::types::primitives::OrderedMap;
+
+    let mut expected = OrderedMap::new();
+    expected.insert(Symbol::from("region"), Value::from("eu"));
+    expected.insert(Symbol::from("product"), Value::from("fe2o3-amqp"));
+    expected.insert(Symbol::from("version"), Value::from("0.10.0"));
+    expected.insert(Symbol::from("platform"), Value::from("rust"));
+
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let acceptor = ConnectionAcceptor::new("in-process-peer");
+        let mut connection = acceptor.accept(peer_io).await.unwrap();
+        let properties = connection.remote_properties().cloned();
+        let _ = connection.on_close().await;
+        properties
+    });
+
+    let mut properties = OrderedMap::new();
+    properties.insert(Symbol::from("region"), Value::from("eu"));
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .properties(properties)
+        .product("fe2o3-amqp")
+        .version("0.10.0")
+        .platform("rust")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    connection.close().await.unwrap();
+
+    assert_eq!(peer.await.unwrap(), Some(expected));
+}
+
+#[tokio::test]
+async fn negotiated_connection_parameters_are_the_agreed_minimums() {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let mut acceptor = ConnectionAcceptor::new("in-process-peer");
+        acceptor.local_open.max_frame_size = 1024.into();
+        acceptor.local_open.channel_max = 20.into();
+        acceptor.local_open.idle_time_out = Some(50_000);
+        let mut connection = acceptor.accept(peer_io).await.unwrap();
+
+        assert_eq!(connection.negotiated_max_frame_size(), 1024);
+        assert_eq!(connection.negotiated_channel_max(), 10);
+        assert_eq!(
+            connection.negotiated_idle_timeout(),
+            Some(Duration::from_secs(10))
+        );
+        let _ = connection.on_close().await;
+    });
+
+    // The connection builder advertises half of the configured idle timeout
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .max_frame_size(4096)
+        .channel_max(10)
+        .idle_time_out(20_000u32)
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+
+    assert_eq!(connection.negotiated_max_frame_size(), 1024);
+    assert_eq!(connection.negotiated_channel_max(), 10);
+    assert_eq!(
+        connection.negotiated_idle_timeout(),
+        Some(Duration::from_secs(10))
+    );
+
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+}
+
+#[tokio::test]
+async fn negotiated_idle_timeout_ignores_peer_without_idle_timeout() {
+    async fn open(idle_time_out: Option<u32>) -> Option<Duration> {
+        let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+        let peer = tokio::spawn(async move {
+            let acceptor = ConnectionAcceptor::new("in-process-peer");
+            let mut connection = acceptor.accept(peer_io).await.unwrap();
+            let _ = connection.on_close().await;
+        });
+
+        let mut builder = Connection::builder().container_id("in-process-client");
+        if let Some(idle_time_out) = idle_time_out {
+            builder = builder.idle_time_out(idle_time_out);
+        }
+        let mut connection = builder.open_with_stream(client_io).await.unwrap();
+        let negotiated = connection.negotiated_idle_timeout();
+        connection.close().await.unwrap();
+        peer.await.unwrap();
+        negotiated
+    }
+
+    assert_eq!(open(None).await, None);
+    assert_eq!(open(Some(20_000)).await, Some(Duration::from_secs(10)));
+}
+
+async fn open_with_closing_peer() -> (ConnectionHandle<()>, JoinHandle<()>) {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let acceptor = ConnectionAcceptor::new("in-process-peer");
+        let mut connection = acceptor.accept(peer_io).await.unwrap();
+        let _ = connection.on_close().await;
+    });
+    let connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    (connection, peer)
+}
+
+#[tokio::test]
+async fn closed_connection_returns_underlying_io() {
+    let (mut connection, peer) = open_with_closing_peer().await;
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+
+    let result = connection.into_inner_io::<tokio::io::DuplexStream>().await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn into_inner_io_fails_if_connection_is_not_closed_or_io_type_mismatches() {
+    let (connection, peer) = open_with_closing_peer().await;
+    let result = connection.into_inner_io::<tokio::io::DuplexStream>().await;
+    assert!(matches!(result, Err(IntoInnerIoError::NotClosed)));
+    // Dropping the handle closes the connection
+    peer.await.unwrap();
+
+    let (mut connection, peer) = open_with_closing_peer().await;
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+    let result = connection.into_inner_io::<tokio::net::TcpStream>().await;
+    assert!(matches!(result, Err(IntoInnerIoError::TypeMismatch)));
+}
+
+#[tokio::test]
+async fn connection_events_report_session_and_close_lifecycle() {
+    use fe2o3_amqp::connection::ConnectionEvent;
+    use futures_util::StreamExt;
+
+    let (mut connection, peer, _links) = connect().await;
+    let events = connection.events();
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+
+    let events: Vec<ConnectionEvent> =
+        tokio::time::timeout(Duration::from_secs(5), events.collect())
+            .await
+            .unwrap();
+    assert_eq!(
+        events,
+        vec![
+            ConnectionEvent::Opened,
+            ConnectionEvent::SessionBegun { channel: 0 },
+            ConnectionEvent::SessionEnded { channel: 0 },
+            ConnectionEvent::Closing,
+            ConnectionEvent::Closed,
+        ]
+    );
+
+    // A stream subscribed after the event loop has stopped ends right away
+    assert!(connection.events().next().await.is_none());
+}
+
+#[tokio::test]
+async fn connection_events_report_remote_close_error() {
+    use fe2o3_amqp::connection::ConnectionEvent;
+    use futures_util::StreamExt;
+
+    let (mut connection, peer) = remote_closes_connection_with_error().await;
+    let events = connection.events();
+    peer.await.unwrap();
+
+    let events: Vec<ConnectionEvent> =
+        tokio::time::timeout(Duration::from_secs(5), events.collect())
+            .await
+            .unwrap();
+    match &events[..] {
+        [ConnectionEvent::Opened, ConnectionEvent::Closing, ConnectionEvent::ClosedWithError(error)] => {
+            assert_resource_limit_exceeded(error)
+        }
+        events => panic!("Unexpected events {:?}", events),
+    }
+}
+
+#[tokio::test]
+async fn set_idle_timeout_changes_local_idle_timeout_of_open_connection() {
+    // Neither side advertises an idle-time-out, so the peer never sends empty frames
+    let (mut connection, _peer, _links) = connect().await;
+
+    connection
+        .set_idle_timeout(Duration::from_millis(50))
+        .await
+        .unwrap();
+    connection.set_idle_timeout(Duration::ZERO).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!connection.is_closed());
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    connection
+        .set_idle_timeout(Duration::from_millis(50))
+        .await
+        .unwrap();
+    let _ = tokio::time::timeout(Duration::from_secs(5), connection.on_close())
+        .await
+        .expect("Expecting the connection to stop after the idle timeout");
+    assert!(connection.is_closed());
+    assert!(connection
+        .set_idle_timeout(Duration::from_millis(50))
+        .await
+        .is_err());
+    let _ = session.end().await;
+}
+
+#[tokio::test]
+async fn continuous_sends_keep_connection_alive_past_idle_timeout() {
+    // Neither side advertises an idle-time-out, so the peer never sends empty frames, and
+    // pre-settled transfers are not answered by the remote receiver
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::builder()
+        .name("sender")
+        .target("q1")
+        .sender_settle_mode(SenderSettleMode::Settled)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    drive(links.recv().await.unwrap());
+
+    let idle_timeout = Duration::from_millis(100);
+    connection.set_idle_timeout(idle_timeout).await.unwrap();
+    for _ in 0..20 {
+        sender.send("keep alive").await.unwrap();
+        tokio::time::sleep(idle_timeout / 5).await;
+    }
+    assert!(!connection.is_closed());
+
+    // The idle timeout elapses once the sender stops
+    let _ = tokio::time::timeout(Duration::from_secs(5), connection.on_close())
+        .await
+        .expect("Expecting the connection to stop after the idle timeout");
+}
+
+#[tokio::test]
+async fn reconnecting_connection_reopens_after_remote_close() {
+    use fe2o3_amqp::connection::{Reconnected, ReconnectingConnection};
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    let attempts = Arc::new(AtomicU32::new(0));
+    let connect = {
+        let attempts = attempts.clone();
+        move || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                // The second and third attempts fail as if the remote peer was restarting
+                if attempt == 2 || attempt == 3 {
+                    return Err(OpenError::Io(std::io::Error::new(
+                        std::io::ErrorKind::ConnectionRefused,
+                        format!("attempt {}", attempt),
+                    )));
+                }
+                let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+                if attempt == 1 {
+                    // The first connection is closed by the remote peer right away
+                    tokio::spawn(async move {
+                        let mut connection = ConnectionAcceptor::new("in-process-peer")
+                            .accept(peer_io)
+                            .await
+                            .unwrap();
+                        connection.close().await.unwrap();
+                    });
+                } else {
+                    spawn_peer(peer_io);
+                }
+                Connection::builder()
+                    .container_id("in-process-client")
+                    .open_with_stream(client_io)
+                    .await
+            }
+        }
+    };
+
+    let backoff = fe2o3_amqp::connection::Backoff {
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+        multiplier: 2,
+        max_attempts: Some(5),
+    };
+    let mut connection = ReconnectingConnection::open(connect, backoff)
+        .await
+        .unwrap();
+    let mut events = connection.subscribe();
+
+    let reconnected = connection.on_disconnect().await.unwrap();
+    let expected = Reconnected {
+        attempts: 3,
+        reconnections: 1,
+    };
+    assert_eq!(reconnected, expected);
+    assert_eq!(events.recv().await.unwrap(), expected);
+    assert_eq!(connection.reconnections(), 1);
+
+    // Sessions are re-established on the new connection
+    let mut session = Session::begin(connection.connection_mut()).await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn reconnecting_connection_returns_last_error_after_max_attempts() {
+    use fe2o3_amqp::connection::ReconnectingConnection;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    let attempts = Arc::new(AtomicU32::new(0));
+    let connect = {
+        let attempts = attempts.clone();
+        move || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                Err(OpenError::Io(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionRefused,
+                    format!("attempt {}", attempt),
+                )))
+            }
+        }
+    };
+
+    let backoff = fe2o3_amqp::connection::Backoff {
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+        multiplier: 2,
+        max_attempts: Some(3),
+    };
+    match ReconnectingConnection::open(connect, backoff).await {
+        Err(OpenError::Io(error)) => assert_eq!(error.to_string(), "attempt 3"),
+        result => panic!(
+            "Expecting the error of the last attempt, found {:?}",
+            result
+        ),
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+/// Exchanges the protocol header and the Open frames with the client without the acceptor, so
+/// that the test can observe and send raw frames
+async fn open_raw_peer(
+    mut peer_io: tokio::io::DuplexStream,
+    idle_time_out: Option<u32>,
+) -> (
+    fe2o3_amqp::transport::Transport<tokio::io::DuplexStream, fe2o3_amqp::frames::amqp::Frame>,
+    fe2o3_amqp::types::performatives::Open,
+) {
+    use fe2o3_amqp::{
+        frames::amqp::{Frame, FrameBody},
+        transport::Transport,
+        types::performatives::Open,
+    };
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut header = [0u8; 8];
+    peer_io.read_exact(&mut header).await.unwrap();
+    peer_io.write_all(&header).await.unwrap();
+
+    let mut transport = Transport::<_, Frame>::bind(peer_io, 512, None);
+    match transport.next().await.unwrap().unwrap().into_body() {
+        FrameBody::Open(_) => {}
+        body => panic!("Expecting an Open frame, found {:?}", body),
+    }
+    let open = Open {
+        container_id: "in-process-peer".to_string(),
+        hostname: None,
+        max_frame_size: Default::default(),
+        channel_max: Default::default(),
+        idle_time_out,
+        outgoing_locales: None,
+        incoming_locales: None,
+        offered_capabilities: None,
+        desired_capabilities: None,
+        properties: None,
+    };
+    transport
+        .send(Frame::new(0u16, FrameBody::Open(open.clone())))
+        .await
+        .unwrap();
+    (transport, open)
+}
+
+#[tokio::test]
+async fn duplicate_open_closes_connection_with_framing_error() {
+    use fe2o3_amqp::{
+        frames::amqp::{Frame, FrameBody},
+        types::{definitions::ConnectionError, performatives::Close},
+    };
+    use futures_util::{SinkExt, StreamExt};
+
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+
+    // A peer that sends the Open frame twice
+    let peer = tokio::spawn(async move {
+        let (mut transport, open) = open_raw_peer(peer_io, None).await;
+        transport
+            .send(Frame::new(0u16, FrameBody::Open(open)))
+            .await
+            .unwrap();
+
+        loop {
+            match transport.next().await.unwrap().unwrap().into_body() {
+                FrameBody::Close(close) => break close,
+                FrameBody::Empty => {}
+                body => panic!("Expecting a Close frame, found {:?}", body),
+            }
+        }
+    });
+
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), connection.on_close())
+        .await
+        .expect("connection engine did not stop");
+    assert!(matches!(
+        result,
+        Err(fe2o3_amqp::connection::Error::TransportError(
+            fe2o3_amqp::transport::Error::FramingError
+        ))
+    ));
+
+    let close: Close = tokio::time::timeout(Duration::from_secs(5), peer)
+        .await
+        .expect("close frame is not sent to the peer")
+        .unwrap();
+    assert_eq!(
+        close.error.unwrap().condition,
+        definitions::ErrorCondition::ConnectionError(ConnectionError::FramingError)
+    );
+}
+
+#[tokio::test]
+async fn heartbeats_are_sent_at_half_the_remote_idle_timeout() {
+    use fe2o3_amqp::frames::amqp::FrameBody;
+    use futures_util::StreamExt;
+    use tokio::time::Instant;
+
+    let idle_time_out = Duration::from_millis(400);
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let (mut transport, _) =
+            open_raw_peer(peer_io, Some(idle_time_out.as_millis() as u32)).await;
+
+        // The first heartbeat is sent right after the connection is opened
+        let mut empties = Vec::new();
+        while empties.len() < 3 {
+            if let FrameBody::Empty = transport.next().await.unwrap().unwrap().into_body() {
+                empties.push(Instant::now());
+            }
+        }
+        empties[2] - empties[1]
+    });
+
+    let connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+
+    let period = tokio::time::timeout(Duration::from_secs(5), peer)
+        .await
+        .expect("heartbeats are not sent")
+        .unwrap();
+    assert!(
+        period <= idle_time_out * 3 / 4,
+        "heartbeat period is {:?}",
+        period
+    );
+    drop(connection);
+}
+
+#[tokio::test]
+async fn local_idle_timeout_elapses_while_sending_heartbeats_to_silent_peer() {
+    use fe2o3_amqp::frames::amqp::FrameBody;
+    use futures_util::StreamExt;
+
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+
+    // A peer that advertises an idle-time-out so that heartbeats are sent to it, but never
+    // sends anything after the Open frame
+    let peer = tokio::spawn(async move {
+        let (mut transport, _) = open_raw_peer(peer_io, Some(100)).await;
+        let mut empties = 0;
+        while let Some(Ok(frame)) = transport.next().await {
+            if let FrameBody::Empty = frame.into_body() {
+                empties += 1;
+            }
+        }
+        empties
+    });
+
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .idle_time_out(300u32)
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), connection.on_close())
+        .await
+        .expect("Expecting the connection to stop after the idle timeout");
+    assert!(matches!(
+        result,
+        Err(fe2o3_amqp::connection::Error::TransportError(
+            fe2o3_amqp::transport::Error::IdleTimeoutElapsed
+        ))
+    ));
+
+    let empties = tokio::time::timeout(Duration::from_secs(5), peer)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(empties > 1, "only {} heartbeats are sent", empties);
+}
+
+#[tokio::test]
+async fn open_times_out_if_remote_never_sends_open() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (client_io, mut peer_io) = tokio::io::duplex(64 * 1024);
+
+    // A peer that completes the protocol header exchange but never sends the Open frame
+    let peer = tokio::spawn(async move {
+        let mut header = [0u8; 8];
+        peer_io.read_exact(&mut header).await.unwrap();
+        peer_io.write_all(&header).await.unwrap();
+        let mut buf = Vec::new();
+        let _ = peer_io.read_to_end(&mut buf).await;
+    });
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        Connection::builder()
+            .container_id("in-process-client")
+            .open_timeout(Duration::from_millis(100))
+            .open_with_stream(client_io),
+    )
+    .await
+    .expect("open is not bounded by the open timeout");
+    assert!(matches!(result, Err(OpenError::OpenTimeout)));
+
+    // The stream is dropped with the timed out handshake
+    peer.await.unwrap();
+}
+
+#[tokio::test]
+async fn open_with_url_times_out_if_remote_never_responds() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("amqp://{}", listener.local_addr().unwrap());
+
+    // A peer that accepts the socket but never sends anything
+    let peer = tokio::spawn(async move {
+        let (_stream, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    });
+
+    let result = Connection::builder()
+        .container_id("in-process-client")
+        .open_timeout(Duration::from_millis(100))
+        .open(&url[..])
+        .await;
+    assert!(matches!(result, Err(OpenError::OpenTimeout)));
+    peer.abort();
+}
+
+#[tokio::test]
+async fn open_timeout_does_not_affect_successful_open() {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let mut connection = ConnectionAcceptor::new("in-process-peer")
+            .accept(peer_io)
+            .await
+            .unwrap();
+        let _ = connection.on_close().await;
+    });
+
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_timeout(Duration::from_secs(5))
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+}
+
+/// Accepts any ANONYMOUS login and records the hostname of the SASL init frame
+#[derive(Debug, Clone, Default)]
+struct SaslHostnameRecorder {
+    hostname: std::sync::Arc<std::sync::Mutex<Option<String>>>,
+}
+
+impl SaslAcceptor for SaslHostnameRecorder {
+    fn mechanisms(&self) -> Array<Symbol> {
+        vec![Symbol::from("ANONYMOUS")].into()
+    }
+
+    fn on_init(&mut self, init: SaslInit) -> SaslServerFrame {
+        *self.hostname.lock().unwrap() = init.hostname;
+        SaslServerFrame::Outcome(SaslOutcome {
+            code: SaslCode::Ok,
+            additional_data: None,
+        })
+    }
+
+    fn on_response(&mut self, _response: SaslResponse) -> SaslServerFrame {
+        SaslServerFrame::Outcome(SaslOutcome {
+            code: SaslCode::Auth,
+            additional_data: None,
+        })
+    }
+}
+
+/// Opens a connection with SASL ANONYMOUS and returns the hostname of the SASL init frame
+async fn open_and_record_sasl_hostname(
+    hostname: Option<&'static str>,
+    sasl_hostname: Option<&'static str>,
+) -> Option<String> {
+    let recorder = SaslHostnameRecorder::default();
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = {
+        let recorder = recorder.clone();
+        tokio::spawn(async move {
+            let mut connection = ConnectionAcceptor::builder()
+                .container_id("in-process-peer")
+                .sasl_acceptor(recorder)
+                .build()
+                .accept(peer_io)
+                .await
+                .unwrap();
+            let _ = connection.on_close().await;
+        })
+    };
+
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .hostname(hostname)
+        .sasl_hostname(sasl_hostname)
+        .sasl_profile(SaslProfile::Anonymous)
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+
+    let hostname = recorder.hostname.lock().unwrap().clone();
+    hostname
+}
+
+#[tokio::test]
+async fn sasl_init_carries_sasl_hostname() {
+    let hostname = open_and_record_sasl_hostname(Some("example.com"), Some("vhost-1")).await;
+    assert_eq!(hostname.as_deref(), Some("vhost-1"));
+}
+
+#[tokio::test]
+async fn sasl_init_carries_hostname_if_sasl_hostname_is_not_set() {
+    let hostname = open_and_record_sasl_hostname(Some("example.com"), None).await;
+    assert_eq!(hostname.as_deref(), Some("example.com"));
+}
@@ -1,4 +1,5 @@
-//! Tests sending and receiving small messages with active mq artemis
+//! Tests sending and receiving small messages with active mq artemis and rabbitmq, and tests of
+//! links against an in-process peer
 
 macro_rules! cfg_not_wasm32 {
     ($($item:item)*) => {
@@ -129,3 +130,605 @@ cfg_not_wasm32! {
         connection.close().await.unwrap();
     }
 }
+
+#[cfg(all(feature = "acceptor", not(target_arch = "wasm32")))]
+mod in_process {
+    //! Link tests against an in-process peer built with the acceptor over an in-memory duplex
+    //! stream
+
+    use crate::common::{
+        application_error, assert_application_error, connect, connect_with, drive, drive_receiver,
+        drive_sender, next_remote_receiver, next_remote_sender,
+    };
+    use fe2o3_amqp::{
+        acceptor::{
+            link::{LinkAcceptor, LinkEndpoint},
+            session::SessionAcceptor,
+            ConnectionAcceptor,
+        },
+        connection::ConnectionHandle,
+        link::{LinkStateError, ReceiverAttachError, RecvError, SenderAttachError},
+        request_reply::{RequestReplyClient, RequestReplyError},
+        types::{
+            definitions::Fields,
+            messaging::{AmqpValue, Message, MessageId, Properties, Source, Target},
+            primitives::{Symbol, Value},
+        },
+        Delivery, Receiver, Sender, Session,
+    };
+    use std::time::Duration;
+
+    use tokio::{sync::mpsc::UnboundedReceiver, task::JoinHandle};
+
+    #[tokio::test]
+    async fn attaching_duplicated_link_name_fails_before_sending_attach() {
+        let (mut connection, _peer, mut links) = connect().await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let sender = Sender::attach(&mut session, "dup-link", "q1")
+            .await
+            .unwrap();
+        assert_eq!(drive(links.recv().await.unwrap()), "dup-link");
+
+        let err = Sender::attach(&mut session, "dup-link", "q2")
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, SenderAttachError::DuplicatedLinkName(name) if name == "dup-link"));
+        assert!(err.to_string().contains("dup-link"));
+
+        let receiver = Receiver::attach(&mut session, "dup-receiver", "q1")
+            .await
+            .unwrap();
+        assert_eq!(drive(links.recv().await.unwrap()), "dup-receiver");
+
+        let err = Receiver::attach(&mut session, "dup-receiver", "q2")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, ReceiverAttachError::DuplicatedLinkName(name) if name == "dup-receiver")
+        );
+        assert!(err.to_string().contains("dup-receiver"));
+
+        // The session is still usable after the local errors. The peer receives the Attach of
+        // this link next, so it has not seen a second Attach of either duplicated link
+        let other = Sender::attach(&mut session, "other-link", "q2")
+            .await
+            .unwrap();
+        assert_eq!(drive(links.recv().await.unwrap()), "other-link");
+
+        sender.close().await.unwrap();
+        receiver.close().await.unwrap();
+        other.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn links_attached_without_name_get_distinct_generated_names() {
+        let (mut connection, _peer, mut links) = connect().await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let first = Sender::attach(&mut session, "", "q1").await.unwrap();
+        assert_eq!(drive(links.recv().await.unwrap()), first.name());
+        let second = Sender::attach(&mut session, "", "q1").await.unwrap();
+        assert_eq!(drive(links.recv().await.unwrap()), second.name());
+        let receiver = Receiver::attach(&mut session, "", "q1").await.unwrap();
+        assert_eq!(drive(links.recv().await.unwrap()), receiver.name());
+
+        assert!(!first.name().is_empty());
+        assert_ne!(first.name(), second.name());
+        assert_ne!(first.name(), receiver.name());
+        assert_ne!(second.name(), receiver.name());
+
+        first.close().await.unwrap();
+        second.close().await.unwrap();
+        receiver.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn link_name_and_handles_are_exposed() {
+        use fe2o3_amqp::types::definitions::Handle;
+
+        let (mut connection, _peer, mut links) = connect().await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let sender = Sender::attach(&mut session, "sender-link", "q1")
+            .await
+            .unwrap();
+        let remote_receiver = next_remote_receiver(&mut links).await;
+        let receiver = Receiver::attach(&mut session, "receiver-link", "q1")
+            .await
+            .unwrap();
+        let remote_sender = next_remote_sender(&mut links).await;
+
+        assert_eq!(sender.name(), "sender-link");
+        assert_eq!(receiver.name(), "receiver-link");
+        assert_eq!(sender.output_handle(), Some(Handle(0)));
+        assert_eq!(receiver.output_handle(), Some(Handle(1)));
+
+        // The input handle of a link is the output handle chosen by the remote peer
+        assert_eq!(sender.input_handle(), remote_receiver.output_handle());
+        assert_eq!(remote_receiver.input_handle(), sender.output_handle());
+        assert_eq!(receiver.input_handle(), remote_sender.output_handle());
+        assert_eq!(remote_sender.input_handle(), receiver.output_handle());
+
+        tokio::spawn(drive_receiver(remote_receiver));
+        tokio::spawn(drive_sender(remote_sender));
+        sender.close().await.unwrap();
+        receiver.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    /// Connects to a peer whose link acceptor creates a node at `address` for every dynamic source
+    /// or target
+    async fn connect_to_dynamic_node_creator(
+        address: &'static str,
+    ) -> (
+        ConnectionHandle<()>,
+        JoinHandle<()>,
+        UnboundedReceiver<LinkEndpoint>,
+    ) {
+        let link_acceptor = LinkAcceptor::builder()
+            .on_dynamic_source(move |mut source| {
+                source.address = Some(address.to_string());
+                Some(source)
+            })
+            .on_dynamic_target(move |mut target| {
+                target.address = Some(address.to_string());
+                Some(target)
+            })
+            .build();
+        connect_with(
+            ConnectionAcceptor::new("in-process-peer"),
+            SessionAcceptor::new(),
+            link_acceptor,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn receiver_attach_dynamic_returns_address_of_node_created_by_remote() {
+        let (mut connection, _peer, mut links) =
+            connect_to_dynamic_node_creator("dynamic-q1").await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let mut properties = Fields::new();
+        properties.insert(
+            Symbol::from("lifetime-policy"),
+            Value::from("delete-on-close"),
+        );
+        let (receiver, address) = Receiver::attach_dynamic(&mut session, "receiver", properties)
+            .await
+            .unwrap();
+        assert_eq!(address, "dynamic-q1");
+
+        let source = receiver.source().as_ref().unwrap();
+        assert!(source.dynamic);
+        assert_eq!(source.address.as_deref(), Some("dynamic-q1"));
+
+        // The remote has seen the dynamic node properties
+        let remote = next_remote_sender(&mut links).await;
+        let remote_source = remote.source().as_ref().unwrap();
+        assert!(remote_source
+            .dynamic_node_properties
+            .as_ref()
+            .unwrap()
+            .contains_key(&Symbol::from("lifetime-policy")));
+        tokio::spawn(drive_sender(remote));
+
+        receiver.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn receiver_attach_dynamic_fails_if_remote_does_not_create_node() {
+        // The default link acceptor rejects dynamic sources
+        let (mut connection, _peer, mut links) = connect().await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+        tokio::spawn(async move {
+            while let Some(link) = links.recv().await {
+                drive(link);
+            }
+        });
+
+        let err = Receiver::attach_dynamic(&mut session, "receiver", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ReceiverAttachError::IncomingSourceIsNone));
+
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dynamic_source_with_address_fails_to_attach() {
+        let (mut connection, _peer, mut links) = connect().await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let source = Source::builder().address("q1").dynamic(true).build();
+        let err = Receiver::builder()
+            .name("receiver")
+            .source(source)
+            .attach(&mut session)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ReceiverAttachError::SourceAddressIsSomeWhenDynamicIsTrue
+        ));
+
+        // The peer must not have seen an Attach
+        assert!(links.try_recv().is_err());
+
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sender_attach_dynamic_returns_address_of_node_created_by_remote() {
+        let (mut connection, _peer, mut links) =
+            connect_to_dynamic_node_creator("dynamic-q2").await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let mut properties = Fields::new();
+        properties.insert(
+            Symbol::from("lifetime-policy"),
+            Value::from("delete-on-close"),
+        );
+        let (sender, address) = Sender::attach_dynamic(&mut session, "sender", properties)
+            .await
+            .unwrap();
+        assert_eq!(address, "dynamic-q2");
+
+        let target = sender.target().as_ref().unwrap();
+        assert!(target.dynamic);
+        assert_eq!(target.address.as_deref(), Some("dynamic-q2"));
+
+        // The remote has seen the dynamic node properties
+        let remote = next_remote_receiver(&mut links).await;
+        let remote_target = remote.target().as_ref().unwrap();
+        assert!(remote_target
+            .dynamic_node_properties
+            .as_ref()
+            .unwrap()
+            .contains_key(&Symbol::from("lifetime-policy")));
+        tokio::spawn(drive_receiver(remote));
+
+        sender.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sender_attach_dynamic_fails_if_remote_does_not_create_node() {
+        // The default link acceptor rejects dynamic targets
+        let (mut connection, _peer, mut links) = connect().await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+        tokio::spawn(async move {
+            while let Some(link) = links.recv().await {
+                drive(link);
+            }
+        });
+
+        let err = Sender::attach_dynamic(&mut session, "sender", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SenderAttachError::IncomingTargetIsNone));
+
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dynamic_target_with_address_fails_to_attach() {
+        let (mut connection, _peer, mut links) = connect().await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let target = Target::builder().address("q1").dynamic(true).build();
+        let err = Sender::builder()
+            .name("sender")
+            .target(target)
+            .attach(&mut session)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SenderAttachError::TargetAddressIsSomeWhenDynamicIsTrue
+        ));
+
+        // The peer must not have seen an Attach
+        assert!(links.try_recv().is_err());
+
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    /// Accepts the links of a `RequestReplyClient` on the remote peer, returning the remote receiver
+    /// of requests and the remote sender to the reply node
+    async fn accept_request_reply_links(
+        links: &mut UnboundedReceiver<LinkEndpoint>,
+    ) -> (Receiver, Sender) {
+        let service = next_remote_receiver(links).await;
+        let replier = next_remote_sender(links).await;
+        (service, replier)
+    }
+
+    /// Receives a request on the remote peer and returns its `message-id` and `reply-to`
+    async fn recv_request(service: &mut Receiver) -> (MessageId, String) {
+        let delivery = service.recv::<String>().await.unwrap();
+        service.accept(&delivery).await.unwrap();
+        let properties = delivery.message().properties.clone().unwrap();
+        (properties.message_id.unwrap(), properties.reply_to.unwrap())
+    }
+
+    fn response(correlation_id: MessageId, body: &str) -> Message<AmqpValue<String>> {
+        Message::builder()
+            .properties(Properties::builder().correlation_id(correlation_id).build())
+            .value(body.to_string())
+            .build()
+    }
+
+    #[tokio::test]
+    async fn request_reply_client_returns_correlated_response() {
+        let (mut connection, _peer, mut links) = connect_to_dynamic_node_creator("reply-q1").await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let mut client = RequestReplyClient::attach(&mut session, "client", "service")
+            .await
+            .unwrap();
+        assert_eq!(client.reply_to(), "reply-q1");
+        let (mut service, mut replier) = accept_request_reply_links(&mut links).await;
+
+        let remote = tokio::spawn(async move {
+            let (message_id, reply_to) = recv_request(&mut service).await;
+            assert_eq!(reply_to, "reply-q1");
+            // A response that does not belong to the pending request is discarded
+            replier
+                .send(response(MessageId::from(u64::MAX), "stale"))
+                .await
+                .unwrap();
+            replier.send(response(message_id, "pong")).await.unwrap();
+            (service, replier)
+        });
+
+        let delivery: Delivery<String> = client.request(Message::from("ping")).await.unwrap();
+        assert_eq!(delivery.body(), "pong");
+
+        let (service, replier) = remote.await.unwrap();
+        tokio::spawn(drive_receiver(service));
+        tokio::spawn(drive_sender(replier));
+        client.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn request_reply_client_times_out_and_discards_late_response() {
+        let (mut connection, _peer, mut links) = connect_to_dynamic_node_creator("reply-q1").await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let mut client = RequestReplyClient::attach(&mut session, "client", "service")
+            .await
+            .unwrap();
+        client.set_timeout(Duration::from_millis(100));
+        let (mut service, mut replier) = accept_request_reply_links(&mut links).await;
+
+        // The remote neither settles nor responds to the first request in time
+        let err = client
+            .request::<_, String>(Message::from("first"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestReplyError::Timeout));
+        let (first_id, _) = recv_request(&mut service).await;
+
+        let remote = tokio::spawn(async move {
+            let (second_id, _) = recv_request(&mut service).await;
+            assert_ne!(first_id, second_id);
+            replier.send(response(first_id, "late")).await.unwrap();
+            replier.send(response(second_id, "second")).await.unwrap();
+            (service, replier)
+        });
+
+        let delivery: Delivery<String> = client.request(Message::from("second")).await.unwrap();
+        assert_eq!(delivery.body(), "second");
+
+        let (service, replier) = remote.await.unwrap();
+        tokio::spawn(drive_receiver(service));
+        tokio::spawn(drive_sender(replier));
+        client.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn request_reply_client_settles_late_response_of_another_type() {
+        use fe2o3_amqp::types::messaging::Outcome;
+
+        let (mut connection, _peer, mut links) = connect_to_dynamic_node_creator("reply-q1").await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let mut client = RequestReplyClient::attach(&mut session, "client", "service")
+            .await
+            .unwrap();
+        client.set_timeout(Duration::from_millis(100));
+        let (mut service, mut replier) = accept_request_reply_links(&mut links).await;
+
+        let err = client
+            .request::<_, i32>(Message::from("first"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestReplyError::Timeout));
+        let (first_id, _) = recv_request(&mut service).await;
+
+        let remote = tokio::spawn(async move {
+            let (second_id, _) = recv_request(&mut service).await;
+            // The late response cannot be decoded as the response type of the second request,
+            // and is settled without being returned
+            let late = replier.send(response(first_id, "late")).await.unwrap();
+            assert!(matches!(late, Outcome::Accepted(_)));
+            let late = Message::builder()
+                .properties(Properties::builder().correlation_id(second_id).build())
+                .value(2)
+                .build();
+            let outcome = replier.send(late).await.unwrap();
+            (service, replier, outcome)
+        });
+
+        client.set_timeout(Duration::from_secs(5));
+        let delivery: Delivery<i32> = client.request(Message::from("second")).await.unwrap();
+        assert_eq!(*delivery.body(), 2);
+
+        let (service, replier, outcome) = remote.await.unwrap();
+        assert!(matches!(outcome, Outcome::Accepted(_)));
+        tokio::spawn(drive_receiver(service));
+        tokio::spawn(drive_sender(replier));
+        client.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn request_reply_client_rejects_response_of_another_type() {
+        use fe2o3_amqp::types::messaging::Outcome;
+
+        let (mut connection, _peer, mut links) = connect_to_dynamic_node_creator("reply-q1").await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let mut client = RequestReplyClient::attach(&mut session, "client", "service")
+            .await
+            .unwrap();
+        let (mut service, mut replier) = accept_request_reply_links(&mut links).await;
+
+        let remote = tokio::spawn(async move {
+            let (message_id, _) = recv_request(&mut service).await;
+            let outcome = replier.send(response(message_id, "pong")).await.unwrap();
+            (service, replier, outcome)
+        });
+
+        let err = client
+            .request::<_, i32>(Message::from("ping"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RequestReplyError::Recv(RecvError::MessageDecode(_))
+        ));
+
+        let (service, replier, outcome) = remote.await.unwrap();
+        assert!(matches!(outcome, Outcome::Rejected(_)));
+        tokio::spawn(drive_receiver(service));
+        tokio::spawn(drive_sender(replier));
+        client.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn detach_with_error_sends_error_to_remote() {
+        let (mut connection, _peer, mut links) = connect().await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let receiver = Receiver::attach(&mut session, "receiver", "q1")
+            .await
+            .unwrap();
+        let mut remote = next_remote_sender(&mut links).await;
+        let remote = tokio::spawn(async move {
+            let error = remote.on_detach().await;
+            let _ = remote.detach().await;
+            error
+        });
+
+        assert!(receiver
+            .detach_with_error(application_error())
+            .await
+            .is_ok());
+        match remote.await.unwrap() {
+            fe2o3_amqp::link::DetachError::RemoteDetachedWithError(error) => {
+                assert_application_error(&error)
+            }
+            error => panic!("Expecting remote detach with an error, found {:?}", error),
+        }
+
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn close_link_with_error_sends_error_to_remote() {
+        let (mut connection, _peer, mut links) = connect().await;
+        let mut session = Session::begin(&mut connection).await.unwrap();
+
+        let sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+        let mut remote = next_remote_receiver(&mut links).await;
+        let remote = tokio::spawn(async move {
+            let result = remote.recv::<Value>().await;
+            let _ = remote.close().await;
+            result
+        });
+
+        sender.close_with_error(application_error()).await.unwrap();
+        match remote.await.unwrap() {
+            Err(RecvError::LinkStateError(LinkStateError::RemoteClosedWithError(error))) => {
+                assert_application_error(&error)
+            }
+            result => panic!("Expecting remote close with an error, found {:?}", result),
+        }
+
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn blocking_wrappers_send_and_receive_without_async_context() {
+        use crate::common::spawn_peer;
+        use fe2o3_amqp::{blocking, Connection};
+
+        // The peer runs on its own runtime, while the client only makes blocking calls
+        let peer_runtime = tokio::runtime::Runtime::new().unwrap();
+        let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+        let (_peer, mut links) = {
+            let _guard = peer_runtime.enter();
+            spawn_peer(peer_io)
+        };
+
+        let connection = blocking::block_on(
+            Connection::builder()
+                .container_id("in-process-client")
+                .open_with_stream(client_io),
+        )
+        .unwrap();
+        let mut connection = blocking::Connection::from(connection);
+        let mut session = blocking::Session::begin(&mut connection).unwrap();
+
+        let mut sender = blocking::Sender::attach(&mut session, "sender", "q1").unwrap();
+        {
+            let _guard = peer_runtime.enter();
+            drive(links.blocking_recv().unwrap());
+        }
+        let outcome = sender.send("hello").unwrap();
+        assert!(outcome.is_accepted());
+
+        let mut receiver = blocking::Receiver::attach(&mut session, "receiver", "q2").unwrap();
+        let mut remote_sender = match links.blocking_recv().unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a remote sender"),
+        };
+        peer_runtime.spawn(async move {
+            remote_sender.send("world").await.unwrap();
+            drive_sender(remote_sender).await;
+        });
+        let delivery = receiver.recv::<String>().unwrap();
+        assert_eq!(delivery.body(), "world");
+        receiver.accept(&delivery).unwrap();
+
+        sender.close().unwrap();
+        receiver.close().unwrap();
+        session.end().unwrap();
+        connection.close().unwrap();
+    }
+}
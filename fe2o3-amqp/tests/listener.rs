@@ -1 +1,151 @@
+//! Tests of the listener side of connections against an in-process peer built with the acceptor
+//! over an in-memory duplex stream
 
+#![cfg(all(feature = "acceptor", not(target_arch = "wasm32")))]
+
+use fe2o3_amqp::{
+    acceptor::{ConnectionAcceptor, ListenerConnectionHandle, SaslCallbackAcceptor},
+    connection::{ConnectionHandle, OpenError},
+    sasl_profile::SaslProfile,
+    types::sasl::SaslCode,
+    Connection,
+};
+
+#[tokio::test]
+async fn incoming_connections_are_accepted_from_tcp_listener() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut incoming = ConnectionAcceptor::new("in-process-peer").incoming(listener);
+    let url = format!("amqp://{}", incoming.local_addr().unwrap());
+
+    let clients = tokio::spawn(async move {
+        for id in ["client-1", "client-2"] {
+            let mut connection = Connection::open(id, &url[..]).await.unwrap();
+            connection.close().await.unwrap();
+        }
+    });
+
+    for _ in 0..2 {
+        let mut connection = incoming.next_connection().await.unwrap().unwrap();
+        let _ = connection.on_close().await;
+    }
+    clients.await.unwrap();
+}
+
+#[tokio::test]
+async fn failed_handshake_does_not_stop_incoming_connections() {
+    use tokio::io::AsyncWriteExt;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut incoming = ConnectionAcceptor::new("in-process-peer").incoming(listener);
+    let addr = incoming.local_addr().unwrap();
+
+    // A peer that never sends a protocol header must not hold back the others
+    let _stalled = tokio::net::TcpStream::connect(addr).await.unwrap();
+
+    let mut invalid = tokio::net::TcpStream::connect(addr).await.unwrap();
+    invalid.write_all(b"HTTP/1.1").await.unwrap();
+    let result = incoming.next_connection().await.unwrap();
+    assert!(matches!(result, Err(OpenError::ProtocolHeaderMismatch(_))));
+
+    let url = format!("amqp://{}", addr);
+    let client = tokio::spawn(async move {
+        let mut connection = Connection::open("in-process-client", &url[..])
+            .await
+            .unwrap();
+        connection.close().await.unwrap();
+    });
+    let mut connection = incoming.next_connection().await.unwrap().unwrap();
+    let _ = connection.on_close().await;
+    client.await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn stalled_handshake_times_out() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut incoming = ConnectionAcceptor::new("in-process-peer").incoming(listener);
+    let addr = incoming.local_addr().unwrap();
+
+    // A peer that never sends a protocol header
+    let _stalled = tokio::net::TcpStream::connect(addr).await.unwrap();
+
+    let result = incoming.next_connection().await.unwrap();
+    assert!(matches!(result, Err(OpenError::OpenTimeout)));
+}
+
+async fn open_with_sasl_profile(
+    sasl_profile: SaslProfile,
+) -> (
+    Result<ConnectionHandle<()>, OpenError>,
+    Result<ListenerConnectionHandle, OpenError>,
+) {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let sasl_acceptor = SaslCallbackAcceptor::builder()
+            .plain(|username, password| username == "guest" && password == "secret")
+            .anonymous(|| false)
+            .build();
+        ConnectionAcceptor::builder()
+            .container_id("in-process-peer")
+            .sasl_acceptor(sasl_acceptor)
+            .build()
+            .accept(peer_io)
+            .await
+    });
+
+    let client = Connection::builder()
+        .container_id("in-process-client")
+        .sasl_profile(sasl_profile)
+        .open_with_stream(client_io)
+        .await;
+    (client, peer.await.unwrap())
+}
+
+#[tokio::test]
+async fn sasl_callback_acceptor_accepts_valid_plain_login() {
+    let profile = SaslProfile::Plain {
+        username: String::from("guest"),
+        password: String::from("secret"),
+    };
+    let (client, peer) = open_with_sasl_profile(profile).await;
+    let mut connection = client.unwrap();
+    let mut peer_connection = peer.unwrap();
+
+    connection.close().await.unwrap();
+    let _ = peer_connection.on_close().await;
+}
+
+#[tokio::test]
+async fn sasl_callback_acceptor_rejects_invalid_plain_login() {
+    let profile = SaslProfile::Plain {
+        username: String::from("guest"),
+        password: String::from("wrong"),
+    };
+    let (client, peer) = open_with_sasl_profile(profile).await;
+    assert!(matches!(
+        client,
+        Err(OpenError::SaslError {
+            code: SaslCode::Auth,
+            ..
+        })
+    ));
+    assert!(matches!(
+        peer,
+        Err(OpenError::SaslError {
+            code: SaslCode::Auth,
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn sasl_callback_acceptor_rejects_disallowed_anonymous_login() {
+    let (client, peer) = open_with_sasl_profile(SaslProfile::Anonymous).await;
+    assert!(matches!(
+        client,
+        Err(OpenError::SaslError {
+            code: SaslCode::Auth,
+            ..
+        })
+    ));
+    assert!(peer.is_err());
+}
@@ -0,0 +1,1090 @@
+//! Tests of receiver links against an in-process peer built with the acceptor over an in-memory
+//! duplex stream
+
+#![cfg(all(feature = "acceptor", not(target_arch = "wasm32")))]
+
+mod common;
+
+use common::{
+    connect, connect_with_injection, drive, drive_sender, flow_frame, next_remote_sender,
+    transfer_frame, SPLIT_MESSAGE,
+};
+use fe2o3_amqp::{
+    link::{receiver::CreditMode, IllegalLinkStateError, ReceiverAttachError, RecvError},
+    types::{
+        definitions::{self, ReceiverSettleMode, SenderSettleMode},
+        messaging::{
+            DeliveryAnnotations, Footer, Message, MessageAnnotations, Modified, Outcome, Properties,
+        },
+        primitives::{Symbol, Timestamp, Value},
+    },
+    Receiver, Session,
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn modified_disposition_carries_flags_and_annotations() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let mut annotations = definitions::Fields::new();
+    annotations.insert(Symbol::from("x-reason"), Value::from("redeliver elsewhere"));
+    let modified = Modified {
+        delivery_failed: Some(true),
+        undeliverable_here: Some(true),
+        message_annotations: Some(annotations.clone()),
+    };
+
+    let peer = tokio::spawn(async move {
+        let outcome = remote.send("hello").await.unwrap();
+        drive_sender(remote).await;
+        outcome
+    });
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    receiver.modify(&delivery, modified).await.unwrap();
+
+    receiver.close().await.unwrap();
+    match peer.await.unwrap() {
+        Outcome::Modified(modified) => {
+            assert_eq!(modified.delivery_failed, Some(true));
+            assert_eq!(modified.undeliverable_here, Some(true));
+            assert_eq!(modified.message_annotations, Some(annotations));
+        }
+        outcome => panic!("Expecting a modified outcome, found {:?}", outcome),
+    }
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn received_delivery_exposes_annotations_and_footer() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let delivery_annotations = DeliveryAnnotations::builder()
+        .insert("x-opt-trace-id", "trace")
+        .build();
+    let message_annotations = MessageAnnotations::builder()
+        .insert("x-opt-routing-key", "orders.created")
+        .build();
+    let footer = Footer::builder().insert("x-opt-checksum", 42u32).build();
+    let message = Message::builder()
+        .delivery_annotations(delivery_annotations.clone())
+        .message_annotations(message_annotations.clone())
+        .footer(footer.clone())
+        .value("hello")
+        .build();
+    let peer = tokio::spawn(async move {
+        remote.send(message).await.unwrap();
+        remote.send("plain").await.unwrap();
+        drive_sender(remote).await;
+    });
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.delivery_annotations(), Some(&delivery_annotations));
+    assert_eq!(delivery.message_annotations(), Some(&message_annotations));
+    assert_eq!(delivery.footer(), Some(&footer));
+    receiver.accept(&delivery).await.unwrap();
+
+    // Sections that are not sent are absent
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert!(delivery.delivery_annotations().is_none());
+    assert!(delivery.message_annotations().is_none());
+    assert!(delivery.footer().is_none());
+    receiver.accept(&delivery).await.unwrap();
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn received_delivery_exposes_delivery_tag_and_id_set_by_sender() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let first = remote.send_batchable("first").await.unwrap();
+    let second = remote.send_batchable("second").await.unwrap();
+    let tags = [first.delivery_tag().clone(), second.delivery_tag().clone()];
+    assert_ne!(tags[0], tags[1]);
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.delivery_tag(), &tags[0]);
+    let first_id = delivery.delivery_id();
+    receiver.accept(&delivery).await.unwrap();
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.delivery_tag(), &tags[1]);
+    assert_eq!(delivery.delivery_id(), first_id.wrapping_add(1));
+    receiver.accept(&delivery).await.unwrap();
+
+    assert!(first.await.unwrap().is_accepted());
+    assert!(second.await.unwrap().is_accepted());
+
+    let peer = tokio::spawn(drive_sender(remote));
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn dispositions_fail_with_illegal_session_state_once_session_has_ended() {
+    use fe2o3_amqp::link::IllegalLinkStateError;
+
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+    for body in ["first", "second", "third", "fourth"] {
+        remote.send_batchable(body).await.unwrap();
+    }
+    let mut deliveries = Vec::new();
+    for _ in 0..4 {
+        deliveries.push(receiver.recv::<String>().await.unwrap());
+    }
+
+    session.end().await.unwrap();
+
+    let result = receiver.accept(&deliveries[0]).await;
+    assert!(matches!(
+        result,
+        Err(IllegalLinkStateError::IllegalSessionState)
+    ));
+    let result = receiver.reject(&deliveries[1], None).await;
+    assert!(matches!(
+        result,
+        Err(IllegalLinkStateError::IllegalSessionState)
+    ));
+    let result = receiver.release(&deliveries[2]).await;
+    assert!(matches!(
+        result,
+        Err(IllegalLinkStateError::IllegalSessionState)
+    ));
+    let modified = Modified {
+        delivery_failed: Some(true),
+        undeliverable_here: None,
+        message_annotations: None,
+    };
+    let result = receiver.modify(&deliveries[3], modified).await;
+    assert!(matches!(
+        result,
+        Err(IllegalLinkStateError::IllegalSessionState)
+    ));
+
+    drop(remote);
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn receiver_attaches_with_source_filters() {
+    use serde_amqp::{described::Described, descriptor::Descriptor};
+
+    const SELECTOR: &str = "apache.org:selector-filter:string";
+    const NO_LOCAL: &str = "apache.org:no-local-filter:list";
+
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let selector = Described {
+        descriptor: Descriptor::Name(Symbol::from(SELECTOR)),
+        value: Value::String("color = 'red'".to_string()),
+    };
+    let no_local = Described {
+        descriptor: Descriptor::Name(Symbol::from(NO_LOCAL)),
+        value: Value::List(vec![]),
+    };
+    let receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .source_filter(SELECTOR, selector.clone())
+        .source_filter(NO_LOCAL, no_local)
+        .attach(&mut session)
+        .await
+        .unwrap();
+
+    // The filters are carried by the source of the outgoing Attach
+    let remote = next_remote_sender(&mut links).await;
+    let remote_filter = remote.source().as_ref().unwrap().filter.as_ref().unwrap();
+    assert_eq!(
+        remote_filter.get(&Symbol::from(SELECTOR)),
+        Some(&Value::Described(Box::new(selector.clone())))
+    );
+    assert!(remote_filter.get(&Symbol::from(NO_LOCAL)).is_some());
+
+    // The filters echoed by the remote peer are in place
+    let filter = receiver.source_filter().unwrap();
+    assert_eq!(
+        filter.get(&Symbol::from(SELECTOR)),
+        Some(&Value::Described(Box::new(selector)))
+    );
+    assert_eq!(filter.len(), 2);
+
+    let peer = tokio::spawn(drive_sender(remote));
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn receiver_settle_mode_second_waits_for_sender_to_settle() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .receiver_settle_mode(ReceiverSettleMode::Second)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        for i in 0..2 {
+            let outcome = remote.send(i.to_string()).await.unwrap();
+            assert!(matches!(outcome, Outcome::Accepted(_)));
+        }
+        drive_sender(remote).await;
+    });
+
+    for i in 0..2 {
+        let delivery = receiver.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), &i.to_string());
+
+        // The first stage only sends an unsettled disposition, and the remote sender settles
+        // the delivery in the second stage
+        receiver.accept(&delivery).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), receiver.on_settled(&delivery))
+            .await
+            .expect("delivery is not settled by the remote sender")
+            .unwrap();
+    }
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn on_settled_fails_once_link_is_detached() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .receiver_settle_mode(ReceiverSettleMode::Second)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let _fut = remote.send_batchable("hello").await.unwrap();
+    let delivery = receiver.recv::<String>().await.unwrap();
+
+    // The remote sender closes the link without settling the delivery
+    let peer = tokio::spawn(async move {
+        let _ = remote.close().await;
+    });
+    let result = tokio::time::timeout(Duration::from_secs(5), receiver.on_settled(&delivery))
+        .await
+        .expect("on_settled does not resolve after the link is detached");
+    assert!(matches!(result, Err(IllegalLinkStateError::IllegalState)));
+
+    let _ = receiver.close().await;
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn auto_accept_settles_deliveries_returned_by_recv() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .auto_accept(true)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        for i in 0..3 {
+            let outcome = remote.send(i.to_string()).await.unwrap();
+            assert!(matches!(outcome, Outcome::Accepted(_)));
+        }
+        drive_sender(remote).await;
+    });
+
+    for i in 0..3 {
+        let delivery = receiver.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), &i.to_string());
+    }
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn auto_accept_with_receiver_settle_mode_second_fails_to_attach() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let err = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .auto_accept(true)
+        .receiver_settle_mode(ReceiverSettleMode::Second)
+        .attach(&mut session)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ReceiverAttachError::AutoAcceptWithRcvSettleModeSecond
+    ));
+
+    // The peer must not have seen an Attach
+    assert!(links.try_recv().is_err());
+
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn receiver_drops_expired_delivery() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .drop_expired(true)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        let expired = Message::builder()
+            .properties(
+                Properties::builder()
+                    .absolute_expiry_time(Timestamp::from_milliseconds(1_000))
+                    .build(),
+            )
+            .value("expired")
+            .build();
+        let outcome = remote.send(expired).await.unwrap();
+        assert!(matches!(
+            outcome,
+            Outcome::Modified(Modified {
+                delivery_failed: Some(true),
+                ..
+            })
+        ));
+
+        let outcome = remote.send("fresh").await.unwrap();
+        assert!(matches!(outcome, Outcome::Accepted(_)));
+        drive_sender(remote).await;
+    });
+
+    // Only the unexpired delivery is surfaced
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.body(), "fresh");
+    receiver.accept(&delivery).await.unwrap();
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn auto_credit_mode_replenishes_link_credit() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .credit_mode(CreditMode::Auto(4))
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    // The remote sender can only send more than the initial credit if the receiver keeps
+    // topping the credit back up with flow frames
+    let peer = tokio::spawn(async move {
+        for i in 0..12 {
+            let outcome = remote.send(i.to_string()).await.unwrap();
+            assert!(matches!(outcome, Outcome::Accepted(_)));
+        }
+        drive_sender(remote).await;
+    });
+
+    for i in 0..12 {
+        let delivery = receiver.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), &i.to_string());
+        receiver.accept(&delivery).await.unwrap();
+    }
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn auto_credit_mode_replenishes_link_credit_for_presettled_deliveries() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .sender_settle_mode(SenderSettleMode::Settled)
+        .credit_mode(CreditMode::Auto(4))
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        for i in 0..12 {
+            remote.send(i.to_string()).await.unwrap();
+        }
+        drive_sender(remote).await;
+    });
+
+    // Pre-settled deliveries are never disposed by the receiver
+    for i in 0..12 {
+        let delivery = tokio::time::timeout(Duration::from_secs(5), receiver.recv::<String>())
+            .await
+            .expect("link credit is not replenished")
+            .unwrap();
+        assert_eq!(delivery.body(), &i.to_string());
+    }
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn manual_credit_mode_waits_for_explicit_credit() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .credit_mode(CreditMode::Manual)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        for i in 0..4 {
+            remote.send(i.to_string()).await.unwrap();
+        }
+        drive_sender(remote).await;
+    });
+
+    receiver.set_credit(2).await.unwrap();
+    for i in 0..2 {
+        let delivery = receiver.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), &i.to_string());
+        receiver.accept(&delivery).await.unwrap();
+    }
+
+    // No credit is issued without an explicit flow
+    let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv::<String>()).await;
+    assert!(result.is_err());
+
+    receiver.set_credit(2).await.unwrap();
+    for i in 2..4 {
+        let delivery = receiver.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), &i.to_string());
+        receiver.accept(&delivery).await.unwrap();
+    }
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn recv_with_timeout_returns_none_without_losing_messages() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .credit_mode(CreditMode::Auto(2))
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    for _ in 0..3 {
+        let delivery = receiver
+            .recv_with_timeout::<String>(Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert!(delivery.is_none());
+    }
+
+    // The link credit is still available after the timeouts
+    let peer = tokio::spawn(async move {
+        for i in 0..4 {
+            remote.send(i.to_string()).await.unwrap();
+        }
+        drive_sender(remote).await;
+    });
+
+    for i in 0..4 {
+        let delivery = receiver
+            .recv_with_timeout::<String>(Duration::from_secs(5))
+            .await
+            .unwrap()
+            .expect("message is not received");
+        assert_eq!(delivery.body(), &i.to_string());
+        receiver.accept(&delivery).await.unwrap();
+    }
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn try_recv_returns_buffered_delivery_without_waiting() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    assert!(receiver.try_recv::<String>().await.unwrap().is_none());
+
+    let peer = tokio::spawn(async move {
+        let outcome = remote.send("hello").await.unwrap();
+        assert!(matches!(outcome, Outcome::Accepted(_)));
+        drive_sender(remote).await;
+    });
+
+    let delivery = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match receiver.try_recv::<String>().await.unwrap() {
+                Some(delivery) => break delivery,
+                None => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    })
+    .await
+    .expect("delivery is not buffered");
+    assert_eq!(delivery.body(), "hello");
+    receiver.accept(&delivery).await.unwrap();
+    assert!(receiver.try_recv::<String>().await.unwrap().is_none());
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn link_credit_and_delivery_count_reflect_flow_and_transfers() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .credit_mode(CreditMode::Manual)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+    assert_eq!(receiver.credit(), 0);
+    assert_eq!(remote.available_credit(), 0);
+
+    receiver.set_credit(5).await.unwrap();
+    assert_eq!(receiver.credit(), 5);
+    let credit = remote
+        .await_credit(5, Some(Duration::from_secs(5)))
+        .await
+        .unwrap();
+    assert_eq!(credit, 5);
+    assert_eq!(remote.available_credit(), 5);
+
+    let delivery_count = remote.delivery_count();
+    for i in 0..2 {
+        remote.send_batchable(i.to_string()).await.unwrap();
+    }
+    assert_eq!(remote.available_credit(), 3);
+    assert_eq!(remote.delivery_count(), delivery_count.wrapping_add(2));
+
+    for _ in 0..2 {
+        let delivery = receiver.recv::<String>().await.unwrap();
+        receiver.accept(&delivery).await.unwrap();
+    }
+    assert_eq!(receiver.credit(), 3);
+    assert_eq!(receiver.delivery_count(), delivery_count.wrapping_add(2));
+
+    tokio::spawn(drive_sender(remote));
+    receiver.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn drain_yields_available_messages_then_completes() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .credit_mode(CreditMode::Manual)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+    receiver.set_credit(5).await.unwrap();
+
+    // Only three messages are available on the remote sender
+    let (sent_tx, sent_rx) = tokio::sync::oneshot::channel();
+    let (more_tx, more_rx) = tokio::sync::oneshot::channel::<()>();
+    let peer = tokio::spawn(async move {
+        let mut futs = Vec::new();
+        for i in 0..3 {
+            futs.push(remote.send_batchable(i.to_string()).await.unwrap());
+        }
+        sent_tx.send(()).unwrap();
+        for fut in futs {
+            assert!(matches!(fut.await.unwrap(), Outcome::Accepted(_)));
+        }
+
+        more_rx.await.unwrap();
+        let outcome = remote.send("3").await.unwrap();
+        assert!(matches!(outcome, Outcome::Accepted(_)));
+        drive_sender(remote).await;
+    });
+    sent_rx.await.unwrap();
+
+    receiver.drain().await.unwrap();
+    let mut received = Vec::new();
+    while let Some(delivery) = receiver.recv_or_drained::<String>().await.unwrap() {
+        receiver.accept(&delivery).await.unwrap();
+        received.push(delivery.into_body());
+    }
+    assert_eq!(received, vec!["0", "1", "2"]);
+
+    // The credit consumed by the drain cycle is accounted for
+    more_tx.send(()).unwrap();
+    let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv::<String>()).await;
+    assert!(result.is_err());
+    receiver.set_credit(1).await.unwrap();
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.body(), "3");
+    receiver.accept(&delivery).await.unwrap();
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn receiver_stream_composes_with_stream_combinators() {
+    use futures_util::StreamExt;
+
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .sender_settle_mode(SenderSettleMode::Settled)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        for i in 0..10 {
+            remote.send(i.to_string()).await.unwrap();
+        }
+        drive_sender(remote).await;
+    });
+
+    let mut stream = receiver.into_stream::<String>();
+    let even: Vec<String> = stream
+        .by_ref()
+        .take(6)
+        .filter_map(|delivery| async move {
+            let body = delivery.unwrap().into_body();
+            match body.parse::<u32>().unwrap() % 2 {
+                0 => Some(body),
+                _ => None,
+            }
+        })
+        .collect()
+        .await;
+    assert_eq!(even, vec!["0", "2", "4"]);
+
+    let delivery = stream.next().await.unwrap().unwrap();
+    assert_eq!(delivery.body(), "6");
+
+    let receiver = stream.into_inner().unwrap();
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn receiver_stream_ends_when_remote_closes_link() {
+    use futures_util::{stream::FusedStream, StreamExt};
+
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        for i in 0..3 {
+            let outcome = remote.send(i.to_string()).await.unwrap();
+            assert!(outcome.is_accepted());
+        }
+        remote.close().await.unwrap();
+    });
+
+    let mut stream = receiver.into_stream::<String>();
+    let mut bodies = Vec::new();
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(delivery) => {
+                // Settlement stays explicit through the receiver
+                stream.get_ref().unwrap().accept(&delivery).await.unwrap();
+                bodies.push(delivery.into_body());
+            }
+            Err(err) => assert!(matches!(err, RecvError::LinkStateError(_))),
+        }
+    }
+    assert_eq!(bodies, vec!["0", "1", "2"]);
+    assert!(stream.is_terminated());
+    assert!(stream.next().await.is_none());
+
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn receiver_tracks_available_advertised_by_remote_sender() {
+    let (mut connection, _peer, mut links, inject) = connect_with_injection().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    drive(links.recv().await.unwrap());
+
+    let credit = receiver.credit();
+    let delivery_count = receiver.delivery_count();
+    inject.send(flow_frame(delivery_count, credit, 42)).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while receiver.available() != 42 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("available is not updated");
+    assert_eq!(receiver.credit(), credit);
+    assert_eq!(receiver.delivery_count(), delivery_count);
+
+    let (first, second) = SPLIT_MESSAGE;
+    let mut whole = first.to_vec();
+    whole.extend_from_slice(second);
+    inject
+        .send(transfer_frame(
+            Some((0, b"tag")),
+            Some(true),
+            false,
+            false,
+            &whole,
+        ))
+        .unwrap();
+    let delivery = tokio::time::timeout(Duration::from_secs(5), receiver.recv::<Value>())
+        .await
+        .expect("delivery is not received")
+        .unwrap();
+    assert_eq!(delivery.body(), &Value::from("hi"));
+    assert_eq!(receiver.available(), 41);
+
+    receiver.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn aborted_multi_frame_delivery_is_discarded() {
+    let (mut connection, _peer, mut links, inject) = connect_with_injection().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    drive(links.recv().await.unwrap());
+
+    let (first, second) = SPLIT_MESSAGE;
+    inject
+        .send(transfer_frame(
+            Some((0, b"aborted")),
+            None,
+            true,
+            false,
+            first,
+        ))
+        .unwrap();
+    inject
+        .send(transfer_frame(None, None, false, true, &[]))
+        .unwrap();
+    let mut whole = first.to_vec();
+    whole.extend_from_slice(second);
+    inject
+        .send(transfer_frame(
+            Some((1, b"complete")),
+            Some(true),
+            false,
+            false,
+            &whole,
+        ))
+        .unwrap();
+
+    // The partial payload of the aborted delivery is not prepended to the next one
+    let delivery = tokio::time::timeout(Duration::from_secs(5), receiver.recv::<Value>())
+        .await
+        .expect("delivery is not received")
+        .unwrap();
+    assert_eq!(delivery.delivery_tag().as_ref(), b"complete");
+    assert_eq!(delivery.body(), &Value::from("hi"));
+
+    receiver.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn aborted_delivery_is_not_yielded_and_consumes_credit() {
+    let (mut connection, _peer, mut links, inject) = connect_with_injection().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    drive(links.recv().await.unwrap());
+    let credit = receiver.credit();
+    let delivery_count = receiver.delivery_count();
+
+    let (first, _) = SPLIT_MESSAGE;
+    inject
+        .send(transfer_frame(
+            Some((0, b"aborted")),
+            None,
+            true,
+            false,
+            first,
+        ))
+        .unwrap();
+    inject
+        .send(transfer_frame(None, None, false, true, &[]))
+        .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv::<Value>()).await;
+    assert!(result.is_err(), "aborted delivery is yielded: {:?}", result);
+
+    // The sender counts the aborted delivery, so does the receiver
+    assert_eq!(receiver.credit(), credit - 1);
+    assert_eq!(receiver.delivery_count(), delivery_count.wrapping_add(1));
+
+    receiver.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn delivery_without_body_section_is_decoded_as_empty_body() {
+    use fe2o3_amqp::types::messaging::{ApplicationProperties, Body};
+
+    let (mut connection, _peer, mut links, inject) = connect_with_injection().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    drive(links.recv().await.unwrap());
+
+    // A message that consists of only the application-properties section
+    let properties = ApplicationProperties::builder()
+        .insert("statusCode", 204)
+        .build();
+    let payload = serde_amqp::to_vec(&properties).unwrap();
+    inject
+        .send(transfer_frame(
+            Some((0, b"body")),
+            Some(true),
+            false,
+            false,
+            &payload,
+        ))
+        .unwrap();
+    inject
+        .send(transfer_frame(
+            Some((1, b"option")),
+            Some(true),
+            false,
+            false,
+            &payload,
+        ))
+        .unwrap();
+
+    let delivery = tokio::time::timeout(Duration::from_secs(5), receiver.recv::<Body<Value>>())
+        .await
+        .expect("delivery is not received")
+        .unwrap();
+    assert!(delivery.body().is_empty());
+    assert_eq!(
+        delivery.message().application_properties.as_ref(),
+        Some(&properties)
+    );
+
+    let delivery = tokio::time::timeout(Duration::from_secs(5), receiver.recv::<Option<String>>())
+        .await
+        .expect("delivery is not received")
+        .unwrap();
+    assert!(delivery.body().is_none());
+
+    receiver.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn inconsistent_continuation_transfer_is_rejected() {
+    let (mut connection, _peer, mut links, inject) = connect_with_injection().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    drive(links.recv().await.unwrap());
+
+    // The continuation carries a different delivery-tag
+    let (first, second) = SPLIT_MESSAGE;
+    inject
+        .send(transfer_frame(
+            Some((0, b"first")),
+            None,
+            true,
+            false,
+            first,
+        ))
+        .unwrap();
+    inject
+        .send(transfer_frame(
+            Some((0, b"second")),
+            None,
+            false,
+            false,
+            second,
+        ))
+        .unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(5), receiver.recv::<Value>())
+        .await
+        .expect("error is not returned");
+    assert!(matches!(
+        result,
+        Err(RecvError::InconsistentFieldInMultiFrameDelivery)
+    ));
+
+    // A settled delivery cannot become unsettled in a later transfer
+    inject
+        .send(transfer_frame(
+            Some((1, b"settled")),
+            Some(true),
+            true,
+            false,
+            first,
+        ))
+        .unwrap();
+    inject
+        .send(transfer_frame(None, Some(false), false, false, second))
+        .unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(5), receiver.recv::<Value>())
+        .await
+        .expect("error is not returned");
+    assert!(matches!(
+        result,
+        Err(RecvError::InconsistentFieldInMultiFrameDelivery)
+    ));
+
+    // The corrupted partial deliveries are discarded and the link remains usable
+    inject
+        .send(transfer_frame(
+            Some((2, b"next")),
+            Some(true),
+            true,
+            false,
+            first,
+        ))
+        .unwrap();
+    inject
+        .send(transfer_frame(None, None, false, false, second))
+        .unwrap();
+    let delivery = tokio::time::timeout(Duration::from_secs(5), receiver.recv::<Value>())
+        .await
+        .expect("delivery is not received")
+        .unwrap();
+    assert_eq!(delivery.delivery_tag().as_ref(), b"next");
+    assert_eq!(delivery.body(), &Value::from("hi"));
+
+    receiver.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
@@ -0,0 +1,582 @@
+//! Tests of sender links against an in-process peer built with the acceptor over an in-memory
+//! duplex stream
+
+#![cfg(all(feature = "acceptor", not(target_arch = "wasm32")))]
+
+mod common;
+
+use common::{
+    connect, connect_with_recording, drive, drive_receiver, next_remote_receiver, recorded_frames,
+};
+use fe2o3_amqp::{
+    frames::amqp::FrameBody,
+    link::{delivery::Sendable, LinkStateError, RecvError, SendError, DEFAULT_CREDIT},
+    types::{
+        definitions::{self, AmqpError, SenderSettleMode},
+        messaging::Outcome,
+        primitives::Value,
+    },
+    Sender, Session,
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn rejected_disposition_resolves_send_with_rejected_outcome() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        let delivery = remote.recv::<Value>().await.unwrap();
+        let error = definitions::Error::new(AmqpError::PreconditionFailed, None, None);
+        remote.reject(&delivery, error).await.unwrap();
+        drive_receiver(remote).await;
+    });
+
+    let outcome = sender.send("hello").await.unwrap();
+    match outcome {
+        Outcome::Rejected(rejected) => {
+            let error = rejected.error.unwrap();
+            assert_eq!(error.condition, AmqpError::PreconditionFailed.into());
+        }
+        _ => panic!("Expecting a rejected outcome, found {:?}", outcome),
+    }
+
+    sender.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn send_with_timeout_fails_if_remote_never_settles() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    let (settle_tx, settle_rx) = tokio::sync::oneshot::channel::<()>();
+    let peer = tokio::spawn(async move {
+        let delivery = remote.recv::<Value>().await.unwrap();
+        // Only settle the first delivery after the sender has given up waiting
+        settle_rx.await.unwrap();
+        remote.accept(&delivery).await.unwrap();
+        drive_receiver(remote).await;
+    });
+
+    let result = sender
+        .send_with_timeout("hello", Duration::from_millis(200))
+        .await;
+    assert!(matches!(result, Err(SendError::SettlementTimeout)));
+
+    // The late disposition does not disturb the following deliveries
+    settle_tx.send(()).unwrap();
+    let outcome = sender
+        .send_with_timeout("world", Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, Outcome::Accepted(_)));
+
+    sender.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn send_fails_if_remote_does_not_settle_within_settlement_timeout() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::builder()
+        .name("sender")
+        .target("q1")
+        .settlement_timeout(Duration::from_millis(200))
+        .attach(&mut session)
+        .await
+        .unwrap();
+    assert_eq!(
+        sender.settlement_timeout(),
+        Some(Duration::from_millis(200))
+    );
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    let (settle_tx, settle_rx) = tokio::sync::oneshot::channel::<()>();
+    let peer = tokio::spawn(async move {
+        let delivery = remote.recv::<Value>().await.unwrap();
+        // Only settle the first delivery after the sender has given up waiting
+        settle_rx.await.unwrap();
+        remote.accept(&delivery).await.unwrap();
+        drive_receiver(remote).await;
+    });
+
+    let result = sender.send("hello").await;
+    assert!(matches!(result, Err(SendError::SettlementTimeout)));
+
+    // The late disposition does not disturb the following deliveries
+    settle_tx.send(()).unwrap();
+    sender.set_settlement_timeout(None);
+    let outcome = sender.send("world").await.unwrap();
+    assert!(matches!(outcome, Outcome::Accepted(_)));
+
+    sender.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn resumed_sender_resends_unsettled_delivery() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    // The remote receives the delivery but leaves it unsettled until the link is detached
+    let peer = tokio::spawn(async move {
+        let delivery = remote.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), "hello");
+        let err = remote.recv::<String>().await.unwrap_err();
+        assert!(matches!(
+            err,
+            RecvError::LinkStateError(LinkStateError::RemoteDetached)
+        ));
+    });
+
+    let result = sender
+        .send_with_timeout("hello", Duration::from_millis(200))
+        .await;
+    assert!(matches!(result, Err(SendError::SettlementTimeout)));
+
+    let detached = sender.detach().await.unwrap();
+    peer.await.unwrap();
+
+    // The remote peer holds no unsettled state, so the sender resends the unsettled delivery,
+    // suspends the link once more and then completes the resumption
+    let peer = tokio::spawn(async move {
+        let mut remote = next_remote_receiver(&mut links).await;
+        assert_eq!(remote.name(), "sender");
+        let delivery = remote.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), "hello");
+        remote.accept(&delivery).await.unwrap();
+        let err = remote.recv::<String>().await.unwrap_err();
+        assert!(matches!(
+            err,
+            RecvError::LinkStateError(LinkStateError::RemoteDetached)
+        ));
+
+        drive_receiver(next_remote_receiver(&mut links).await).await;
+    });
+    let mut sender = detached.resume().await.unwrap();
+
+    let outcome = sender.send("world").await.unwrap();
+    assert!(matches!(outcome, Outcome::Accepted(_)));
+
+    sender.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn send_settled_returns_without_waiting_for_disposition() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    // The remote never settles, which would block `send`
+    tokio::time::timeout(Duration::from_secs(5), sender.send_settled("hello"))
+        .await
+        .unwrap()
+        .unwrap();
+    let delivery = remote.recv::<String>().await.unwrap();
+    assert_eq!(delivery.body(), "hello");
+    tokio::spawn(drive_receiver(remote));
+
+    sender.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn send_settled_fails_with_unsettled_sender_settle_mode() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::builder()
+        .name("sender")
+        .target("q1")
+        .sender_settle_mode(SenderSettleMode::Unsettled)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    drive(links.recv().await.unwrap());
+
+    let result = sender.send_settled("hello").await;
+    assert!(matches!(result, Err(SendError::IllegalSenderSettleMode)));
+
+    sender.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn send_receives_disposition_arriving_right_after_transfer() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    drive(links.recv().await.unwrap());
+
+    // The remote may settle a delivery before `send` has returned from writing the transfer
+    for _ in 0..1000 {
+        let outcome = tokio::time::timeout(Duration::from_secs(5), sender.send("hello"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(outcome, Outcome::Accepted(_)));
+    }
+
+    sender.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn send_batch_returns_outcome_of_every_message() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    // Less credit than the size of the batch so that the sender has to wait for more
+    remote.set_credit(10).await.unwrap();
+    let peer = tokio::spawn(async move {
+        for i in 0..100 {
+            let delivery = remote.recv::<String>().await.unwrap();
+            assert_eq!(delivery.body(), &i.to_string());
+            remote.accept(&delivery).await.unwrap();
+        }
+        drive_receiver(remote).await;
+    });
+
+    let outcomes = sender
+        .send_batch((0..100).map(|i| i.to_string()))
+        .await
+        .unwrap();
+    assert_eq!(outcomes.len(), 100);
+    assert!(outcomes
+        .iter()
+        .all(|outcome| matches!(outcome, Outcome::Accepted(_))));
+
+    sender.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn send_batch_sends_last_transfer_as_not_batchable() {
+    let (mut connection, _peer, mut links, mut record) = connect_with_recording().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    tokio::spawn(drive_receiver(next_remote_receiver(&mut links).await));
+
+    sender
+        .send_batch((0..3).map(|i| i.to_string()))
+        .await
+        .unwrap();
+    sender.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+
+    let batchable: Vec<bool> = recorded_frames(&mut record)
+        .await
+        .into_iter()
+        .filter_map(|body| match body {
+            FrameBody::Transfer { performative, .. } => Some(performative.batchable),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(batchable, vec![true, true, false]);
+}
+
+#[tokio::test]
+async fn send_batch_reports_results_of_partially_sent_batch() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::builder()
+        .name("sender")
+        .target("q1")
+        .max_message_size(1024u64)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    assert_eq!(drive(links.recv().await.unwrap()), "sender");
+
+    // Sending stops at the message that fails to be transferred
+    let batch = vec![
+        String::from("first"),
+        String::from("second"),
+        "a".repeat(2048),
+        String::from("not sent"),
+    ];
+    let error = sender.send_batch(batch).await.unwrap_err();
+    assert_eq!(error.results.len(), 3);
+    assert!(error.results[..2]
+        .iter()
+        .all(|result| matches!(result, Ok(Outcome::Accepted(_)))));
+    assert!(matches!(
+        error.results[2],
+        Err(SendError::MessageSizeExceeded { max: 1024, .. })
+    ));
+
+    sender.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn transfer_carries_message_format_set_on_sendable() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        let mut formats = Vec::new();
+        for _ in 0..2 {
+            let delivery = remote.recv::<Value>().await.unwrap();
+            formats.push(*delivery.message_format());
+            remote.accept(&delivery).await.unwrap();
+        }
+        drive_receiver(remote).await;
+        formats
+    });
+
+    let sendable = Sendable::builder()
+        .message("custom format")
+        .message_format(0x0001_0203)
+        .build();
+    assert!(sender.send(sendable).await.unwrap().is_accepted());
+    assert!(sender.send("default format").await.unwrap().is_accepted());
+
+    sender.close().await.unwrap();
+    let formats = peer.await.unwrap();
+    assert_eq!(formats, [Some(0x0001_0203), Some(0)]);
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn close_after_settled_sends_writes_all_transfers() {
+    const COUNT: usize = 500;
+
+    let (mut connection, peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::builder()
+        .name("sender")
+        .target("q1")
+        .sender_settle_mode(SenderSettleMode::Settled)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+    let received = tokio::spawn(async move {
+        let mut received = 0;
+        while let Ok(delivery) = remote.recv::<String>().await {
+            assert_eq!(delivery.body(), &received.to_string());
+            received += 1;
+        }
+        received
+    });
+
+    for i in 0..COUNT {
+        sender.send(i.to_string()).await.unwrap();
+    }
+    // Close the connection right away without detaching the link or ending the session
+    connection.close().await.unwrap();
+
+    let received = tokio::time::timeout(Duration::from_secs(5), received)
+        .await
+        .expect("remote receiver did not stop")
+        .unwrap();
+    assert_eq!(received, COUNT);
+    peer.await.unwrap();
+}
+
+#[tokio::test]
+async fn sender_awaits_credit_granted_by_remote_receiver() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    // The remote receiver grants the default credit upon attach
+    let credit = sender.await_credit(1, None).await.unwrap();
+    assert_eq!(credit, DEFAULT_CREDIT);
+
+    // Only the available credit is returned if not enough credit is granted in time
+    let credit = sender
+        .await_credit(DEFAULT_CREDIT + 1, Some(Duration::from_millis(100)))
+        .await
+        .unwrap();
+    assert_eq!(credit, DEFAULT_CREDIT);
+
+    let (granted_tx, granted_rx) = tokio::sync::oneshot::channel();
+    let peer = tokio::spawn(async move {
+        remote.set_credit(DEFAULT_CREDIT * 2).await.unwrap();
+        granted_rx.await.unwrap();
+        drive_receiver(remote).await;
+    });
+
+    let credit = sender.await_credit(DEFAULT_CREDIT + 1, None).await.unwrap();
+    assert_eq!(credit, DEFAULT_CREDIT * 2);
+    granted_tx.send(()).unwrap();
+
+    sender.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn large_message_is_split_into_frames_and_reassembled() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    // Larger than the default max frame size of the connection
+    let body = "a".repeat(200 * 1024);
+    let expected = body.clone();
+    let peer = tokio::spawn(async move {
+        let delivery = remote.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), &expected);
+        remote.accept(&delivery).await.unwrap();
+        drive_receiver(remote).await;
+    });
+
+    let outcome = sender.send(body).await.unwrap();
+    assert!(outcome.is_accepted());
+
+    sender.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn message_exceeding_max_message_size_is_rejected() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::builder()
+        .name("sender")
+        .target("q1")
+        .max_message_size(1024u64)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    assert_eq!(drive(links.recv().await.unwrap()), "sender");
+    assert_eq!(sender.max_message_size(), Some(1024));
+
+    let err = sender.send("a".repeat(2048)).await.unwrap_err();
+    assert!(matches!(
+        err,
+        SendError::MessageSizeExceeded { max: 1024, .. }
+    ));
+
+    // The link is still usable after the rejection
+    let outcome = sender.send("small").await.unwrap();
+    assert!(outcome.is_accepted());
+
+    sender.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn stream_of_messages_is_forwarded_into_sender_sink() {
+    use futures_util::StreamExt;
+
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    let peer = tokio::spawn(async move {
+        let mut bodies = Vec::new();
+        for _ in 0..5 {
+            let delivery = remote.recv::<String>().await.unwrap();
+            remote.accept(&delivery).await.unwrap();
+            bodies.push(delivery.into_body());
+        }
+        drive_receiver(remote).await;
+        bodies
+    });
+
+    let mut sink = sender.into_sink();
+    futures_util::stream::iter(0..5)
+        .map(|i| Sendable::from(i.to_string()))
+        .map(Ok)
+        .forward(&mut sink)
+        .await
+        .unwrap();
+
+    let sender = sink.into_inner().unwrap();
+    sender.close().await.unwrap();
+    assert_eq!(peer.await.unwrap(), vec!["0", "1", "2", "3", "4"]);
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn sender_sink_is_not_ready_without_link_credit() {
+    use futures_util::SinkExt;
+
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    // Draining makes the sender consume all of its link credit
+    remote.drain().await.unwrap();
+    assert!(remote.recv_or_drained::<String>().await.unwrap().is_none());
+
+    let mut sink = sender.into_sink();
+    let not_ready = tokio::time::timeout(
+        Duration::from_millis(100),
+        sink.send(Sendable::from("hello")),
+    )
+    .await;
+    assert!(not_ready.is_err());
+
+    remote.set_credit(1).await.unwrap();
+    sink.send(Sendable::from("hello")).await.unwrap();
+    let delivery = remote.recv::<String>().await.unwrap();
+    assert_eq!(delivery.body(), "hello");
+    remote.accept(&delivery).await.unwrap();
+
+    let peer = tokio::spawn(drive_receiver(remote));
+    let sender = sink.into_inner().unwrap();
+    sender.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
@@ -0,0 +1,414 @@
+//! Tests of sessions against an in-process peer built with the acceptor over an in-memory duplex
+//! stream
+
+#![cfg(all(feature = "acceptor", not(target_arch = "wasm32")))]
+
+mod common;
+
+use common::{
+    application_error, assert_application_error, connect, connect_with, drive, drive_receiver,
+    drive_sender, next_remote_receiver, next_remote_sender, spawn_peer, spawn_peer_with,
+};
+use fe2o3_amqp::{
+    acceptor::{
+        link::{LinkAcceptor, LinkEndpoint},
+        session::SessionAcceptor,
+        ConnectionAcceptor,
+    },
+    link::{receiver::CreditMode, DetachError, LinkStateError, SendError},
+    session::{BeginError, RebindError},
+    types::{definitions::SenderSettleMode, messaging::Outcome},
+    Connection, Receiver, Sender, Session,
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn large_incoming_burst_does_not_deadlock_with_generous_session_buffer() {
+    const BURST: usize = 2000;
+
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::builder()
+        .buffer_size(4 * BURST)
+        .begin(&mut connection)
+        .await
+        .unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .credit_mode(CreditMode::Auto(BURST as u32))
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+    let peer = tokio::spawn(async move {
+        let outcomes = remote
+            .send_batch((0..BURST).map(|i| i.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(outcomes.len(), BURST);
+        drive_sender(remote).await;
+    });
+
+    let received = tokio::time::timeout(Duration::from_secs(30), async {
+        for i in 0..BURST {
+            let delivery = receiver.recv::<String>().await.unwrap();
+            assert_eq!(delivery.body(), &i.to_string());
+            receiver.accept(&delivery).await.unwrap();
+        }
+    })
+    .await;
+    assert!(received.is_ok(), "burst is not fully received");
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn constrained_remote_incoming_window_throttles_outgoing_transfers() {
+    const COUNT: usize = 50;
+
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let session_acceptor = SessionAcceptor::builder().incoming_window(4).build();
+    let (_peer, mut links) = spawn_peer_with(
+        peer_io,
+        ConnectionAcceptor::new("in-process-peer"),
+        session_acceptor,
+        LinkAcceptor::new(),
+    );
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::builder()
+        .name("sender")
+        .target("q1")
+        .sender_settle_mode(SenderSettleMode::Settled)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_receiver(&mut links).await;
+
+    // The remote session ends with window-violation if more than 4 transfers are sent before
+    // the remote peer replenishes its incoming-window
+    for i in 0..COUNT {
+        sender.send_settled(i.to_string()).await.unwrap();
+    }
+    let received = tokio::time::timeout(Duration::from_secs(10), async {
+        for i in 0..COUNT {
+            let delivery = remote.recv::<String>().await.unwrap();
+            assert_eq!(delivery.body(), &i.to_string());
+        }
+    })
+    .await;
+    assert!(received.is_ok(), "transfers are not fully received");
+    tokio::spawn(drive_receiver(remote));
+
+    sender.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn local_incoming_window_is_replenished_with_session_flow() {
+    const COUNT: usize = 50;
+
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::builder()
+        .incoming_window(4)
+        .begin(&mut connection)
+        .await
+        .unwrap();
+
+    let mut receiver = Receiver::builder()
+        .name("receiver")
+        .source("q1")
+        .credit_mode(CreditMode::Auto(COUNT as u32))
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let mut remote = next_remote_sender(&mut links).await;
+    let peer = tokio::spawn(async move {
+        let outcomes = remote
+            .send_batch((0..COUNT).map(|i| i.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(outcomes.len(), COUNT);
+        drive_sender(remote).await;
+    });
+
+    let received = tokio::time::timeout(Duration::from_secs(10), async {
+        for i in 0..COUNT {
+            let delivery = receiver.recv::<String>().await.unwrap();
+            assert_eq!(delivery.body(), &i.to_string());
+            receiver.accept(&delivery).await.unwrap();
+        }
+    })
+    .await;
+    assert!(received.is_ok(), "transfers are not fully received");
+
+    receiver.close().await.unwrap();
+    peer.await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn exhausted_remote_incoming_window_does_not_block_detach() {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let session_acceptor = SessionAcceptor::builder().incoming_window(0).build();
+    let (_peer, mut links) = spawn_peer_with(
+        peer_io,
+        ConnectionAcceptor::new("in-process-peer"),
+        session_acceptor,
+        LinkAcceptor::new(),
+    );
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    tokio::spawn(drive_receiver(next_remote_receiver(&mut links).await));
+
+    // The transfer is held back by the session until the remote peer opens its incoming-window
+    let _fut = sender.send_batchable("hello").await.unwrap();
+    let closed = tokio::time::timeout(Duration::from_secs(10), sender.close()).await;
+    assert!(matches!(closed, Ok(Ok(()))));
+
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn rebound_session_reattaches_links_on_new_connection() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::builder()
+        .rebindable(true)
+        .begin(&mut connection)
+        .await
+        .unwrap();
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q2")
+        .await
+        .unwrap();
+    drive(links.recv().await.unwrap());
+    drive(links.recv().await.unwrap());
+
+    let (mut new_connection, _new_peer, mut new_links) = connect().await;
+    let err = session.rebind(&mut new_connection).await.unwrap_err();
+    assert!(matches!(err, RebindError::StillRunning));
+
+    // Losing the connection stops the session event loop
+    connection.close().await.unwrap();
+    assert!(session.on_end().await.is_err());
+
+    session.rebind(&mut new_connection).await.unwrap();
+    for _ in 0..2 {
+        match new_links.recv().await.unwrap() {
+            LinkEndpoint::Sender(mut remote) => {
+                assert_eq!(remote.name(), "receiver");
+                tokio::spawn(async move {
+                    remote.send("world").await.unwrap();
+                    drive_sender(remote).await;
+                });
+            }
+            LinkEndpoint::Receiver(remote) => {
+                assert_eq!(remote.name(), "sender");
+                tokio::spawn(drive_receiver(remote));
+            }
+        }
+    }
+
+    // The links are usable without being re-created
+    let outcome = sender.send("hello").await.unwrap();
+    assert!(matches!(outcome, Outcome::Accepted(_)));
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.body(), "world");
+    receiver.accept(&delivery).await.unwrap();
+
+    sender.close().await.unwrap();
+    receiver.close().await.unwrap();
+    session.close().await.unwrap();
+    new_connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn rebound_session_fails_unsettled_sends() {
+    let (mut connection, _peer, mut links) = connect().await;
+    let mut session = Session::builder()
+        .rebindable(true)
+        .begin(&mut connection)
+        .await
+        .unwrap();
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    // The remote receiver never settles the delivery
+    let _remote = next_remote_receiver(&mut links).await;
+    let fut = sender.send_batchable("hello").await.unwrap();
+
+    connection.close().await.unwrap();
+    assert!(session.on_end().await.is_err());
+
+    let (mut new_connection, _new_peer, mut new_links) = connect().await;
+    session.rebind(&mut new_connection).await.unwrap();
+    drive(new_links.recv().await.unwrap());
+
+    let result = tokio::time::timeout(Duration::from_secs(10), fut).await;
+    assert!(matches!(
+        result,
+        Ok(Err(SendError::LinkStateError(
+            LinkStateError::IllegalSessionState
+        )))
+    ));
+
+    sender.close().await.unwrap();
+    session.close().await.unwrap();
+    new_connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn rebound_session_detaches_links_whose_reattach_is_refused() {
+    let result = tokio::time::timeout(Duration::from_secs(10), async {
+        let link_acceptor = LinkAcceptor::builder()
+            .on_dynamic_target(|mut target| {
+                target.address = Some("dynamic-q1".to_string());
+                Some(target)
+            })
+            .build();
+        let (mut connection, _peer, mut links) = connect_with(
+            ConnectionAcceptor::new("in-process-peer"),
+            SessionAcceptor::new(),
+            link_acceptor,
+        )
+        .await;
+        let mut session = Session::builder()
+            .rebindable(true)
+            .begin(&mut connection)
+            .await
+            .unwrap();
+        let (mut dynamic_sender, _) = Sender::attach_dynamic(&mut session, "dynamic-sender", None)
+            .await
+            .unwrap();
+        let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+        drive(links.recv().await.unwrap());
+        drive(links.recv().await.unwrap());
+
+        connection.close().await.unwrap();
+        assert!(session.on_end().await.is_err());
+
+        // The default link acceptor refuses to create a dynamic node
+        let (mut new_connection, _new_peer, mut new_links) = connect().await;
+        session.rebind(&mut new_connection).await.unwrap();
+        tokio::spawn(async move {
+            while let Some(link) = new_links.recv().await {
+                drive(link);
+            }
+        });
+
+        // The refused link is detached by the remote while the other link is resumed
+        let err = dynamic_sender.on_detach().await;
+        assert!(matches!(
+            err,
+            DetachError::RemoteClosedWithError(_) | DetachError::RemoteDetachedWithError(_)
+        ));
+        let outcome = sender.send("hello").await.unwrap();
+        assert!(matches!(outcome, Outcome::Accepted(_)));
+
+        sender.close().await.unwrap();
+        session.close().await.unwrap();
+        new_connection.close().await.unwrap();
+    })
+    .await;
+    assert!(result.is_ok(), "refused re-attach did not detach the link");
+}
+
+#[tokio::test]
+async fn end_with_error_sends_error_to_remote() {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let peer = tokio::spawn(async move {
+        let mut connection = ConnectionAcceptor::new("in-process-peer")
+            .accept(peer_io)
+            .await
+            .unwrap();
+        let mut session = SessionAcceptor::new()
+            .accept(&mut connection)
+            .await
+            .unwrap();
+        let result = session.on_end().await;
+        let _ = connection.on_close().await;
+        result
+    });
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+
+    session.end_with_error(application_error()).await.unwrap();
+    connection.close().await.unwrap();
+    match peer.await.unwrap() {
+        Err(fe2o3_amqp::session::Error::RemoteEndedWithError(error)) => {
+            assert_application_error(&error)
+        }
+        result => panic!("Expecting remote end with an error, found {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn begin_fails_once_local_channel_max_is_reached() {
+    let (client_io, peer_io) = tokio::io::duplex(64 * 1024);
+    let (peer, _links) = spawn_peer(peer_io);
+    let mut connection = Connection::builder()
+        .container_id("in-process-client")
+        .channel_max(1)
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+
+    // channel-max is the highest channel number, so channels 0 and 1 are available
+    let mut first = Session::begin(&mut connection).await.unwrap();
+    let mut second = Session::begin(&mut connection).await.unwrap();
+    assert!(matches!(
+        Session::begin(&mut connection).await,
+        Err(BeginError::LocalChannelMaxReached)
+    ));
+
+    // The channel is available again once the session has ended
+    first.end().await.unwrap();
+    let mut third = Session::begin(&mut connection).await.unwrap();
+
+    second.end().await.unwrap();
+    third.end().await.unwrap();
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+}
+
+#[tokio::test]
+async fn begin_fails_once_channel_max_of_remote_is_reached() {
+    let mut connection_acceptor = ConnectionAcceptor::new("in-process-peer");
+    connection_acceptor.local_open.channel_max = 0.into();
+    let (mut connection, peer, _links) = connect_with(
+        connection_acceptor,
+        SessionAcceptor::new(),
+        LinkAcceptor::new(),
+    )
+    .await;
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    assert!(matches!(
+        Session::begin(&mut connection).await,
+        Err(BeginError::LocalChannelMaxReached)
+    ));
+
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+}
@@ -0,0 +1,105 @@
+//! Tests of client code against the scripted test server
+
+#![cfg(all(feature = "test-server", not(target_arch = "wasm32")))]
+
+use fe2o3_amqp::{
+    acceptor::test_server::{TestServer, TestServerError},
+    types::{
+        messaging::{Accepted, Body, Outcome, Rejected},
+        primitives::Value,
+    },
+    Connection, Receiver, Sender, Session,
+};
+
+#[tokio::test]
+async fn client_accepts_message_delivered_by_test_server() {
+    let server = TestServer::builder()
+        .transfer(
+            Body::from(Value::from("hello")),
+            Outcome::Accepted(Accepted {}),
+        )
+        .bind("127.0.0.1:0")
+        .await
+        .unwrap();
+
+    let mut connection = Connection::open("test-client", &server.url()[..])
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.body(), "hello");
+    receiver.accept(&delivery).await.unwrap();
+
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+
+    let received = server.finish().await.unwrap();
+    assert!(received.is_empty());
+}
+
+#[tokio::test]
+async fn test_server_reports_unexpected_outcome() {
+    let server = TestServer::builder()
+        .transfer(
+            Body::from(Value::from("hello")),
+            Outcome::Accepted(Accepted {}),
+        )
+        .bind("127.0.0.1:0")
+        .await
+        .unwrap();
+
+    let mut connection = Connection::open("test-client", &server.url()[..])
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    receiver.reject(&delivery, None).await.unwrap();
+
+    let err = server.finish().await.unwrap_err();
+    assert!(matches!(
+        err,
+        TestServerError::UnexpectedOutcome {
+            found: Outcome::Rejected(_),
+            ..
+        }
+    ));
+
+    receiver.close().await.ok();
+    session.end().await.ok();
+    connection.close().await.ok();
+}
+
+#[tokio::test]
+async fn test_server_settles_message_from_client_with_scripted_outcome() {
+    let server = TestServer::builder()
+        .receive(Rejected { error: None })
+        .bind("127.0.0.1:0")
+        .await
+        .unwrap();
+
+    let mut connection = Connection::open("test-client", &server.url()[..])
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+
+    let outcome = sender.send("hello").await.unwrap();
+    assert!(matches!(outcome, Outcome::Rejected(_)));
+
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+
+    let received = server.finish().await.unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].body, Body::from(Value::from("hello")));
+}
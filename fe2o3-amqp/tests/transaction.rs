@@ -0,0 +1,368 @@
+//! Tests of transactions against an in-process peer built with the acceptor over an in-memory
+//! duplex stream
+
+#![cfg(all(
+    feature = "acceptor",
+    feature = "transaction",
+    not(target_arch = "wasm32")
+))]
+
+mod common;
+
+use common::{connect_with, drive, drive_sender, next_remote_sender};
+use fe2o3_amqp::transaction::coordinator::ControlLinkAcceptor;
+use fe2o3_amqp::{
+    acceptor::{link::LinkAcceptor, session::SessionAcceptor, ConnectionAcceptor},
+    link::SendError,
+    types::{
+        definitions::{self, AmqpError},
+        messaging::Outcome,
+    },
+    Receiver, Sender, Session,
+};
+use std::time::Duration;
+
+/// Session acceptor whose session accepts remotely initiated control links with the given
+/// `ControlLinkAcceptor`
+fn coordinator_session(control_link_acceptor: ControlLinkAcceptor) -> SessionAcceptor {
+    SessionAcceptor::builder()
+        .control_link_acceptor(control_link_acceptor)
+        .build()
+}
+
+#[tokio::test]
+async fn declare_with_global_id_allocates_local_txn_id_and_rejects_duplicates() {
+    use fe2o3_amqp::transaction::{
+        Controller, ControllerSendError, Transaction, TransactionDischarge, TransactionExt,
+    };
+    use fe2o3_amqp::types::transaction::TransactionId;
+
+    let (mut connection, _peer, _links) = connect_with(
+        ConnectionAcceptor::new("in-process-peer"),
+        coordinator_session(ControlLinkAcceptor::default()),
+        LinkAcceptor::new(),
+    )
+    .await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let controller = Controller::attach(&mut session, "controller")
+        .await
+        .unwrap();
+
+    let global_id = TransactionId::from(b"global-txn".to_vec());
+    let txn = Transaction::declare(&controller, global_id.clone())
+        .await
+        .unwrap();
+    assert_ne!(txn.txn_id(), &global_id);
+
+    // The global id is already associated with an active transaction
+    let err = Transaction::declare(&controller, global_id.clone())
+        .await
+        .unwrap_err();
+    match err {
+        ControllerSendError::Rejected(rejected) => assert_eq!(
+            rejected.error.unwrap().condition,
+            definitions::ErrorCondition::AmqpError(AmqpError::NotAllowed)
+        ),
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    // The mapping is released once the transaction is discharged
+    txn.commit().await.unwrap();
+    let txn = Transaction::declare(&controller, global_id).await.unwrap();
+    txn.rollback().await.unwrap();
+
+    controller.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn declare_with_global_id_is_rejected_if_not_accepted_by_coordinator() {
+    use fe2o3_amqp::transaction::{
+        Controller, ControllerSendError, Transaction, TransactionDischarge,
+    };
+    use fe2o3_amqp::types::transaction::{TransactionError, TransactionId};
+
+    let control_link_acceptor = ControlLinkAcceptor::builder()
+        .accept_global_id(false)
+        .build();
+    let (mut connection, _peer, _links) = connect_with(
+        ConnectionAcceptor::new("in-process-peer"),
+        coordinator_session(control_link_acceptor),
+        LinkAcceptor::new(),
+    )
+    .await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let controller = Controller::attach(&mut session, "controller")
+        .await
+        .unwrap();
+
+    let err = Transaction::declare(&controller, TransactionId::from(b"global-txn".to_vec()))
+        .await
+        .unwrap_err();
+    match err {
+        ControllerSendError::Rejected(rejected) => assert_eq!(
+            rejected.error.unwrap().condition,
+            definitions::ErrorCondition::TransactionError(TransactionError::UnknownId)
+        ),
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    // Transactions without a global id are still supported
+    let txn = Transaction::declare(&controller, None).await.unwrap();
+    txn.commit().await.unwrap();
+
+    controller.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+/// Allocates transaction IDs with a routing prefix followed by an incrementing counter
+#[derive(Debug, Default)]
+struct PrefixedTxnIdAllocator {
+    prefix: &'static [u8],
+    next: std::sync::atomic::AtomicU32,
+}
+
+impl fe2o3_amqp::transaction::coordinator::TxnIdAllocator for PrefixedTxnIdAllocator {
+    fn allocate(&self) -> fe2o3_amqp::types::transaction::TransactionId {
+        let n = self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let mut id = self.prefix.to_vec();
+        id.extend_from_slice(&n.to_be_bytes());
+        id.into()
+    }
+}
+
+#[tokio::test]
+async fn post_fails_if_transfer_exceeds_max_frame_size() {
+    use fe2o3_amqp::transaction::{Controller, PostError, Transaction, TransactionDischarge};
+
+    // The transactional state carries the txn-id, which cannot be split across frames
+    let control_link_acceptor = ControlLinkAcceptor::builder()
+        .txn_id_allocator(PrefixedTxnIdAllocator {
+            prefix: &[b'x'; 600],
+            ..Default::default()
+        })
+        .build();
+    let mut connection_acceptor = ConnectionAcceptor::new("in-process-peer");
+    connection_acceptor.local_open.max_frame_size = 512.into();
+    let (mut connection, peer, mut links) = connect_with(
+        connection_acceptor,
+        coordinator_session(control_link_acceptor),
+        LinkAcceptor::new(),
+    )
+    .await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let controller = Controller::attach(&mut session, "controller")
+        .await
+        .unwrap();
+    let mut sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+    drive(links.recv().await.unwrap());
+
+    let txn = Transaction::declare(&controller, None).await.unwrap();
+    match txn.post(&mut sender, "hello").await {
+        Err(PostError::MaxFrameSizeExceeded { size, max }) => {
+            assert_eq!(max, 512);
+            assert!(size > max);
+        }
+        result => panic!("Expecting MaxFrameSizeExceeded, found {:?}", result),
+    }
+
+    // Neither link credit nor the link is lost
+    let outcome = sender.send("hello").await.unwrap();
+    assert!(outcome.is_accepted());
+
+    txn.rollback().await.unwrap();
+    sender.close().await.unwrap();
+    controller.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+    peer.await.unwrap();
+}
+
+#[tokio::test]
+async fn control_link_acceptor_uses_custom_txn_id_allocator() {
+    use fe2o3_amqp::transaction::{Controller, Transaction, TransactionDischarge, TransactionExt};
+
+    let control_link_acceptor = ControlLinkAcceptor::builder()
+        .txn_id_allocator(PrefixedTxnIdAllocator {
+            prefix: b"node-1/",
+            ..Default::default()
+        })
+        .build();
+    let (mut connection, _peer, _links) = connect_with(
+        ConnectionAcceptor::new("in-process-peer"),
+        coordinator_session(control_link_acceptor),
+        LinkAcceptor::new(),
+    )
+    .await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let controller = Controller::attach(&mut session, "controller")
+        .await
+        .unwrap();
+
+    let txn1 = Transaction::declare(&controller, None).await.unwrap();
+    let txn2 = Transaction::declare(&controller, None).await.unwrap();
+    assert_eq!(&txn1.txn_id()[..], b"node-1/\0\0\0\0");
+    assert_eq!(&txn2.txn_id()[..], b"node-1/\0\0\0\x01");
+
+    txn1.commit().await.unwrap();
+    txn2.rollback().await.unwrap();
+
+    controller.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn declare_is_rejected_if_allocated_txn_id_is_in_use() {
+    use fe2o3_amqp::transaction::{
+        coordinator::TxnIdAllocator, Controller, ControllerSendError, Transaction,
+        TransactionDischarge,
+    };
+    use fe2o3_amqp::types::transaction::TransactionId;
+
+    #[derive(Debug)]
+    struct ConstantTxnIdAllocator;
+
+    impl TxnIdAllocator for ConstantTxnIdAllocator {
+        fn allocate(&self) -> TransactionId {
+            TransactionId::from(b"txn".to_vec())
+        }
+    }
+
+    let control_link_acceptor = ControlLinkAcceptor::builder()
+        .txn_id_allocator(ConstantTxnIdAllocator)
+        .build();
+    let (mut connection, _peer, _links) = connect_with(
+        ConnectionAcceptor::new("in-process-peer"),
+        coordinator_session(control_link_acceptor),
+        LinkAcceptor::new(),
+    )
+    .await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let controller = Controller::attach(&mut session, "controller")
+        .await
+        .unwrap();
+
+    let txn = Transaction::declare(&controller, None).await.unwrap();
+    let err = Transaction::declare(&controller, None).await.unwrap_err();
+    match err {
+        ControllerSendError::Rejected(rejected) => assert_eq!(
+            rejected.error.unwrap().condition,
+            definitions::ErrorCondition::AmqpError(AmqpError::InternalError)
+        ),
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    // The id can be allocated again once the transaction is discharged
+    txn.commit().await.unwrap();
+    let txn = Transaction::declare(&controller, None).await.unwrap();
+    txn.commit().await.unwrap();
+
+    controller.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+/// Sends a single message from the remote sender and reports the outcome once the delivery is
+/// settled. The remote sender is driven until it is detached by the client
+async fn send_from_remote(
+    mut sender: Sender,
+    body: &'static str,
+) -> tokio::sync::oneshot::Receiver<Result<Outcome, SendError>> {
+    let fut = sender.send_batchable(body).await.unwrap();
+    tokio::spawn(drive_sender(sender));
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        let _ = tx.send(fut.await);
+    });
+    rx
+}
+
+#[tokio::test]
+async fn txn_scoped_accept_takes_effect_on_commit() {
+    use fe2o3_amqp::transaction::{
+        Controller, Transaction, TransactionDischarge, TransactionalRetirement,
+    };
+
+    let (mut connection, _peer, mut links) = connect_with(
+        ConnectionAcceptor::new("in-process-peer"),
+        coordinator_session(ControlLinkAcceptor::default()),
+        LinkAcceptor::new(),
+    )
+    .await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let controller = Controller::attach(&mut session, "controller")
+        .await
+        .unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    let mut outcome = send_from_remote(next_remote_sender(&mut links).await, "hello").await;
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.body(), "hello");
+
+    let txn = Transaction::declare(&controller, None).await.unwrap();
+    txn.accept(&mut receiver, &delivery).await.unwrap();
+
+    // The outcome only takes effect once the transaction is committed
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), &mut outcome)
+            .await
+            .is_err()
+    );
+
+    txn.commit().await.unwrap();
+    let outcome = tokio::time::timeout(Duration::from_secs(5), outcome)
+        .await
+        .expect("delivery is not settled on commit")
+        .unwrap();
+    assert!(matches!(outcome, Ok(Outcome::Accepted(_))));
+
+    receiver.close().await.unwrap();
+    controller.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn txn_scoped_reject_is_discarded_on_rollback() {
+    use fe2o3_amqp::transaction::{
+        Controller, Transaction, TransactionDischarge, TransactionalRetirement,
+    };
+
+    let (mut connection, _peer, mut links) = connect_with(
+        ConnectionAcceptor::new("in-process-peer"),
+        coordinator_session(ControlLinkAcceptor::default()),
+        LinkAcceptor::new(),
+    )
+    .await;
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let controller = Controller::attach(&mut session, "controller")
+        .await
+        .unwrap();
+    let mut receiver = Receiver::attach(&mut session, "receiver", "q1")
+        .await
+        .unwrap();
+    let mut outcome = send_from_remote(next_remote_sender(&mut links).await, "hello").await;
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+
+    let txn = Transaction::declare(&controller, None).await.unwrap();
+    txn.reject(&mut receiver, &delivery, None).await.unwrap();
+    txn.rollback().await.unwrap();
+
+    // The delivery is left unsettled at the resource
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), &mut outcome)
+            .await
+            .is_err()
+    );
+
+    receiver.close().await.unwrap();
+    controller.close().await.unwrap();
+    session.close().await.unwrap();
+    connection.close().await.unwrap();
+}
@@ -0,0 +1,45 @@
+#![allow(clippy::all)]
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::distributions::{Alphanumeric, DistString};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Owned {
+    id: String,
+    subject: String,
+    body: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Borrowed<'a> {
+    id: &'a str,
+    subject: &'a str,
+    #[serde(borrow)]
+    body: Vec<&'a str>,
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut rng = rand::thread_rng();
+
+    // 1000 strings of 1kB each
+    let value = Owned {
+        id: Alphanumeric.sample_string(&mut rng, 36),
+        subject: Alphanumeric.sample_string(&mut rng, 128),
+        body: (0..1000)
+            .map(|_| Alphanumeric.sample_string(&mut rng, 1024))
+            .collect(),
+    };
+    let buf = serde_amqp::to_vec(&value).unwrap();
+
+    c.bench_function("deserialize owned strings 1MB", |b| {
+        b.iter(|| serde_amqp::from_slice::<Owned>(black_box(&buf)).unwrap())
+    });
+
+    c.bench_function("deserialize borrowed strings 1MB", |b| {
+        b.iter(|| serde_amqp::from_slice::<Borrowed>(black_box(&buf)).unwrap())
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
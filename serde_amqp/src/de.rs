@@ -29,7 +29,26 @@ pub fn from_reader<T: de::DeserializeOwned>(reader: impl std::io::Read) -> Resul
     T::deserialize(&mut de)
 }
 
+/// Deserialize an instance of type T from an async IO stream
+///
+/// Only the bytes of a single encoded value are read from the stream, so the reader can be
+/// used to decode the following values or other data afterwards.
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader<T, R>(reader: &mut R) -> Result<T, Error>
+where
+    T: de::DeserializeOwned,
+    R: tokio::io::AsyncRead + Unpin + ?Sized,
+{
+    let buf = crate::read::read_encoded_value(reader).await?;
+    from_slice(&buf)
+}
+
 /// Deserialize and instance of type T from a bytes slice
+///
+/// Strings, symbols and binaries are borrowed from `slice` when the target type allows it
+/// (eg. `&str`, `&[u8]` with `serde_bytes`, or [`SymbolRef`](crate::primitives::SymbolRef)),
+/// so no allocation is needed for them.
 pub fn from_slice<'de, T: de::Deserialize<'de>>(slice: &'de [u8]) -> Result<T, Error> {
     let reader = SliceReader::new(slice);
     let mut de = Deserializer::new(reader);
@@ -1304,14 +1323,18 @@ where
     where
         V: de::Visitor<'de>,
     {
-        // The deserializer will only peek the next u8
+        // The whole value must be consumed so that the reader is positioned at the next value
         let code = self
-            .reader
-            .peek()
-            .ok_or_else(|| Error::unexpected_eof(""))?;
+            .get_elem_code_or_peek_byte()
+            .ok_or_else(|| Error::unexpected_eof(""))??;
         match code.try_into()? {
-            EncodingCodes::DescribedType => self.parse_described_identifier(visitor),
-            _ => visitor.visit_u8(code),
+            EncodingCodes::DescribedType => {
+                // A described type can only be decoded into a `Value` without knowing its
+                // definition
+                let _: crate::Value = de::Deserialize::deserialize(self)?;
+                visitor.visit_unit()
+            }
+            _ => self.deserialize_any(visitor),
         }
     }
 }
@@ -2505,4 +2528,128 @@ mod tests {
         let buf = to_vec(&expected).unwrap();
         assert_eq_from_reader_vs_expected(&buf, expected);
     }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_from_async_reader_does_not_over_read() {
+        use crate::{from_async_reader, to_vec, Value};
+
+        let mut buf = to_vec(&Value::Uint(5672)).unwrap();
+        buf.extend(to_vec(&Value::from("amqp")).unwrap());
+        let mut reader = tokio_test::io::Builder::new().read(&buf).build();
+
+        let first: Value = from_async_reader(&mut reader).await.unwrap();
+        assert_eq!(first, Value::Uint(5672));
+        let second: Value = from_async_reader(&mut reader).await.unwrap();
+        assert_eq!(second, Value::from("amqp"));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_from_async_reader_described_list_over_split_reads() {
+        use crate::{
+            described::Described, descriptor::Descriptor, from_async_reader, primitives::Symbol,
+            to_vec, Value,
+        };
+
+        let expected = Value::Described(Box::new(Described {
+            descriptor: Descriptor::Name(Symbol::from("amqp:test:list")),
+            value: Value::List(vec![
+                Value::Bool(true),
+                Value::from("hello"),
+                Value::Array(vec![Value::Long(1), Value::Long(2)].into()),
+            ]),
+        }));
+        let mut buf = to_vec(&expected).unwrap();
+        buf.extend(to_vec(&Value::Null).unwrap());
+
+        let mut builder = tokio_test::io::Builder::new();
+        for chunk in buf.chunks(3) {
+            builder.read(chunk);
+        }
+        let mut reader = builder.build();
+
+        let value: Value = from_async_reader(&mut reader).await.unwrap();
+        assert_eq!(value, expected);
+        let value: Value = from_async_reader(&mut reader).await.unwrap();
+        assert_eq!(value, Value::Null);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_from_async_reader_incomplete_value() {
+        use crate::{from_async_reader, Error, Value};
+
+        // str8 with a length of 4 but only 2 bytes of content
+        let mut reader = tokio_test::io::Builder::new()
+            .read(&[EncodingCodes::Str8 as u8, 4, b'a', b'm'])
+            .build();
+        let result: Result<Value, _> = from_async_reader(&mut reader).await;
+        assert!(
+            matches!(result, Err(Error::Io(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof)
+        );
+    }
+
+    fn assert_borrowed_from(buf: &[u8], borrowed: &[u8]) {
+        let range = buf.as_ptr_range();
+        assert!(range.contains(&borrowed.as_ptr()));
+        assert!(borrowed.as_ptr_range().end <= range.end);
+    }
+
+    #[test]
+    fn test_from_slice_borrows_str_and_bytes() {
+        use crate::{primitives::SymbolRef, to_vec};
+        use serde::Serialize;
+
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        struct Borrowed<'a> {
+            text: &'a str,
+            #[serde(with = "serde_bytes")]
+            bin: &'a [u8],
+            #[serde(borrow)]
+            symbol: SymbolRef<'a>,
+            #[serde(borrow)]
+            list: Vec<&'a str>,
+        }
+
+        let long_text = "a".repeat(300);
+        let expected = Borrowed {
+            text: &long_text,
+            bin: b"amqp",
+            symbol: SymbolRef("amqp:test"),
+            list: vec!["first", "second"],
+        };
+        let buf = to_vec(&expected).unwrap();
+
+        let value: Borrowed = from_slice(&buf).unwrap();
+        assert_eq!(value, expected);
+        assert_borrowed_from(&buf, value.text.as_bytes());
+        assert_borrowed_from(&buf, value.bin);
+        assert_borrowed_from(&buf, value.symbol.0.as_bytes());
+        for item in &value.list {
+            assert_borrowed_from(&buf, item.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_ignored_any_consumes_whole_value() {
+        use serde::de::IgnoredAny;
+
+        use crate::{described::Described, descriptor::Descriptor, to_vec, Value};
+
+        let values = vec![
+            Value::Uint(1),
+            Value::from("amqp"),
+            Value::List(vec![Value::Uint(2), Value::from("x")]),
+            Value::Described(Box::new(Described {
+                descriptor: Descriptor::Code(7),
+                value: Value::List(vec![Value::from("x"), Value::Null]),
+            })),
+        ];
+        for value in values {
+            let buf = to_vec(&(value, 5672u32)).unwrap();
+            let (_, next): (IgnoredAny, u32) = from_slice(&buf).unwrap();
+            assert_eq!(next, 5672);
+        }
+    }
 }
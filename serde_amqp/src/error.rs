@@ -39,6 +39,10 @@ pub enum Error {
     /// Length is invalid
     #[error("Invalid length")]
     InvalidLength,
+
+    /// Symbol is not seven bit ASCII
+    #[error(transparent)]
+    InvalidSymbol(#[from] crate::primitives::SymbolError),
 }
 
 impl Error {
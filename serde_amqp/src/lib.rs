@@ -20,6 +20,7 @@
 //!
 //! - [`from_slice`]
 //! - [`from_reader`]
+//! - `from_async_reader` (requires the `"tokio"` feature)
 //!
 //! # Primitive types
 //!
@@ -109,6 +110,9 @@
 //! |`"chrono"`| enables conversion of `Timestamp` from/to `chrono::Duration` and `chrono::DateTime`, added since "0.5.1" |
 //! |`"chrono-preview"`| a temporary feature that removes the use of deprecated APIs in `chrono` crate |
 //! |`"uuid"`| enables conversion of `Uuid` from/to `uuid::Uuid`, added since "0.5.1" |
+//! |`"json"`| enables conversion of `Value` from/to `serde_json::Value` |
+//! |`"rust_decimal"`| enables conversion of `Dec128` from/to `rust_decimal::Decimal` |
+//! |`"tokio"`| enables `from_async_reader` which decodes a value from a `tokio::io::AsyncRead` |
 //!
 //! ## `SerializeComposite` and `DeserializeComposite`
 //!
@@ -277,10 +281,17 @@ mod format;
 pub use serde;
 
 pub use de::{from_reader, from_slice};
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[cfg(feature = "tokio")]
+pub use de::from_async_reader;
 pub use error::Error;
 pub use ser::to_vec;
 pub use size_ser::serialized_size;
-pub use value::{de::from_value, ser::to_value, Value};
+pub use value::{
+    de::{from_value, from_value_lenient},
+    ser::to_value,
+    Value,
+};
 
 #[cfg(feature = "derive")]
 pub mod macros;
@@ -8,7 +8,38 @@ use serde::{
     ser,
 };
 
-use crate::{__constants::ARRAY, format_code::EncodingCodes};
+use crate::{__constants::ARRAY, format_code::EncodingCodes, Value};
+
+/// Error constructing an array from values that are not of a single type
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ArrayError {
+    /// The element at `index` has a different format code from the first element
+    #[error(
+        "Array element at index {index} has format code {found:#04x} instead of {expected:#04x}"
+    )]
+    Heterogeneous {
+        /// Index of the first element whose format code differs
+        index: usize,
+        /// Format code of the first element
+        expected: u8,
+        /// Format code of the element at `index`
+        found: u8,
+    },
+}
+
+pub(crate) fn check_homogeneous(values: &[Value]) -> Result<(), ArrayError> {
+    let mut codes = values.iter().map(Value::format_code).enumerate();
+    if let Some((_, expected)) = codes.next() {
+        if let Some((index, found)) = codes.find(|(_, code)| *code != expected) {
+            return Err(ArrayError::Heterogeneous {
+                index,
+                expected,
+                found,
+            });
+        }
+    }
+    Ok(())
+}
 
 /// A sequence of values of a single type.
 ///
@@ -61,6 +92,14 @@ impl<T> Array<T> {
     }
 }
 
+impl Array<Value> {
+    /// Creates a new [`Array`], returning an error if the values do not share the same format code
+    pub fn try_new(values: Vec<Value>) -> Result<Self, ArrayError> {
+        check_homogeneous(&values)?;
+        Ok(Self(values))
+    }
+}
+
 impl<T: ser::Serialize> ser::Serialize for Array<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -234,9 +273,9 @@ impl<'de, T: de::Deserialize<'de>> de::Deserialize<'de> for Array<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{from_slice, to_vec};
+    use crate::{from_slice, to_vec, Value};
 
-    use super::Array;
+    use super::{Array, ArrayError};
 
     #[test]
     fn test_serialize_and_deserialize_multiple_elem_array() {
@@ -282,4 +321,37 @@ mod tests {
         let array: Array<String> = from_slice(&buf).unwrap();
         assert_eq!(array, expected);
     }
+
+    #[test]
+    fn test_try_new_accepts_homogeneous_values() {
+        let array = Array::try_new(vec![Value::Int(1), Value::Int(-2)]).unwrap();
+        let buf = to_vec(&Value::Array(array.clone())).unwrap();
+        let value: Value = from_slice(&buf).unwrap();
+        assert_eq!(value, Value::Array(array));
+
+        assert!(Array::try_new(vec![]).is_ok());
+    }
+
+    #[test]
+    fn test_try_new_rejects_heterogeneous_values() {
+        let values = vec![Value::Int(1), Value::Int(2), Value::from("three")];
+        let err = Array::try_new(values).unwrap_err();
+        assert_eq!(
+            err,
+            ArrayError::Heterogeneous {
+                index: 2,
+                expected: Value::Int(1).format_code(),
+                found: Value::from("three").format_code(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_serializing_heterogeneous_array_fails() {
+        let value = Value::Array(Array::from(vec![Value::Int(1), Value::from("two")]));
+        assert!(to_vec(&value).is_err());
+
+        let value = Value::List(vec![value]);
+        assert!(to_vec(&value).is_err());
+    }
 }
@@ -8,6 +8,247 @@ use serde_bytes::Bytes;
 
 use crate::error::Error;
 
+/// Error converting a [`Dec128`] into a `rust_decimal::Decimal`
+#[cfg_attr(docsrs, doc(cfg(feature = "rust_decimal")))]
+#[cfg(feature = "rust_decimal")]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DecimalError {
+    /// Infinity and NaN have no `rust_decimal::Decimal` counterpart
+    #[error("Infinity and NaN cannot be converted")]
+    NotFinite,
+
+    /// The value needs more than 96 bits of mantissa or a scale above 28
+    #[error("Decimal value is out of range")]
+    OutOfRange,
+}
+
+/// Parameters of an IEEE 754-2008 decimal interchange format
+struct Format {
+    /// Total number of bits
+    bits: u32,
+    /// Number of bits of the biased exponent
+    exponent_bits: u32,
+    bias: i32,
+    /// Maximum number of decimal digits in the coefficient
+    precision: u32,
+}
+
+const BID32: Format = Format {
+    bits: 32,
+    exponent_bits: 8,
+    bias: 101,
+    precision: 7,
+};
+
+const BID64: Format = Format {
+    bits: 64,
+    exponent_bits: 10,
+    bias: 398,
+    precision: 16,
+};
+
+const BID128: Format = Format {
+    bits: 128,
+    exponent_bits: 14,
+    bias: 6176,
+    precision: 34,
+};
+
+/// A decimal number decoded from the Binary Integer Decimal encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bid {
+    /// `(-1)^negative * coefficient * 10^exponent`
+    Finite {
+        negative: bool,
+        coefficient: u128,
+        exponent: i32,
+    },
+    Infinite {
+        negative: bool,
+    },
+    NaN,
+}
+
+const fn mask(n: u32) -> u128 {
+    (1u128 << n) - 1
+}
+
+/// Divides by `10^n`, rounding half to even
+fn div_pow10_round(value: u128, n: u32) -> u128 {
+    let divisor = match 10u128.checked_pow(n) {
+        Some(divisor) => divisor,
+        None => return 0,
+    };
+    let (quotient, remainder) = (value / divisor, value % divisor);
+    let half = divisor / 2;
+    if remainder > half || (remainder == half && quotient % 2 == 1) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+impl Format {
+    fn max_coefficient(&self) -> u128 {
+        10u128.pow(self.precision) - 1
+    }
+
+    fn min_exponent(&self) -> i32 {
+        -self.bias
+    }
+
+    fn max_exponent(&self) -> i32 {
+        (3 << (self.exponent_bits - 2)) - 1 - self.bias
+    }
+
+    fn decode(&self, bits: u128) -> Bid {
+        let k = self.bits;
+        let negative = (bits >> (k - 1)) & 1 == 1;
+
+        let (biased, coefficient) = if (bits >> (k - 3)) & 0b11 == 0b11 {
+            match (bits >> (k - 6)) & 0b11111 {
+                0b11110 => return Bid::Infinite { negative },
+                0b11111 => return Bid::NaN,
+                _ => {}
+            }
+            let trailing = k - 3 - self.exponent_bits;
+            let biased = (bits >> trailing) & mask(self.exponent_bits);
+            (biased, (0b100 << trailing) | (bits & mask(trailing)))
+        } else {
+            let trailing = k - 1 - self.exponent_bits;
+            let biased = (bits >> trailing) & mask(self.exponent_bits);
+            (biased, bits & mask(trailing))
+        };
+
+        // Non-canonical coefficients are interpreted as zero
+        let coefficient = if coefficient > self.max_coefficient() {
+            0
+        } else {
+            coefficient
+        };
+
+        Bid::Finite {
+            negative,
+            coefficient,
+            exponent: biased as i32 - self.bias,
+        }
+    }
+
+    /// Rounds a finite value into the range of this format. Values too large are
+    /// turned into infinity and values too small lose precision down to zero.
+    fn fit(&self, negative: bool, mut coefficient: u128, mut exponent: i32) -> Bid {
+        let max_coefficient = self.max_coefficient();
+        while coefficient > max_coefficient {
+            coefficient = div_pow10_round(coefficient, 1);
+            exponent += 1;
+        }
+
+        if exponent < self.min_exponent() {
+            let shift = (self.min_exponent() - exponent) as u32;
+            coefficient = div_pow10_round(coefficient, shift);
+            exponent = self.min_exponent();
+        }
+
+        if exponent > self.max_exponent() {
+            if coefficient == 0 {
+                exponent = self.max_exponent();
+            } else {
+                while exponent > self.max_exponent() && coefficient * 10 <= max_coefficient {
+                    coefficient *= 10;
+                    exponent -= 1;
+                }
+                if exponent > self.max_exponent() {
+                    return Bid::Infinite { negative };
+                }
+            }
+        }
+
+        Bid::Finite {
+            negative,
+            coefficient,
+            exponent,
+        }
+    }
+
+    /// Encodes a value. Finite values must already be within range of this format
+    fn encode(&self, value: Bid) -> u128 {
+        let k = self.bits;
+        match value {
+            Bid::Finite {
+                negative,
+                coefficient,
+                exponent,
+            } => {
+                let sign = (negative as u128) << (k - 1);
+                let biased = (exponent + self.bias) as u128;
+                let trailing = k - 1 - self.exponent_bits;
+                if coefficient <= mask(trailing) {
+                    sign | (biased << trailing) | coefficient
+                } else {
+                    let trailing = k - 3 - self.exponent_bits;
+                    sign | (0b11 << (k - 3)) | (biased << trailing) | (coefficient & mask(trailing))
+                }
+            }
+            Bid::Infinite { negative } => ((negative as u128) << (k - 1)) | (0b11110 << (k - 6)),
+            Bid::NaN => 0b11111 << (k - 6),
+        }
+    }
+
+    /// Converts a `f64` into the nearest value of this format
+    fn round_f64(&self, value: f64) -> Bid {
+        if value.is_nan() {
+            return Bid::NaN;
+        }
+        let negative = value.is_sign_negative();
+        if value.is_infinite() {
+            return Bid::Infinite { negative };
+        }
+        if value == 0.0 {
+            return Bid::Finite {
+                negative,
+                coefficient: 0,
+                exponent: 0,
+            };
+        }
+
+        // The shortest representation that round trips, or rounded to the precision
+        // of this format if that is still too long
+        let value = value.abs();
+        let mut repr = format!("{:e}", value);
+        if repr.find('e').unwrap_or(repr.len()) > self.precision as usize + 1 {
+            repr = format!("{:.*e}", self.precision as usize - 1, value);
+        }
+        let (mantissa, exponent) = repr.split_once('e').unwrap_or((&repr, "0"));
+        let digits = mantissa.replace('.', "");
+        let digits = digits.trim_end_matches('0');
+        let coefficient: u128 = digits.parse().unwrap_or(0);
+        let exponent = exponent.parse::<i32>().unwrap_or(0) - (digits.len() as i32 - 1);
+
+        self.fit(negative, coefficient, exponent)
+    }
+}
+
+impl Bid {
+    /// Converts into the nearest `f64`
+    fn to_f64(self) -> f64 {
+        match self {
+            Bid::Finite {
+                negative,
+                coefficient,
+                exponent,
+            } => {
+                let sign = if negative { "-" } else { "" };
+                format!("{}{}e{}", sign, coefficient, exponent)
+                    .parse()
+                    .unwrap_or(f64::NAN)
+            }
+            Bid::Infinite { negative: false } => f64::INFINITY,
+            Bid::Infinite { negative: true } => f64::NEG_INFINITY,
+            Bid::NaN => f64::NAN,
+        }
+    }
+}
+
 mod dec32 {
     // use serde_bytes::ByteBuf;
 
@@ -56,6 +297,24 @@ mod dec32 {
         }
     }
 
+    /// Converts to the nearest `f64`. This is lossy because most decimal fractions
+    /// cannot be represented exactly in binary floating point.
+    impl From<Dec32> for f64 {
+        fn from(val: Dec32) -> Self {
+            BID32.decode(u32::from_be_bytes(val.0) as u128).to_f64()
+        }
+    }
+
+    /// Converts to the nearest value representable in Dec32. This is lossy: the
+    /// shortest decimal representation of the `f64` is rounded to the precision of
+    /// the format, values too large become infinity and values too small become zero.
+    impl From<f64> for Dec32 {
+        fn from(val: f64) -> Self {
+            let bits = BID32.encode(BID32.round_f64(val)) as u32;
+            Self(bits.to_be_bytes())
+        }
+    }
+
     impl ser::Serialize for Dec32 {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
@@ -134,6 +393,24 @@ mod dec64 {
         }
     }
 
+    /// Converts to the nearest `f64`. This is lossy because most decimal fractions
+    /// cannot be represented exactly in binary floating point.
+    impl From<Dec64> for f64 {
+        fn from(val: Dec64) -> Self {
+            BID64.decode(u64::from_be_bytes(val.0) as u128).to_f64()
+        }
+    }
+
+    /// Converts to the nearest value representable in Dec64. This is lossy: the
+    /// shortest decimal representation of the `f64` is rounded to the precision of
+    /// the format, values too large become infinity and values too small become zero.
+    impl From<f64> for Dec64 {
+        fn from(val: f64) -> Self {
+            let bits = BID64.encode(BID64.round_f64(val)) as u64;
+            Self(bits.to_be_bytes())
+        }
+    }
+
     impl ser::Serialize for Dec64 {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
@@ -212,6 +489,81 @@ mod dec128 {
         }
     }
 
+    /// Converts to the nearest `f64`. This is lossy because most decimal fractions
+    /// cannot be represented exactly in binary floating point.
+    impl From<Dec128> for f64 {
+        fn from(val: Dec128) -> Self {
+            BID128.decode(u128::from_be_bytes(val.0)).to_f64()
+        }
+    }
+
+    /// Converts to the nearest value representable in Dec128. This is lossy: the
+    /// shortest decimal representation of the `f64` is rounded to the precision of
+    /// the format, values too large become infinity and values too small become zero.
+    impl From<f64> for Dec128 {
+        fn from(val: f64) -> Self {
+            let bits = BID128.encode(BID128.round_f64(val));
+            Self(bits.to_be_bytes())
+        }
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "rust_decimal")))]
+    #[cfg(feature = "rust_decimal")]
+    impl From<rust_decimal::Decimal> for Dec128 {
+        fn from(val: rust_decimal::Decimal) -> Self {
+            let value = Bid::Finite {
+                negative: val.is_sign_negative(),
+                coefficient: val.mantissa().unsigned_abs(),
+                exponent: -(val.scale() as i32),
+            };
+            Self(BID128.encode(value).to_be_bytes())
+        }
+    }
+
+    /// The conversion is exact. Trailing zeros of the coefficient are dropped when
+    /// necessary, and an error is returned if the value cannot be represented without
+    /// rounding.
+    #[cfg_attr(docsrs, doc(cfg(feature = "rust_decimal")))]
+    #[cfg(feature = "rust_decimal")]
+    impl TryFrom<Dec128> for rust_decimal::Decimal {
+        type Error = DecimalError;
+
+        fn try_from(val: Dec128) -> Result<Self, Self::Error> {
+            use rust_decimal::Decimal;
+
+            let (negative, mut coefficient, mut exponent) =
+                match BID128.decode(u128::from_be_bytes(val.0)) {
+                    Bid::Finite {
+                        negative,
+                        coefficient,
+                        exponent,
+                    } => (negative, coefficient, exponent),
+                    Bid::Infinite { .. } | Bid::NaN => return Err(DecimalError::NotFinite),
+                };
+
+            if coefficient == 0 {
+                return Ok(Decimal::ZERO);
+            }
+            while exponent < 0 && coefficient % 10 == 0 {
+                coefficient /= 10;
+                exponent += 1;
+            }
+
+            let (mantissa, scale) = if exponent >= 0 {
+                let mantissa = 10u128
+                    .checked_pow(exponent as u32)
+                    .and_then(|factor| coefficient.checked_mul(factor))
+                    .ok_or(DecimalError::OutOfRange)?;
+                (mantissa, 0)
+            } else {
+                (coefficient, exponent.unsigned_abs())
+            };
+            let mantissa = i128::try_from(mantissa).map_err(|_| DecimalError::OutOfRange)?;
+            let mantissa = if negative { -mantissa } else { mantissa };
+            Decimal::try_from_i128_with_scale(mantissa, scale).map_err(|_| DecimalError::OutOfRange)
+        }
+    }
+
     impl ser::Serialize for Dec128 {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
@@ -251,3 +603,129 @@ mod dec128 {
 pub use dec128::*;
 pub use dec32::*;
 pub use dec64::*;
+
+#[cfg(test)]
+mod tests {
+    use super::{Dec128, Dec32, Dec64};
+
+    fn dec32(bits: u32) -> Dec32 {
+        Dec32::from(bits.to_be_bytes())
+    }
+
+    fn dec64(bits: u64) -> Dec64 {
+        Dec64::from(bits.to_be_bytes())
+    }
+
+    fn dec128(bits: u128) -> Dec128 {
+        Dec128::from(bits.to_be_bytes())
+    }
+
+    #[test]
+    fn test_decode_known_dec32() {
+        assert_eq!(f64::from(dec32(0x3280_0000)), 0.0);
+        assert_eq!(f64::from(dec32(0x3280_0001)), 1.0);
+        assert_eq!(f64::from(dec32(0xB280_0001)), -1.0);
+        assert_eq!(f64::from(dec32(0x3200_0001)), 0.1);
+        assert_eq!(f64::from(dec32(0x77F8_967F)), 9.999999e96);
+        assert_eq!(f64::from(dec32(0x7800_0000)), f64::INFINITY);
+        assert_eq!(f64::from(dec32(0xF800_0000)), f64::NEG_INFINITY);
+        assert!(f64::from(dec32(0x7C00_0000)).is_nan());
+    }
+
+    #[test]
+    fn test_decode_known_dec64() {
+        assert_eq!(f64::from(dec64(0x31C0_0000_0000_0001)), 1.0);
+        assert_eq!(f64::from(dec64(0xB1A0_0000_0000_004B)), -7.5);
+        // Largest coefficient, which needs the "11" combination field
+        assert_eq!(
+            f64::from(dec64(0x6C73_86F2_6FC0_FFFF)),
+            9_999_999_999_999_999.0
+        );
+    }
+
+    #[test]
+    fn test_decode_known_dec128() {
+        assert_eq!(
+            f64::from(dec128(0x3040_0000_0000_0000_0000_0000_0000_0001)),
+            1.0
+        );
+        assert_eq!(
+            f64::from(dec128(0x303C_0000_0000_0000_0000_0000_0000_3039)),
+            123.45
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_coefficient_is_zero() {
+        // Coefficient 0x9FFFFF with the "11" combination field exceeds 9_999_999
+        assert_eq!(f64::from(dec32(0x6CBF_FFFF)), 0.0);
+    }
+
+    #[test]
+    fn test_encode_f64() {
+        assert_eq!(Dec32::from(1.0).into_inner(), 0x3280_0001u32.to_be_bytes());
+        assert_eq!(Dec32::from(0.1).into_inner(), 0x3200_0001u32.to_be_bytes());
+        assert_eq!(
+            Dec32::from(9.999999e96).into_inner(),
+            0x77F8_967Fu32.to_be_bytes()
+        );
+        assert_eq!(Dec32::from(1e97).into_inner(), 0x7800_0000u32.to_be_bytes());
+        assert_eq!(
+            Dec32::from(f64::NAN).into_inner(),
+            0x7C00_0000u32.to_be_bytes()
+        );
+        assert_eq!(
+            Dec64::from(-7.5).into_inner(),
+            0xB1A0_0000_0000_004Bu64.to_be_bytes()
+        );
+        assert_eq!(
+            Dec128::from(123.45).into_inner(),
+            0x303C_0000_0000_0000_0000_0000_0000_3039u128.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn test_f64_round_trip() {
+        for val in [0.1, -2.5e-300, 5e-324, 123456.789] {
+            assert_eq!(f64::from(Dec128::from(val)), val);
+            assert_eq!(f64::from(Dec64::from(val)), val);
+        }
+        // Needs 17 significant digits, which only decimal128 preserves
+        assert_eq!(f64::from(Dec128::from(f64::MAX)), f64::MAX);
+        assert_eq!(f64::from(Dec64::from(f64::MAX)), f64::INFINITY);
+        // Rounded to 7 significant digits
+        assert_eq!(f64::from(Dec32::from(123456.789)), 123456.8);
+        // Below the smallest subnormal of decimal32
+        assert_eq!(f64::from(Dec32::from(1e-102)), 0.0);
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn test_rust_decimal_conversion() {
+        use super::DecimalError;
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        let val = Decimal::from_str("123.45").unwrap();
+        let dec = Dec128::from(val);
+        assert_eq!(
+            dec.clone().into_inner(),
+            0x303C_0000_0000_0000_0000_0000_0000_3039u128.to_be_bytes()
+        );
+        assert_eq!(Decimal::try_from(dec).unwrap(), val);
+
+        let val = Decimal::from_str("-0.0000000000000000000000000001").unwrap();
+        assert_eq!(Decimal::try_from(Dec128::from(val)).unwrap(), val);
+
+        // 1E+2 is exact with a scale of 0
+        let dec = dec128(0x3044_0000_0000_0000_0000_0000_0000_0001);
+        assert_eq!(Decimal::try_from(dec).unwrap(), Decimal::from(100));
+
+        // 1E-29 needs a scale above 28
+        let dec = dec128(0x3006_0000_0000_0000_0000_0000_0000_0001);
+        assert_eq!(Decimal::try_from(dec), Err(DecimalError::OutOfRange));
+
+        let dec = dec128(0x7800_0000_0000_0000_0000_0000_0000_0000);
+        assert_eq!(Decimal::try_from(dec), Err(DecimalError::NotFinite));
+    }
+}
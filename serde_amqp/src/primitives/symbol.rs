@@ -10,6 +10,21 @@ use serde::{
 
 use crate::__constants::{SYMBOL, SYMBOL_REF};
 
+/// Error constructing a symbol from a string that is not seven bit ASCII
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SymbolError {
+    /// The string contains a non-ASCII character starting at the given byte index
+    #[error("Symbol contains a non-ASCII character at byte {0}")]
+    NonAscii(usize),
+}
+
+fn check_ascii(val: &str) -> Result<(), SymbolError> {
+    match val.bytes().position(|b| !b.is_ascii()) {
+        Some(index) => Err(SymbolError::NonAscii(index)),
+        None => Ok(()),
+    }
+}
+
 /// Symbolic values from a constrained domain. This is similar to `Symbol` but
 /// takes a slice instead of `String`.
 ///
@@ -29,10 +44,18 @@ use crate::__constants::{SYMBOL, SYMBOL_REF};
 ///
 /// Symbol should only contain ASCII characters. The implementation, however, wraps
 /// over a String. `AmqpNetLite` also wraps around a String, which in c# is utf-16.
+/// Use [`SymbolRef::try_new`] to validate the content up front; serializing a
+/// [`SymbolRef`] that contains non-ASCII characters returns an error.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SymbolRef<'a>(pub &'a str);
 
 impl<'a> SymbolRef<'a> {
+    /// Creates a new [`SymbolRef`], returning an error if `val` is not seven bit ASCII
+    pub fn try_new(val: &'a str) -> Result<Self, SymbolError> {
+        check_ascii(val)?;
+        Ok(Self(val))
+    }
+
     /// Returns the inner value as str
     pub fn as_str(&self) -> &str {
         self.0
@@ -109,7 +132,7 @@ impl<'de> Visitor<'de> for SymbolRefVisitor {
     }
 }
 
-impl<'de> de::Deserialize<'de> for SymbolRef<'de> {
+impl<'de: 'a, 'a> de::Deserialize<'de> for SymbolRef<'a> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
@@ -136,15 +159,29 @@ impl<'de> de::Deserialize<'de> for SymbolRef<'de> {
 ///
 /// Symbol should only contain ASCII characters. The implementation, however, wraps
 /// over a String. `AmqpNetLite` also wraps around a String, which in c# is utf-16.
+///
+/// [`Symbol::new`] and the `From` conversions do not validate their input. Use
+/// [`Symbol::try_new`] for strings that are not known to be ASCII; serializing a
+/// [`Symbol`] that contains non-ASCII characters returns an error.
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Symbol(pub String);
 
 impl Symbol {
     /// Creates a new [`Symbol`]
+    ///
+    /// The content is not validated. A symbol that is not seven bit ASCII will fail to
+    /// serialize; use [`Symbol::try_new`] to catch this early.
     pub fn new(val: impl Into<String>) -> Self {
         Self(val.into())
     }
 
+    /// Creates a new [`Symbol`], returning an error if `val` is not seven bit ASCII
+    pub fn try_new(val: impl Into<String>) -> Result<Self, SymbolError> {
+        let val = val.into();
+        check_ascii(&val)?;
+        Ok(Self(val))
+    }
+
     /// Consume the wrapper into the inner string
     pub fn into_inner(self) -> String {
         self.0
@@ -249,7 +286,7 @@ impl<'de> de::Deserialize<'de> for Symbol {
 mod tests {
     use crate::{from_slice, primitives::OrderedMap, to_vec};
 
-    use super::{Symbol, SymbolRef};
+    use super::{Symbol, SymbolError, SymbolRef};
 
     #[test]
     fn test_serialize_symbol_ref() {
@@ -286,4 +323,28 @@ mod tests {
         let val = map.get(&Symbol::from("hello"));
         assert_eq!(val, Some(&Value::String(String::from("world"))));
     }
+
+    #[test]
+    fn test_try_new_rejects_non_ascii() {
+        assert_eq!(Symbol::try_new("amqp:ok").unwrap(), Symbol::from("amqp:ok"));
+        assert_eq!(Symbol::try_new("caf\u{e9}"), Err(SymbolError::NonAscii(3)));
+        assert_eq!(
+            SymbolRef::try_new("\u{1f980}"),
+            Err(SymbolError::NonAscii(0))
+        );
+    }
+
+    #[test]
+    fn test_serialize_non_ascii_symbol_fails() {
+        use crate::{primitives::Array, Error};
+
+        let symbol = Symbol::from("caf\u{e9}");
+        assert!(matches!(to_vec(&symbol), Err(Error::InvalidSymbol(_))));
+
+        let symbol_ref = SymbolRef("caf\u{e9}");
+        assert!(matches!(to_vec(&symbol_ref), Err(Error::InvalidSymbol(_))));
+
+        let array = Array(vec![Symbol::from("ok"), Symbol::from("caf\u{e9}")]);
+        assert!(matches!(to_vec(&array), Err(Error::InvalidSymbol(_))));
+    }
 }
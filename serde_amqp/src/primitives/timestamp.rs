@@ -72,12 +72,56 @@ impl<'de> de::Deserialize<'de> for Timestamp {
     }
 }
 
+impl TryFrom<std::time::SystemTime> for Timestamp {
+    type Error = std::time::SystemTime;
+
+    /// Sub-millisecond precision is truncated towards the past, so a point in time before the
+    /// unix epoch is rounded down to the previous millisecond. An error is returned with the input
+    /// value if it is out of the range of [`Timestamp`].
+    fn try_from(val: std::time::SystemTime) -> Result<Self, Self::Error> {
+        match val.duration_since(std::time::UNIX_EPOCH) {
+            Ok(since_epoch) => i64::try_from(since_epoch.as_millis())
+                .map(Self)
+                .map_err(|_| val),
+            Err(err) => {
+                // Pre-epoch
+                let before_epoch = err.duration();
+                let mut millis = before_epoch.as_millis();
+                if before_epoch.subsec_nanos() % 1_000_000 != 0 {
+                    millis += 1;
+                }
+                i128::try_from(millis)
+                    .ok()
+                    .and_then(|millis| i64::try_from(-millis).ok())
+                    .map(Self)
+                    .ok_or(val)
+            }
+        }
+    }
+}
+
+impl TryFrom<Timestamp> for std::time::SystemTime {
+    type Error = Timestamp;
+
+    /// An error is returned with the input value if the timestamp cannot be represented by
+    /// [`std::time::SystemTime`] on the current platform
+    fn try_from(value: Timestamp) -> Result<Self, Self::Error> {
+        let millis = std::time::Duration::from_millis(value.0.unsigned_abs());
+        match value.0 < 0 {
+            true => std::time::UNIX_EPOCH.checked_sub(millis),
+            false => std::time::UNIX_EPOCH.checked_add(millis),
+        }
+        .ok_or(value)
+    }
+}
+
 /// Please note that this conversion does NOT check for overflow
 #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
 #[cfg(feature = "time")]
 impl From<time::OffsetDateTime> for Timestamp {
     fn from(val: time::OffsetDateTime) -> Self {
-        Self((val.unix_timestamp_nanos() / 1_000_000) as i64)
+        // Round down so that a point in time before the unix epoch is not rounded towards it
+        Self(val.unix_timestamp_nanos().div_euclid(1_000_000) as i64)
     }
 }
 
@@ -138,15 +182,15 @@ impl From<chrono::DateTime<chrono::Utc>> for Timestamp {
 )]
 #[cfg(all(feature = "chrono", not(feature = "chrono-preview")))]
 impl From<Timestamp> for chrono::DateTime<chrono::Utc> {
-    #[deprecated(
-        since = "0.5.3",
-        note = r#"Deprecated due to chrono's deprecation of from_timestamp(), use try_from with "chrono-preview" feature"#
-    )]
+    /// Deprecated since "0.5.3" due to chrono's deprecation of `from_timestamp()`, use `try_from`
+    /// with the "chrono-preview" feature instead
+    #[allow(deprecated)]
     fn from(value: Timestamp) -> Self {
+        // The nanoseconds must be non-negative for a point in time before the unix epoch
         chrono::DateTime::<chrono::Utc>::from_utc(
             chrono::NaiveDateTime::from_timestamp(
-                value.0 / 1000,
-                (value.0 % 1000) as u32 * 1_000_000,
+                value.0.div_euclid(1000),
+                value.0.rem_euclid(1000) as u32 * 1_000_000,
             ),
             chrono::Utc,
         )
@@ -199,3 +243,82 @@ impl From<Timestamp> for Option<chrono::DateTime<chrono::Utc>> {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::Timestamp;
+
+    #[test]
+    fn system_time_roundtrip() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let timestamp = Timestamp::try_from(time).unwrap();
+        assert_eq!(timestamp.milliseconds(), 1_700_000_000_123);
+        assert_eq!(SystemTime::try_from(timestamp), Ok(time));
+    }
+
+    #[test]
+    fn pre_epoch_system_time_roundtrip() {
+        let time = UNIX_EPOCH - Duration::from_millis(1500);
+        let timestamp = Timestamp::try_from(time).unwrap();
+        assert_eq!(timestamp.milliseconds(), -1500);
+        assert_eq!(SystemTime::try_from(timestamp), Ok(time));
+    }
+
+    #[test]
+    fn sub_millisecond_system_time_is_rounded_down() {
+        let after = UNIX_EPOCH + Duration::from_micros(1500);
+        assert_eq!(Timestamp::try_from(after).unwrap().milliseconds(), 1);
+
+        let before = UNIX_EPOCH - Duration::from_micros(1500);
+        assert_eq!(Timestamp::try_from(before).unwrap().milliseconds(), -2);
+    }
+
+    #[test]
+    fn out_of_range_system_time_is_rejected() {
+        let max = Duration::from_millis(i64::MAX as u64) + Duration::from_millis(1);
+        if let Some(time) = UNIX_EPOCH.checked_add(max) {
+            assert_eq!(Timestamp::try_from(time), Err(time));
+        }
+    }
+
+    #[test]
+    fn extreme_timestamp_does_not_panic() {
+        for millis in [i64::MIN, i64::MAX] {
+            let timestamp = Timestamp::from_milliseconds(millis);
+            match SystemTime::try_from(timestamp.clone()) {
+                Ok(time) => assert_eq!(Timestamp::try_from(time), Ok(timestamp)),
+                Err(err) => assert_eq!(err, timestamp),
+            }
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn pre_epoch_chrono_roundtrip() {
+        use chrono::{DateTime, TimeZone, Utc};
+
+        let datetime = Utc.timestamp_millis_opt(-1500).unwrap();
+        let timestamp = Timestamp::from(datetime);
+        assert_eq!(timestamp.milliseconds(), -1500);
+
+        #[cfg(not(feature = "chrono-preview"))]
+        let converted: DateTime<Utc> = timestamp.into();
+        #[cfg(feature = "chrono-preview")]
+        let converted = DateTime::<Utc>::try_from(timestamp).unwrap();
+        assert_eq!(converted, datetime);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn pre_epoch_time_roundtrip() {
+        let datetime = time::OffsetDateTime::UNIX_EPOCH - time::Duration::microseconds(1500);
+        let timestamp = Timestamp::from(datetime);
+        assert_eq!(timestamp.milliseconds(), -2);
+        assert_eq!(
+            time::OffsetDateTime::from(timestamp),
+            time::OffsetDateTime::UNIX_EPOCH - time::Duration::milliseconds(2)
+        );
+    }
+}
@@ -175,4 +175,18 @@ mod tests {
         assert_eq!(s.len(), 36);
         assert_eq!("616D7170-0506-0708-090A-0B0C0D0E0F10", s);
     }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_uuid_crate_roundtrip_preserves_byte_order() {
+        let uuid = uuid::Uuid::from_u128(0x0011_2233_4455_6677_8899_aabb_ccdd_eeff);
+        let converted = Uuid::from(uuid);
+        assert_eq!(converted.as_inner(), uuid.as_bytes());
+        assert_eq!(format!("{:x}", converted), uuid.hyphenated().to_string());
+        assert_eq!(uuid::Uuid::from(converted.clone()), uuid);
+
+        let buf = crate::to_vec(&converted).unwrap();
+        let deserialized: Uuid = crate::from_slice(&buf).unwrap();
+        assert_eq!(uuid::Uuid::from(deserialized), uuid);
+    }
 }
@@ -0,0 +1,66 @@
+//! Reading a single encoded value from an async stream
+
+use std::convert::TryFrom;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::{
+    error::Error,
+    format::{Category, EncodedWidth},
+    format_code::EncodingCodes,
+};
+
+/// Reads the bytes of exactly one encoded value (including the constructor) from the reader.
+///
+/// The width of each value is determined from its format code, so no byte beyond the end of
+/// the value is consumed from the reader.
+pub(crate) async fn read_encoded_value<R>(reader: &mut R) -> Result<Vec<u8>, Error>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let mut buf = Vec::new();
+
+    // A described type is a descriptor followed by the described value, thus a described
+    // constructor adds one more value to read
+    let mut pending = 1usize;
+    while pending > 0 {
+        let code = reader.read_u8().await?;
+        buf.push(code);
+        let code = EncodingCodes::try_from(code)?;
+        if let EncodingCodes::DescribedType = code {
+            pending += 1;
+            continue;
+        }
+
+        let width = match Category::try_from(code)? {
+            Category::Fixed(width) => width as usize,
+            Category::Encoded(EncodedWidth::Zero) => 0,
+            Category::Encoded(EncodedWidth::One) => {
+                let size = reader.read_u8().await?;
+                buf.push(size);
+                size as usize
+            }
+            Category::Encoded(EncodedWidth::Four) => {
+                let size = reader.read_u32().await?;
+                buf.extend_from_slice(&size.to_be_bytes());
+                size as usize
+            }
+        };
+        read_exact_into(reader, &mut buf, width).await?;
+        pending -= 1;
+    }
+
+    Ok(buf)
+}
+
+/// Appends exactly `n` bytes to `buf` without allocating for a size that is not yet read
+async fn read_exact_into<R>(reader: &mut R, buf: &mut Vec<u8>, n: usize) -> Result<(), Error>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let read = reader.take(n as u64).read_to_end(buf).await?;
+    if read < n {
+        return Err(Error::unexpected_eof("incomplete value"));
+    }
+    Ok(())
+}
@@ -10,6 +10,11 @@ pub use ioread::*;
 mod sliceread;
 pub use sliceread::*;
 
+#[cfg(feature = "tokio")]
+mod asyncread;
+#[cfg(feature = "tokio")]
+pub(crate) use asyncread::read_encoded_value;
+
 mod private {
     pub trait Sealed {}
 }
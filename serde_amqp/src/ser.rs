@@ -16,6 +16,7 @@ use crate::{
     error::Error,
     format::{OFFSET_LIST32, OFFSET_LIST8, OFFSET_MAP32, OFFSET_MAP8},
     format_code::EncodingCodes,
+    primitives::SymbolRef,
     util::{FieldRole, IsArrayElement, NewType, StructEncoding},
 };
 
@@ -406,6 +407,7 @@ impl<'a, W: Write + 'a> ser::Serializer for &'a mut Serializer<W> {
                         // Returns the length of this String, in bytes,
                         // not chars or graphemes. In other words, it might
                         // not be what a human considers the length of the string.
+                        SymbolRef::try_new(v)?;
                         let l = v.len();
 
                         match l {
@@ -456,6 +458,7 @@ impl<'a, W: Write + 'a> ser::Serializer for &'a mut Serializer<W> {
                     // Returns the length of this String, in bytes,
                     // not chars or graphemes. In other words, it might
                     // not be what a human considers the length of the string.
+                    SymbolRef::try_new(v)?;
                     let l = v.len();
 
                     let code = [EncodingCodes::Sym32 as u8];
@@ -482,6 +485,7 @@ impl<'a, W: Write + 'a> ser::Serializer for &'a mut Serializer<W> {
                     // Returns the length of this String, in bytes,
                     // not chars or graphemes. In other words, it might
                     // not be what a human considers the length of the string.
+                    SymbolRef::try_new(v)?;
                     let l = v.len();
 
                     let width = (l as u32).to_be_bytes();
@@ -209,7 +209,8 @@ impl<'de> de::Visitor<'de> for ValueVisitor {
                 Ok(Value::Described(val))
             }
             ValueType::Null => {
-                de.newtype_variant()?;
+                // Annotated so that the unit type is not inferred from the never type fallback
+                de.newtype_variant::<()>()?;
                 Ok(Value::Null)
             }
             ValueType::Bool => {
@@ -524,35 +525,81 @@ impl<'de> de::Deserialize<'de> for Value {
 }
 
 /// Interprete a [`Value`] as an instance of type `T`
+///
+/// The [`Value`] is consumed, so `T` cannot borrow from it. Use [`from_slice`](crate::from_slice)
+/// on the encoded bytes to deserialize borrowed strings and binaries without allocation.
 pub fn from_value<T: de::DeserializeOwned>(value: Value) -> Result<T, Error> {
     let de = Deserializer::new(value);
     T::deserialize(de)
 }
 
+/// Interprete a [`Value`] as an instance of type `T`, coercing integer values into integer types
+/// of a different width or signedness
+///
+/// For example, a `Value::Ulong(7)` can be deserialized into a `u32` or an `i8`. An
+/// [`Error::InvalidValue`] is returned only if the integer does not fit in the requested type.
+pub fn from_value_lenient<T: de::DeserializeOwned>(value: Value) -> Result<T, Error> {
+    let de = Deserializer::lenient(value);
+    T::deserialize(de)
+}
+
 /// A structure that deserializes a [`Value`] into type `T`
 #[derive(Debug)]
 pub struct Deserializer {
     new_type: NewType,
     value: Value,
     enum_type: EnumType,
+    /// Whether integer values are coerced into the requested integer type
+    lenient: bool,
 }
 
 impl Deserializer {
     /// Creates a new value deserializer
     pub fn new(value: Value) -> Self {
+        Self::nested(value, false)
+    }
+
+    /// Creates a new value deserializer that coerces integer values into integer types of a
+    /// different width or signedness if the value fits
+    pub fn lenient(value: Value) -> Self {
+        Self::nested(value, true)
+    }
+
+    fn nested(value: Value, lenient: bool) -> Self {
         Self {
             new_type: Default::default(),
             enum_type: Default::default(),
             value,
+            lenient,
         }
     }
 
-    pub(crate) fn array(value: Value) -> Self {
+    pub(crate) fn array(value: Value, lenient: bool) -> Self {
         Self {
             new_type: NewType::Array,
             enum_type: Default::default(),
             value,
+            lenient,
+        }
+    }
+
+    /// Coerces an integer value into `T` if lenient, otherwise returns `Error::InvalidValue`
+    fn coerce_integer<T: TryFrom<i128>>(&self) -> Result<T, Error> {
+        if !self.lenient {
+            return Err(Error::InvalidValue);
         }
+        let value = match self.value {
+            Value::Ubyte(v) => i128::from(v),
+            Value::Ushort(v) => i128::from(v),
+            Value::Uint(v) => i128::from(v),
+            Value::Ulong(v) => i128::from(v),
+            Value::Byte(v) => i128::from(v),
+            Value::Short(v) => i128::from(v),
+            Value::Int(v) => i128::from(v),
+            Value::Long(v) => i128::from(v),
+            _ => return Err(Error::InvalidValue),
+        };
+        T::try_from(value).map_err(|_| Error::InvalidValue)
     }
 }
 
@@ -615,7 +662,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     {
         match self.value {
             Value::Byte(v) => visitor.visit_i8(v),
-            _ => Err(Error::InvalidValue),
+            _ => visitor.visit_i8(self.coerce_integer()?),
         }
     }
 
@@ -626,7 +673,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     {
         match self.value {
             Value::Short(v) => visitor.visit_i16(v),
-            _ => Err(Error::InvalidValue),
+            _ => visitor.visit_i16(self.coerce_integer()?),
         }
     }
 
@@ -637,7 +684,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     {
         match self.value {
             Value::Int(v) => visitor.visit_i32(v),
-            _ => Err(Error::InvalidValue),
+            _ => visitor.visit_i32(self.coerce_integer()?),
         }
     }
 
@@ -649,7 +696,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         match self.new_type {
             NewType::None => match self.value {
                 Value::Long(v) => visitor.visit_i64(v),
-                _ => Err(Error::InvalidValue),
+                _ => visitor.visit_i64(self.coerce_integer()?),
             },
             NewType::Timestamp => match self.value {
                 Value::Timestamp(ref v) => visitor.visit_i64(v.milliseconds()),
@@ -666,7 +713,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     {
         match self.value {
             Value::Ubyte(v) => visitor.visit_u8(v),
-            _ => Err(Error::InvalidValue),
+            _ => visitor.visit_u8(self.coerce_integer()?),
         }
     }
 
@@ -677,7 +724,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     {
         match self.value {
             Value::Ushort(v) => visitor.visit_u16(v),
-            _ => Err(Error::InvalidValue),
+            _ => visitor.visit_u16(self.coerce_integer()?),
         }
     }
 
@@ -688,7 +735,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     {
         match self.value {
             Value::Uint(v) => visitor.visit_u32(v),
-            _ => Err(Error::InvalidValue),
+            _ => visitor.visit_u32(self.coerce_integer()?),
         }
     }
 
@@ -699,7 +746,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     {
         match self.value {
             Value::Ulong(v) => visitor.visit_u64(v),
-            _ => Err(Error::InvalidValue),
+            _ => visitor.visit_u64(self.coerce_integer()?),
         }
     }
 
@@ -884,6 +931,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
                     visitor.visit_seq(SeqAccess {
                         iter,
                         seq_type: SeqType::List,
+                        lenient: self.lenient,
                     })
                 }
                 _ => Err(Error::InvalidValue),
@@ -895,6 +943,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
                     visitor.visit_seq(SeqAccess {
                         iter,
                         seq_type: SeqType::Array,
+                        lenient: self.lenient,
                     })
                 }
                 _ => Err(Error::InvalidValue),
@@ -945,7 +994,11 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         match self.value {
             Value::Map(map) => {
                 let iter = map.into_iter();
-                visitor.visit_map(MapAccess { iter })
+                visitor.visit_map(MapAccess {
+                    iter,
+                    value: None,
+                    lenient: self.lenient,
+                })
             }
             _ => Err(Error::InvalidValue),
         }
@@ -980,6 +1033,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
                     visitor.visit_seq(SeqAccess {
                         iter,
                         seq_type: SeqType::Array,
+                        lenient: self.lenient,
                     })
                 }
                 _ => {
@@ -992,12 +1046,15 @@ impl<'de> de::Deserializer<'de> for Deserializer {
                 // An Uint should represent a unit_variant
                 v @ Value::Uint(_) => visitor.visit_enum(VariantAccess {
                     iter: vec![v].into_iter(),
+                    lenient: self.lenient,
                 }),
                 Value::List(v) => visitor.visit_enum(VariantAccess {
                     iter: v.into_iter(),
+                    lenient: self.lenient,
                 }),
                 v @ Value::Symbol(_) => visitor.visit_enum(VariantAccess {
                     iter: vec![v].into_iter(),
+                    lenient: self.lenient,
                 }),
                 _ => Err(Error::InvalidValue),
             }
@@ -1033,8 +1090,11 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        // self.deserialize_any(visitor)
-        visitor.visit_unit()
+        match self.value {
+            // A described type cannot be visited without knowing its definition
+            Value::Described(_) => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        }
     }
 }
 
@@ -1049,6 +1109,7 @@ enum SeqType {
 pub struct SeqAccess {
     iter: <Vec<Value> as IntoIterator>::IntoIter,
     seq_type: SeqType,
+    lenient: bool,
 }
 
 impl<'de> de::SeqAccess<'de> for SeqAccess {
@@ -1060,8 +1121,12 @@ impl<'de> de::SeqAccess<'de> for SeqAccess {
     {
         match self.iter.next() {
             Some(elem) => match self.seq_type {
-                SeqType::List => seed.deserialize(Deserializer::new(elem)).map(Some),
-                SeqType::Array => seed.deserialize(Deserializer::array(elem)).map(Some),
+                SeqType::List => seed
+                    .deserialize(Deserializer::nested(elem, self.lenient))
+                    .map(Some),
+                SeqType::Array => seed
+                    .deserialize(Deserializer::array(elem, self.lenient))
+                    .map(Some),
             },
             None => Ok(None),
         }
@@ -1072,23 +1137,38 @@ impl<'de> de::SeqAccess<'de> for SeqAccess {
 #[derive(Debug)]
 pub struct MapAccess {
     iter: <OrderedMap<Value, Value> as IntoIterator>::IntoIter,
+    /// Value of the entry whose key has been visited by `next_key_seed`
+    value: Option<Value>,
+    lenient: bool,
 }
 
 impl<'de> de::MapAccess<'de> for MapAccess {
     type Error = Error;
 
-    fn next_key_seed<K>(&mut self, _seed: K) -> Result<Option<K::Value>, Self::Error>
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
     where
         K: de::DeserializeSeed<'de>,
     {
-        unimplemented!()
+        match self.iter.next() {
+            Some((k, v)) => {
+                self.value = Some(v);
+                seed.deserialize(Deserializer::nested(k, self.lenient))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
     }
 
-    fn next_value_seed<V>(&mut self, _seed: V) -> Result<V::Value, Self::Error>
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
     where
         V: de::DeserializeSeed<'de>,
     {
-        unimplemented!()
+        match self.value.take() {
+            Some(v) => seed.deserialize(Deserializer::nested(v, self.lenient)),
+            None => Err(Error::Message(
+                "next_value_seed is called before next_key_seed".to_string(),
+            )),
+        }
     }
 
     fn next_entry_seed<K, V>(
@@ -1102,8 +1182,8 @@ impl<'de> de::MapAccess<'de> for MapAccess {
     {
         match self.iter.next() {
             Some((k, v)) => {
-                let key = kseed.deserialize(Deserializer::new(k))?;
-                let value = vseed.deserialize(Deserializer::new(v))?;
+                let key = kseed.deserialize(Deserializer::nested(k, self.lenient))?;
+                let value = vseed.deserialize(Deserializer::nested(v, self.lenient))?;
                 Ok(Some((key, value)))
             }
             None => Ok(None),
@@ -1115,6 +1195,7 @@ impl<'de> de::MapAccess<'de> for MapAccess {
 #[derive(Debug)]
 pub struct VariantAccess {
     iter: <Vec<Value> as IntoIterator>::IntoIter,
+    lenient: bool,
 }
 
 impl<'de> de::EnumAccess<'de> for VariantAccess {
@@ -1127,7 +1208,7 @@ impl<'de> de::EnumAccess<'de> for VariantAccess {
     {
         match self.iter.next() {
             Some(value) => {
-                let val = seed.deserialize(Deserializer::new(value))?;
+                let val = seed.deserialize(Deserializer::nested(value, self.lenient))?;
                 Ok((val, self))
             }
             None => Err(Error::Message("Expecting a Value".to_string())),
@@ -1147,7 +1228,7 @@ impl<'de> de::VariantAccess<'de> for VariantAccess {
         T: de::DeserializeSeed<'de>,
     {
         match self.iter.next() {
-            Some(value) => seed.deserialize(Deserializer::new(value)),
+            Some(value) => seed.deserialize(Deserializer::nested(value, self.lenient)),
             None => Err(Error::Message("Expecting a value".to_string())),
         }
     }
@@ -1158,9 +1239,11 @@ impl<'de> de::VariantAccess<'de> for VariantAccess {
     {
         match self.iter.next() {
             Some(value) => match &value {
-                Value::List(_) => {
-                    de::Deserializer::deserialize_tuple(Deserializer::new(value), len, visitor)
-                }
+                Value::List(_) => de::Deserializer::deserialize_tuple(
+                    Deserializer::nested(value, self.lenient),
+                    len,
+                    visitor,
+                ),
                 _ => Err(Error::InvalidValue),
             },
             None => Err(Error::Message("Expecting Value::List".to_string())),
@@ -1282,6 +1365,182 @@ mod tests {
         assert_eq_from_value_vs_expected(value, expected);
     }
 
+    #[test]
+    fn test_deserialize_map_into_hash_map() {
+        use std::collections::HashMap;
+
+        let mut map = OrderedMap::new();
+        map.insert(Value::Uint(1), Value::from("a"));
+        map.insert(Value::Uint(2), Value::from("b"));
+
+        let expected: HashMap<u32, String> = [(1, "a".to_string()), (2, "b".to_string())]
+            .into_iter()
+            .collect();
+        assert_eq_from_value_vs_expected(Value::Map(map), expected);
+    }
+
+    #[test]
+    fn test_deserialize_map_into_btree_map() {
+        use std::collections::BTreeMap;
+
+        let mut map = OrderedMap::new();
+        map.insert(Value::Long(-3), Value::Bool(true));
+        map.insert(Value::Long(7), Value::Bool(false));
+
+        let expected: BTreeMap<i64, bool> = [(-3, true), (7, false)].into_iter().collect();
+        assert_eq_from_value_vs_expected(Value::Map(map), expected);
+    }
+
+    #[test]
+    fn test_deserialize_map_into_ordered_map() {
+        let mut map = OrderedMap::new();
+        map.insert(Value::Uint(9), Value::from("z"));
+        map.insert(Value::Uint(1), Value::from("a"));
+
+        let mut expected: OrderedMap<u32, String> = OrderedMap::new();
+        expected.insert(9, "z".to_string());
+        expected.insert(1, "a".to_string());
+        let deserialized: OrderedMap<u32, String> = from_value(Value::Map(map)).unwrap();
+        assert_eq!(deserialized, expected);
+        // The order of the entries is preserved
+        assert!(deserialized.keys().eq(expected.keys()));
+    }
+
+    #[test]
+    fn test_deserialize_map_with_key_value_access() {
+        use std::{collections::HashMap, fmt};
+
+        use serde::de::{Deserializer, MapAccess, Visitor};
+
+        /// Visits the map with `next_key` and `next_value` instead of `next_entry`
+        #[derive(Debug, PartialEq)]
+        struct KeyValueMap(HashMap<u32, String>);
+
+        impl<'de> de::Deserialize<'de> for KeyValueMap {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct KeyValueVisitor;
+
+                impl<'de> Visitor<'de> for KeyValueVisitor {
+                    type Value = KeyValueMap;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("a map")
+                    }
+
+                    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+                    where
+                        A: MapAccess<'de>,
+                    {
+                        let mut map = HashMap::new();
+                        while let Some(key) = access.next_key()? {
+                            let value = access.next_value()?;
+                            map.insert(key, value);
+                        }
+                        Ok(KeyValueMap(map))
+                    }
+                }
+
+                deserializer.deserialize_map(KeyValueVisitor)
+            }
+        }
+
+        let mut map = OrderedMap::new();
+        map.insert(Value::Uint(1), Value::from("a"));
+        map.insert(Value::Uint(2), Value::from("b"));
+
+        let expected = KeyValueMap(
+            [(1, "a".to_string()), (2, "b".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        assert_eq_from_value_vs_expected(Value::Map(map), expected);
+    }
+
+    #[test]
+    fn test_strict_from_value_rejects_other_integer_width() {
+        assert!(from_value::<u32>(Value::Ulong(1)).is_err());
+        assert!(from_value::<u32>(Value::Ubyte(1)).is_err());
+        assert!(from_value::<i64>(Value::Int(1)).is_err());
+    }
+
+    #[test]
+    fn test_lenient_from_value_widens_unsigned_integers() {
+        use super::from_value_lenient;
+
+        assert_eq!(from_value_lenient::<u16>(Value::Ubyte(7)).unwrap(), 7);
+        assert_eq!(from_value_lenient::<u32>(Value::Ubyte(7)).unwrap(), 7);
+        assert_eq!(from_value_lenient::<u32>(Value::Ushort(700)).unwrap(), 700);
+        assert_eq!(
+            from_value_lenient::<u64>(Value::Uint(70_000)).unwrap(),
+            70_000
+        );
+    }
+
+    #[test]
+    fn test_lenient_from_value_widens_signed_integers() {
+        use super::from_value_lenient;
+
+        assert_eq!(from_value_lenient::<i16>(Value::Byte(-7)).unwrap(), -7);
+        assert_eq!(from_value_lenient::<i32>(Value::Short(-700)).unwrap(), -700);
+        assert_eq!(
+            from_value_lenient::<i64>(Value::Int(-70_000)).unwrap(),
+            -70_000
+        );
+        assert_eq!(from_value_lenient::<i64>(Value::Uint(7)).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_lenient_from_value_narrows_integers_that_fit() {
+        use super::from_value_lenient;
+
+        assert_eq!(from_value_lenient::<u32>(Value::Ulong(7)).unwrap(), 7);
+        assert_eq!(from_value_lenient::<u8>(Value::Uint(255)).unwrap(), 255);
+        assert_eq!(from_value_lenient::<i8>(Value::Long(-128)).unwrap(), -128);
+        assert_eq!(from_value_lenient::<u16>(Value::Int(7)).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_lenient_from_value_fails_on_overflow() {
+        use super::from_value_lenient;
+        use crate::Error;
+
+        assert!(matches!(
+            from_value_lenient::<u32>(Value::Ulong(u64::from(u32::MAX) + 1)),
+            Err(Error::InvalidValue)
+        ));
+        assert!(matches!(
+            from_value_lenient::<u8>(Value::Int(-1)),
+            Err(Error::InvalidValue)
+        ));
+        assert!(matches!(
+            from_value_lenient::<i64>(Value::Ulong(u64::MAX)),
+            Err(Error::InvalidValue)
+        ));
+        assert!(matches!(
+            from_value_lenient::<u32>(Value::from("7")),
+            Err(Error::InvalidValue)
+        ));
+    }
+
+    #[test]
+    fn test_lenient_from_value_coerces_nested_integers() {
+        use std::collections::HashMap;
+
+        use super::from_value_lenient;
+
+        let value = Value::List(vec![Value::Ubyte(1), Value::Ulong(2)]);
+        let list: Vec<u32> = from_value_lenient(value).unwrap();
+        assert_eq!(list, vec![1, 2]);
+
+        let mut map = OrderedMap::new();
+        map.insert(Value::Ulong(1), Value::Byte(-1));
+        let map: HashMap<u32, i64> = from_value_lenient(Value::Map(map)).unwrap();
+        assert_eq!(map.get(&1), Some(&-1));
+    }
+
     #[test]
     fn test_deserialize_value_unit_variant() {
         use serde::{Deserialize, Serialize};
@@ -1415,4 +1674,24 @@ mod tests {
         let value2: Array<Value> = serde_json::from_str(&json).unwrap();
         println!("{:?}", value2);
     }
+
+    #[test]
+    fn test_ignored_any() {
+        use serde::de::IgnoredAny;
+
+        use crate::{described::Described, descriptor::Descriptor};
+
+        let values = vec![
+            Value::Uint(1),
+            Value::from("amqp"),
+            Value::List(vec![Value::Uint(2), Value::from("x")]),
+            Value::Described(Box::new(Described {
+                descriptor: Descriptor::Code(7),
+                value: Value::List(vec![Value::from("x"), Value::Null]),
+            })),
+        ];
+        for value in values {
+            let _: IgnoredAny = from_value(value).unwrap();
+        }
+    }
 }
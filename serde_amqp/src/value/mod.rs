@@ -610,6 +610,11 @@ where
     }
 }
 
+/// Converts a [`serde_json::Value`] into a [`Value`].
+///
+/// JSON numbers are converted to `long` if they fit in an `i64`, `ulong` if they fit in a `u64`,
+/// and `double` otherwise. JSON objects are converted to maps with `string` keys. This conversion
+/// never fails, so `TryFrom<serde_json::Value>` is available through the blanket impl.
 #[cfg(feature = "json")]
 impl From<serde_json::Value> for Value {
     fn from(value: serde_json::Value) -> Self {
@@ -643,6 +648,109 @@ impl From<serde_json::Value> for Value {
     }
 }
 
+/// Error converting a [`Value`] into a [`serde_json::Value`]
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ToJsonError {
+    /// Described types carry a descriptor that has no JSON counterpart
+    #[error("Described types cannot be represented in JSON")]
+    Described,
+
+    /// Decimal types are not converted because there is no lossless JSON number representation
+    #[error("Decimal types cannot be represented in JSON")]
+    Decimal,
+
+    /// JSON numbers cannot represent NaN or infinity
+    #[error("Non-finite floating point number cannot be represented in JSON")]
+    NonFiniteFloat,
+
+    /// JSON object keys must be strings. Only [`Value::String`] and [`Value::Symbol`] keys are
+    /// accepted
+    #[error("Map key {0:?} cannot be represented as a JSON object key")]
+    InvalidMapKey(Value),
+}
+
+/// Converts a [`Value`] into a [`serde_json::Value`].
+///
+/// The conversion follows the rules below
+///
+/// - integers, `float` and `double` are converted to JSON numbers
+/// - `char`, `string` and `symbol` are converted to JSON strings
+/// - `binary` is converted to a base64 (standard alphabet with padding) encoded string
+/// - `timestamp` is converted to the number of milliseconds since the unix epoch
+/// - `uuid` is converted to its lowercase hyphenated string representation
+/// - `list` and `array` are converted to JSON arrays
+/// - `map` is converted to a JSON object if all keys are `string` or `symbol`
+///
+/// Described types and decimal types cannot be converted and will return an error.
+///
+/// Please note that the conversion is not reversible for types other than `null`, `bool`,
+/// `long`, `ulong`, `double`, `string`, `list`, and `map` with `string` keys because JSON does not
+/// carry the AMQP type information.
+#[cfg(feature = "json")]
+impl TryFrom<Value> for serde_json::Value {
+    type Error = ToJsonError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        use base64::Engine;
+
+        let value = match value {
+            Value::Described(_) => return Err(ToJsonError::Described),
+            Value::Null => serde_json::Value::Null,
+            Value::Bool(b) => serde_json::Value::Bool(b),
+            Value::Ubyte(n) => serde_json::Value::from(n),
+            Value::Ushort(n) => serde_json::Value::from(n),
+            Value::Uint(n) => serde_json::Value::from(n),
+            Value::Ulong(n) => serde_json::Value::from(n),
+            Value::Byte(n) => serde_json::Value::from(n),
+            Value::Short(n) => serde_json::Value::from(n),
+            Value::Int(n) => serde_json::Value::from(n),
+            Value::Long(n) => serde_json::Value::from(n),
+            Value::Float(n) => serde_json::Number::from_f64(n.into_inner() as f64)
+                .map(serde_json::Value::Number)
+                .ok_or(ToJsonError::NonFiniteFloat)?,
+            Value::Double(n) => serde_json::Number::from_f64(n.into_inner())
+                .map(serde_json::Value::Number)
+                .ok_or(ToJsonError::NonFiniteFloat)?,
+            Value::Decimal32(_) | Value::Decimal64(_) | Value::Decimal128(_) => {
+                return Err(ToJsonError::Decimal)
+            }
+            Value::Char(c) => serde_json::Value::String(c.to_string()),
+            Value::Timestamp(t) => serde_json::Value::from(t.milliseconds()),
+            Value::Uuid(uuid) => serde_json::Value::String(format!("{:x}", uuid)),
+            Value::Binary(buf) => serde_json::Value::String(
+                base64::engine::general_purpose::STANDARD.encode(buf.into_vec()),
+            ),
+            Value::String(s) => serde_json::Value::String(s),
+            Value::Symbol(s) => serde_json::Value::String(s.into_inner()),
+            Value::List(vec) => vec
+                .into_iter()
+                .map(serde_json::Value::try_from)
+                .collect::<Result<Vec<_>, _>>()
+                .map(serde_json::Value::Array)?,
+            Value::Array(arr) => arr
+                .into_inner()
+                .into_iter()
+                .map(serde_json::Value::try_from)
+                .collect::<Result<Vec<_>, _>>()
+                .map(serde_json::Value::Array)?,
+            Value::Map(map) => map
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        Value::String(s) => s,
+                        Value::Symbol(s) => s.into_inner(),
+                        _ => return Err(ToJsonError::InvalidMapKey(key)),
+                    };
+                    serde_json::Value::try_from(value).map(|value| (key, value))
+                })
+                .collect::<Result<serde_json::Map<_, _>, _>>()
+                .map(serde_json::Value::Object)?,
+        };
+        Ok(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ordered_float::OrderedFloat;
@@ -931,4 +1039,91 @@ mod tests {
         let value: Value = from_slice(&buf).unwrap();
         println!("{:?}", value);
     }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_value_round_trip() {
+        let json = serde_json::json!({
+            "null": null,
+            "bool": true,
+            "long": -13,
+            "ulong": u64::MAX,
+            "double": 1.5,
+            "string": "amqp",
+            "list": [1, "two", [3.0]],
+            "map": { "nested": false },
+        });
+
+        let value = Value::from(json.clone());
+        let round_trip = serde_json::Value::try_from(value.clone()).unwrap();
+        assert_eq!(round_trip, json);
+        assert_eq!(Value::from(round_trip), value);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_value_into_json_lossy_types() {
+        use serde_bytes::ByteBuf;
+
+        use crate::primitives::{Array, Symbol, Timestamp, Uuid};
+
+        let value = Value::List(vec![
+            Value::Binary(ByteBuf::from(b"amqp".to_vec())),
+            Value::Timestamp(Timestamp::from_milliseconds(1_600_000_000_000)),
+            Value::Symbol(Symbol::from("sym")),
+            Value::Char('c'),
+            Value::Uuid(Uuid::from([0u8; 16])),
+            Value::Array(Array(vec![Value::Ubyte(1), Value::Ubyte(2)])),
+        ]);
+        let json = serde_json::Value::try_from(value).unwrap();
+        let expected = serde_json::json!([
+            "YW1xcA==",
+            1_600_000_000_000i64,
+            "sym",
+            "c",
+            "00000000-0000-0000-0000-000000000000",
+            [1, 2],
+        ]);
+        assert_eq!(json, expected);
+
+        let mut map = OrderedMap::new();
+        map.insert(Value::Symbol(Symbol::from("key")), Value::Int(1));
+        let json = serde_json::Value::try_from(Value::Map(map)).unwrap();
+        assert_eq!(json, serde_json::json!({ "key": 1 }));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_value_into_json_errors() {
+        use super::ToJsonError;
+        use crate::{described::Described, descriptor::Descriptor, primitives::Dec32};
+
+        let described = Value::Described(Box::new(Described {
+            descriptor: Descriptor::Code(0x13),
+            value: Value::Null,
+        }));
+        assert_eq!(
+            serde_json::Value::try_from(described),
+            Err(ToJsonError::Described)
+        );
+
+        let decimal = Value::Decimal32(Dec32::from([0u8; 4]));
+        assert_eq!(
+            serde_json::Value::try_from(decimal),
+            Err(ToJsonError::Decimal)
+        );
+
+        let nan = Value::Double(OrderedFloat(f64::NAN));
+        assert_eq!(
+            serde_json::Value::try_from(nan),
+            Err(ToJsonError::NonFiniteFloat)
+        );
+
+        let mut map = OrderedMap::new();
+        map.insert(Value::Int(1), Value::Null);
+        assert_eq!(
+            serde_json::Value::try_from(Value::Map(map)),
+            Err(ToJsonError::InvalidMapKey(Value::Int(1)))
+        );
+    }
 }
@@ -14,7 +14,9 @@ use crate::{
     described::Described,
     descriptor::Descriptor,
     error::Error,
-    primitives::{Array, Dec128, Dec32, Dec64, OrderedMap, Symbol, Timestamp, Uuid},
+    primitives::{
+        check_homogeneous, Array, Dec128, Dec32, Dec64, OrderedMap, Symbol, Timestamp, Uuid,
+    },
     util::{FieldRole, NewType},
 };
 
@@ -51,7 +53,11 @@ impl ser::Serialize for Value {
             Value::Symbol(v) => v.serialize(serializer),
             Value::List(v) => v.serialize(serializer),
             Value::Map(v) => v.serialize(serializer),
-            Value::Array(v) => v.serialize(serializer),
+            Value::Array(v) => {
+                // A heterogeneous array cannot be encoded with a single element constructor
+                check_homogeneous(v).map_err(ser::Error::custom)?;
+                v.serialize(serializer)
+            }
         }
     }
 }
@@ -0,0 +1,198 @@
+#[cfg(feature = "derive")]
+use serde_amqp::{from_slice, to_vec, DeserializeComposite, SerializeComposite};
+
+#[cfg(feature = "derive")]
+#[derive(Debug, SerializeComposite, DeserializeComposite, PartialEq)]
+#[amqp_contract(encoding = "list", rename_all = "kebab-case")]
+enum Choice {
+    #[amqp_contract(name = "test:choice:unit", code = "0x0000_0001:0x0000_0001")]
+    Unit,
+
+    #[amqp_contract(
+        name = "test:choice:newtype",
+        code = "0x0000_0001:0x0000_0002",
+        encoding = "basic"
+    )]
+    Newtype(String),
+
+    #[amqp_contract(name = "test:choice:tuple", code = "0x0000_0001:0x0000_0003")]
+    Tuple(u32, Option<bool>),
+
+    #[amqp_contract(name = "test:choice:list", code = "0x0000_0001:0x0000_0004")]
+    List {
+        first_field: u32,
+        optional: Option<String>,
+        #[amqp_contract(default)]
+        defaulted: bool,
+    },
+
+    #[amqp_contract(
+        name = "test:choice:map",
+        code = "0x0000_0001:0x0000_0005",
+        encoding = "map"
+    )]
+    Map {
+        first_field: u32,
+        optional: Option<String>,
+    },
+
+    /// The descriptor name defaults to the name of the variant
+    Named { a: i32 },
+}
+
+#[cfg(feature = "derive")]
+#[derive(Debug, SerializeComposite, DeserializeComposite, PartialEq)]
+#[amqp_contract(
+    name = "test:choice:list",
+    code = "0x0000_0001:0x0000_0004",
+    encoding = "list",
+    rename_all = "kebab-case"
+)]
+struct ListStruct {
+    first_field: u32,
+    optional: Option<String>,
+    #[amqp_contract(default)]
+    defaulted: bool,
+}
+
+#[cfg(feature = "derive")]
+#[derive(Debug, SerializeComposite)]
+#[amqp_contract(
+    name = "test:choice:map",
+    code = "0x0000_0001:0x0000_0005",
+    encoding = "map",
+    rename_all = "kebab-case"
+)]
+struct MapStruct {
+    first_field: u32,
+    optional: Option<String>,
+}
+
+#[cfg(feature = "derive")]
+#[derive(Debug, SerializeComposite, DeserializeComposite, PartialEq)]
+#[amqp_contract(name = "test:choice:tuple", encoding = "list")]
+struct TupleStructByName(u32, Option<bool>);
+
+#[cfg(feature = "derive")]
+#[derive(Debug, SerializeComposite, DeserializeComposite, PartialEq)]
+#[amqp_contract(code = "0x0000_0001:0x0000_00ff", encoding = "list")]
+struct Unknown {
+    a: i32,
+}
+
+#[cfg(feature = "derive")]
+fn assert_roundtrip(value: Choice) -> Vec<u8> {
+    let buf = to_vec(&value).unwrap();
+    let decoded: Choice = from_slice(&buf).unwrap();
+    assert_eq!(decoded, value);
+    buf
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn unit_variant() {
+    let buf = assert_roundtrip(Choice::Unit);
+    let expected = [
+        0x00, 0x80, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x45,
+    ];
+    assert_eq!(buf, expected);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn newtype_variant() {
+    let buf = assert_roundtrip(Choice::Newtype(String::from("amqp")));
+    let expected = [
+        0x00, 0x80, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0xa1, 0x04, b'a', b'm', b'q',
+        b'p',
+    ];
+    assert_eq!(buf, expected);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn tuple_variant() {
+    assert_roundtrip(Choice::Tuple(13, Some(true)));
+    let buf = assert_roundtrip(Choice::Tuple(13, None));
+    let expected = [
+        0x00, 0x80, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0xc0, 0x03, 0x01, 0x52, 0x0d,
+    ];
+    assert_eq!(buf, expected);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn struct_variant_is_encoded_as_equivalent_struct() {
+    let value = Choice::List {
+        first_field: 7,
+        optional: None,
+        defaulted: false,
+    };
+    let buf = assert_roundtrip(value);
+    let expected = to_vec(&ListStruct {
+        first_field: 7,
+        optional: None,
+        defaulted: false,
+    })
+    .unwrap();
+    assert_eq!(buf, expected);
+
+    let value = Choice::List {
+        first_field: 7,
+        optional: Some(String::from("hello")),
+        defaulted: true,
+    };
+    let buf = assert_roundtrip(value);
+    let expected = to_vec(&ListStruct {
+        first_field: 7,
+        optional: Some(String::from("hello")),
+        defaulted: true,
+    })
+    .unwrap();
+    assert_eq!(buf, expected);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn map_encoded_struct_variant_is_encoded_as_equivalent_struct() {
+    let value = Choice::Map {
+        first_field: 7,
+        optional: Some(String::from("hello")),
+    };
+    let buf = to_vec(&value).unwrap();
+    let expected = to_vec(&MapStruct {
+        first_field: 7,
+        optional: Some(String::from("hello")),
+    })
+    .unwrap();
+    assert_eq!(buf, expected);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn variant_name_defaults_to_variant_ident() {
+    let buf = assert_roundtrip(Choice::Named { a: -1 });
+    let symbol = b"Named";
+    assert_eq!(&buf[..3], &[0x00, 0xa3, symbol.len() as u8]);
+    assert_eq!(&buf[3..3 + symbol.len()], symbol);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn variant_is_selected_by_descriptor_name() {
+    let buf = to_vec(&TupleStructByName(3, Some(false))).unwrap();
+    let decoded: Choice = from_slice(&buf).unwrap();
+    assert_eq!(decoded, Choice::Tuple(3, Some(false)));
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn unknown_descriptor_is_an_error() {
+    let buf = to_vec(&Unknown { a: 1 }).unwrap();
+    let result: Result<Choice, _> = from_slice(&buf);
+    assert!(result.is_err());
+
+    let buf = to_vec(&1u32).unwrap();
+    let result: Result<Choice, _> = from_slice(&buf);
+    assert!(result.is_err());
+}
@@ -480,3 +480,144 @@ fn single_custom_struct() {
     let decoded: Single<CustomStruct> = from_slice(&buf).unwrap();
     assert_eq!(decoded, value);
 }
+
+#[cfg(feature = "derive")]
+#[derive(Debug, SerializeComposite, DeserializeComposite, PartialEq)]
+#[amqp_contract(
+    name = "test:example:tuple",
+    code = "0x0000_0001:0x0000_0002",
+    encoding = "list"
+)]
+struct Pair(pub u32, pub Option<String>);
+
+#[cfg(feature = "derive")]
+#[derive(Debug, SerializeComposite, DeserializeComposite, PartialEq)]
+#[amqp_contract(
+    name = "test:example:tuple",
+    code = "0x0000_0001:0x0000_0002",
+    encoding = "list"
+)]
+struct GenericPair<A, B>(A, Option<B>);
+
+#[cfg(feature = "derive")]
+#[test]
+fn tuple_struct_with_two_fields() {
+    let value = Pair(1, Some(String::from("a")));
+    let buf = to_vec(&value).unwrap();
+    let expected = [
+        0x0, 0x80, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x2, 0xc0, 0x6, 0x2, 0x52, 0x1, 0xa1, 0x1,
+        0x61,
+    ];
+    assert_eq!(buf, expected);
+
+    let decoded: Pair = from_slice(&buf).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn tuple_struct_with_trailing_none() {
+    let value = Pair(1, None);
+    let buf = to_vec(&value).unwrap();
+    let expected = [
+        0x0, 0x80, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x2, 0xc0, 0x3, 0x1, 0x52, 0x1,
+    ];
+    assert_eq!(buf, expected);
+
+    let decoded: Pair = from_slice(&buf).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn generic_tuple_struct_with_two_fields() {
+    let value: GenericPair<u32, String> = GenericPair(1, Some(String::from("a")));
+    let buf = to_vec(&value).unwrap();
+    let expected = to_vec(&Pair(1, Some(String::from("a")))).unwrap();
+    assert_eq!(buf, expected);
+
+    let decoded: GenericPair<u32, String> = from_slice(&buf).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[cfg(feature = "derive")]
+#[derive(Debug, SerializeComposite, DeserializeComposite, PartialEq)]
+#[amqp_contract(
+    name = "test:example:versioned",
+    code = "0x0000_0001:0x0000_0003",
+    encoding = "list"
+)]
+struct VersionedV2 {
+    a: u32,
+    b: Vec<String>,
+    c: Option<Single<Vec<u8>>>,
+}
+
+#[cfg(feature = "derive")]
+#[derive(Debug, SerializeComposite, DeserializeComposite, PartialEq)]
+#[amqp_contract(
+    name = "test:example:versioned",
+    code = "0x0000_0001:0x0000_0003",
+    encoding = "list"
+)]
+struct VersionedV1 {
+    a: u32,
+    b: Vec<String>,
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn ignore_trailing_fields_of_described_list() {
+    let value = (
+        VersionedV2 {
+            a: 1,
+            b: vec![String::from("amqp")],
+            c: Some(Single { a: vec![1, 2, 3] }),
+        },
+        5672u32,
+    );
+    let buf = to_vec(&value).unwrap();
+
+    let decoded: (VersionedV1, u32) = from_slice(&buf).unwrap();
+    assert_eq!(
+        decoded,
+        (
+            VersionedV1 {
+                a: 1,
+                b: vec![String::from("amqp")]
+            },
+            5672
+        )
+    );
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn ignore_unknown_described_field_of_map() {
+    use serde_amqp::{primitives::OrderedMap, Value};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Known {
+        a: u32,
+        c: String,
+    }
+
+    let mut map = OrderedMap::new();
+    map.insert(Value::from("a"), Value::Uint(1));
+    map.insert(
+        Value::from("b"),
+        serde_amqp::to_value(&Single {
+            a: vec![Value::Bool(true)],
+        })
+        .unwrap(),
+    );
+    map.insert(Value::from("c"), Value::from("amqp"));
+    let expected = Known {
+        a: 1,
+        c: String::from("amqp"),
+    };
+
+    let buf = to_vec(&Value::Map(map)).unwrap();
+    let decoded: Known = from_slice(&buf).unwrap();
+    assert_eq!(decoded, expected);
+}
@@ -1,11 +1,11 @@
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{spanned::Spanned, DeriveInput, Fields};
 
 use crate::{
     util::{
         convert_to_case, generic_visitor, get_span_of, macro_rules_unwrap_or_default,
-        macro_rules_unwrap_or_none, parse_described_struct_attr, parse_named_field_attrs,
-        where_deserialize,
+        macro_rules_unwrap_or_none, parse_described_struct_attr, parse_described_variant_attr,
+        parse_named_field_attrs, where_deserialize,
     },
     DescribedStructAttr, EncodingType, FieldAttr,
 };
@@ -20,10 +20,135 @@ pub(crate) fn expand_deserialize(
         syn::Data::Struct(data) => {
             expand_deserialize_on_datastruct(&attr, ident, generics, data, input)
         }
-        _ => unimplemented!(),
+        syn::Data::Enum(data) => {
+            expand_deserialize_on_dataenum(&attr, ident, generics, data, input)
+        }
+        syn::Data::Union(_) => Err(syn::Error::new(
+            ident.span(),
+            "DeserializeComposite is not supported for union",
+        )),
     }
 }
 
+/// The descriptor of the described value selects the variant, which is then deserialized into a
+/// private struct that mirrors the fields of the variant
+fn expand_deserialize_on_dataenum(
+    attr: &DescribedStructAttr,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    data: &syn::DataEnum,
+    ctx: &DeriveInput,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    if !generics.params.is_empty() {
+        return Err(syn::Error::new(
+            generics.span(),
+            "DeserializeComposite is not supported for generic enum",
+        ));
+    }
+
+    let mut variant_structs: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut variant_impls: Vec<proc_macro2::TokenStream> = Vec::new();
+    for variant in &data.variants {
+        let variant_attr = parse_described_variant_attr(attr, variant);
+        let variant_ident = &variant.ident;
+        let struct_ident = format_ident!("__{}{}", ident, variant_ident);
+
+        let struct_data = syn::DataStruct {
+            struct_token: Default::default(),
+            fields: variant.fields.clone(),
+            semi_token: None,
+        };
+        // The field attributes are only used by this macro and are not valid on the private struct
+        let mut fields = variant.fields.clone();
+        fields.iter_mut().for_each(|f| f.attrs.clear());
+        let impl_de = expand_deserialize_on_datastruct(
+            &variant_attr,
+            &struct_ident,
+            generics,
+            &struct_data,
+            ctx,
+        )?;
+        let (definition, into_variant) = match &fields {
+            Fields::Named(named) => {
+                let field_idents: Vec<&syn::Ident> = named
+                    .named
+                    .iter()
+                    .map(|f| f.ident.as_ref().unwrap())
+                    .collect();
+                (
+                    quote!(struct #struct_ident #fields),
+                    quote!(Self::#variant_ident { #(#field_idents: __variant.#field_idents),* }),
+                )
+            }
+            Fields::Unnamed(unnamed) => {
+                let field_indices: Vec<syn::Index> =
+                    (0..unnamed.unnamed.len()).map(syn::Index::from).collect();
+                (
+                    quote!(struct #struct_ident #fields;),
+                    quote!(Self::#variant_ident( #(__variant.#field_indices),* )),
+                )
+            }
+            Fields::Unit => (
+                quote!(struct #struct_ident;),
+                quote!({
+                    let _ = __variant;
+                    Self::#variant_ident
+                }),
+            ),
+        };
+        variant_structs.push(quote! {
+            #[allow(dead_code)]
+            #definition
+
+            #impl_de
+        });
+
+        let name = &variant_attr.name[..];
+        let match_code = match variant_attr.code {
+            Some(code) => quote!(*__c == #code),
+            None => quote!(false),
+        };
+        variant_impls.push(quote! {
+            let __is_variant = match __descriptor {
+                serde_amqp::descriptor::Descriptor::Name(__symbol) => __symbol.as_str() == #name,
+                serde_amqp::descriptor::Descriptor::Code(__c) => #match_code,
+            };
+            if __is_variant {
+                let __variant: #struct_ident = serde_amqp::from_slice(&__buf)
+                    .map_err(serde_amqp::serde::de::Error::custom)?;
+                return Ok(#into_variant)
+            }
+        });
+    }
+
+    let token = quote! {
+        #( #variant_structs )*
+
+        #[automatically_derived]
+        impl<'de> serde_amqp::serde::de::Deserialize<'de> for #ident {
+            fn deserialize<_D>(deserializer: _D) -> Result<Self, _D::Error>
+            where
+                _D: serde_amqp::serde::de::Deserializer<'de>,
+            {
+                let __value: serde_amqp::Value =
+                    serde_amqp::serde::de::Deserialize::deserialize(deserializer)?;
+                let __descriptor = match &__value {
+                    serde_amqp::Value::Described(__described) => &__described.descriptor,
+                    _ => return Err(serde_amqp::serde::de::Error::custom("Expecting a described type")),
+                };
+                // The encoding of the variant is only known after the descriptor is read
+                let __buf = serde_amqp::to_vec(&__value)
+                    .map_err(serde_amqp::serde::de::Error::custom)?;
+
+                #( #variant_impls )*
+
+                Err(serde_amqp::serde::de::Error::custom("Descriptor mismatch"))
+            }
+        }
+    };
+    Ok(token)
+}
+
 fn expand_deserialize_on_datastruct(
     attr: &DescribedStructAttr,
     ident: &syn::Ident,
@@ -93,9 +218,9 @@ fn impl_visit_seq_for_unit_struct(
     evaluate_descriptor: &proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
     quote! {
-        fn visit_seq<A>(self, mut __seq: A) -> Result<Self::Value, A::Error>
+        fn visit_seq<_A>(self, mut __seq: _A) -> Result<Self::Value, _A::Error>
         where
-            A: serde_amqp::serde::de::SeqAccess<'de>,
+            _A: serde_amqp::serde::de::SeqAccess<'de>,
         {
             let __descriptor: serde_amqp::descriptor::Descriptor = match __seq.next_element()? {
                 Some(__val) => __val,
@@ -177,9 +302,9 @@ fn impl_visit_seq_for_tuple_struct(
         _ => macro_rules_unwrap_or_none(),
     };
     quote! {
-        fn visit_seq<A>(self, mut __seq: A) -> Result<Self::Value, A::Error>
+        fn visit_seq<_A>(self, mut __seq: _A) -> Result<Self::Value, _A::Error>
         where
-            A: serde_amqp::serde::de::SeqAccess<'de>,
+            _A: serde_amqp::serde::de::SeqAccess<'de>,
         {
             let __descriptor: serde_amqp::descriptor::Descriptor = match __seq.next_element()? {
                 Some(val) => val,
@@ -192,6 +317,9 @@ fn impl_visit_seq_for_tuple_struct(
 
             #( unwrap_or_none!(#field_idents, __seq.next_element()?, #field_types); )*
 
+            // Skip trailing fields that are not defined in this type
+            while let Some(serde_amqp::serde::de::IgnoredAny) = __seq.next_element()? {}
+
             Ok( #ident( #(#field_idents, )* ) )
         }
     }
@@ -491,6 +619,9 @@ fn impl_visit_seq_for_struct(
             // #( unwrap_or_none!(#field_idents, __seq, #field_types); )*
             #( #field_impls; )*
 
+            // Skip trailing fields that are not defined in this type
+            while let Some(serde_amqp::serde::de::IgnoredAny) = __seq.next_element()? {}
+
             Ok( #ident{ #(#field_idents, )* } )
         }
     }
@@ -113,8 +113,39 @@
 //! )]
 //! pub struct ApplicationProperties(pub BTreeMap<String, SimpleValue>);
 //! ```
+//!
+//! ## Enums
+//!
+//! Each variant of an enum is encoded as a described type of its own. The `name` and `code` of
+//! the descriptor are taken from the `amqp_contract` attribute on the variant, and the name
+//! defaults to the name of the variant. The `encoding` and `rename_all` on the enum apply to all
+//! variants unless they are overridden on the variant. Unit, newtype and struct variants are
+//! encoded the same way as the corresponding structs. During deserialization, the descriptor
+//! selects the variant. Generic enums can only be serialized.
+//!
+//! ```rust
+//! #[derive(Debug, SerializeComposite, DeserializeComposite)]
+//! #[amqp_contract(encoding = "list", rename_all = "kebab-case")]
+//! pub enum DistributionMode {
+//!     #[amqp_contract(name = "example:move:list", code = "0x0000_0001:0x0000_0001")]
+//!     Move,
+//!
+//!     #[amqp_contract(
+//!         name = "example:copy:basic",
+//!         code = "0x0000_0001:0x0000_0002",
+//!         encoding = "basic"
+//!     )]
+//!     Copy(String),
+//!
+//!     #[amqp_contract(name = "example:filter:list", code = "0x0000_0001:0x0000_0003")]
+//!     Filter {
+//!         selector: String,
+//!         max_count: Option<u32>,
+//!     },
+//! }
+//! ```
 
-use darling::{FromDeriveInput, FromMeta};
+use darling::{FromDeriveInput, FromMeta, FromVariant};
 use quote::quote;
 use syn::DeriveInput;
 
@@ -146,6 +177,15 @@ struct DescribedAttr {
     pub no_descriptor: Option<()>,
 }
 
+#[derive(Debug, Clone, FromVariant)]
+#[darling(attributes(amqp_contract))]
+struct VariantAttr {
+    pub name: Option<String>,
+    pub code: Option<String>,
+    pub encoding: Option<EncodingType>,
+    pub rename_all: Option<String>,
+}
+
 #[derive(Debug, darling::FromMeta, PartialEq)]
 struct FieldAttr {
     // default: syn::Lit
@@ -5,8 +5,8 @@ use crate::{
     util::{
         convert_to_case, macro_rules_buffer_if_eq_default, macro_rules_buffer_if_none,
         macro_rules_buffer_if_none_for_tuple_struct, macro_rules_serialize_if_neq_default,
-        macro_rules_serialize_if_some, parse_described_struct_attr, parse_named_field_attrs,
-        where_serialize,
+        macro_rules_serialize_if_some, parse_described_struct_attr, parse_described_variant_attr,
+        parse_named_field_attrs, where_serialize,
     },
     DescribedStructAttr, EncodingType, FieldAttr,
 };
@@ -21,7 +21,23 @@ pub(crate) fn expand_serialize(
         syn::Data::Struct(data) => {
             expand_serialize_on_datastruct(&amqp_attr, ident, generics, data, input)
         }
-        _ => unimplemented!(),
+        syn::Data::Enum(data) => {
+            expand_serialize_on_dataenum(&amqp_attr, ident, generics, data, input)
+        }
+        syn::Data::Union(_) => Err(syn::Error::new(
+            ident.span(),
+            "SerializeComposite is not supported for union",
+        )),
+    }
+}
+
+fn descriptor_token(amqp_attr: &DescribedStructAttr) -> proc_macro2::TokenStream {
+    match amqp_attr.code {
+        Some(code) => quote!(serde_amqp::descriptor::Descriptor::Code(#code)),
+        None => {
+            let name = &amqp_attr.name[..];
+            quote!(serde_amqp::descriptor::Descriptor::Name(serde_amqp::primitives::Symbol::from(#name)))
+        }
     }
 }
 
@@ -32,13 +48,7 @@ fn expand_serialize_on_datastruct(
     data: &syn::DataStruct,
     ctx: &DeriveInput,
 ) -> Result<proc_macro2::TokenStream, syn::Error> {
-    let descriptor = match amqp_attr.code {
-        Some(code) => quote!(serde_amqp::descriptor::Descriptor::Code(#code)),
-        None => {
-            let name = &amqp_attr.name[..];
-            quote!(serde_amqp::descriptor::Descriptor::Name(serde_amqp::primitives::Symbol::from(#name)))
-        }
-    };
+    let descriptor = descriptor_token(amqp_attr);
 
     match &data.fields {
         Fields::Named(fields) => {
@@ -81,6 +91,24 @@ fn expand_serialize_unit_struct(
     ident: &syn::Ident,
     descriptor: &proc_macro2::TokenStream,
     encoding: &EncodingType,
+) -> proc_macro2::TokenStream {
+    let body = serialize_unit_body(descriptor, encoding);
+    quote! {
+        #[automatically_derived]
+        impl serde_amqp::serde::ser::Serialize for #ident {
+            fn serialize<_S>(&self, serializer: _S) -> Result<_S::Ok, _S::Error>
+            where
+                _S: serde_amqp::serde::ser::Serializer,
+            {
+                #body
+            }
+        }
+    }
+}
+
+fn serialize_unit_body(
+    descriptor: &proc_macro2::TokenStream,
+    encoding: &EncodingType,
 ) -> proc_macro2::TokenStream {
     let struct_name = match encoding {
         EncodingType::List => quote!(serde_amqp::__constants::DESCRIBED_LIST),
@@ -88,29 +116,58 @@ fn expand_serialize_unit_struct(
         EncodingType::Map => panic!("Map encoding on unit struct is not supported"),
     };
     quote! {
+        use serde_amqp::serde::ser::SerializeTupleStruct;
+        // len + 1 for compatibility with other serializer
+        let mut state = serializer.serialize_tuple_struct(#struct_name, 0 + 1)?;
+        // serialize descriptor
+        state.serialize_field(&#descriptor)?;
+        state.end()
+    }
+}
+
+fn expand_serialize_tuple_struct(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    descriptor: &proc_macro2::TokenStream,
+    encoding: &EncodingType,
+    fields: &syn::FieldsUnnamed,
+) -> proc_macro2::TokenStream {
+    let field_accessors: Vec<proc_macro2::TokenStream> = fields
+        .unnamed
+        .iter()
+        .enumerate()
+        .map(|(i, _)| syn::Index::from(i))
+        .map(|index| quote!(&self.#index))
+        .collect();
+    let buffer_if_none = macro_rules_buffer_if_none_for_tuple_struct();
+    let body = serialize_tuple_body(descriptor, encoding, fields, &field_accessors);
+    let where_clause = match generics.params.len() {
+        0 => quote! {},
+        _ => where_serialize(generics),
+    };
+
+    quote! {
+        #buffer_if_none
+
         #[automatically_derived]
-        impl serde_amqp::serde::ser::Serialize for #ident {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        impl #generics serde_amqp::serde::ser::Serialize for #ident #generics #where_clause
+        {
+            fn serialize<_S>(&self, serializer: _S) -> Result<_S::Ok, _S::Error>
             where
-                S: serde_amqp::serde::ser::Serializer,
+                _S: serde_amqp::serde::ser::Serializer,
             {
-                use serde_amqp::serde::ser::SerializeTupleStruct;
-                // len + 1 for compatibility with other serializer
-                let mut state = serializer.serialize_tuple_struct(#struct_name, 0 + 1)?;
-                // serialize descriptor
-                state.serialize_field(&#descriptor)?;
-                state.end()
+                #body
             }
         }
     }
 }
 
-fn expand_serialize_tuple_struct(
-    ident: &syn::Ident,
-    generics: &syn::Generics,
+/// `field_accessors` are expressions that evaluate to a reference to each field
+fn serialize_tuple_body(
     descriptor: &proc_macro2::TokenStream,
     encoding: &EncodingType,
     fields: &syn::FieldsUnnamed,
+    field_accessors: &[proc_macro2::TokenStream],
 ) -> proc_macro2::TokenStream {
     let struct_name = match encoding {
         EncodingType::List => quote!(serde_amqp::__constants::DESCRIBED_LIST),
@@ -119,27 +176,60 @@ fn expand_serialize_tuple_struct(
                 // Basic encoding is allowed on newtype struct
                 quote!(serde_amqp::__constants::DESCRIBED_BASIC)
             } else {
-                unimplemented!()
+                panic!("Basic encoding on tuple struct with more than one field is not supported")
             }
         }
         EncodingType::Map => panic!("Map encoding for tuple struct is not supported"),
     };
-    let field_indices: Vec<syn::Index> = fields
-        .unnamed
+    let field_types: Vec<&syn::Type> = fields.unnamed.iter().map(|f| &f.ty).collect();
+    let len = field_accessors.len();
+
+    quote! {
+        use serde_amqp::serde::ser::SerializeTupleStruct;
+        let mut null_count = 0u32;
+        // len + 1 for compatibility with other serializer
+        let mut state = serializer.serialize_tuple_struct(#struct_name, #len + 1)?;
+        // serialize descriptor
+        // descriptor does not count towards number of element in list
+        // in serde_amqp serializer, this will be deducted
+        state.serialize_field(&#descriptor)?;
+        #( buffer_if_none_for_tuple!(state, null_count, #field_accessors, #field_types); )*
+        state.end()
+    }
+}
+
+fn expand_serialize_struct(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    descriptor: &proc_macro2::TokenStream,
+    encoding: &EncodingType,
+    rename_all: &str,
+    fields: &syn::FieldsNamed,
+    ctx: &DeriveInput,
+) -> proc_macro2::TokenStream {
+    let field_accessors: Vec<proc_macro2::TokenStream> = fields
+        .named
         .iter()
-        .enumerate()
-        .map(|(i, _)| syn::Index::from(i))
+        .map(|f| f.ident.as_ref().unwrap())
+        .map(|id| quote!(&self.#id))
         .collect();
-    let field_types: Vec<&syn::Type> = fields.unnamed.iter().map(|f| &f.ty).collect();
-    let len = field_indices.len();
-    let buffer_if_none = macro_rules_buffer_if_none_for_tuple_struct();
+    let declarative_macros = macro_rules_for_struct(encoding, fields);
+    let body = serialize_struct_body(
+        descriptor,
+        encoding,
+        rename_all,
+        fields,
+        &field_accessors,
+        ctx,
+    );
+
     let where_clause = match generics.params.len() {
         0 => quote! {},
         _ => where_serialize(generics),
     };
 
     quote! {
-        #buffer_if_none
+        #( #declarative_macros )*
 
         #[automatically_derived]
         impl #generics serde_amqp::serde::ser::Serialize for #ident #generics #where_clause
@@ -148,29 +238,39 @@ fn expand_serialize_tuple_struct(
             where
                 _S: serde_amqp::serde::ser::Serializer,
             {
-                use serde_amqp::serde::ser::SerializeTupleStruct;
-                let mut null_count = 0u32;
-                // len + 1 for compatibility with other serializer
-                let mut state = serializer.serialize_tuple_struct(#struct_name, #len + 1)?;
-                // serialize descriptor
-                // descriptor does not count towards number of element in list
-                // in serde_amqp serializer, this will be deducted
-                state.serialize_field(&#descriptor)?;
-                // #( state.serialize_field(&self.#field_indices)?; )*
-                #( buffer_if_none_for_tuple!(state, null_count, &self.#field_indices, #field_types); )*
-                state.end()
+                #body
             }
         }
     }
 }
 
-fn expand_serialize_struct(
-    ident: &syn::Ident,
-    generics: &syn::Generics,
+fn macro_rules_for_struct(
+    encoding: &EncodingType,
+    fields: &syn::FieldsNamed,
+) -> Vec<proc_macro2::TokenStream> {
+    let field_attrs = parse_named_field_attrs(fields.named.iter());
+    match encoding {
+        EncodingType::Basic | EncodingType::List => {
+            let mut macros = vec![macro_rules_buffer_if_none()];
+            if field_attrs.contains(&FieldAttr { default: true }) {
+                macros.push(macro_rules_buffer_if_eq_default());
+            }
+            macros
+        }
+        EncodingType::Map => vec![
+            macro_rules_serialize_if_some(),
+            macro_rules_serialize_if_neq_default(),
+        ],
+    }
+}
+
+/// `field_accessors` are expressions that evaluate to a reference to each field
+fn serialize_struct_body(
     descriptor: &proc_macro2::TokenStream,
     encoding: &EncodingType,
     rename_all: &str,
     fields: &syn::FieldsNamed,
+    field_accessors: &[proc_macro2::TokenStream],
     ctx: &DeriveInput,
 ) -> proc_macro2::TokenStream {
     let len = fields.named.len();
@@ -186,45 +286,19 @@ fn expand_serialize_struct(
         EncodingType::List => quote!(serde_amqp::__constants::DESCRIBED_LIST),
         EncodingType::Map => quote!(serde_amqp::__constants::DESCRIBED_MAP),
     };
-    let field_idents: Vec<syn::Ident> = fields
+    let field_names: Vec<String> = fields
         .named
         .iter()
-        .map(|f| f.ident.clone().unwrap())
-        .collect();
-    let field_names: Vec<String> = field_idents
-        .iter()
+        .map(|f| f.ident.as_ref().unwrap())
         .map(|i| convert_to_case(rename_all, i.to_string(), ctx).unwrap())
         .collect();
     let field_types: Vec<&syn::Type> = fields.named.iter().map(|f| &f.ty).collect();
     let field_attrs = parse_named_field_attrs(fields.named.iter());
-    let declarative_macro = match encoding {
-        EncodingType::Basic | EncodingType::List => {
-            let buffer_if_none = macro_rules_buffer_if_none();
-
-            let buffer_if_eq_default = match field_attrs.contains(&FieldAttr { default: true }) {
-                true => macro_rules_buffer_if_eq_default(),
-                false => quote! {},
-            };
-            quote! {
-                #buffer_if_none
-                #buffer_if_eq_default
-            }
-        }
-        EncodingType::Map => {
-            let serialize_if_some = macro_rules_serialize_if_some();
-            let serialize_if_neq_default = macro_rules_serialize_if_neq_default();
-            quote! {
-                #serialize_if_some
-                #serialize_if_neq_default
-            }
-        }
-    };
 
     let mut field_impls: Vec<proc_macro2::TokenStream> = vec![];
     match encoding {
         EncodingType::Basic | EncodingType::List => {
-            // for ((id, name), ty) in field_idents
-            for (((id, name), ty), attr) in field_idents
+            for (((accessor, name), ty), attr) in field_accessors
                 .iter()
                 .zip(field_names.iter())
                 .zip(field_types.iter())
@@ -232,17 +306,17 @@ fn expand_serialize_struct(
             {
                 let token = match attr.default {
                     true => quote! {
-                        buffer_if_eq_default!(state, nulls, &self.#id, #name, #ty);
+                        buffer_if_eq_default!(state, nulls, #accessor, #name, #ty);
                     },
                     false => quote! {
-                        buffer_if_none!(state, nulls, &self.#id, #name, #ty);
+                        buffer_if_none!(state, nulls, #accessor, #name, #ty);
                     },
                 };
                 field_impls.push(token);
             }
         }
         EncodingType::Map => {
-            for (((id, name), ty), attr) in field_idents
+            for (((accessor, name), ty), attr) in field_accessors
                 .iter()
                 .zip(field_names.iter())
                 .zip(field_types.iter())
@@ -250,10 +324,10 @@ fn expand_serialize_struct(
             {
                 let token = match attr.default {
                     true => quote! {
-                        serialize_if_neq_default!(state, &self.#id, #name, #ty);
+                        serialize_if_neq_default!(state, #accessor, #name, #ty);
                     },
                     false => quote! {
-                        serialize_if_some!(state, &self.#id, #name, #ty);
+                        serialize_if_some!(state, #accessor, #name, #ty);
                     },
                 };
                 field_impls.push(token);
@@ -261,13 +335,95 @@ fn expand_serialize_struct(
         }
     }
 
+    quote! {
+        use serde_amqp::serde::ser::SerializeStruct;
+        let mut nulls: Vec<&str> = Vec::new();
+        // len + 1 for compatibility with other serializer
+        let mut state = serializer.serialize_struct(#struct_name, #len + 1)?;
+        // serialize descriptor
+        // descriptor does not count towards number of element in list
+        // in serde_amqp serializer, this will be deducted
+        state.serialize_field(serde_amqp::__constants::DESCRIPTOR, &#descriptor)?;
+        #( #field_impls; )*
+        state.end()
+    }
+}
+
+/// Each variant is serialized as if it were a struct with the descriptor and encoding of the
+/// variant
+fn expand_serialize_on_dataenum(
+    amqp_attr: &DescribedStructAttr,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    data: &syn::DataEnum,
+    ctx: &DeriveInput,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let mut declarative_macros: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut arms: Vec<proc_macro2::TokenStream> = Vec::new();
+    for variant in &data.variants {
+        let variant_attr = parse_described_variant_attr(amqp_attr, variant);
+        let descriptor = descriptor_token(&variant_attr);
+        let variant_ident = &variant.ident;
+
+        let arm = match &variant.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => {
+                let field_idents: Vec<&syn::Ident> = fields
+                    .named
+                    .iter()
+                    .map(|f| f.ident.as_ref().unwrap())
+                    .collect();
+                let bindings = variant_field_bindings(fields.named.len());
+                let field_accessors: Vec<proc_macro2::TokenStream> =
+                    bindings.iter().map(|b| quote!(#b)).collect();
+                declarative_macros.extend(macro_rules_for_struct(&variant_attr.encoding, fields));
+                let body = serialize_struct_body(
+                    &descriptor,
+                    &variant_attr.encoding,
+                    &variant_attr.rename_field,
+                    fields,
+                    &field_accessors,
+                    ctx,
+                );
+                quote! {
+                    Self::#variant_ident { #(#field_idents: #bindings),* } => { #body }
+                }
+            }
+            Fields::Unnamed(fields) if !fields.unnamed.is_empty() => {
+                let bindings = variant_field_bindings(fields.unnamed.len());
+                let field_accessors: Vec<proc_macro2::TokenStream> =
+                    bindings.iter().map(|b| quote!(#b)).collect();
+                declarative_macros.push(macro_rules_buffer_if_none_for_tuple_struct());
+                let body = serialize_tuple_body(
+                    &descriptor,
+                    &variant_attr.encoding,
+                    fields,
+                    &field_accessors,
+                );
+                quote! {
+                    Self::#variant_ident( #(#bindings),* ) => { #body }
+                }
+            }
+            Fields::Named(_) | Fields::Unnamed(_) | Fields::Unit => {
+                let body = serialize_unit_body(&descriptor, &variant_attr.encoding);
+                quote! {
+                    Self::#variant_ident { .. } => { #body }
+                }
+            }
+        };
+        arms.push(arm);
+    }
+
+    // The same declarative macro may be needed by multiple variants
+    let mut seen = std::collections::HashSet::new();
+    declarative_macros.retain(|m| seen.insert(m.to_string()));
+
     let where_clause = match generics.params.len() {
         0 => quote! {},
         _ => where_serialize(generics),
     };
 
-    quote! {
-        #declarative_macro
+    Ok(quote! {
+        #( #declarative_macros )*
 
         #[automatically_derived]
         impl #generics serde_amqp::serde::ser::Serialize for #ident #generics #where_clause
@@ -276,20 +432,16 @@ fn expand_serialize_struct(
             where
                 _S: serde_amqp::serde::ser::Serializer,
             {
-                use serde_amqp::serde::ser::SerializeStruct;
-                // let mut null_count = 0u32;
-                let mut nulls: Vec<&str> = Vec::new();
-                // len + 1 for compatibility with other serializer
-                let mut state = serializer.serialize_struct(#struct_name, #len + 1)?;
-                // serialize descriptor
-                // descriptor does not count towards number of element in list
-                // in serde_amqp serializer, this will be deducted
-                state.serialize_field(serde_amqp::__constants::DESCRIPTOR, &#descriptor)?;
-                // #( state.serialize_field(#field_names, &self.#field_idents)?; )*
-                // #(buffer_if_none!(state, null_count, &self.#field_idents, #field_names, #field_types);) *
-                #( #field_impls; )*
-                state.end()
+                match self {
+                    #( #arms )*
+                }
             }
         }
-    }
+    })
+}
+
+fn variant_field_bindings(len: usize) -> Vec<syn::Ident> {
+    (0..len)
+        .map(|i| quote::format_ident!("__field{}", i))
+        .collect()
 }
@@ -1,11 +1,11 @@
 use std::num::ParseIntError;
 
-use darling::{FromDeriveInput, FromMeta};
+use darling::{FromDeriveInput, FromMeta, FromVariant};
 use proc_macro2::Span;
 use quote::quote;
 use syn::{parse::Parser, DeriveInput, Field};
 
-use crate::{DescribedAttr, DescribedStructAttr, EncodingType, FieldAttr};
+use crate::{DescribedAttr, DescribedStructAttr, EncodingType, FieldAttr, VariantAttr};
 
 pub(crate) fn parse_described_struct_attr(input: &syn::DeriveInput) -> DescribedStructAttr {
     let attr = DescribedAttr::from_derive_input(input).unwrap();
@@ -22,6 +22,28 @@ pub(crate) fn parse_described_struct_attr(input: &syn::DeriveInput) -> Described
     }
 }
 
+/// The encoding and `rename_all` of the enum are used if not specified on the variant. The
+/// descriptor name defaults to the name of the variant.
+pub(crate) fn parse_described_variant_attr(
+    enum_attr: &DescribedStructAttr,
+    variant: &syn::Variant,
+) -> DescribedStructAttr {
+    let attr = VariantAttr::from_variant(variant).unwrap();
+
+    let name = attr.name.unwrap_or_else(|| variant.ident.to_string());
+    let code = attr.code.map(parse_descriptor_code).transpose().unwrap();
+    let encoding = attr.encoding.unwrap_or_else(|| enum_attr.encoding.clone());
+    let rename_field = attr
+        .rename_all
+        .unwrap_or_else(|| enum_attr.rename_field.clone());
+    DescribedStructAttr {
+        name,
+        code,
+        encoding,
+        rename_field,
+    }
+}
+
 /// Error with parsing descriptor code
 #[derive(Debug)]
 pub enum ParseDescriptorCodeError {